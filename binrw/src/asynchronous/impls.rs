@@ -0,0 +1,636 @@
+// Impls with higher-ranked bounds on their arguments return `impl Future +
+// Send` rather than using `async fn`, since otherwise the compiler has to look
+// through the future to prove it is `Send`, which fails for those bounds
+// (rust-lang/rust#100013).
+#![allow(clippy::manual_async_fn)]
+
+use super::{AsyncRead, AsyncSeek, AsyncWrite, BinReadAsync, BinWriteAsync, read_exact, write_all};
+use crate::{
+    __private::{
+        not_enough_bytes, restore_position_async, seek_async as seek,
+        stream_position_async as stream_position, write_zeroes_async,
+    },
+    BinResult, EncodedString, Endian, Error, FilePtr, NullString, NullWideString, VecArgs,
+    file_ptr::{
+        FilePtrArgs, FilePtrWriteArgs, IntoSeekFrom, TryFromOffset, pointee_padding, pointer_to,
+    },
+    io::{self, SeekFrom},
+    strings,
+};
+use core::{
+    any::Any,
+    future::Future,
+    marker::PhantomData,
+    num::{
+        NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroU8, NonZeroU16,
+        NonZeroU32, NonZeroU64, NonZeroU128,
+    },
+};
+
+macro_rules! binread_async_num_impl {
+    ($($type_name:ty),*$(,)?) => {
+        $(
+            impl BinReadAsync for $type_name {
+                async fn read_options_async<R>(reader: &mut R, endian: Endian, (): Self::Args<'_>) -> BinResult<Self>
+                where
+                    R: AsyncRead + AsyncSeek + Unpin + Send,
+                {
+                    let mut val = [0; core::mem::size_of::<$type_name>()];
+                    let pos = stream_position(reader).await?;
+
+                    if let Err(err) = read_exact(reader, &mut val).await {
+                        return Err(restore_position_async(reader, pos, err.into()).await);
+                    }
+                    Ok(match endian {
+                        Endian::Big => <$type_name>::from_be_bytes(val),
+                        Endian::Little => <$type_name>::from_le_bytes(val),
+                    })
+                }
+            }
+
+            impl BinWriteAsync for $type_name {
+                async fn write_options_async<W>(
+                    &self,
+                    writer: &mut W,
+                    endian: Endian,
+                    (): Self::Args<'_>,
+                ) -> BinResult<()>
+                where
+                    W: AsyncWrite + AsyncSeek + Unpin + Send,
+                {
+                    let bytes = match endian {
+                        Endian::Big => self.to_be_bytes(),
+                        Endian::Little => self.to_le_bytes(),
+                    };
+                    write_all(writer, &bytes).await.map_err(Into::into)
+                }
+            }
+        )*
+    }
+}
+
+binread_async_num_impl!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+fn unexpected_zero_num() -> Error {
+    Error::Io(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "unexpected zero found",
+    ))
+}
+
+macro_rules! binread_async_nonzero_impl {
+    ($($Ty:ty, $Int:ty),* $(,)?) => {
+        $(
+            impl BinReadAsync for $Ty {
+                async fn read_options_async<R>(
+                    reader: &mut R,
+                    endian: Endian,
+                    (): Self::Args<'_>,
+                ) -> BinResult<Self>
+                where
+                    R: AsyncRead + AsyncSeek + Unpin + Send,
+                {
+                    match <$Ty>::new(<$Int>::read_options_async(reader, endian, ()).await?) {
+                        Some(x) => Ok(x),
+                        None => Err(unexpected_zero_num()),
+                    }
+                }
+            }
+
+            impl BinWriteAsync for $Ty {
+                async fn write_options_async<W>(
+                    &self,
+                    writer: &mut W,
+                    endian: Endian,
+                    (): Self::Args<'_>,
+                ) -> BinResult<()>
+                where
+                    W: AsyncWrite + AsyncSeek + Unpin + Send,
+                {
+                    self.get().write_options_async(writer, endian, ()).await
+                }
+            }
+        )+
+    }
+}
+
+binread_async_nonzero_impl! {
+    NonZeroU8, u8, NonZeroU16, u16, NonZeroU32, u32, NonZeroU64, u64, NonZeroU128, u128,
+    NonZeroI8, i8, NonZeroI16, i16, NonZeroI32, i32, NonZeroI64, i64, NonZeroI128, i128,
+}
+
+/// The most bytes read into a `Vec<u8>` at once, so that an untrusted count
+/// only allocates as much memory as the reader actually provides.
+const READ_CHUNK_LEN: usize = 0x1000;
+
+/// Reads `count` elements, reading bytes in bulk.
+async fn read_vec<R, T>(
+    reader: &mut R,
+    endian: Endian,
+    count: usize,
+    args: T::Args<'_>,
+) -> BinResult<Vec<T>>
+where
+    R: AsyncRead + AsyncSeek + Unpin + Send,
+    T: BinReadAsync + Send + 'static,
+    for<'a> T::Args<'a>: Clone + Send,
+{
+    if core::any::TypeId::of::<T>() == core::any::TypeId::of::<u8>() {
+        let pos = stream_position(reader).await?;
+        let mut bytes = Vec::with_capacity(count.min(READ_CHUNK_LEN));
+        while bytes.len() < count {
+            let start = bytes.len();
+            bytes.resize(start + (count - start).min(READ_CHUNK_LEN), 0);
+            if let Err(err) = read_exact(reader, &mut bytes[start..]).await {
+                let err = if err.kind() == io::ErrorKind::UnexpectedEof {
+                    not_enough_bytes()
+                } else {
+                    err.into()
+                };
+                return Err(restore_position_async(reader, pos, err).await);
+            }
+        }
+        let bytes: Box<dyn Any> = Box::new(bytes);
+        return Ok(*bytes.downcast::<Vec<T>>().unwrap());
+    }
+
+    let mut values = Vec::with_capacity(count.min(READ_CHUNK_LEN));
+    for _ in 0..count {
+        values.push(T::read_options_async(reader, endian, args.clone()).await?);
+    }
+    Ok(values)
+}
+
+impl<T> BinReadAsync for Vec<T>
+where
+    T: BinReadAsync + Send + 'static,
+    for<'a> T::Args<'a>: Clone + Send,
+{
+    fn read_options_async<R>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+    {
+        async move {
+            let VecArgs { count, inner } = args;
+            read_vec(reader, endian, count, inner).await
+        }
+    }
+}
+
+impl<T, const N: usize> BinReadAsync for [T; N]
+where
+    T: BinReadAsync + Send + 'static,
+    for<'a> T::Args<'a>: Clone + Send,
+{
+    fn read_options_async<R>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+    {
+        async move {
+            let values = read_vec(reader, endian, N, args).await?;
+            Ok(values
+                .try_into()
+                .unwrap_or_else(|_| unreachable!("read_vec returns N elements")))
+        }
+    }
+}
+
+impl BinReadAsync for () {
+    async fn read_options_async<R>(_: &mut R, _: Endian, (): Self::Args<'_>) -> BinResult<Self>
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+    {
+        Ok(())
+    }
+}
+
+impl<T> BinReadAsync for Box<T>
+where
+    T: BinReadAsync + Send,
+    for<'a> T::Args<'a>: Send,
+{
+    fn read_options_async<R>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+    {
+        async move { Ok(Box::new(T::read_options_async(reader, endian, args).await?)) }
+    }
+}
+
+impl<T> BinReadAsync for Option<T>
+where
+    T: BinReadAsync + Send,
+    for<'a> T::Args<'a>: Send,
+{
+    fn read_options_async<R>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+    {
+        async move { Ok(Some(T::read_options_async(reader, endian, args).await?)) }
+    }
+}
+
+impl<T> BinReadAsync for PhantomData<T>
+where
+    T: Send,
+{
+    async fn read_options_async<R>(_: &mut R, _: Endian, (): Self::Args<'_>) -> BinResult<Self>
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+    {
+        Ok(PhantomData)
+    }
+}
+
+impl BinReadAsync for NullString {
+    async fn read_options_async<R>(
+        reader: &mut R,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<Self>
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+    {
+        let mut values = vec![];
+
+        loop {
+            let val = <u8>::read_options_async(reader, endian, ()).await?;
+            if val == 0 {
+                return Ok(Self(values));
+            }
+            values.push(val);
+        }
+    }
+}
+
+impl BinReadAsync for EncodedString {
+    async fn read_options_async<R>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self>
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+    {
+        let encoding = args.encoding;
+        let pos = stream_position(reader).await?;
+        let mut unit = vec![0; encoding.unit_len()];
+        let mut bytes = vec![];
+
+        loop {
+            read_exact(reader, &mut unit).await?;
+            if unit.iter().all(|byte| *byte == 0) {
+                break;
+            }
+            bytes.extend_from_slice(&unit);
+        }
+
+        strings::decode(encoding, &bytes, endian, pos).map(Self)
+    }
+}
+
+impl BinReadAsync for NullWideString {
+    async fn read_options_async<R>(
+        reader: &mut R,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<Self>
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+    {
+        let mut values = vec![];
+
+        loop {
+            let val = <u16>::read_options_async(reader, endian, ()).await?;
+            if val == 0 {
+                return Ok(Self(values));
+            }
+            values.push(val);
+        }
+    }
+}
+
+impl<Ptr, Value> BinReadAsync for FilePtr<Ptr, Value>
+where
+    Ptr: for<'a> BinReadAsync<Args<'a> = ()> + IntoSeekFrom + Send,
+    Value: BinReadAsync + Send,
+    for<'a> Value::Args<'a>: Send,
+{
+    fn read_options_async<R>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+    {
+        async move {
+            let ptr = Ptr::read_options_async(reader, endian, ()).await?;
+            let FilePtrArgs { offset, inner } = args;
+            let before = stream_position(reader).await?;
+            seek(reader, SeekFrom::Start(offset)).await?;
+            seek(reader, ptr.into_seek_from()?).await?;
+            let value = Value::read_options_async(reader, endian, inner).await;
+            seek(reader, SeekFrom::Start(before)).await?;
+            Ok(FilePtr { ptr, value: value? })
+        }
+    }
+}
+
+impl<Ptr, Value> BinWriteAsync for FilePtr<Ptr, Value>
+where
+    Ptr: for<'a> BinWriteAsync<Args<'a> = ()> + IntoSeekFrom + TryFromOffset + Send + Sync,
+    Value: BinWriteAsync + Sync,
+    for<'a> Value::Args<'a>: Clone + Send,
+{
+    fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        async move {
+            let FilePtrWriteArgs {
+                queue,
+                offset,
+                align,
+                inner,
+            } = args;
+
+            if !queue.is_placing() {
+                queue.queue();
+                return self.ptr.write_options_async(writer, endian, ()).await;
+            }
+
+            let ptr_pos = stream_position(writer).await?;
+            let end = seek(writer, SeekFrom::End(0)).await?;
+            let padding = pointee_padding(end, align);
+            write_zeroes_async(writer, padding).await?;
+            let pos = end + padding;
+
+            let state = queue.begin();
+            self.value
+                .write_options_async(writer, endian, inner.clone())
+                .await?;
+            if queue.place() {
+                seek(writer, SeekFrom::Start(pos)).await?;
+                self.value
+                    .write_options_async(writer, endian, inner)
+                    .await?;
+            }
+            queue.end(state);
+
+            let ptr = pointer_to::<Ptr>(pos, offset, ptr_pos)?;
+            seek(writer, SeekFrom::Start(ptr_pos)).await?;
+            ptr.write_options_async(writer, endian, ()).await
+        }
+    }
+}
+
+impl<T, const N: usize> BinWriteAsync for [T; N]
+where
+    T: BinWriteAsync + Sync + 'static,
+    for<'a> T::Args<'a>: Clone + Send,
+{
+    fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        async move {
+            if let Some(this) = <dyn Any>::downcast_ref::<[u8; N]>(self) {
+                write_all(writer, this).await.map_err(Into::into)
+            } else {
+                self[..].write_options_async(writer, endian, args).await
+            }
+        }
+    }
+}
+
+impl<T> BinWriteAsync for [T]
+where
+    T: BinWriteAsync + Sync,
+    for<'a> T::Args<'a>: Clone + Send,
+{
+    fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        async move {
+            for item in self {
+                item.write_options_async(writer, endian, args.clone())
+                    .await?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+impl BinWriteAsync for str {
+    async fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        _: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()>
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        write_all(writer, self.as_bytes()).await.map_err(Into::into)
+    }
+}
+
+impl<T> BinWriteAsync for Vec<T>
+where
+    T: BinWriteAsync + Sync + 'static,
+    for<'a> T::Args<'a>: Clone + Send,
+{
+    fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        async move {
+            if let Some(this) = <dyn Any>::downcast_ref::<Vec<u8>>(self) {
+                write_all(writer, this).await.map_err(Into::into)
+            } else {
+                self[..].write_options_async(writer, endian, args).await
+            }
+        }
+    }
+}
+
+impl<T> BinWriteAsync for &T
+where
+    T: BinWriteAsync + Sync + ?Sized,
+    for<'a> T::Args<'a>: Send,
+{
+    fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        async move { (**self).write_options_async(writer, endian, args).await }
+    }
+}
+
+impl<T> BinWriteAsync for Box<T>
+where
+    T: BinWriteAsync + Sync + ?Sized + 'static,
+    for<'a> T::Args<'a>: Send,
+{
+    fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        async move { (**self).write_options_async(writer, endian, args).await }
+    }
+}
+
+impl<T> BinWriteAsync for Option<T>
+where
+    T: BinWriteAsync + Sync,
+    for<'a> T::Args<'a>: Send,
+{
+    fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        async move {
+            match self {
+                Some(inner) => inner.write_options_async(writer, endian, args).await,
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+impl<T> BinWriteAsync for PhantomData<T>
+where
+    T: Sync,
+{
+    async fn write_options_async<W>(
+        &self,
+        _: &mut W,
+        _: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()>
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        Ok(())
+    }
+}
+
+impl BinWriteAsync for () {
+    async fn write_options_async<W>(
+        &self,
+        _: &mut W,
+        _: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()>
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        Ok(())
+    }
+}
+
+impl BinWriteAsync for NullString {
+    async fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        _: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()>
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        write_all(writer, &self.0).await?;
+        write_all(writer, &[0]).await.map_err(Into::into)
+    }
+}
+
+impl BinWriteAsync for EncodedString {
+    async fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()>
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        let encoding = args.encoding;
+        let pos = stream_position(writer).await?;
+        let bytes = strings::encode(encoding, &self.0, endian, pos)?;
+        write_all(writer, &bytes).await?;
+        write_all(writer, &vec![0; encoding.unit_len()])
+            .await
+            .map_err(Into::into)
+    }
+}
+
+impl BinWriteAsync for NullWideString {
+    async fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()>
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+    {
+        for c in &self.0 {
+            c.write_options_async(writer, endian, ()).await?;
+        }
+        0u16.write_options_async(writer, endian, ()).await
+    }
+}
@@ -0,0 +1,304 @@
+//! Reading and writing over asynchronous streams.
+//!
+//! [`BinReadAsync`] and [`BinWriteAsync`] are the counterparts of [`BinRead`]
+//! and [`BinWrite`] for streams which implement the [`futures_io`] traits
+//! [`AsyncRead`] or [`AsyncWrite`], plus [`AsyncSeek`]. They share their
+//! [`Args`](BinRead::Args) type with the synchronous traits, which they extend,
+//! so a type which implements both can be read or written the same way either
+//! way.
+//!
+//! Both traits can be derived with `#[derive(BinReadAsync)]` and
+//! `#[derive(BinWriteAsync)]` alongside the synchronous derives, and use the
+//! same `br`, `bw`, and `brw` attributes. Types with temporary fields use
+//! `#[binrw(async)]`, `#[binread(async)]`, or `#[binwrite(async)]` instead,
+//! which generate the synchronous and asynchronous impls together. Directives
+//! which run user code against the stream (`parse_with`, `write_with`,
+//! `len_prefix`, `map_stream`), or which need to wrap it (`size`, `checksum`,
+//! `bits`), are not supported.
+//!
+//! [`FilePtr`](crate::FilePtr) values are written asynchronously with
+//! [`write_with_pointees_async`](crate::file_ptr::write_with_pointees_async).
+//!
+//! Tokio streams can be used through the compatibility wrappers in the
+//! `tokio-util` crate.
+//!
+//! The futures returned by these traits are [`Send`], so they can be run on a
+//! multithreaded executor. As a result, generic types need [`Send`] bounds on
+//! the types they read and [`Sync`] bounds on the types they write.
+//!
+//! # Examples
+//!
+//! ```
+//! # use binrw::{prelude::*, BinReadAsync, BinWriteAsync};
+//! # use futures_util::io::Cursor;
+//! #[derive(BinRead, BinReadAsync, BinWrite, BinWriteAsync, Debug, PartialEq)]
+//! #[brw(big, magic = b"PT")]
+//! struct Point {
+//!     x: u16,
+//!     y: u16,
+//! }
+//!
+//! # futures_executor::block_on(async {
+//! let mut stream = Cursor::new(Vec::new());
+//! Point { x: 1, y: 2 }.write_async(&mut stream).await.unwrap();
+//!
+//! stream.set_position(0);
+//! let point = Point::read_async(&mut stream).await.unwrap();
+//! assert_eq!(point, Point { x: 1, y: 2 });
+//! # });
+//! ```
+
+mod impls;
+
+use crate::{
+    __private::Required,
+    BinRead, BinResult, BinWrite, Endian, io,
+    meta::{ReadEndian, WriteEndian},
+};
+use core::{future::Future, pin::Pin};
+pub use futures_io::{AsyncRead, AsyncSeek, AsyncWrite};
+
+/// The `BinReadAsync` trait reads data from asynchronous streams and converts
+/// it into objects.
+///
+/// This is the asynchronous counterpart of [`BinRead`]. It can be derived
+/// with `#[derive(BinReadAsync)]`, or manually implemented by writing an
+/// appropriate [`read_options_async()`](Self::read_options_async) function.
+pub trait BinReadAsync: BinRead {
+    /// Read `Self` from the reader using default arguments.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_async<R>(reader: &mut R) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+        Self: ReadEndian,
+        for<'a> Self::Args<'a>: Required,
+    {
+        Self::read_args_async(reader, Self::Args::args())
+    }
+
+    /// Read `Self` from the reader using default arguments and assuming
+    /// big-endian byte order.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_be_async<R>(reader: &mut R) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+        for<'a> Self::Args<'a>: Required,
+    {
+        Self::read_options_async(reader, Endian::Big, Self::Args::args())
+    }
+
+    /// Read `Self` from the reader using default arguments and assuming
+    /// little-endian byte order.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_le_async<R>(reader: &mut R) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+        for<'a> Self::Args<'a>: Required,
+    {
+        Self::read_options_async(reader, Endian::Little, Self::Args::args())
+    }
+
+    /// Read `Self` from the reader using default arguments and assuming
+    /// native-endian byte order.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_ne_async<R>(reader: &mut R) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+        for<'a> Self::Args<'a>: Required,
+    {
+        Self::read_options_async(reader, Endian::NATIVE, Self::Args::args())
+    }
+
+    /// Read `Self` from the reader using the given arguments.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_args_async<R>(
+        reader: &mut R,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send,
+        Self: ReadEndian,
+    {
+        Self::read_options_async(reader, Endian::Little, args)
+    }
+
+    /// Read `Self` from the reader using the given [`Endian`] and arguments.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    fn read_options_async<R>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<Self>> + Send
+    where
+        R: AsyncRead + AsyncSeek + Unpin + Send;
+}
+
+/// The `BinWriteAsync` trait serialises objects and writes them to
+/// asynchronous streams.
+///
+/// This is the asynchronous counterpart of [`BinWrite`]. It can be derived
+/// with `#[derive(BinWriteAsync)]`, or manually implemented by writing an
+/// appropriate [`write_options_async()`](Self::write_options_async) function.
+pub trait BinWriteAsync: BinWrite {
+    /// Write `Self` to the writer using default arguments.
+    ///
+    /// # Errors
+    ///
+    /// If writing fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn write_async<W>(&self, writer: &mut W) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+        Self: WriteEndian,
+        for<'a> Self::Args<'a>: Required,
+    {
+        self.write_args_async(writer, Self::Args::args())
+    }
+
+    /// Write `Self` to the writer assuming big-endian byte order.
+    ///
+    /// # Errors
+    ///
+    /// If writing fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn write_be_async<W>(&self, writer: &mut W) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+        for<'a> Self::Args<'a>: Required,
+    {
+        self.write_options_async(writer, Endian::Big, Self::Args::args())
+    }
+
+    /// Write `Self` to the writer assuming little-endian byte order.
+    ///
+    /// # Errors
+    ///
+    /// If writing fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn write_le_async<W>(&self, writer: &mut W) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+        for<'a> Self::Args<'a>: Required,
+    {
+        self.write_options_async(writer, Endian::Little, Self::Args::args())
+    }
+
+    /// Write `Self` to the writer assuming native-endian byte order.
+    ///
+    /// # Errors
+    ///
+    /// If writing fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn write_ne_async<W>(&self, writer: &mut W) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+        for<'a> Self::Args<'a>: Required,
+    {
+        self.write_options_async(writer, Endian::NATIVE, Self::Args::args())
+    }
+
+    /// Write `Self` to the writer using the given arguments.
+    ///
+    /// # Errors
+    ///
+    /// If writing fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn write_args_async<W>(
+        &self,
+        writer: &mut W,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send,
+        Self: WriteEndian,
+    {
+        self.write_options_async(writer, Endian::Little, args)
+    }
+
+    /// Write `Self` to the writer using the given [`Endian`] and arguments.
+    ///
+    /// # Errors
+    ///
+    /// If writing fails, an [`Error`](crate::Error) variant will be returned.
+    fn write_options_async<W>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> impl Future<Output = BinResult<()>> + Send
+    where
+        W: AsyncWrite + AsyncSeek + Unpin + Send;
+}
+
+/// Reads exactly enough bytes to fill `buf`.
+pub(crate) async fn read_exact<R>(reader: &mut R, buf: &mut [u8]) -> io::Result<()>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    let mut filled = 0;
+    while filled < buf.len() {
+        let read =
+            core::future::poll_fn(|cx| Pin::new(&mut *reader).poll_read(cx, &mut buf[filled..]))
+                .await;
+        match read {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "failed to fill whole buffer",
+                ));
+            }
+            Ok(count) => filled += count,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Writes all of `buf`.
+pub(crate) async fn write_all<W>(writer: &mut W, buf: &[u8]) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut written = 0;
+    while written < buf.len() {
+        let write =
+            core::future::poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, &buf[written..]))
+                .await;
+        match write {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                ));
+            }
+            Ok(count) => written += count,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
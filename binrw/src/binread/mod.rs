@@ -1,4 +1,5 @@
 mod impls;
+mod stream;
 
 use crate::{
     __private::Required,
@@ -7,6 +8,7 @@ use crate::{
     meta::ReadEndian,
 };
 pub use impls::VecArgs;
+pub use stream::BinReadStream;
 
 /// The `BinRead` trait reads data from streams and converts it into objects.
 ///
@@ -0,0 +1,297 @@
+use super::BinRead;
+use crate::{
+    __private::Required,
+    BinResult, Endian, FixedString, FixedWideString, I24, I40, I48, I56, NullString,
+    NullWideString, PosValue, U24, U40, U48, U56, VarInt, VarUint,
+    io::{self, NoSeek, Read, Seek, SeekFrom},
+    meta::ReadEndian,
+};
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    marker::PhantomData,
+    num::{
+        NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroU8, NonZeroU16,
+        NonZeroU32, NonZeroU64, NonZeroU128,
+    },
+};
+
+/// The `BinReadStream` trait reads objects from streams which cannot seek,
+/// like pipes, sockets, and standard input.
+///
+/// Types which implement this trait never need to seek backwards while they
+/// are being read, so they can be read from anything that implements
+/// [`Read`]. Forward seeks, like those used by the [`pad_before`] and
+/// [`align_after`] directives, are performed by discarding bytes. Positions
+/// seen by the type, including those in errors, are relative to where the
+/// reader was when reading started.
+///
+/// If reading fails, the bytes which were already consumed from the reader are
+/// lost, since there is no way to put them back.
+///
+/// [`align_after`]: crate::docs::attribute#padding-and-alignment
+/// [`pad_before`]: crate::docs::attribute#padding-and-alignment
+///
+/// # Derivable
+///
+/// `#[derive(BinRead)]` implements this trait automatically for types which
+/// are read in a single forward pass. A derived type does not implement this
+/// trait if:
+///
+/// * it is a union, or an enum with more than one data variant (or unit
+///   variants with more than one type of magic), since these backtrack to try
+///   each variant;
+/// * it uses `map_stream`, or any field uses `parse_with`, `len_prefix`,
+///   `map_stream`, `restore_position`, `seek_before`, or `try`;
+/// * it, or any field, uses `map` or `try_map` with a closure which does not
+///   declare the type of its input; or
+/// * any of the types it reads do not implement `BinReadStream`, like
+///   [`FilePtr`](crate::FilePtr).
+///
+/// For other types, this trait can be implemented with an empty `impl` block,
+/// as long as [`read_options()`](BinRead::read_options) never seeks backwards
+/// except to rewind after an error.
+///
+/// # Examples
+///
+/// ```
+/// use binrw::{BinRead, BinReadStream};
+///
+/// #[derive(BinRead, Debug, PartialEq)]
+/// #[br(big)]
+/// struct Packet {
+///     kind: u8,
+///     #[br(pad_before = 1)]
+///     len: u16,
+///     #[br(count = len)]
+///     data: Vec<u8>,
+/// }
+///
+/// // `&[u8]` implements `Read` but not `Seek`
+/// let mut input: &[u8] = b"\x01\xff\x00\x02ab\x02";
+/// let packet = Packet::read_stream(&mut input).unwrap();
+/// assert_eq!(packet, Packet { kind: 1, len: 2, data: b"ab".to_vec() });
+/// assert_eq!(input, b"\x02");
+/// ```
+pub trait BinReadStream: BinRead {
+    /// Read `Self` from the reader using default arguments.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_stream<R: Read>(reader: &mut R) -> BinResult<Self>
+    where
+        Self: ReadEndian,
+        for<'a> Self::Args<'a>: Required,
+    {
+        Self::read_stream_args(reader, Self::Args::args())
+    }
+
+    /// Read `Self` from the reader using default arguments and assuming
+    /// big-endian byte order.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_stream_be<R: Read>(reader: &mut R) -> BinResult<Self>
+    where
+        for<'a> Self::Args<'a>: Required,
+    {
+        Self::read_stream_options(reader, Endian::Big, Self::Args::args())
+    }
+
+    /// Read `Self` from the reader using default arguments and assuming
+    /// little-endian byte order.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_stream_le<R: Read>(reader: &mut R) -> BinResult<Self>
+    where
+        for<'a> Self::Args<'a>: Required,
+    {
+        Self::read_stream_options(reader, Endian::Little, Self::Args::args())
+    }
+
+    /// Read `Self` from the reader using default arguments and assuming
+    /// native-endian byte order.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_stream_ne<R: Read>(reader: &mut R) -> BinResult<Self>
+    where
+        for<'a> Self::Args<'a>: Required,
+    {
+        Self::read_stream_options(reader, Endian::NATIVE, Self::Args::args())
+    }
+
+    /// Read `Self` from the reader using the given arguments.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_stream_args<R: Read>(reader: &mut R, args: Self::Args<'_>) -> BinResult<Self>
+    where
+        Self: ReadEndian,
+    {
+        Self::read_stream_options(reader, Endian::Little, args)
+    }
+
+    /// Read `Self` from the reader using the given [`Endian`] and arguments.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    #[inline]
+    fn read_stream_options<R: Read>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        Self::read_options(&mut Unrewindable::new(reader), endian, args)
+    }
+}
+
+/// A forward-only stream which lets failed reads try to rewind.
+///
+/// Reads rewind the stream to their starting position when they fail, which is
+/// impossible here. Instead of replacing the original error with a seek error,
+/// rewinding is allowed to succeed, after which the stream refuses to do
+/// anything else.
+struct Unrewindable<R> {
+    inner: NoSeek<R>,
+    rewound: bool,
+}
+
+impl<R> Unrewindable<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner: NoSeek::new(inner),
+            rewound: false,
+        }
+    }
+}
+
+fn rewound_error() -> io::Error {
+    io::Error::other("stream used after rewinding a failed read")
+}
+
+impl<R: Read> Read for Unrewindable<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.rewound {
+            return Err(rewound_error());
+        }
+        self.inner.read(buf)
+    }
+}
+
+impl<R> Seek for Unrewindable<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        if !self.rewound {
+            match self.inner.seek(pos) {
+                // `NoSeek` only rejects a seek from the start if it goes
+                // backwards
+                Err(_) if matches!(pos, SeekFrom::Start(_)) => self.rewound = true,
+                result => return result,
+            }
+        }
+
+        // Each read up the call stack rewinds again as the error propagates
+        match pos {
+            SeekFrom::Start(n) => Ok(n),
+            _ => Err(rewound_error()),
+        }
+    }
+}
+
+macro_rules! binread_stream_impl {
+    ($($ty:ty),* $(,)?) => {
+        $(impl BinReadStream for $ty {})*
+    };
+}
+
+binread_stream_impl!(
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    f32,
+    f64,
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    (),
+    U24,
+    U40,
+    U48,
+    U56,
+    I24,
+    I40,
+    I48,
+    I56,
+    NullString,
+    NullWideString,
+    VarInt,
+    VarUint,
+);
+
+impl<const N: usize> BinReadStream for FixedString<N> {}
+
+impl<const N: usize> BinReadStream for FixedWideString<N> {}
+
+impl<B> BinReadStream for Vec<B>
+where
+    B: BinReadStream + 'static,
+    for<'a> B::Args<'a>: Clone,
+{
+}
+
+impl<B, const N: usize> BinReadStream for [B; N]
+where
+    B: BinReadStream,
+    for<'a> B::Args<'a>: Clone,
+{
+}
+
+macro_rules! binread_stream_tuple_impl {
+    ($type1:ident $(, $types:ident)*) => {
+        #[allow(non_camel_case_types)]
+        impl<Args: Clone, $type1: for<'a> BinRead<Args<'a> = Args> + BinReadStream, $($types: for<'a> BinRead<Args<'a> = Args> + BinReadStream),*> BinReadStream for ($type1, $($types),*) {}
+
+        binread_stream_tuple_impl!($($types),*);
+    };
+
+    () => {};
+}
+
+binread_stream_tuple_impl!(
+    b1, b2, b3, b4, b5, b6, b7, b8, b9, b10, b11, b12, b13, b14, b15, b16, b17, b18, b19, b20, b21,
+    b22, b23, b24, b25, b26, b27, b28, b29, b30, b31, b32
+);
+
+impl<T: BinReadStream> BinReadStream for Box<T> {}
+
+impl<T: BinReadStream> BinReadStream for Option<T> {}
+
+impl<T> BinReadStream for PhantomData<T> {}
+
+impl<T: BinReadStream> BinReadStream for PosValue<T> {}
@@ -1,4 +1,5 @@
 use crate::{
+    __private::span_index,
     BinResult, BinWrite, Endian,
     io::{Seek, Write},
 };
@@ -120,6 +121,19 @@ where
     }
 }
 
+impl BinWrite for str {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.as_bytes().write_options(writer, endian, ())
+    }
+}
+
 impl<T> BinWrite for Vec<T>
 where
     T: BinWrite + 'static,
@@ -138,8 +152,10 @@ where
         } else if let Some(this) = <dyn Any>::downcast_ref::<Vec<i8>>(self) {
             writer.write_all(bytemuck::cast_slice(this.as_slice()))?;
         } else {
-            for item in self {
-                T::write_options(item, writer, endian, args.clone())?;
+            for (index, item) in self.iter().enumerate() {
+                span_index(writer, index, |writer| {
+                    T::write_options(item, writer, endian, args.clone())
+                })?;
             }
         }
 
@@ -0,0 +1,275 @@
+//! Zero-copy reading of values which borrow from an in-memory buffer.
+//!
+//! `&'data [u8]`, `&'data [u8; N]`, `&'data str`, and [`NullStr`] implement
+//! [`BinRead`] by borrowing their bytes directly from the buffer being read
+//! instead of copying them. The buffer is read with a [`SliceReader`], and
+//! because [`BinRead`] works with any reader, these types also take the
+//! [`BorrowSource`] of that `SliceReader` as an argument. A shared reference
+//! to a `SliceReader` is itself a reader, so the reader and the source share
+//! the same position.
+//!
+//! Borrowed values are sliced from the buffer at the current position of the
+//! `SliceReader`, and the reader they are read with is then moved forward past
+//! them. The reader can wrap the `SliceReader`, like the window created by the
+//! [`size`](crate::docs::attribute#size) directive, but it must not change the
+//! bytes being read, since borrowed values always come from the buffer.
+//!
+//! A derived type can contain borrowed fields by declaring a lifetime
+//! parameter and [importing](crate::docs::attribute#arguments) the
+//! `BorrowSource`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use binrw::{prelude::*, NullStr, borrowed::{BorrowSource, SliceReader}};
+//! #[derive(BinRead)]
+//! #[br(big, import(source: BorrowSource<'_, 'data>))]
+//! struct Record<'data> {
+//!     len: u8,
+//!     #[br(count = len, args { source })]
+//!     name: &'data str,
+//!     #[br(args { source })]
+//!     tag: &'data [u8; 2],
+//!     #[br(args { source })]
+//!     comment: NullStr<'data>,
+//! }
+//!
+//! let data = b"\x05helloOKall good\0";
+//! let reader = SliceReader::new(data);
+//! let record = Record::read_args(&mut &reader, (reader.source(),)).unwrap();
+//! assert_eq!(record.name, "hello");
+//! assert_eq!(record.tag, b"OK");
+//! assert_eq!(record.comment.to_string(), "all good");
+//!
+//! // The fields point into the original buffer
+//! assert!(core::ptr::eq(record.name.as_ptr(), &data[1]));
+//! ```
+
+use crate::{
+    __private::{not_enough_bytes, restore_position},
+    BinRead, BinResult, Endian, Error, NamedArgs,
+    io::{self, Cursor, Read, Seek, SeekFrom},
+    strings::NullStr,
+};
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, format};
+use core::cell::Cell;
+
+/// A reader over an in-memory buffer which read values can borrow from.
+///
+/// Reading and seeking work through a shared reference, so the same
+/// `SliceReader` can be used as the reader while its [`source()`](Self::source)
+/// is passed to borrowed values. See the [module documentation](self) for
+/// details.
+#[derive(Debug)]
+pub struct SliceReader<'data> {
+    data: &'data [u8],
+    pos: Cell<u64>,
+}
+
+impl<'data> SliceReader<'data> {
+    /// Creates a new reader at the start of `data`.
+    #[must_use]
+    pub const fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            pos: Cell::new(0),
+        }
+    }
+
+    /// Gets the buffer being read.
+    #[must_use]
+    pub const fn get_ref(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Returns the current position of the reader.
+    #[must_use]
+    pub fn position(&self) -> u64 {
+        self.pos.get()
+    }
+
+    /// Sets the position of the reader.
+    pub fn set_position(&self, pos: u64) {
+        self.pos.set(pos);
+    }
+
+    /// Returns the source to pass to borrowed values which are read from this
+    /// reader.
+    #[must_use]
+    pub fn source(&self) -> BorrowSource<'_, 'data> {
+        BorrowSource {
+            data: self.data,
+            pos: &self.pos,
+        }
+    }
+
+    fn with_cursor<T>(&self, f: impl FnOnce(&mut Cursor<&'data [u8]>) -> T) -> T {
+        let mut cursor = Cursor::new(self.data);
+        cursor.set_position(self.pos.get());
+        let result = f(&mut cursor);
+        self.pos.set(cursor.position());
+        result
+    }
+}
+
+impl Read for &SliceReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.with_cursor(|cursor| cursor.read(buf))
+    }
+}
+
+impl Seek for &SliceReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.with_cursor(|cursor| cursor.seek(pos))
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.pos.get())
+    }
+}
+
+impl Read for SliceReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+}
+
+impl Seek for SliceReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        (&*self).seek(pos)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.pos.get())
+    }
+}
+
+/// The buffer and position of a [`SliceReader`], which borrowed values are
+/// sliced from.
+#[derive(Clone, Copy, Debug)]
+pub struct BorrowSource<'a, 'data> {
+    data: &'data [u8],
+    pos: &'a Cell<u64>,
+}
+
+impl<'data> BorrowSource<'_, 'data> {
+    /// Returns the part of the buffer after the current position.
+    fn remaining(&self) -> BinResult<&'data [u8]> {
+        usize::try_from(self.pos.get())
+            .ok()
+            .and_then(|pos| self.data.get(pos..))
+            .ok_or_else(not_enough_bytes)
+    }
+}
+
+/// Named arguments for the [`BinRead::read_options()`] implementations of
+/// borrowed types with a fixed size or terminator.
+#[derive(Clone, Copy, NamedArgs)]
+pub struct BorrowArgs<'a, 'data> {
+    /// The source of the buffer to borrow from.
+    pub source: BorrowSource<'a, 'data>,
+}
+
+/// Named arguments for the [`BinRead::read_options()`] implementations of
+/// borrowed types whose length is given by a count.
+#[derive(Clone, Copy, NamedArgs)]
+pub struct BorrowCountArgs<'a, 'data> {
+    /// The source of the buffer to borrow from.
+    pub source: BorrowSource<'a, 'data>,
+    /// The number of bytes to borrow.
+    pub count: usize,
+}
+
+impl<'data> BinRead for &'data [u8] {
+    type Args<'a> = BorrowCountArgs<'a, 'data>;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        _: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        borrow_bytes(reader, args.source, args.count)
+    }
+}
+
+impl<'data, const N: usize> BinRead for &'data [u8; N] {
+    type Args<'a> = BorrowArgs<'a, 'data>;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        _: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        borrow_bytes(reader, args.source, N)
+            .map(|bytes| bytes.try_into().expect("borrowed slice has length N"))
+    }
+}
+
+impl<'data> BinRead for &'data str {
+    type Args<'a> = BorrowCountArgs<'a, 'data>;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        _: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let pos = reader.stream_position()?;
+        let bytes = borrow_bytes(reader, args.source, args.count)?;
+        core::str::from_utf8(bytes)
+            .map_err(|err| Error::Custom {
+                pos,
+                err: Box::new(err),
+            })
+            .map_err(restore_position(reader, pos))
+    }
+}
+
+impl<'data> BinRead for NullStr<'data> {
+    type Args<'a> = BorrowArgs<'a, 'data>;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        _: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let len = args
+            .source
+            .remaining()?
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or_else(not_enough_bytes)?;
+        let bytes = borrow_bytes(reader, args.source, len + 1)?;
+        Ok(NullStr(&bytes[..len]))
+    }
+}
+
+/// Borrows `len` bytes from `source` at its current position, and moves
+/// `reader` past them.
+fn borrow_bytes<'data, R: Read + Seek>(
+    reader: &mut R,
+    source: BorrowSource<'_, 'data>,
+    len: usize,
+) -> BinResult<&'data [u8]> {
+    let start = source.pos.get();
+    let bytes = source
+        .remaining()?
+        .get(..len)
+        .ok_or_else(not_enough_bytes)?;
+
+    // Seeking through the reader, instead of moving the source directly,
+    // keeps any window the reader applies in sync with the source
+    let pos = reader.stream_position()?;
+    let offset = i64::try_from(len).map_err(|_| not_enough_bytes())?;
+    reader.seek(SeekFrom::Current(offset))?;
+    if source.pos.get() != start + len as u64 {
+        return Err(restore_position(reader, pos)(Error::AssertFail {
+            pos,
+            message: format!(
+                "borrowed bytes at {pos:#x} were not read from the SliceReader they borrow from"
+            ),
+        }));
+    }
+
+    Ok(bytes)
+}
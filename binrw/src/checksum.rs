@@ -0,0 +1,355 @@
+//! Checksum algorithms for use with the
+//! [`checksum`](crate::docs::attribute#checksum) directive.
+//!
+//! Additional algorithms can be used by implementing [`Checksum`].
+
+use crate::io::{self, Read, Seek, SeekFrom, Write};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// A checksum algorithm.
+///
+/// An algorithm is created using its [`Default`] implementation, receives the
+/// covered bytes through one or more calls to [`update`](Self::update), and
+/// then produces the checksum value with [`finish`](Self::finish).
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{binrw, checksum::Checksum, io::Cursor, BinRead};
+/// #[derive(Default)]
+/// struct Xor(u8);
+///
+/// impl Checksum for Xor {
+///     type Output = u8;
+///
+///     fn update(&mut self, bytes: &[u8]) {
+///         self.0 = bytes.iter().fold(self.0, |acc, byte| acc ^ byte);
+///     }
+///
+///     fn finish(&self) -> Self::Output {
+///         self.0
+///     }
+/// }
+///
+/// #[binrw]
+/// struct Packet {
+///     a: u8,
+///     b: u8,
+///     #[brw(checksum(Xor, a..=b))]
+///     check: u8,
+/// }
+///
+/// # assert!(Packet::read_le(&mut Cursor::new(b"\x0f\xf0\xff")).is_ok());
+/// # assert!(Packet::read_le(&mut Cursor::new(b"\x0f\xf0\x00")).is_err());
+/// ```
+pub trait Checksum: Default {
+    /// The type of the checksum value.
+    type Output: Copy + PartialEq + core::fmt::Debug;
+
+    /// Adds bytes to the checksum.
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Returns the checksum of all bytes added so far.
+    fn finish(&self) -> Self::Output;
+}
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        // Lint: `i` is always < 256
+        #[allow(clippy::cast_possible_truncation)]
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 0 {
+                crc >> 1
+            } else {
+                (crc >> 1) ^ 0xedb8_8320
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+/// The CRC-32 checksum used by zlib, PNG, and ZIP (CRC-32/ISO-HDLC).
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32(u32);
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self(!0)
+    }
+}
+
+impl Checksum for Crc32 {
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = CRC32_TABLE[usize::from(self.0.to_le_bytes()[0] ^ byte)] ^ (self.0 >> 8);
+        }
+    }
+
+    fn finish(&self) -> Self::Output {
+        !self.0
+    }
+}
+
+/// The CRC-16 checksum used by ARC and LHA (CRC-16/ARC).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Crc16(u16);
+
+impl Checksum for Crc16 {
+    type Output = u16;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u16::from(byte);
+            for _ in 0..8 {
+                self.0 = if self.0 & 1 == 0 {
+                    self.0 >> 1
+                } else {
+                    (self.0 >> 1) ^ 0xa001
+                };
+            }
+        }
+    }
+
+    fn finish(&self) -> Self::Output {
+        self.0
+    }
+}
+
+/// The Adler-32 checksum used by zlib.
+#[derive(Clone, Copy, Debug)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Self { a: 1, b: 0 }
+    }
+}
+
+impl Checksum for Adler32 {
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        const MOD: u32 = 65521;
+        // The largest number of bytes which can be summed before `b` could
+        // overflow
+        const CHUNK: usize = 5552;
+        for chunk in bytes.chunks(CHUNK) {
+            for &byte in chunk {
+                self.a += u32::from(byte);
+                self.b += self.a;
+            }
+            self.a %= MOD;
+            self.b %= MOD;
+        }
+    }
+
+    fn finish(&self) -> Self::Output {
+        (self.b << 16) | self.a
+    }
+}
+
+macro_rules! sum_impl {
+    ($(#[$meta:meta])* $name:ident, $ty:ty) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default)]
+        pub struct $name($ty);
+
+        impl Checksum for $name {
+            type Output = $ty;
+
+            fn update(&mut self, bytes: &[u8]) {
+                for &byte in bytes {
+                    self.0 = self.0.wrapping_add(<$ty>::from(byte));
+                }
+            }
+
+            fn finish(&self) -> Self::Output {
+                self.0
+            }
+        }
+    };
+}
+
+sum_impl!(
+    /// The sum of all bytes, wrapping on overflow.
+    Sum8,
+    u8
+);
+sum_impl!(
+    /// The sum of all bytes as a 16-bit integer, wrapping on overflow.
+    Sum16,
+    u16
+);
+sum_impl!(
+    /// The sum of all bytes as a 32-bit integer, wrapping on overflow.
+    Sum32,
+    u32
+);
+
+/// A stream wrapper which adds all bytes read from or written to the inner
+/// stream to a [`Checksum`].
+///
+/// Seeking is passed through to the inner stream without affecting the
+/// checksum.
+pub struct ChecksumStream<'a, T, C> {
+    inner: &'a mut T,
+    checksum: &'a mut C,
+}
+
+impl<'a, T, C: Checksum> ChecksumStream<'a, T, C> {
+    /// Creates a new wrapper which adds bytes from the given stream to the
+    /// given checksum.
+    pub fn new(inner: &'a mut T, checksum: &'a mut C) -> Self {
+        Self { inner, checksum }
+    }
+}
+
+impl<T: Read, C: Checksum> Read for ChecksumStream<'_, T, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.checksum.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<T: Write, C: Checksum> Write for ChecksumStream<'_, T, C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.checksum.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T: Seek, C> Seek for ChecksumStream<'_, T, C> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        self.inner.stream_position()
+    }
+}
+
+/// A stream wrapper which keeps a copy of the bytes written to the inner
+/// stream, so that a [`Checksum`] can be computed over a range of stream
+/// positions once writing is done.
+///
+/// Only bytes written at or after the position of the inner stream when the
+/// wrapper was created are kept.
+pub struct ChecksumRecorder<'a, T> {
+    inner: &'a mut T,
+    start: u64,
+    pos: u64,
+    data: Vec<u8>,
+    written: Vec<bool>,
+}
+
+impl<'a, T: Seek> ChecksumRecorder<'a, T> {
+    /// Creates a new wrapper which records bytes written to the given stream
+    /// from its current position onwards.
+    ///
+    /// # Errors
+    ///
+    /// If the position of the stream cannot be retrieved, an error is
+    /// returned.
+    pub fn new(inner: &'a mut T) -> io::Result<Self> {
+        let start = inner.stream_position()?;
+        Ok(Self {
+            inner,
+            start,
+            pos: start,
+            data: Vec::new(),
+            written: Vec::new(),
+        })
+    }
+}
+
+impl<T> ChecksumRecorder<'_, T> {
+    /// Adds the bytes written to the given range of stream positions to a
+    /// checksum.
+    ///
+    /// Returns `false` without changing the checksum if any byte in the range
+    /// was not written through this wrapper.
+    pub fn update<C: Checksum>(&self, checksum: &mut C, range: Range<u64>) -> bool {
+        let offset = |pos: u64| {
+            pos.checked_sub(self.start)
+                .and_then(|offset| usize::try_from(offset).ok())
+        };
+
+        let (Some(start), Some(end)) = (offset(range.start), offset(range.end)) else {
+            return false;
+        };
+
+        match self.written.get(start..end) {
+            Some(written) if written.iter().all(|written| *written) => {
+                checksum.update(&self.data[start..end]);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn record(&mut self, bytes: &[u8]) {
+        let pos = self.pos;
+        self.pos += bytes.len() as u64;
+
+        // Bytes before the starting position are not kept
+        let skip = usize::try_from(self.start.saturating_sub(pos)).unwrap_or(usize::MAX);
+        let Some(bytes) = bytes.get(skip..) else {
+            return;
+        };
+        let Ok(offset) = usize::try_from(pos.saturating_sub(self.start)) else {
+            return;
+        };
+
+        let end = offset + bytes.len();
+        if self.data.len() < end {
+            self.data.resize(end, 0);
+            self.written.resize(end, false);
+        }
+        self.data[offset..end].copy_from_slice(bytes);
+        self.written[offset..end].fill(true);
+    }
+}
+
+impl<T: Write> Write for ChecksumRecorder<'_, T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.record(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T: Seek> Seek for ChecksumRecorder<'_, T> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.pos)
+    }
+}
@@ -0,0 +1,399 @@
+//! Text encodings for reading and writing strings.
+//!
+//! An [`Encoding`] converts between bytes and Rust strings. It is passed as
+//! the `encoding` argument of [`EncodedString`](crate::EncodedString), which
+//! holds null-terminated text decoded into a [`String`], and of
+//! [`FixedString`](crate::FixedString) and
+//! [`FixedWideString`](crate::FixedWideString), which then hold the decoded
+//! text:
+//!
+//! ```
+//! # use binrw::{prelude::*, io::Cursor, EncodedString, encoding::{Latin1, Utf16Bom}};
+//! #[binrw]
+//! #[brw(little)]
+//! # #[derive(Debug, PartialEq)]
+//! struct Save {
+//!     #[brw(args { encoding: &Latin1 })]
+//!     player: EncodedString,
+//!     #[brw(args { encoding: &Utf16Bom })]
+//!     note: EncodedString,
+//! }
+//!
+//! // The note is big-endian, but is written back in the byte order of `Save`
+//! let data = b"Zo\xeb\0\xfe\xff\0H\0i\0\0";
+//! let save = Save::read(&mut Cursor::new(data)).unwrap();
+//! assert_eq!(save.player.as_str(), "Zoë");
+//! assert_eq!(save.note.as_str(), "Hi");
+//!
+//! let mut out = Cursor::new(Vec::new());
+//! save.write(&mut out).unwrap();
+//! assert_eq!(out.into_inner(), b"Zo\xeb\0\xff\xfeH\0i\0\0\0");
+//! ```
+//!
+//! Other types can use an encoding directly with [`Encoding::decode`] and
+//! [`Encoding::encode`].
+//!
+//! The `codepages` feature implements `Encoding` for every
+//! [`encoding_rs::Encoding`], which includes legacy codepages like Shift-JIS
+//! and Windows-1252:
+//!
+//! ```
+//! # #[cfg(not(feature = "codepages"))] fn main() {}
+//! # #[cfg(feature = "codepages")]
+//! # fn main() {
+//! # use binrw::{prelude::*, io::Cursor, EncodedString, encoding::encoding_rs::SHIFT_JIS};
+//! #[derive(BinRead)]
+//! struct Dialogue {
+//!     #[br(args { encoding: &SHIFT_JIS })]
+//!     line: EncodedString,
+//! }
+//!
+//! let dialogue = Dialogue::read_le(&mut Cursor::new(b"\x82\xb1\x82\xf1\0")).unwrap();
+//! assert_eq!(dialogue.line.as_str(), "こん");
+//! # }
+//! ```
+
+use crate::Endian;
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+#[cfg(feature = "codepages")]
+#[cfg_attr(all(doc, nightly), doc(cfg(feature = "codepages")))]
+pub use encoding_rs;
+
+/// A text encoding.
+///
+/// Encodings are passed by shared reference, so they must be [`Sync`] to be
+/// used by asynchronous readers and writers.
+pub trait Encoding: Sync {
+    /// The number of bytes in one code unit.
+    ///
+    /// A null terminator in this encoding is one code unit where every byte is
+    /// zero.
+    fn unit_len(&self) -> usize {
+        1
+    }
+
+    /// Decodes `bytes` into a string.
+    ///
+    /// The byte order is used by encodings which have multi-byte code units
+    /// and do not specify their own byte order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not valid in this encoding.
+    fn decode(&self, bytes: &[u8], endian: Endian) -> Result<String, EncodingError>;
+
+    /// Encodes `text` into bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `text` contains a character which cannot be
+    /// represented in this encoding.
+    fn encode(&self, text: &str, endian: Endian) -> Result<Vec<u8>, EncodingError>;
+}
+
+/// An error which occurred when decoding or encoding text.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EncodingError {
+    /// The offset of the invalid data, in bytes, from the start of the input.
+    pub offset: usize,
+    /// A description of the error.
+    pub message: String,
+}
+
+impl EncodingError {
+    fn new(offset: usize, message: impl Into<String>) -> Self {
+        Self {
+            offset,
+            message: message.into(),
+        }
+    }
+
+    fn unencodable(offset: usize, c: char, encoding: &str) -> Self {
+        Self::new(offset, format!("{c:?} cannot be encoded as {encoding}"))
+    }
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodingError {}
+
+/// UTF-8.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Utf8;
+
+impl Encoding for Utf8 {
+    fn decode(&self, bytes: &[u8], _: Endian) -> Result<String, EncodingError> {
+        core::str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(|err| EncodingError::new(err.valid_up_to(), "invalid UTF-8"))
+    }
+
+    fn encode(&self, text: &str, _: Endian) -> Result<Vec<u8>, EncodingError> {
+        Ok(text.as_bytes().to_vec())
+    }
+}
+
+/// 7-bit ASCII. Bytes above `0x7f` are rejected instead of being replaced.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Ascii;
+
+impl Encoding for Ascii {
+    fn decode(&self, bytes: &[u8], _: Endian) -> Result<String, EncodingError> {
+        match bytes.iter().position(|byte| !byte.is_ascii()) {
+            Some(offset) => Err(EncodingError::new(
+                offset,
+                format!("invalid ASCII byte {:#04x}", bytes[offset]),
+            )),
+            None => Ok(bytes.iter().copied().map(char::from).collect()),
+        }
+    }
+
+    fn encode(&self, text: &str, _: Endian) -> Result<Vec<u8>, EncodingError> {
+        match text.char_indices().find(|(_, c)| !c.is_ascii()) {
+            Some((offset, c)) => Err(EncodingError::unencodable(offset, c, "ASCII")),
+            None => Ok(text.as_bytes().to_vec()),
+        }
+    }
+}
+
+/// ISO-8859-1, where each byte is the Unicode code point with the same value.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Latin1;
+
+impl Encoding for Latin1 {
+    fn decode(&self, bytes: &[u8], _: Endian) -> Result<String, EncodingError> {
+        Ok(bytes.iter().copied().map(char::from).collect())
+    }
+
+    fn encode(&self, text: &str, _: Endian) -> Result<Vec<u8>, EncodingError> {
+        text.char_indices()
+            .map(|(offset, c)| {
+                u8::try_from(c).map_err(|_| EncodingError::unencodable(offset, c, "Latin-1"))
+            })
+            .collect()
+    }
+}
+
+/// Little-endian UTF-16.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Utf16Le;
+
+impl Encoding for Utf16Le {
+    fn unit_len(&self) -> usize {
+        2
+    }
+
+    fn decode(&self, bytes: &[u8], _: Endian) -> Result<String, EncodingError> {
+        decode_utf16(bytes, Endian::Little, 0)
+    }
+
+    fn encode(&self, text: &str, _: Endian) -> Result<Vec<u8>, EncodingError> {
+        Ok(encode_utf16(text, Endian::Little, Vec::new()))
+    }
+}
+
+/// Big-endian UTF-16.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Utf16Be;
+
+impl Encoding for Utf16Be {
+    fn unit_len(&self) -> usize {
+        2
+    }
+
+    fn decode(&self, bytes: &[u8], _: Endian) -> Result<String, EncodingError> {
+        decode_utf16(bytes, Endian::Big, 0)
+    }
+
+    fn encode(&self, text: &str, _: Endian) -> Result<Vec<u8>, EncodingError> {
+        Ok(encode_utf16(text, Endian::Big, Vec::new()))
+    }
+}
+
+/// UTF-16 which starts with a [byte order mark].
+///
+/// When decoding, the byte order is detected from the byte order mark. If
+/// there is no byte order mark, the given byte order is used instead. When
+/// encoding, a byte order mark for the given byte order is written first.
+///
+/// [byte order mark]: https://en.wikipedia.org/wiki/Byte_order_mark
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Utf16Bom;
+
+impl Encoding for Utf16Bom {
+    fn unit_len(&self) -> usize {
+        2
+    }
+
+    fn decode(&self, bytes: &[u8], endian: Endian) -> Result<String, EncodingError> {
+        match bytes {
+            [a, b, rest @ ..] => match Endian::from_utf16_bom_bytes([*a, *b]) {
+                Ok(endian) => decode_utf16(rest, endian, 2),
+                Err(_) => decode_utf16(bytes, endian, 0),
+            },
+            _ => decode_utf16(bytes, endian, 0),
+        }
+    }
+
+    fn encode(&self, text: &str, endian: Endian) -> Result<Vec<u8>, EncodingError> {
+        Ok(encode_utf16(
+            text,
+            endian,
+            endian.into_utf16_bom_bytes().to_vec(),
+        ))
+    }
+}
+
+/// UTF-32 in the given byte order.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Utf32;
+
+impl Encoding for Utf32 {
+    fn unit_len(&self) -> usize {
+        4
+    }
+
+    fn decode(&self, bytes: &[u8], endian: Endian) -> Result<String, EncodingError> {
+        check_unit_len(bytes, 4, "UTF-32")?;
+        bytes
+            .chunks_exact(4)
+            .enumerate()
+            .map(|(index, unit)| {
+                let unit = [unit[0], unit[1], unit[2], unit[3]];
+                let code = match endian {
+                    Endian::Big => u32::from_be_bytes(unit),
+                    Endian::Little => u32::from_le_bytes(unit),
+                };
+                char::from_u32(code).ok_or_else(|| {
+                    EncodingError::new(index * 4, format!("invalid UTF-32 code point {code:#x}"))
+                })
+            })
+            .collect()
+    }
+
+    fn encode(&self, text: &str, endian: Endian) -> Result<Vec<u8>, EncodingError> {
+        Ok(text
+            .chars()
+            .flat_map(|c| match endian {
+                Endian::Big => u32::from(c).to_be_bytes(),
+                Endian::Little => u32::from(c).to_le_bytes(),
+            })
+            .collect())
+    }
+}
+
+fn check_unit_len(bytes: &[u8], unit_len: usize, encoding: &str) -> Result<(), EncodingError> {
+    let extra = bytes.len() % unit_len;
+    if extra == 0 {
+        Ok(())
+    } else {
+        Err(EncodingError::new(
+            bytes.len() - extra,
+            format!("incomplete {encoding} code unit"),
+        ))
+    }
+}
+
+/// Decodes UTF-16 which starts `offset` bytes into the original input.
+fn decode_utf16(bytes: &[u8], endian: Endian, offset: usize) -> Result<String, EncodingError> {
+    check_unit_len(bytes, 2, "UTF-16").map_err(|err| EncodingError {
+        offset: offset + err.offset,
+        ..err
+    })?;
+    let units = bytes.chunks_exact(2).map(|unit| match endian {
+        Endian::Big => u16::from_be_bytes([unit[0], unit[1]]),
+        Endian::Little => u16::from_le_bytes([unit[0], unit[1]]),
+    });
+
+    let mut text = String::with_capacity(bytes.len() / 2);
+    let mut unit_offset = offset;
+    for c in char::decode_utf16(units) {
+        let c = c.map_err(|_| EncodingError::new(unit_offset, "unpaired UTF-16 surrogate"))?;
+        unit_offset += c.len_utf16() * 2;
+        text.push(c);
+    }
+    Ok(text)
+}
+
+fn encode_utf16(text: &str, endian: Endian, mut out: Vec<u8>) -> Vec<u8> {
+    out.reserve(text.len() * 2);
+    for unit in text.encode_utf16() {
+        out.extend_from_slice(&match endian {
+            Endian::Big => unit.to_be_bytes(),
+            Endian::Little => unit.to_le_bytes(),
+        });
+    }
+    out
+}
+
+// `encoding_rs` follows the WHATWG Encoding Standard, which decodes UTF-16
+// but never encodes it, so encoding to UTF-16 is reported as an error instead
+// of silently producing UTF-8
+#[cfg(feature = "codepages")]
+#[cfg_attr(all(doc, nightly), doc(cfg(feature = "codepages")))]
+impl Encoding for &'static encoding_rs::Encoding {
+    fn unit_len(&self) -> usize {
+        if *self == encoding_rs::UTF_16LE || *self == encoding_rs::UTF_16BE {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn decode(&self, bytes: &[u8], _: Endian) -> Result<String, EncodingError> {
+        let mut decoder = self.new_decoder_without_bom_handling();
+        let mut text = String::with_capacity(
+            decoder
+                .max_utf8_buffer_length_without_replacement(bytes.len())
+                .unwrap_or_default(),
+        );
+        let (result, read) = decoder.decode_to_string_without_replacement(bytes, &mut text, true);
+        match result {
+            encoding_rs::DecoderResult::InputEmpty => Ok(text),
+            encoding_rs::DecoderResult::Malformed(len, consumed) => Err(EncodingError::new(
+                read.saturating_sub(usize::from(len) + usize::from(consumed)),
+                format!("invalid {} data", self.name()),
+            )),
+            encoding_rs::DecoderResult::OutputFull => {
+                unreachable!("the buffer is large enough for the whole input")
+            }
+        }
+    }
+
+    fn encode(&self, text: &str, _: Endian) -> Result<Vec<u8>, EncodingError> {
+        if self.output_encoding() != *self {
+            return Err(EncodingError::new(
+                0,
+                format!("text cannot be encoded as {}", self.name()),
+            ));
+        }
+
+        let mut encoder = self.new_encoder();
+        let mut bytes = Vec::with_capacity(
+            encoder
+                .max_buffer_length_from_utf8_without_replacement(text.len())
+                .unwrap_or_default(),
+        );
+        let (result, read) =
+            encoder.encode_from_utf8_to_vec_without_replacement(text, &mut bytes, true);
+        match result {
+            encoding_rs::EncoderResult::InputEmpty => Ok(bytes),
+            encoding_rs::EncoderResult::Unmappable(c) => Err(EncodingError::unencodable(
+                read - c.len_utf8(),
+                c,
+                self.name(),
+            )),
+            encoding_rs::EncoderResult::OutputFull => {
+                unreachable!("the buffer is large enough for the whole input")
+            }
+        }
+    }
+}
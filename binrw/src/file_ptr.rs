@@ -9,7 +9,8 @@
 //! after the offset is read. In these cases, it is faster to read the offset
 //! table into a collection (e.g. `Vec<u32>`) and then either pass it to
 //! [`parse_from_iter`] or write a function that is called to lazily load values
-//! as needed.
+//! as needed. Alternatively, a collection of [`LazyPtr`] only reads the
+//! offsets, and each pointed-to value is read when it is first accessed.
 //!
 //! ## Using `parse_from_iter` to read an offset table
 //!
@@ -130,14 +131,26 @@
 
 use crate::NamedArgs;
 use crate::{
-    BinRead, BinResult, Endian,
-    io::{Read, Seek, SeekFrom},
+    __private::span_pointee,
+    BinRead, BinResult, BinWrite, Endian, Error,
+    io::{Read, Seek, SeekFrom, Write},
 };
+use alloc::format;
+use core::cell::OnceCell;
 use core::num::{
     NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroU8, NonZeroU16, NonZeroU32,
     NonZeroU64, NonZeroU128,
 };
 use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(feature = "async")]
+use {
+    crate::{
+        __private::{seek_async, stream_position_async},
+        asynchronous::{AsyncSeek, AsyncWrite, BinWriteAsync},
+    },
+    core::future::Future,
+};
 
 /// A type alias for [`FilePtr`] with 8-bit offsets.
 pub type FilePtr8<T> = FilePtr<u8, T>;
@@ -169,6 +182,12 @@ pub type NonZeroFilePtr128<T> = FilePtr<NonZeroU128, T>;
 /// [offset](crate::docs::attribute#offset) directive can be used to adjust the
 /// offset before the pointed-to value is read.
 ///
+/// Writing a `FilePtr` requires a [`PointeeQueue`], which is only available
+/// while writing an object with [`write_with_pointees`] or its asynchronous
+/// counterpart. The pointed-to value
+/// is appended to the end of the stream once the enclosing object has been
+/// written, and the pointer is written with its real offset.
+///
 /// `FilePtr` is not efficient when reading offset tables; see the
 /// [module documentation](binrw::file_ptr) for more information.
 ///
@@ -195,7 +214,7 @@ pub type NonZeroFilePtr128<T> = FilePtr<NonZeroU128, T>;
 ///           [pointer]           [value]
 /// 00000000: 0000 0008 0000 0000 ff                   ............
 /// ```
-#[derive(Debug, Eq)]
+#[derive(Clone, Debug, Eq)]
 pub struct FilePtr<Ptr: IntoSeekFrom, T> {
     /// The raw offset to the value.
     pub ptr: Ptr,
@@ -222,6 +241,74 @@ where
     }
 }
 
+impl<Ptr, Value> BinWrite for FilePtr<Ptr, Value>
+where
+    Ptr: for<'a> BinWrite<Args<'a> = ()> + IntoSeekFrom + TryFromOffset,
+    Value: BinWrite,
+    for<'a> Value::Args<'a>: Clone,
+{
+    type Args<'a> = FilePtrWriteArgs<'a, Value::Args<'a>>;
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let FilePtrWriteArgs {
+            queue,
+            offset,
+            align,
+            inner,
+        } = args;
+
+        if !queue.is_placing() {
+            queue.queue();
+            return self.ptr.write_options(writer, endian, ());
+        }
+
+        let ptr_pos = writer.stream_position()?;
+        let end = writer.seek(SeekFrom::End(0))?;
+        let padding = pointee_padding(end, align);
+        crate::__private::write_zeroes(writer, padding)?;
+        let pos = end + padding;
+
+        queue.write(writer, pos, |writer| {
+            self.value.write_options(writer, endian, inner.clone())
+        })?;
+
+        let ptr = pointer_to::<Ptr>(pos, offset, ptr_pos)?;
+        writer.seek(SeekFrom::Start(ptr_pos))?;
+        ptr.write_options(writer, endian, ())
+    }
+}
+
+/// Returns the number of bytes needed to align a pointed-to value written at
+/// `end`.
+pub(crate) fn pointee_padding(end: u64, align: u64) -> u64 {
+    match end % align.max(1) {
+        0 => 0,
+        rem => align - rem,
+    }
+}
+
+/// Returns the pointer to a value written at `pos`, for a pointer written at
+/// `ptr_pos`.
+pub(crate) fn pointer_to<Ptr: TryFromOffset>(
+    pos: u64,
+    offset: u64,
+    ptr_pos: u64,
+) -> BinResult<Ptr> {
+    let relative = i128::from(pos) - i128::from(offset);
+    Ptr::try_from_offset(relative).ok_or_else(|| Error::AssertFail {
+        pos: ptr_pos,
+        message: format!(
+            "file pointer offset {relative} is out of range for {}",
+            core::any::type_name::<Ptr>()
+        ),
+    })
+}
+
 impl<Ptr, Value> FilePtr<Ptr, Value>
 where
     Ptr: IntoSeekFrom,
@@ -337,7 +424,7 @@ where
         let before = reader.stream_position()?;
         reader.seek(SeekFrom::Start(relative_to))?;
         reader.seek(ptr.into_seek_from()?)?;
-        let value = parser(reader, endian, args.inner);
+        let value = span_pointee(reader, |reader| parser(reader, endian, args.inner));
         reader.seek(SeekFrom::Start(before))?;
         value
     }
@@ -412,6 +499,224 @@ where
     }
 }
 
+#[cfg(feature = "serde")]
+impl<Ptr, Value> ::serde::Serialize for FilePtr<Ptr, Value>
+where
+    Ptr: IntoSeekFrom + ::serde::Serialize,
+    Value: ::serde::Serialize,
+{
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::serde::serialize_pair(
+            serializer,
+            "FilePtr",
+            &["ptr", "value"],
+            &self.ptr,
+            &self.value,
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Ptr, Value> ::serde::Deserialize<'de> for FilePtr<Ptr, Value>
+where
+    Ptr: IntoSeekFrom + ::serde::Deserialize<'de>,
+    Value: ::serde::Deserialize<'de>,
+{
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (ptr, value) =
+            crate::serde::deserialize_pair(deserializer, "FilePtr", &["ptr", "value"])?;
+        Ok(Self { ptr, value })
+    }
+}
+
+/// A type alias for [`LazyPtr`] with 16-bit offsets.
+pub type LazyPtr16<T> = LazyPtr<u16, T>;
+/// A type alias for [`LazyPtr`] with 32-bit offsets.
+pub type LazyPtr32<T> = LazyPtr<u32, T>;
+/// A type alias for [`LazyPtr`] with 64-bit offsets.
+pub type LazyPtr64<T> = LazyPtr<u64, T>;
+
+/// A wrapper type which represents a layer of indirection within a file, whose
+/// pointed-to value is only read when it is first accessed.
+///
+/// Reading a `LazyPtr` reads the offset and records the absolute position of
+/// the pointed-to value along with the endianness and arguments needed to
+/// read it, without seeking. The value is read by [`get`](Self::get), which
+/// must be given a reader for the same data, and is cached after it is read
+/// successfully.
+///
+/// The arguments for the pointed-to value are stored in the `LazyPtr`, so
+/// they cannot borrow from the enclosing object.
+///
+/// When writing, `LazyPtr` writes its `ptr` unchanged. The pointed-to value
+/// is never written.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{prelude::*, io::Cursor, LazyPtr};
+/// #[derive(BinRead)]
+/// #[br(big)]
+/// struct Index {
+///     count: u8,
+///     #[br(count = count)]
+///     entries: Vec<LazyPtr<u8, u16>>,
+/// }
+///
+/// let mut data = Cursor::new(b"\x02\x03\x05\x00\x01\xff\xff");
+/// let index = Index::read(&mut data).unwrap();
+/// assert_eq!(index.entries[1].pos(), 5);
+/// assert_eq!(index.entries[1].get_cached(), None);
+/// assert_eq!(*index.entries[1].get(&mut data).unwrap(), 0xffff);
+/// assert_eq!(index.entries[1].get_cached(), Some(&0xffff));
+/// ```
+pub struct LazyPtr<Ptr: IntoSeekFrom, Value: BinRead> {
+    /// The raw offset to the value.
+    pub ptr: Ptr,
+    pos: u64,
+    endian: Endian,
+    args: Value::Args<'static>,
+    value: OnceCell<Value>,
+}
+
+impl<Ptr, Value> LazyPtr<Ptr, Value>
+where
+    Ptr: IntoSeekFrom,
+    Value: BinRead,
+{
+    /// Returns the absolute position of the pointed-to value.
+    pub fn pos(&self) -> u64 {
+        self.pos
+    }
+
+    /// Returns the pointed-to value, reading it from the given reader if it
+    /// has not been read yet.
+    ///
+    /// The position of the reader is restored after reading.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned,
+    /// and the value will be read again on the next call.
+    pub fn get<R: Read + Seek>(&self, reader: &mut R) -> BinResult<&Value>
+    where
+        Value::Args<'static>: Clone,
+    {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+
+        let before = reader.stream_position()?;
+        reader.seek(SeekFrom::Start(self.pos))?;
+        let value = span_pointee(reader, |reader| {
+            Value::read_options(reader, self.endian, self.args.clone())
+        });
+        reader.seek(SeekFrom::Start(before))?;
+        let value = value?;
+        Ok(self.value.get_or_init(|| value))
+    }
+
+    /// Returns the pointed-to value if it has already been read.
+    pub fn get_cached(&self) -> Option<&Value> {
+        self.value.get()
+    }
+
+    /// Consumes this object, returning the pointed-to value if it has already
+    /// been read.
+    pub fn into_inner(self) -> Option<Value> {
+        self.value.into_inner()
+    }
+}
+
+impl<Ptr, Value, Args> BinRead for LazyPtr<Ptr, Value>
+where
+    Ptr: for<'a> BinRead<Args<'a> = ()> + IntoSeekFrom,
+    Value: for<'a> BinRead<Args<'a> = Args>,
+{
+    type Args<'a> = FilePtrArgs<Args>;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let ptr = Ptr::read_options(reader, endian, ())?;
+        let pos = match ptr.into_seek_from()? {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::Current(offset) => args.offset.checked_add_signed(offset),
+            seek @ SeekFrom::End(_) => {
+                let before = reader.stream_position()?;
+                let pos = reader.seek(seek)?;
+                reader.seek(SeekFrom::Start(before))?;
+                Some(pos)
+            }
+        }
+        .ok_or_else(|| {
+            Error::Io(crate::io::Error::new(
+                crate::io::ErrorKind::InvalidInput,
+                "file pointer offset out of range",
+            ))
+        })?;
+
+        Ok(Self {
+            ptr,
+            pos,
+            endian,
+            args: args.inner,
+            value: OnceCell::new(),
+        })
+    }
+}
+
+impl<Ptr, Value> BinWrite for LazyPtr<Ptr, Value>
+where
+    Ptr: for<'a> BinWrite<Args<'a> = ()> + IntoSeekFrom,
+    Value: BinRead,
+{
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.ptr.write_options(writer, endian, ())
+    }
+}
+
+impl<Ptr, Value> Clone for LazyPtr<Ptr, Value>
+where
+    Ptr: IntoSeekFrom,
+    Value: BinRead + Clone,
+    Value::Args<'static>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            ptr: self.ptr,
+            pos: self.pos,
+            endian: self.endian,
+            args: self.args.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<Ptr, Value> core::fmt::Debug for LazyPtr<Ptr, Value>
+where
+    Ptr: IntoSeekFrom + core::fmt::Debug,
+    Value: BinRead + core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LazyPtr")
+            .field("ptr", &self.ptr)
+            .field("pos", &self.pos)
+            .field("endian", &self.endian)
+            .field("value", &self.value.get())
+            .finish_non_exhaustive()
+    }
+}
+
 /// Creates a parser that reads a collection of values from an iterator of
 /// file offsets using the [`BinRead`] implementation of `Value`.
 ///
@@ -605,6 +910,53 @@ impl_into_seek_from_for_non_zero!(
     NonZeroU8
 );
 
+/// A trait to convert an offset relative to
+/// [`FilePtrWriteArgs::offset`] back into a pointer value.
+pub trait TryFromOffset: Sized {
+    /// Converts the offset, returning `None` if it cannot be represented by
+    /// this type.
+    fn try_from_offset(offset: i128) -> Option<Self>;
+}
+
+macro_rules! impl_try_from_offset {
+    ($($t:ty),*) => {
+        $(
+            impl TryFromOffset for $t {
+                fn try_from_offset(offset: i128) -> Option<Self> {
+                    TryInto::try_into(offset).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_offset!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+macro_rules! impl_try_from_offset_for_non_zero {
+    ($($t:ty),*) => {
+        $(
+            impl TryFromOffset for $t {
+                fn try_from_offset(offset: i128) -> Option<Self> {
+                    TryFromOffset::try_from_offset(offset).and_then(Self::new)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_offset_for_non_zero!(
+    NonZeroI128,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI8,
+    NonZeroU128,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU8
+);
+
 /// Named arguments for the [`BinRead::read_options()`] implementation of [`FilePtr`].
 ///
 /// The `inner` field can be omitted completely if the inner type doesn’t
@@ -620,3 +972,245 @@ pub struct FilePtrArgs<Inner> {
     #[named_args(try_optional)]
     pub inner: Inner,
 }
+
+/// Named arguments for the [`BinWrite::write_options()`] implementation of
+/// [`FilePtr`].
+///
+/// The `inner` field can be omitted completely if the inner type doesn’t
+/// require arguments, in which case a default value will be used.
+#[derive(Clone, NamedArgs)]
+pub struct FilePtrWriteArgs<'a, Inner> {
+    /// The queue of the [`write_with_pointees`] call which writes the
+    /// pointer.
+    pub queue: &'a PointeeQueue,
+
+    /// An absolute offset subtracted from the position of the pointed-to
+    /// value to get the value of the [`FilePtr::ptr`](crate::FilePtr::ptr)
+    /// offset.
+    #[named_args(default = 0)]
+    pub offset: u64,
+
+    /// The alignment of the position where the pointed-to value is written.
+    #[named_args(default = 1)]
+    pub align: u64,
+
+    /// The [arguments](crate::BinWrite::Args) for the inner type.
+    #[named_args(try_optional)]
+    pub inner: Inner,
+}
+
+/// Writes a value along with the values pointed to by any [`FilePtr`] objects
+/// inside of it.
+///
+/// The value is written twice. The first time, each `FilePtr` writes its
+/// current [`ptr`](FilePtr::ptr) as a placeholder. The second time, each
+/// `FilePtr` appends its pointed-to value to the end of the stream and
+/// replaces the placeholder with the real offset. Values pointed to by
+/// pointers inside of other pointed-to values are written directly after
+/// them in the same way. Since the value is only borrowed, writing it must
+/// produce the same bytes each time.
+///
+/// The arguments for the value are created by calling `args` with the queue
+/// that the `FilePtr` objects must receive through their
+/// [`queue`](FilePtrWriteArgs::queue) argument. Afterwards, the stream is
+/// positioned at its end.
+///
+/// # Errors
+///
+/// If writing fails, or if the offset of a value cannot be represented by its
+/// pointer type, an [`Error`](crate::Error) variant will be returned.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{binwrite, Endian, FilePtr32, io::Cursor, file_ptr::{PointeeQueue, write_with_pointees}};
+/// #[binwrite]
+/// #[bw(big, import(queue: &PointeeQueue))]
+/// struct Header {
+///     #[bw(args { queue })]
+///     name: FilePtr32<u16>,
+///     #[bw(args { queue, align: 4 })]
+///     data: FilePtr32<u8>,
+/// }
+///
+/// let header = Header {
+///     name: FilePtr32 { ptr: 0, value: 0x1234 },
+///     data: FilePtr32 { ptr: 0, value: 0xff },
+/// };
+///
+/// let mut output = Cursor::new(vec![]);
+/// write_with_pointees(&header, &mut output, Endian::Big, |queue| (queue,)).unwrap();
+/// assert_eq!(
+///     output.into_inner(),
+///     b"\0\0\0\x08\0\0\0\x0c\x12\x34\0\0\xff"
+/// );
+/// ```
+pub fn write_with_pointees<T, W, F>(
+    value: &T,
+    writer: &mut W,
+    endian: Endian,
+    args: F,
+) -> BinResult<()>
+where
+    T: BinWrite + ?Sized,
+    W: Write + Seek,
+    F: for<'a> Fn(&'a PointeeQueue) -> T::Args<'a>,
+{
+    let queue = PointeeQueue::default();
+    let pos = writer.stream_position()?;
+    queue.write(writer, pos, |writer| {
+        value.write_options(writer, endian, args(&queue))
+    })?;
+    writer.seek(SeekFrom::End(0))?;
+    Ok(())
+}
+
+/// Writes a value along with the values pointed to by any [`FilePtr`] objects
+/// inside of it to an asynchronous stream.
+///
+/// This is the asynchronous counterpart of [`write_with_pointees`], and
+/// writes the same bytes.
+///
+/// # Errors
+///
+/// If writing fails, or if the offset of a value cannot be represented by its
+/// pointer type, an [`Error`](crate::Error) variant will be returned.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{binwrite, Endian, FilePtr32, file_ptr::{PointeeQueue, write_with_pointees_async}};
+/// # use futures_util::io::Cursor;
+/// #[binwrite(async)]
+/// #[bw(big, import(queue: &PointeeQueue))]
+/// struct Header {
+///     #[bw(args { queue })]
+///     name: FilePtr32<u16>,
+/// }
+///
+/// let header = Header {
+///     name: FilePtr32 { ptr: 0, value: 0x1234 },
+/// };
+///
+/// # futures_executor::block_on(async {
+/// let mut output = Cursor::new(vec![]);
+/// write_with_pointees_async(&header, &mut output, Endian::Big, |queue| (queue,))
+///     .await
+///     .unwrap();
+/// assert_eq!(output.into_inner(), b"\0\0\0\x04\x12\x34");
+/// # });
+/// ```
+// This returns `impl Future + Send` instead of being an `async fn`, since
+// otherwise the `Send` check for the caller's future trips over the
+// higher-ranked bound on `F` (rust-lang/rust#100013)
+#[cfg(feature = "async")]
+#[cfg_attr(all(doc, nightly), doc(cfg(feature = "async")))]
+#[allow(clippy::manual_async_fn)]
+pub fn write_with_pointees_async<'w, T, W, F>(
+    value: &'w T,
+    writer: &'w mut W,
+    endian: Endian,
+    args: F,
+) -> impl Future<Output = BinResult<()>> + Send + 'w
+where
+    T: BinWriteAsync + Sync + ?Sized,
+    W: AsyncWrite + AsyncSeek + Unpin + Send,
+    F: for<'a> Fn(&'a PointeeQueue) -> T::Args<'a> + Send + 'w,
+{
+    async move {
+        let queue = PointeeQueue::default();
+        let pos = stream_position_async(writer).await?;
+        let state = queue.begin();
+        value
+            .write_options_async(writer, endian, args(&queue))
+            .await?;
+        if queue.place() {
+            seek_async(writer, SeekFrom::Start(pos)).await?;
+            value
+                .write_options_async(writer, endian, args(&queue))
+                .await?;
+        }
+        queue.end(state);
+        seek_async(writer, SeekFrom::End(0)).await?;
+        Ok(())
+    }
+}
+
+/// The state of a [`write_with_pointees`] call, which is passed to each
+/// [`FilePtr`] that it writes.
+///
+/// While a value is first written, each `FilePtr` inside of it is queued
+/// until the value is written again to place the pointed-to values.
+#[derive(Default)]
+pub struct PointeeQueue {
+    // Atomics make the queue `Sync`, so it can be borrowed by the `Send`
+    // futures of asynchronous writers. Only loads and stores are used, which
+    // are available on every target.
+    placing: AtomicBool,
+    pending: AtomicUsize,
+}
+
+impl PointeeQueue {
+    /// Writes a value at `pos` using `write`, then writes it again to place
+    /// the values of any pointers inside of it.
+    fn write<W, F>(&self, writer: &mut W, pos: u64, write: F) -> BinResult<()>
+    where
+        W: Write + Seek,
+        F: Fn(&mut W) -> BinResult<()>,
+    {
+        let state = self.begin();
+        write(writer)?;
+
+        if self.place() {
+            writer.seek(SeekFrom::Start(pos))?;
+            write(writer)?;
+        }
+
+        self.end(state);
+        Ok(())
+    }
+
+    /// Starts writing a value, returning the state to restore with
+    /// [`end`](Self::end) once it has been written.
+    pub(crate) fn begin(&self) -> (bool, usize) {
+        let state = (self.is_placing(), self.pending.load(Ordering::Relaxed));
+        self.placing.store(false, Ordering::Relaxed);
+        self.pending.store(0, Ordering::Relaxed);
+        state
+    }
+
+    /// Returns `true` if the value needs to be written again to place the
+    /// values of the pointers inside of it.
+    pub(crate) fn place(&self) -> bool {
+        let pending = self.pending.load(Ordering::Relaxed) != 0;
+        self.placing.store(pending, Ordering::Relaxed);
+        pending
+    }
+
+    /// Finishes writing a value.
+    pub(crate) fn end(&self, (placing, pending): (bool, usize)) {
+        self.placing.store(placing, Ordering::Relaxed);
+        self.pending.store(pending, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if pointers should place their values, or `false` if
+    /// they should be queued.
+    pub(crate) fn is_placing(&self) -> bool {
+        self.placing.load(Ordering::Relaxed)
+    }
+
+    /// Queues a pointer to be placed when the value is written again.
+    pub(crate) fn queue(&self) {
+        self.pending
+            .store(self.pending.load(Ordering::Relaxed) + 1, Ordering::Relaxed);
+    }
+}
+
+impl core::fmt::Debug for PointeeQueue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PointeeQueue")
+            .field("placing", &self.is_placing())
+            .field("pending", &self.pending.load(Ordering::Relaxed))
+            .finish()
+    }
+}
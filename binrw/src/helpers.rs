@@ -1,7 +1,7 @@
 //! Helper functions for reading and writing data.
 
 use crate::{
-    __private::not_enough_bytes,
+    __private::{not_enough_bytes, recover_index, recover_strict, span_index},
     BinRead, BinResult, Endian,
     io::{Read, Seek},
 };
@@ -247,16 +247,20 @@ where
     Reader: Read + Seek,
 {
     move |reader, endian, args| {
-        from_fn(|| match read(reader, endian, args.clone()) {
-            Ok(value) => {
-                if cond(&value) {
-                    None
-                } else {
-                    Some(Ok(value))
+        // The end of the input is found by reading until an error, so this
+        // must not recover
+        from_fn(
+            || match recover_strict(|| read(reader, endian, args.clone())) {
+                Ok(value) => {
+                    if cond(&value) {
+                        None
+                    } else {
+                        Some(Ok(value))
+                    }
                 }
-            }
-            err => Some(err),
-        })
+                err => Some(err),
+            },
+        )
         .fuse()
         .collect()
     }
@@ -376,11 +380,15 @@ where
     Reader: Read + Seek,
 {
     move |reader, endian, args| {
-        from_fn(|| match read(reader, endian, args.clone()) {
-            ok @ Ok(_) => Some(ok),
-            Err(err) if err.is_eof() => None,
-            err => Some(err),
-        })
+        // The end of the input is found by reading until an error, so this
+        // must not recover
+        from_fn(
+            || match recover_strict(|| read(reader, endian, args.clone())) {
+                ok @ Ok(_) => Some(ok),
+                Err(err) if err.is_eof() => None,
+                err => Some(err),
+            },
+        )
         .fuse()
         .collect()
     }
@@ -615,8 +623,12 @@ where
                     Err(not_enough_bytes())
                 }
             } else {
-                core::iter::repeat_with(|| T::read_options(reader, endian, args.clone()))
-                .take(n)
+                (0..n)
+                .map(|index| {
+                    recover_index(index, || {
+                        span_index(reader, index, |reader| T::read_options(reader, endian, args.clone()))
+                    })
+                })
                 .collect()
             }
         })
@@ -671,6 +683,9 @@ where
 
 /// Reads a 24-bit unsigned integer.
 ///
+/// To keep the value in a type which is read and written as 24 bits, use
+/// [`U24`](crate::U24).
+///
 /// # Errors
 ///
 /// If reading fails, an [`Error`](crate::Error) variant will be returned.
@@ -710,6 +725,9 @@ pub fn read_u24() -> binrw::BinResult<u32> {
 
 /// Writes a 24-bit unsigned integer.
 ///
+/// The high byte of `value` is discarded. To keep the value in a type which
+/// is read and written as 24 bits, use [`U24`](crate::U24).
+///
 /// # Errors
 ///
 /// If writing fails, an [`Error`](crate::Error) variant will be returned.
@@ -0,0 +1,304 @@
+//! Type definitions for integers with widths which are not a power of two.
+//!
+//! Each type stores its value in the smallest standard integer type which can
+//! hold it, and is read and written using exactly as many bytes as its width,
+//! in the byte order of the reader or writer.
+//!
+//! Values can only be created from standard integers when they are in range,
+//! so a value always fits in its width when it is written.
+//!
+//! # Examples
+//!
+//! ```
+//! # use binrw::{prelude::*, io::Cursor, I24, U40};
+//! #[binrw]
+//! #[brw(big)]
+//! # #[derive(Debug, PartialEq)]
+//! struct Sample {
+//!     left: I24,
+//!     right: I24,
+//!     timestamp: U40,
+//! }
+//!
+//! let data = b"\xff\xff\xfe\x00\x00\x02\x01\x00\x00\x00\x00";
+//! let sample = Sample::read(&mut Cursor::new(data)).unwrap();
+//! assert_eq!(sample.left.get(), -2);
+//! assert_eq!(i32::from(sample.right), 2);
+//! assert_eq!(u64::from(sample.timestamp), 1 << 32);
+//!
+//! assert_eq!(I24::try_from(1_i32 << 23).ok(), None);
+//! assert_eq!(I24::try_from(-(1_i32 << 23)), Ok(I24::MIN));
+//! ```
+
+use crate::{
+    BinRead, BinResult, BinWrite, Endian,
+    file_ptr::{IntoSeekFrom, TryFromOffset},
+    io::{Read, Seek, SeekFrom, Write},
+    meta::StaticSize,
+};
+use core::fmt;
+
+/// The error returned when converting a standard integer to an integer type in
+/// this module fails because the value is out of range.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TryFromIntError(());
+
+impl fmt::Display for TryFromIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("out of range integral type conversion attempted")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryFromIntError {}
+
+macro_rules! int_type {
+    (
+        $(#[$meta:meta])*
+        $ty:ident($inner:ty, $bits:literal, $min:expr, $max:expr),
+        from: [$($from:ty),*],
+        try_from: [$($try_from:ty),*],
+        into: [$($into:ty),*],
+        try_into: [$($try_into:ty),*] $(,)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub struct $ty($inner);
+
+        impl $ty {
+            /// The size of this integer type in bits.
+            pub const BITS: u32 = $bits;
+
+            /// The smallest value that can be represented by this integer
+            /// type.
+            pub const MIN: Self = Self($min);
+
+            /// The largest value that can be represented by this integer
+            /// type.
+            pub const MAX: Self = Self($max);
+
+            const BYTES: usize = $bits / 8;
+
+            /// The number of unused high bits in the inner value.
+            const EXTRA_BITS: u32 = <$inner>::BITS - $bits;
+
+            /// Creates a new integer, returning `None` if `value` is out of
+            /// range.
+            #[must_use]
+            pub const fn new(value: $inner) -> Option<Self> {
+                if value >= Self::MIN.0 && value <= Self::MAX.0 {
+                    Some(Self(value))
+                } else {
+                    None
+                }
+            }
+
+            /// Creates a new integer from the low bits of `value`, discarding
+            /// the high bits.
+            #[must_use]
+            pub const fn new_wrapping(value: $inner) -> Self {
+                // Shifting a signed value right copies the sign bit
+                Self((value << Self::EXTRA_BITS) >> Self::EXTRA_BITS)
+            }
+
+            /// Returns the value as a standard integer.
+            #[must_use]
+            pub const fn get(self) -> $inner {
+                self.0
+            }
+        }
+
+        impl BinRead for $ty {
+            type Args<'a> = ();
+
+            fn read_options<R: Read + Seek>(
+                reader: &mut R,
+                endian: Endian,
+                (): Self::Args<'_>,
+            ) -> BinResult<Self> {
+                let mut buf = [0; core::mem::size_of::<$inner>()];
+                let pos = reader.stream_position()?;
+                let bytes = match endian {
+                    Endian::Big => &mut buf[Self::EXTRA_BITS as usize / 8..],
+                    Endian::Little => &mut buf[..Self::BYTES],
+                };
+                reader
+                    .read_exact(bytes)
+                    .map_err(crate::__private::restore_position(reader, pos))?;
+                let value = match endian {
+                    Endian::Big => <$inner>::from_be_bytes(buf),
+                    Endian::Little => <$inner>::from_le_bytes(buf),
+                };
+                Ok(Self::new_wrapping(value))
+            }
+        }
+
+        impl BinWrite for $ty {
+            type Args<'a> = ();
+
+            fn write_options<W: Write + Seek>(
+                &self,
+                writer: &mut W,
+                endian: Endian,
+                (): Self::Args<'_>,
+            ) -> BinResult<()> {
+                match endian {
+                    Endian::Big => writer
+                        .write_all(&self.0.to_be_bytes()[Self::EXTRA_BITS as usize / 8..])?,
+                    Endian::Little => writer.write_all(&self.0.to_le_bytes()[..Self::BYTES])?,
+                }
+                Ok(())
+            }
+        }
+
+        impl StaticSize for $ty {
+            const SIZE: usize = Self::BYTES;
+        }
+
+        impl IntoSeekFrom for $ty {
+            fn into_seek_from(self) -> BinResult<SeekFrom> {
+                self.0.into_seek_from()
+            }
+        }
+
+        impl TryFromOffset for $ty {
+            fn try_from_offset(offset: i128) -> Option<Self> {
+                Self::try_from(offset).ok()
+            }
+        }
+
+        #[cfg(feature = "arbitrary")]
+        impl<'a> arbitrary::Arbitrary<'a> for $ty {
+            fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                <$inner>::arbitrary(u).map(Self::new_wrapping)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $ty {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.0.serialize(serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $ty {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <$inner>::deserialize(deserializer)?;
+                Self::new(value).ok_or_else(|| ::serde::de::Error::custom(TryFromIntError(())))
+            }
+        }
+
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        $(impl From<$from> for $ty {
+            fn from(value: $from) -> Self {
+                Self(value.into())
+            }
+        })*
+
+        $(impl TryFrom<$try_from> for $ty {
+            type Error = TryFromIntError;
+
+            fn try_from(value: $try_from) -> Result<Self, Self::Error> {
+                <$inner>::try_from(value)
+                    .ok()
+                    .and_then(Self::new)
+                    .ok_or(TryFromIntError(()))
+            }
+        })*
+
+        $(impl From<$ty> for $into {
+            // Every value fits, but the inner type may not convert losslessly,
+            // like `u64` to `i64` for `U40`
+            #[allow(clippy::cast_lossless, clippy::cast_possible_wrap)]
+            fn from(value: $ty) -> Self {
+                value.0 as $into
+            }
+        })*
+
+        $(impl TryFrom<$ty> for $try_into {
+            type Error = core::num::TryFromIntError;
+
+            fn try_from(value: $ty) -> Result<Self, Self::Error> {
+                <$try_into>::try_from(value.0)
+            }
+        })*
+    };
+}
+
+int_type!(
+    /// A 24-bit unsigned integer.
+    U24(u32, 24, 0, (1 << 24) - 1),
+    from: [u8, u16],
+    try_from: [u32, u64, u128, usize, i8, i16, i32, i64, i128, isize],
+    into: [u32, u64, u128, i32, i64, i128],
+    try_into: [u8, u16, usize, i8, i16, isize],
+);
+
+int_type!(
+    /// A 24-bit signed integer.
+    I24(i32, 24, -(1 << 23), (1 << 23) - 1),
+    from: [u8, u16, i8, i16],
+    try_from: [u32, u64, u128, usize, i32, i64, i128, isize],
+    into: [i32, i64, i128],
+    try_into: [u8, u16, u32, u64, u128, usize, i8, i16, isize],
+);
+
+int_type!(
+    /// A 40-bit unsigned integer.
+    U40(u64, 40, 0, (1 << 40) - 1),
+    from: [u8, u16, u32],
+    try_from: [u64, u128, usize, i8, i16, i32, i64, i128, isize],
+    into: [u64, u128, i64, i128],
+    try_into: [u8, u16, u32, usize, i8, i16, i32, isize],
+);
+
+int_type!(
+    /// A 40-bit signed integer.
+    I40(i64, 40, -(1 << 39), (1 << 39) - 1),
+    from: [u8, u16, u32, i8, i16, i32],
+    try_from: [u64, u128, usize, i64, i128, isize],
+    into: [i64, i128],
+    try_into: [u8, u16, u32, u64, u128, usize, i8, i16, i32, isize],
+);
+
+int_type!(
+    /// A 48-bit unsigned integer.
+    U48(u64, 48, 0, (1 << 48) - 1),
+    from: [u8, u16, u32],
+    try_from: [u64, u128, usize, i8, i16, i32, i64, i128, isize],
+    into: [u64, u128, i64, i128],
+    try_into: [u8, u16, u32, usize, i8, i16, i32, isize],
+);
+
+int_type!(
+    /// A 48-bit signed integer.
+    I48(i64, 48, -(1 << 47), (1 << 47) - 1),
+    from: [u8, u16, u32, i8, i16, i32],
+    try_from: [u64, u128, usize, i64, i128, isize],
+    into: [i64, i128],
+    try_into: [u8, u16, u32, u64, u128, usize, i8, i16, i32, isize],
+);
+
+int_type!(
+    /// A 56-bit unsigned integer.
+    U56(u64, 56, 0, (1 << 56) - 1),
+    from: [u8, u16, u32],
+    try_from: [u64, u128, usize, i8, i16, i32, i64, i128, isize],
+    into: [u64, u128, i64, i128],
+    try_into: [u8, u16, u32, usize, i8, i16, i32, isize],
+);
+
+int_type!(
+    /// A 56-bit signed integer.
+    I56(i64, 56, -(1 << 55), (1 << 55) - 1),
+    from: [u8, u16, u32, i8, i16, i32],
+    try_from: [u64, u128, usize, i64, i128, isize],
+    into: [i64, i128],
+    try_into: [u8, u16, u32, u64, u128, usize, i8, i16, i32, isize],
+);
@@ -11,6 +11,7 @@ mod no_std;
 pub mod prelude;
 mod seek;
 mod take_seek;
+mod window;
 
 #[cfg(feature = "std")]
 pub use bufreader::BufReader;
@@ -23,3 +24,4 @@ pub use seek::NoSeek;
 #[cfg(feature = "std")]
 pub use std::io::{Bytes, Cursor, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
 pub use take_seek::*;
+pub use window::Window;
@@ -10,17 +10,27 @@ use alloc::{string::String, vec::Vec};
 ///
 /// This is useful when reading or writing from unseekable streams where binrw
 /// does not *actually* need to seek to successfully parse or write the data.
+///
+/// Seeking forward is supported by skipping over bytes: the next read discards
+/// them, and the next write fills them with zeroes. Seeking backwards is only
+/// possible within a skip which has not happened yet.
 pub struct NoSeek<T> {
     /// The original stream.
     inner: T,
     /// The virtual position of the seekable stream.
     pos: u64,
+    /// The number of bytes to skip before the next read or write.
+    skip: u64,
 }
 
 impl<T> NoSeek<T> {
     /// Creates a new seekable wrapper for the given value.
     pub fn new(inner: T) -> Self {
-        NoSeek { inner, pos: 0 }
+        NoSeek {
+            inner,
+            pos: 0,
+            skip: 0,
+        }
     }
 
     /// Gets a mutable reference to the underlying value.
@@ -41,9 +51,21 @@ impl<T> NoSeek<T> {
 
 impl<T> super::Seek for NoSeek<T> {
     fn seek(&mut self, pos: SeekFrom) -> super::Result<u64> {
-        match pos {
-            SeekFrom::Start(n) if self.pos == n => Ok(n),
-            SeekFrom::Current(0) => Ok(self.pos),
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::Current(n) => self.pos.checked_add_signed(n),
+            SeekFrom::End(_) => None,
+        };
+
+        // The skip has not happened yet, so the real position of the inner
+        // stream is before it
+        let inner_pos = self.pos - self.skip;
+        match target {
+            Some(target) if target >= inner_pos => {
+                self.skip = target - inner_pos;
+                self.pos = target;
+                Ok(target)
+            }
             // https://github.com/rust-lang/rust/issues/86442
             _ => Err(Error::other("seek on unseekable file")),
         }
@@ -54,8 +76,24 @@ impl<T> super::Seek for NoSeek<T> {
     }
 }
 
+impl<T: super::Read> NoSeek<T> {
+    /// Discards the bytes which were skipped by seeking forward.
+    fn discard_skipped(&mut self) -> super::Result<()> {
+        let mut buf = [0; 0x100];
+        while self.skip != 0 {
+            // Lint: `len` is guaranteed to be <= the size of `buf`
+            #[allow(clippy::cast_possible_truncation)]
+            let len = self.skip.min(buf.len() as u64) as usize;
+            self.inner.read_exact(&mut buf[..len])?;
+            self.skip -= len as u64;
+        }
+        Ok(())
+    }
+}
+
 impl<T: super::Read> super::Read for NoSeek<T> {
     fn read(&mut self, buf: &mut [u8]) -> super::Result<usize> {
+        self.discard_skipped()?;
         let n = self.inner.read(buf)?;
         self.pos += n as u64;
         Ok(n)
@@ -63,32 +101,52 @@ impl<T: super::Read> super::Read for NoSeek<T> {
 
     #[cfg(feature = "std")]
     fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> super::Result<usize> {
+        self.discard_skipped()?;
         let n = self.inner.read_vectored(bufs)?;
         self.pos += n as u64;
         Ok(n)
     }
 
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> super::Result<usize> {
+        self.discard_skipped()?;
         let n = self.inner.read_to_end(buf)?;
         self.pos += n as u64;
         Ok(n)
     }
 
     fn read_to_string(&mut self, buf: &mut String) -> super::Result<usize> {
+        self.discard_skipped()?;
         let n = self.inner.read_to_string(buf)?;
         self.pos += n as u64;
         Ok(n)
     }
 
     fn read_exact(&mut self, buf: &mut [u8]) -> super::Result<()> {
+        self.discard_skipped()?;
         self.inner.read_exact(buf)?;
         self.pos += buf.len() as u64;
         Ok(())
     }
 }
 
+impl<T: super::Write> NoSeek<T> {
+    /// Fills the bytes which were skipped by seeking forward with zeroes.
+    fn fill_skipped(&mut self) -> super::Result<()> {
+        const ZEROES: [u8; 0x100] = [0; 0x100];
+        while self.skip != 0 {
+            // Lint: `len` is guaranteed to be <= the size of `ZEROES`
+            #[allow(clippy::cast_possible_truncation)]
+            let len = self.skip.min(ZEROES.len() as u64) as usize;
+            self.inner.write_all(&ZEROES[..len])?;
+            self.skip -= len as u64;
+        }
+        Ok(())
+    }
+}
+
 impl<T: super::Write> super::Write for NoSeek<T> {
     fn write(&mut self, buf: &[u8]) -> super::Result<usize> {
+        self.fill_skipped()?;
         let n = self.inner.write(buf)?;
         self.pos += n as u64;
         Ok(n)
@@ -100,12 +158,14 @@ impl<T: super::Write> super::Write for NoSeek<T> {
 
     #[cfg(feature = "std")]
     fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> super::Result<usize> {
+        self.fill_skipped()?;
         let n = self.inner.write_vectored(bufs)?;
         self.pos += n as u64;
         Ok(n)
     }
 
     fn write_all(&mut self, buf: &[u8]) -> super::Result<()> {
+        self.fill_skipped()?;
         self.inner.write_all(buf)?;
         self.pos += buf.len() as u64;
         Ok(())
@@ -0,0 +1,203 @@
+//! Types for stream adapters which expose a fixed-size region of the
+//! underlying stream as a separate stream.
+
+use super::{Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+/// Stream adapter which exposes a fixed-size region of an underlying stream,
+/// starting at its current position.
+///
+/// Positions in a `Window` are relative to the start of the region, so
+/// offsets read from within the region can be used directly for seeking.
+/// Reads stop at the end of the region, and writes which would go past the
+/// end of the region return an error.
+///
+/// This is the stream used by the [`size`](crate::docs::attribute#size)
+/// directive.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{prelude::*, io::{Cursor, Seek, SeekFrom, Window}};
+/// let mut data = Cursor::new(b"\xff\xff\x01\x02\x03\x04\xff\xff");
+/// data.seek(SeekFrom::Start(2)).unwrap();
+///
+/// let mut window = Window::new(&mut data, 4).unwrap();
+/// window.seek(SeekFrom::Start(2)).unwrap();
+/// assert_eq!(window.read_be::<u16>().unwrap(), 0x0304);
+/// assert!(window.read_be::<u8>().is_err());
+///
+/// window.skip_remaining().unwrap();
+/// assert_eq!(data.position(), 6);
+/// ```
+#[derive(Debug)]
+pub struct Window<T> {
+    inner: T,
+    start: u64,
+    size: u64,
+    pos: u64,
+    written: u64,
+}
+
+impl<T> Window<T> {
+    /// Gets a reference to the underlying stream.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying stream.
+    ///
+    /// Care should be taken to avoid modifying the position of the underlying
+    /// stream as doing so will corrupt the position of this `Window`.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consumes this wrapper, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns the size of the region.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the number of bytes between the current position and the end
+    /// of the region.
+    pub fn remaining(&self) -> u64 {
+        self.size.saturating_sub(self.pos)
+    }
+}
+
+impl<T: Seek> Window<T> {
+    /// Creates a new window of `size` bytes starting at the current position
+    /// of `inner`.
+    ///
+    /// # Errors
+    ///
+    /// If the position of the stream cannot be retrieved, an error is
+    /// returned.
+    pub fn new(mut inner: T, size: u64) -> Result<Self> {
+        let start = inner.stream_position()?;
+        Ok(Self {
+            inner,
+            start,
+            size,
+            pos: 0,
+            written: 0,
+        })
+    }
+
+    /// Moves the underlying stream to the end of the region and returns it.
+    ///
+    /// # Errors
+    ///
+    /// If seeking fails, an error is returned.
+    pub fn skip_remaining(mut self) -> Result<T> {
+        self.inner
+            .seek(SeekFrom::Start(self.start.saturating_add(self.size)))?;
+        Ok(self.inner)
+    }
+}
+
+impl<T: Write + Seek> Window<T> {
+    /// Fills the region with zeroes after the last byte which was written,
+    /// then moves the underlying stream to the end of the region and returns
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// If writing or seeking fails, an error is returned.
+    pub fn pad_remaining(mut self) -> Result<T> {
+        const ZEROES: [u8; 0x20] = [0; 0x20];
+
+        let mut padding = self.size.saturating_sub(self.written);
+        if padding != 0 {
+            self.inner
+                .seek(SeekFrom::Start(self.start.saturating_add(self.written)))?;
+            while padding != 0 {
+                // Lint: The value is at most the length of `ZEROES`.
+                #[allow(clippy::cast_possible_truncation)]
+                let n = padding.min(ZEROES.len() as u64) as usize;
+                self.inner.write_all(&ZEROES[..n])?;
+                padding -= n as u64;
+            }
+        }
+        self.inner
+            .seek(SeekFrom::Start(self.start.saturating_add(self.size)))?;
+        Ok(self.inner)
+    }
+}
+
+impl<T: Read> Read for Window<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let limit = self.remaining();
+
+        // Don't call into inner reader at all at EOF because it may still block
+        if limit == 0 {
+            return Ok(0);
+        }
+
+        // Lint: It is impossible for this cast to truncate because the value
+        // being cast is the minimum of two values, and one of the value types
+        // is already `usize`.
+        #[allow(clippy::cast_possible_truncation)]
+        let max = (buf.len() as u64).min(limit) as usize;
+        let n = self.inner.read(&mut buf[0..max])?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<T: Write> Write for Window<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let limit = self.remaining();
+        if limit == 0 {
+            return Err(Error::new(
+                ErrorKind::WriteZero,
+                "write past the end of the window",
+            ));
+        }
+
+        // Lint: See `read`.
+        #[allow(clippy::cast_possible_truncation)]
+        let max = (buf.len() as u64).min(limit) as usize;
+        let n = self.inner.write(&buf[0..max])?;
+        self.pos += n as u64;
+        self.written = self.written.max(self.pos);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T: Seek> Seek for Window<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+        };
+
+        let Some(pos) = pos.filter(|pos| pos.checked_add(self.start).is_some()) else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ));
+        };
+
+        self.inner.seek(SeekFrom::Start(self.start + pos))?;
+        self.pos = pos;
+        Ok(pos)
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        Ok(self.pos)
+    }
+}
@@ -0,0 +1,1166 @@
+//! Export of binrw types to [Kaitai Struct](https://kaitai.io/) definitions.
+//!
+//! A [`Ksy`] document is built from the [`Schema`] of one or more derived
+//! `BinRead` types. The first type becomes the top-level structure of the
+//! document, and every other type which is added with
+//! [`with_type`](Ksy::with_type) becomes a named type which can be used by
+//! fields of the other types.
+//!
+//! Custom parsers like `parse_with` and `map`, and field types which are not
+//! part of the document, are exported as
+//! [opaque types](https://doc.kaitai.io/user_guide.html#opaque-types). The
+//! original directive is kept in the `doc` key of the field. Expressions are
+//! copied from the Rust source, so complex expressions may need to be
+//! adjusted by hand.
+//!
+//! Directives whose layout cannot be described by Kaitai Struct, like
+//! checksums or bit fields packed in a different order than Kaitai Struct
+//! reads them, cause [`to_yaml`](Ksy::to_yaml) to return an
+//! [`UnsupportedError`] instead of a document which would read different
+//! data.
+//!
+//! # Examples
+//!
+//! ```
+//! # use binrw::{prelude::*, kaitai::Ksy};
+//! #[derive(BinRead)]
+//! #[br(big, magic = b"PK")]
+//! struct Archive {
+//!     len: u16,
+//!     #[br(count = len)]
+//!     entries: Vec<Entry>,
+//! }
+//!
+//! #[derive(BinRead)]
+//! struct Entry {
+//!     #[br(pad_after = 3)]
+//!     kind: u8,
+//! }
+//!
+//! let ksy = Ksy::new::<Archive>("archive").with_type::<Entry>();
+//! assert_eq!(ksy.to_yaml().unwrap(), "\
+//! meta:
+//!   id: archive
+//!   endian: be
+//! seq:
+//!   - id: magic
+//!     contents: [0x50, 0x4b]
+//!   - id: len
+//!     type: u2
+//!   - id: entries
+//!     type: entry
+//!     repeat: expr
+//!     repeat-expr: len
+//! types:
+//!   entry:
+//!     seq:
+//!       - id: kind
+//!         type: u1
+//!       - size: 3
+//! ");
+//! ```
+
+use crate::{
+    Endian,
+    meta::{BitOrder, EndianKind, FieldSchema, Schema, SchemaKind, TypeSchema, VariantSchema},
+};
+#[cfg(not(feature = "std"))]
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+
+/// A Kaitai Struct definition generated from binrw types.
+///
+/// The definition is written as YAML using [`to_yaml`](Self::to_yaml).
+#[derive(Clone, Debug)]
+pub struct Ksy {
+    id: String,
+    types: Vec<TypeSchema>,
+}
+
+impl Ksy {
+    /// Creates a definition with the given id whose top-level structure is
+    /// `T`.
+    #[must_use]
+    pub fn new<T: Schema + ?Sized>(id: &str) -> Self {
+        Self {
+            id: id.to_owned(),
+            types: vec![T::SCHEMA],
+        }
+    }
+
+    /// Adds `T` to the types of the definition, so fields of type `T` are
+    /// exported as references to it instead of as opaque types.
+    #[must_use]
+    pub fn with_type<T: Schema + ?Sized>(mut self) -> Self {
+        if !self.types.iter().any(|ty| ty.name == T::SCHEMA.name) {
+            self.types.push(T::SCHEMA);
+        }
+        self
+    }
+
+    /// Writes the definition as YAML.
+    ///
+    /// # Errors
+    ///
+    /// If a type uses a directive which cannot be expressed in Kaitai Struct,
+    /// an [`UnsupportedError`] is returned.
+    pub fn to_yaml(&self) -> Result<String, UnsupportedError> {
+        let default_endian = self.types[0].endian.endian();
+        let mut exporter = Exporter {
+            types: &self.types,
+            default_endian,
+            ty: self.types[0].name,
+            opaque: false,
+            named_types: Vec::new(),
+            enums: Vec::new(),
+        };
+
+        let root = exporter.type_body(&self.types[0], &snake_case(self.types[0].name))?;
+        for schema in &self.types[1..] {
+            if is_exported(schema) && !matches!(schema.kind, SchemaKind::UnitEnum { .. }) {
+                // Types of enum variants are added while the enum is exported,
+                // and go after it
+                let index = exporter.named_types.len();
+                let body = exporter.type_body(schema, &snake_case(schema.name))?;
+                exporter
+                    .named_types
+                    .insert(index, (snake_case(schema.name), body));
+            }
+        }
+
+        Ok(Document {
+            id: &self.id,
+            default_endian,
+            root,
+            exporter,
+        }
+        .to_string())
+    }
+}
+
+/// The error returned when a type cannot be exported to Kaitai Struct.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnsupportedError {
+    /// The name of the type which cannot be exported.
+    pub ty: &'static str,
+    /// The name of the field which cannot be exported, or `None` if the
+    /// problem is with the type itself.
+    pub field: Option<&'static str>,
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for UnsupportedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot export `{}`", self.ty)?;
+        if let Some(field) = self.field {
+            write!(f, " field `{field}`")?;
+        }
+        write!(f, " to Kaitai Struct: {}", self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnsupportedError {}
+
+/// An exported definition which is ready to be written.
+struct Document<'a> {
+    id: &'a str,
+    default_endian: Option<Endian>,
+    root: TypeBody,
+    exporter: Exporter<'a>,
+}
+
+impl fmt::Display for Document<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let exporter = &self.exporter;
+        writeln!(f, "meta:")?;
+        writeln!(f, "  id: {}", self.id)?;
+        if let Some(endian) = self.default_endian {
+            writeln!(f, "  endian: {}", endian_suffix(endian))?;
+        }
+        if exporter.opaque {
+            writeln!(f, "  ks-opaque-types: true")?;
+        }
+        self.root.write(f, 0)?;
+
+        if !exporter.named_types.is_empty() {
+            writeln!(f, "types:")?;
+            for (id, body) in &exporter.named_types {
+                if body.is_empty() {
+                    writeln!(f, "  {id}: {{}}")?;
+                } else {
+                    writeln!(f, "  {id}:")?;
+                    body.write(f, 4)?;
+                }
+            }
+        }
+
+        if !exporter.enums.is_empty() {
+            writeln!(f, "enums:")?;
+            for (id, values) in &exporter.enums {
+                writeln!(f, "  {id}:")?;
+                for (value, name) in values {
+                    writeln!(f, "    {value}: {name}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The body of a Kaitai Struct type.
+#[derive(Default)]
+struct TypeBody {
+    doc: Option<String>,
+    seq: Vec<Attr>,
+    instances: Vec<(String, Attr)>,
+}
+
+impl TypeBody {
+    fn is_empty(&self) -> bool {
+        self.doc.is_none() && self.seq.is_empty() && self.instances.is_empty()
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let pad = " ".repeat(indent);
+        if let Some(doc) = &self.doc {
+            writeln!(f, "{pad}doc: {}", quote(doc))?;
+        }
+        if !self.seq.is_empty() {
+            writeln!(f, "{pad}seq:")?;
+            for attr in &self.seq {
+                attr.write(f, indent + 2, true)?;
+            }
+        }
+        if !self.instances.is_empty() {
+            writeln!(f, "{pad}instances:")?;
+            for (id, attr) in &self.instances {
+                writeln!(f, "{pad}  {id}:")?;
+                attr.write(f, indent + 4, false)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A sequence item or instance, as a list of keys and YAML values.
+#[derive(Default)]
+struct Attr(Vec<(&'static str, String)>);
+
+impl Attr {
+    fn with_id(id: String) -> Self {
+        Self(vec![("id", id)])
+    }
+
+    fn set(&mut self, key: &'static str, value: String) {
+        self.0.push((key, value));
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn set_type(&mut self, ty: FieldType) {
+        match ty {
+            FieldType::Type(ty) => self.set("type", ty),
+            FieldType::Enum(ty, id) => {
+                self.set("type", ty);
+                self.set("enum", id);
+            }
+            FieldType::String(encoding) => {
+                self.set("type", "strz".to_owned());
+                self.set("encoding", encoding.to_owned());
+            }
+            FieldType::SizedString(encoding, size) => {
+                self.set("type", "str".to_owned());
+                self.set("size", expr(&size));
+                self.set("encoding", encoding.to_owned());
+            }
+            FieldType::Repeat(ty, count) => {
+                self.set_type(*ty);
+                if let Some(count) = count {
+                    self.set("repeat", "expr".to_owned());
+                    self.set("repeat-expr", expr(&count));
+                } else {
+                    self.set("repeat", "eos".to_owned());
+                }
+            }
+            FieldType::Skip => {}
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize, list_item: bool) -> fmt::Result {
+        let pad = " ".repeat(indent);
+        for (index, (key, value)) in self.0.iter().enumerate() {
+            let prefix = match (list_item, index) {
+                (true, 0) => "- ",
+                (true, _) => "  ",
+                (false, _) => "",
+            };
+            if let Some(value) = value.strip_prefix('\n') {
+                // Nested mappings are indented relative to their key
+                let nested = if list_item { "    " } else { "  " };
+                writeln!(f, "{pad}{prefix}{key}:")?;
+                for line in value.lines() {
+                    writeln!(f, "{pad}{nested}{line}")?;
+                }
+            } else {
+                writeln!(f, "{pad}{prefix}{key}: {value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The Kaitai Struct type of a field.
+enum FieldType {
+    /// A built-in or user type.
+    Type(String),
+    /// An integer type with an enum.
+    Enum(String, String),
+    /// A null-terminated string with the given encoding.
+    String(&'static str),
+    /// A string with the given encoding and size in bytes.
+    SizedString(&'static str, String),
+    /// A type repeated a number of times, or until the end of the stream.
+    Repeat(Box<FieldType>, Option<String>),
+    /// A type which is not read at all.
+    Skip,
+}
+
+struct Exporter<'a> {
+    types: &'a [TypeSchema],
+    default_endian: Option<Endian>,
+    /// The name of the type being exported, for errors.
+    ty: &'static str,
+    opaque: bool,
+    named_types: Vec<(String, TypeBody)>,
+    enums: Vec<(String, Vec<(i128, String)>)>,
+}
+
+impl Exporter<'_> {
+    fn find_type(&self, name: &str) -> Option<&TypeSchema> {
+        self.types
+            .iter()
+            .find(|schema| schema.name == name && is_exported(schema))
+    }
+
+    fn unsupported(&self, field: Option<&FieldSchema>, message: String) -> UnsupportedError {
+        UnsupportedError {
+            ty: self.ty,
+            field: field.map(|field| field.name),
+            message,
+        }
+    }
+
+    fn type_body(&mut self, schema: &TypeSchema, id: &str) -> Result<TypeBody, UnsupportedError> {
+        self.ty = schema.name;
+        let mut body = TypeBody::default();
+        if let Some(magic) = schema.magic {
+            body.seq
+                .push(self.magic_attr("magic".to_owned(), magic, schema.endian, None)?);
+        }
+
+        match schema.kind {
+            SchemaKind::Struct(fields) => self.fields(&mut body, fields)?,
+            SchemaKind::Enum(variants) if is_exported(schema) => {
+                self.enum_body(&mut body, variants, id)?;
+            }
+            SchemaKind::UnitEnum { repr, variants } => {
+                // Only reachable for the top-level type
+                if let Some(repr) = repr {
+                    let mut attr = Attr::with_id("value".to_owned());
+                    let ty = self.unit_enum_type(schema, repr, schema.endian, variants);
+                    attr.set_type(ty);
+                    body.seq.push(attr);
+                } else {
+                    body.doc = Some(format!("opaque type `{}`", schema.name));
+                }
+            }
+            SchemaKind::Enum(_) | SchemaKind::Map(_) | SchemaKind::Union(_) => {
+                body.doc = Some(format!("opaque type `{}`", schema.name));
+            }
+        }
+        Ok(body)
+    }
+
+    fn enum_body(
+        &mut self,
+        body: &mut TypeBody,
+        variants: &[VariantSchema],
+        id: &str,
+    ) -> Result<(), UnsupportedError> {
+        let mut cases = Vec::with_capacity(variants.len());
+        let mut switch_ty = None;
+        for variant in variants {
+            let Some(magic) = variant.magic.and_then(parse_magic) else {
+                continue;
+            };
+            let variant_id = format!("{id}_{}", snake_case(variant.name));
+            let key = match &magic.value {
+                MagicValue::Int(value) => value.clone(),
+                MagicValue::Bytes(bytes) => quote(&byte_list(bytes)),
+            };
+            cases.push(format!("\n  {key}: {variant_id}"));
+            switch_ty.get_or_insert(magic);
+
+            let mut variant_body = TypeBody::default();
+            self.fields(&mut variant_body, variant.fields)?;
+            self.named_types.push((variant_id, variant_body));
+        }
+
+        let Some(magic) = switch_ty else {
+            return Ok(());
+        };
+        let mut attr = Attr::with_id("tag".to_owned());
+        match magic.value {
+            MagicValue::Int(_) => {
+                let endian = variants[0].endian;
+                attr.set("type", self.primitive(magic.ty, endian).unwrap_or_default());
+            }
+            MagicValue::Bytes(bytes) => attr.set("size", bytes.len().to_string()),
+        }
+        body.seq.push(attr);
+
+        let mut attr = Attr::with_id("body".to_owned());
+        attr.set(
+            "type",
+            format!("\nswitch-on: tag\ncases:{}", cases.concat()),
+        );
+        body.seq.push(attr);
+        Ok(())
+    }
+
+    fn fields(
+        &mut self,
+        body: &mut TypeBody,
+        fields: &[FieldSchema],
+    ) -> Result<(), UnsupportedError> {
+        let bit_types = self.bit_types(fields)?;
+        for (field, bits) in fields.iter().zip(bit_types) {
+            self.field(body, field, bits)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the Kaitai Struct type of each bit field.
+    ///
+    /// Kaitai Struct reads big-endian bit fields starting from the most
+    /// significant bit of each byte and little-endian bit fields starting
+    /// from the least significant bit, so only groups whose bit order
+    /// matches the byte order of their backing integer can be exported,
+    /// unless the group is a single byte.
+    fn bit_types(&self, fields: &[FieldSchema]) -> Result<Vec<Option<String>>, UnsupportedError> {
+        let mut types = vec![None; fields.len()];
+        let mut start = 0;
+        while start < fields.len() {
+            let len = fields[start..]
+                .iter()
+                .take_while(|field| field.bits.is_some())
+                .count();
+            if len == 0 {
+                start += 1;
+                continue;
+            }
+
+            let group = &fields[start..start + len];
+            let total = group.iter().filter_map(|field| field.bits).sum::<u32>();
+            let first = &group[0];
+            let endian = first.endian.endian().or(self.default_endian);
+            let suffix = match (first.bit_order, endian) {
+                (BitOrder::Msb, _) if total == 8 => "",
+                (BitOrder::Lsb, _) if total == 8 => "le",
+                (BitOrder::Msb, Some(Endian::Big)) => "",
+                (BitOrder::Lsb, Some(Endian::Little)) => "le",
+                (order, endian) => {
+                    let order = match order {
+                        BitOrder::Lsb => "lsb",
+                        BitOrder::Msb => "msb",
+                    };
+                    let endian = match endian {
+                        Some(Endian::Big) => "big-endian",
+                        Some(Endian::Little) => "little-endian",
+                        None => "runtime endian",
+                    };
+                    return Err(self.unsupported(
+                        Some(first),
+                        format!(
+                            "a {total}-bit group of bit fields with `bit_order = {order}` cannot be read from a {endian} integer"
+                        ),
+                    ));
+                }
+            };
+
+            for (index, field) in group.iter().enumerate() {
+                if field.condition.is_some() || field.since.is_some() || field.until.is_some() {
+                    return Err(self.unsupported(
+                        Some(field),
+                        "conditional bit fields cannot be exported".to_owned(),
+                    ));
+                }
+                if field.ty.starts_with('i') {
+                    return Err(self.unsupported(
+                        Some(field),
+                        "signed bit fields cannot be exported".to_owned(),
+                    ));
+                }
+                let bits = field.bits.unwrap_or_default();
+                types[start + index] = Some(format!("b{bits}{suffix}"));
+            }
+            start += len;
+        }
+        Ok(types)
+    }
+
+    fn field(
+        &mut self,
+        body: &mut TypeBody,
+        field: &FieldSchema,
+        bits: Option<String>,
+    ) -> Result<(), UnsupportedError> {
+        if let Some(checksum) = field.checksum {
+            return Err(self.unsupported(
+                Some(field),
+                format!(
+                    "`checksum({}, {})` cannot be verified by Kaitai Struct",
+                    checksum.algorithm, checksum.range
+                ),
+            ));
+        }
+
+        if field.ignore || field.calc.is_some() {
+            // The bits of a bit field are part of the backing integer even
+            // if the field is not read from them
+            if let Some(bits) = &bits {
+                body.seq.push(Attr(vec![("type", bits.clone())]));
+            }
+        }
+
+        if field.ignore {
+            return Ok(());
+        }
+
+        let id = field_id(field.name);
+
+        if let Some(calc) = field.calc {
+            let mut attr = Attr::default();
+            attr.set("value", expr(calc));
+            body.instances.push((id, attr));
+            return Ok(());
+        }
+
+        if let Some(seek_before) = field.seek_before {
+            let Some(pos) = seek_before
+                .strip_prefix("SeekFrom::Start(")
+                .and_then(|pos| pos.strip_suffix(')'))
+            else {
+                return Err(self.unsupported(
+                    Some(field),
+                    format!("`seek_before = {seek_before}` is not an absolute position"),
+                ));
+            };
+            if field.len_prefix.is_some() {
+                return Err(self.unsupported(
+                    Some(field),
+                    "`len_prefix` cannot be combined with `seek_before`".to_owned(),
+                ));
+            }
+
+            let mut attr = Attr::default();
+            attr.set("pos", expr(pos));
+            self.field_attr(&mut attr, field, None)?;
+            body.instances.push((id, attr));
+            return Ok(());
+        }
+
+        if let Some(align) = field.align_before {
+            body.seq.push(align_attr(align));
+        }
+        if let Some(pad) = field.pad_before {
+            body.seq.push(size_attr(pad));
+        }
+        if let Some(magic) = field.magic {
+            body.seq.push(self.magic_attr(
+                format!("{id}_magic"),
+                magic,
+                field.endian,
+                Some(field),
+            )?);
+        }
+        if let Some(len_prefix) = field.len_prefix {
+            let Some(ty) = self.primitive(len_prefix, field.endian) else {
+                return Err(self.unsupported(
+                    Some(field),
+                    format!("`len_prefix = {len_prefix}` is not a primitive integer type"),
+                ));
+            };
+            let mut attr = Attr::with_id(format!("{id}_len"));
+            attr.set("type", ty);
+            self.condition(&mut attr, field)?;
+            body.seq.push(attr);
+        }
+
+        let mut attr = Attr::with_id(id);
+        if self.field_attr(&mut attr, field, bits)? {
+            // Kaitai Struct does not allow a size on primitive types, so the
+            // rest of the window is skipped separately
+            let width = attr
+                .get("type")
+                .filter(|_| attr.get("size").is_none())
+                .and_then(primitive_width);
+            body.seq.push(attr);
+            if let (Some(size), Some(width)) = (field.size, width) {
+                let rest = match size.parse::<usize>() {
+                    Ok(size) => size.saturating_sub(width).to_string(),
+                    Err(_) => format!("{} - {width}", operand(size)),
+                };
+                body.seq.push(size_attr(&rest));
+            }
+        }
+
+        if let Some(pad) = field.pad_after {
+            body.seq.push(size_attr(pad));
+        }
+        if let Some(align) = field.align_after {
+            body.seq.push(align_attr(align));
+        }
+        Ok(())
+    }
+
+    /// Adds the type and related keys of a field. Returns false if the field
+    /// is not read.
+    fn field_attr(
+        &mut self,
+        attr: &mut Attr,
+        field: &FieldSchema,
+        bits: Option<String>,
+    ) -> Result<bool, UnsupportedError> {
+        let len = field
+            .len_prefix
+            .map(|_| format!("{}_len", field_id(field.name)));
+        let mut doc = None;
+        let ty = if let Some(bits) = bits {
+            FieldType::Type(bits)
+        } else if let Some(parse_with) = field.parse_with {
+            doc = Some(format!("parse_with = {parse_with}"));
+            self.opaque_type(field.ty)
+        } else if let Some(map) = field.map {
+            doc = Some(format!("map = {map}"));
+            self.opaque_type(field.ty)
+        } else if let Some(repr) = field.repr {
+            self.field_type(repr, field.endian, None)
+        } else if let Some(len) = len {
+            self.prefixed_type(field, len)?
+        } else {
+            self.field_type(field.ty, field.endian, field.count)
+        };
+
+        if matches!(ty, FieldType::Skip) {
+            return Ok(false);
+        }
+
+        let sized = matches!(&ty, FieldType::Type(ty) if !is_primitive(ty));
+        if let Some(size) = field.size {
+            match &ty {
+                FieldType::Repeat(..) | FieldType::SizedString(..) => {
+                    return Err(self.unsupported(
+                        Some(field),
+                        format!("`size = {size}` cannot be applied to a repeated field"),
+                    ));
+                }
+                FieldType::String(_) => {}
+                FieldType::Type(ty) if is_primitive(ty) => {}
+                _ => attr.set("size", expr(size)),
+            }
+        }
+        let string_size = match &ty {
+            FieldType::String(_) => field.size,
+            _ => None,
+        };
+        attr.set_type(ty);
+        if let Some(size) = string_size {
+            attr.set("size", expr(size));
+        }
+
+        if let Some(pad_size_to) = field.pad_size_to {
+            if sized && field.size.is_none() {
+                attr.set("size", expr(pad_size_to));
+            } else {
+                return Err(self.unsupported(
+                    Some(field),
+                    format!("`pad_size_to = {pad_size_to}` cannot be applied to this type"),
+                ));
+            }
+        }
+        self.condition(attr, field)?;
+        if let Some(doc) = doc {
+            attr.set("doc", quote(&doc));
+        }
+        Ok(true)
+    }
+
+    /// Adds the condition of a field, including its version bounds.
+    fn condition(&self, attr: &mut Attr, field: &FieldSchema) -> Result<(), UnsupportedError> {
+        let mut conditions = Vec::new();
+        if field.since.is_some() || field.until.is_some() {
+            let Some(version) = field.version else {
+                return Err(self.unsupported(
+                    Some(field),
+                    "`since` and `until` require a `version`".to_owned(),
+                ));
+            };
+            let version = operand(version);
+            if let Some(since) = field.since {
+                conditions.push(format!("{version} >= {}", operand(since)));
+            }
+            if let Some(until) = field.until {
+                conditions.push(format!("{version} < {}", operand(until)));
+            }
+        }
+        if let Some(condition) = field.condition {
+            conditions.push(if conditions.is_empty() {
+                condition.to_owned()
+            } else {
+                operand(condition)
+            });
+        }
+        if !conditions.is_empty() {
+            attr.set("if", expr(&conditions.join(" && ")));
+        }
+        Ok(())
+    }
+
+    /// Returns the type of a field with a `len_prefix`, whose length is
+    /// stored in the field `len`.
+    fn prefixed_type(
+        &mut self,
+        field: &FieldSchema,
+        len: String,
+    ) -> Result<FieldType, UnsupportedError> {
+        if generic_arg(field.ty, "Vec").is_some() {
+            return Ok(self.field_type(field.ty, field.endian, Some(&len)));
+        }
+        Ok(match base_name(field.ty) {
+            "String" => FieldType::SizedString("UTF-8", len),
+            "NullString" => FieldType::SizedString("ASCII", len),
+            "NullWideString" => FieldType::SizedString(
+                match field.endian.endian().or(self.default_endian) {
+                    Some(Endian::Big) => "UTF-16BE",
+                    _ => "UTF-16LE",
+                },
+                format!("{len} * 2"),
+            ),
+            _ => {
+                return Err(self.unsupported(
+                    Some(field),
+                    format!("`len_prefix` cannot be applied to `{}`", field.ty),
+                ));
+            }
+        })
+    }
+
+    fn field_type(&mut self, ty: &str, endian: EndianKind, count: Option<&str>) -> FieldType {
+        if let Some(inner) = generic_arg(ty, "Vec") {
+            let inner = self.field_type(inner, endian, None);
+            return FieldType::Repeat(Box::new(inner), count.map(ToOwned::to_owned));
+        }
+
+        if let Some(inner) = generic_arg(ty, "Option").or_else(|| generic_arg(ty, "Box")) {
+            return self.field_type(inner, endian, count);
+        }
+
+        if let Some((inner, len)) = ty
+            .strip_prefix('[')
+            .and_then(|ty| ty.strip_suffix(']'))
+            .and_then(|ty| ty.rsplit_once("; "))
+        {
+            let inner = self.field_type(inner, endian, None);
+            return FieldType::Repeat(Box::new(inner), Some(len.to_owned()));
+        }
+
+        if ty == "()" || base_name(ty) == "PhantomData" {
+            return FieldType::Skip;
+        }
+
+        if let Some(ty) = self.primitive(ty, endian) {
+            return FieldType::Type(ty);
+        }
+
+        match base_name(ty) {
+            "NullString" => return FieldType::String("ASCII"),
+            "NullWideString" => {
+                return FieldType::String(match endian.endian().or(self.default_endian) {
+                    Some(Endian::Big) => "UTF-16BE",
+                    _ => "UTF-16LE",
+                });
+            }
+            _ => {}
+        }
+
+        if let Some(schema) = self.find_type(base_name(ty)).copied() {
+            if let SchemaKind::UnitEnum {
+                repr: Some(repr),
+                variants,
+            } = schema.kind
+            {
+                return self.unit_enum_type(&schema, repr, endian, variants);
+            }
+            return FieldType::Type(snake_case(schema.name));
+        }
+
+        self.opaque_type(ty)
+    }
+
+    fn unit_enum_type(
+        &mut self,
+        schema: &TypeSchema,
+        repr: &str,
+        endian: EndianKind,
+        variants: &[crate::meta::UnitVariantSchema],
+    ) -> FieldType {
+        let id = snake_case(schema.name);
+        if !self.enums.iter().any(|(enum_id, _)| *enum_id == id) {
+            let values = enum_values(variants).unwrap_or_default();
+            self.enums.push((id.clone(), values));
+        }
+        let endian = match schema.endian {
+            EndianKind::Endian(_) => schema.endian,
+            _ => endian,
+        };
+        match self.primitive(repr, endian) {
+            Some(ty) => FieldType::Enum(ty, id),
+            None => self.opaque_type(schema.name),
+        }
+    }
+
+    fn opaque_type(&mut self, ty: &str) -> FieldType {
+        self.opaque = true;
+        let id = snake_case(ty.rsplit("::").next().unwrap_or(ty))
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("_");
+        FieldType::Type(id)
+    }
+
+    fn primitive(&self, ty: &str, endian: EndianKind) -> Option<String> {
+        let (kind, size) = match ty {
+            "u8" => ("u", 1),
+            "u16" => ("u", 2),
+            "u32" => ("u", 4),
+            "u64" => ("u", 8),
+            "i8" => ("s", 1),
+            "i16" => ("s", 2),
+            "i32" => ("s", 4),
+            "i64" => ("s", 8),
+            "f32" => ("f", 4),
+            "f64" => ("f", 8),
+            _ => return None,
+        };
+        let suffix = match endian.endian() {
+            Some(endian) if size > 1 && Some(endian) != self.default_endian => {
+                endian_suffix(endian)
+            }
+            _ => "",
+        };
+        Some(format!("{kind}{size}{suffix}"))
+    }
+
+    fn magic_attr(
+        &self,
+        id: String,
+        magic: &str,
+        endian: EndianKind,
+        field: Option<&FieldSchema>,
+    ) -> Result<Attr, UnsupportedError> {
+        let mut attr = Attr::with_id(id);
+        match parse_magic(magic) {
+            Some(Magic {
+                value: MagicValue::Bytes(bytes),
+                ..
+            }) => attr.set("contents", byte_list(&bytes)),
+            Some(Magic {
+                ty,
+                value: MagicValue::Int(value),
+            }) => {
+                attr.set("type", self.primitive(ty, endian).unwrap_or_default());
+                attr.set("valid", value);
+            }
+            None => {
+                return Err(self.unsupported(field, format!("`magic = {magic}` is not supported")));
+            }
+        }
+        Ok(attr)
+    }
+}
+
+struct Magic {
+    ty: &'static str,
+    value: MagicValue,
+}
+
+impl Magic {
+    /// Returns the type and length of the magic, which must be the same for
+    /// every variant of an enum.
+    fn kind(&self) -> (&'static str, usize) {
+        match &self.value {
+            MagicValue::Int(_) => (self.ty, 0),
+            MagicValue::Bytes(bytes) => (self.ty, bytes.len()),
+        }
+    }
+}
+
+enum MagicValue {
+    Int(String),
+    Bytes(Vec<u8>),
+}
+
+/// Parses a magic literal from its source.
+fn parse_magic(magic: &str) -> Option<Magic> {
+    if let Some(bytes) = magic
+        .strip_prefix("b\"")
+        .and_then(|bytes| bytes.strip_suffix('"'))
+    {
+        return Some(Magic {
+            ty: "",
+            value: MagicValue::Bytes(unescape(bytes)?),
+        });
+    }
+
+    if let Some(byte) = magic
+        .strip_prefix("b'")
+        .and_then(|byte| byte.strip_suffix('\''))
+    {
+        let byte = unescape(byte)?;
+        return (byte.len() == 1).then(|| Magic {
+            ty: "u8",
+            value: MagicValue::Int(format!("{:#04x}", byte[0])),
+        });
+    }
+
+    [
+        "u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64", "f32", "f64",
+    ]
+    .into_iter()
+    .find_map(|ty| {
+        let value = magic.strip_suffix(ty)?.trim_end_matches('_');
+        (!value.is_empty()).then(|| Magic {
+            ty,
+            value: MagicValue::Int(value.replace('_', "")),
+        })
+    })
+}
+
+/// Returns true if the type can be represented in Kaitai Struct.
+fn is_exported(schema: &TypeSchema) -> bool {
+    match schema.kind {
+        SchemaKind::Struct(_) => true,
+        SchemaKind::Map(_) | SchemaKind::Union(_) => false,
+        SchemaKind::Enum(variants) => {
+            // Variants are selected by switching on their magic, so every
+            // variant must have a magic of the same kind
+            let mut kinds = variants.iter().map(|variant| {
+                variant
+                    .magic
+                    .and_then(parse_magic)
+                    .map(|magic| magic.kind())
+            });
+            kinds
+                .next()
+                .is_some_and(|first| first.is_some() && kinds.all(|kind| kind == first))
+        }
+        SchemaKind::UnitEnum { repr, variants } => {
+            repr.is_some() && enum_values(variants).is_some()
+        }
+    }
+}
+
+/// Converts the contents of a byte string literal into bytes.
+fn unescape(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut chars = text.bytes();
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        bytes.push(match chars.next()? {
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'0' => b'\0',
+            b'x' => {
+                let digits = [chars.next()?, chars.next()?];
+                u8::from_str_radix(core::str::from_utf8(&digits).ok()?, 16).ok()?
+            }
+            byte @ (b'\\' | b'\'' | b'"') => byte,
+            _ => return None,
+        });
+    }
+    Some(bytes)
+}
+
+/// Returns the values of a unit-like enum, or `None` if a discriminant is not
+/// an integer literal.
+fn enum_values(variants: &[crate::meta::UnitVariantSchema]) -> Option<Vec<(i128, String)>> {
+    let mut next = 0;
+    variants
+        .iter()
+        .map(|variant| {
+            let value = match variant.discriminant {
+                Some(discriminant) => parse_int(discriminant)?,
+                None => next,
+            };
+            next = value + 1;
+            Some((value, snake_case(variant.name)))
+        })
+        .collect()
+}
+
+fn parse_int(text: &str) -> Option<i128> {
+    let text = text.replace([' ', '_'], "");
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text.as_str()),
+    };
+    let value = if let Some(hex) = text.strip_prefix("0x") {
+        i128::from_str_radix(hex, 16).ok()?
+    } else if let Some(bin) = text.strip_prefix("0b") {
+        i128::from_str_radix(bin, 2).ok()?
+    } else {
+        text.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+/// Returns the type argument of `ty` if it is the generic type `name`.
+fn generic_arg<'a>(ty: &'a str, name: &str) -> Option<&'a str> {
+    let (base, arg) = ty.split_once('<')?;
+    (base.rsplit("::").next() == Some(name))
+        .then(|| arg.strip_suffix('>'))
+        .flatten()
+}
+
+/// Returns the name of a type without its path or generic arguments.
+fn base_name(ty: &str) -> &str {
+    let ty = ty.split('<').next().unwrap_or(ty);
+    ty.rsplit("::").next().unwrap_or(ty)
+}
+
+fn is_primitive(ty: &str) -> bool {
+    let mut chars = ty.chars();
+    matches!(chars.next(), Some('u' | 's' | 'f'))
+        && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
+
+/// Converts a Rust field name into a Kaitai Struct identifier.
+fn field_id(name: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field_{name}")
+    } else {
+        snake_case(name.trim_start_matches("r#"))
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    let chars = name.chars().collect::<Vec<_>>();
+    for (index, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev = index.checked_sub(1).map(|index| chars[index]);
+            let next = chars.get(index + 1);
+            if prev.is_some_and(|prev| prev.is_ascii_lowercase() || prev.is_ascii_digit())
+                || (prev.is_some_and(|prev| prev.is_ascii_uppercase())
+                    && next.is_some_and(char::is_ascii_lowercase))
+            {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn endian_suffix(endian: Endian) -> &'static str {
+    match endian {
+        Endian::Big => "be",
+        Endian::Little => "le",
+    }
+}
+
+fn byte_list(bytes: &[u8]) -> String {
+    let bytes = bytes
+        .iter()
+        .map(|byte| format!("{byte:#04x}"))
+        .collect::<Vec<_>>();
+    format!("[{}]", bytes.join(", "))
+}
+
+/// Converts a Rust expression into a Kaitai Struct expression.
+fn expr(text: &str) -> String {
+    if text.bytes().all(|c| c.is_ascii_digit()) {
+        return text.to_owned();
+    }
+    let text = text
+        .replace(" && ", " and ")
+        .replace(" || ", " or ")
+        .replace("self.", "");
+    if text.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'_') {
+        text
+    } else {
+        quote(&text)
+    }
+}
+
+/// Wraps an expression in parentheses unless it is a single name or number.
+fn operand(text: &str) -> String {
+    if text
+        .bytes()
+        .all(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'.')
+    {
+        text.to_owned()
+    } else {
+        format!("({text})")
+    }
+}
+
+/// Returns the size in bytes of a primitive Kaitai Struct type.
+fn primitive_width(ty: &str) -> Option<usize> {
+    if !is_primitive(ty) {
+        return None;
+    }
+    ty[1..]
+        .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+        .parse()
+        .ok()
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn size_attr(size: &str) -> Attr {
+    Attr(vec![("size", expr(size))])
+}
+
+fn align_attr(align: &str) -> Attr {
+    let align = operand(align);
+    Attr(vec![(
+        "size",
+        quote(&format!("({align} - _io.pos % {align}) % {align}")),
+    )])
+}
@@ -20,34 +20,61 @@ extern crate std;
 #[doc(hidden)]
 #[path = "private.rs"]
 pub mod __private;
+#[cfg(feature = "async")]
+#[cfg_attr(all(doc, nightly), doc(cfg(feature = "async")))]
+pub mod asynchronous;
 mod binread;
 mod binwrite;
+pub mod borrowed;
+pub mod checksum;
 pub mod docs;
+pub mod encoding;
 pub mod endian;
 pub mod error;
 pub mod file_ptr;
 pub mod helpers;
+pub mod int;
 pub mod io;
+pub mod kaitai;
 pub mod meta;
 mod named_args;
 #[doc(hidden)]
 pub mod pos_value;
+#[doc(hidden)]
+pub mod prefixed;
 pub mod punctuated;
+pub mod recover;
+#[cfg(feature = "serde")]
+#[cfg_attr(all(doc, nightly), doc(cfg(feature = "serde")))]
+pub mod serde;
+#[cfg(feature = "spans")]
+#[cfg_attr(all(doc, nightly), doc(cfg(feature = "spans")))]
+pub mod spans;
 #[doc(hidden)]
 pub mod strings;
+#[cfg(feature = "testing")]
+#[cfg_attr(all(doc, nightly), doc(cfg(feature = "testing")))]
+pub mod testing;
+pub mod varint;
 
 #[cfg(all(doc, not(feature = "std")))]
 use alloc::vec::Vec;
+#[cfg(feature = "async")]
+#[doc(inline)]
+pub use asynchronous::{BinReadAsync, BinWriteAsync};
 #[doc(inline)]
 pub use {
     binread::*,
     binwrite::*,
     endian::Endian,
     error::Error,
-    file_ptr::{FilePtr, FilePtr8, FilePtr16, FilePtr32, FilePtr64, FilePtr128},
+    file_ptr::{FilePtr, FilePtr8, FilePtr16, FilePtr32, FilePtr64, FilePtr128, LazyPtr},
+    int::{I24, I40, I48, I56, U24, U40, U48, U56},
     named_args::NamedArgs,
     pos_value::PosValue,
-    strings::{NullString, NullWideString},
+    prefixed::Prefixed,
+    strings::{EncodedString, FixedString, FixedWideString, NullStr, NullString, NullWideString},
+    varint::{VarInt, VarUint},
 };
 
 /// Derive macro generating an impl of the trait [`BinRead`].
@@ -62,6 +89,9 @@ pub use binrw_derive::BinRead;
 /// other attributes that generate code (e.g. `#[derive(Debug)]`) to ensure that
 /// the deleted temporary fields aren’t visible to those macros.
 ///
+/// With the `async` feature, `#[binread(async)]` also generates an impl of
+/// `BinReadAsync`, which can use temporary variables too.
+///
 /// See the [directives glossary](docs::attribute) for usage details.
 pub use binrw_derive::binread;
 
@@ -77,6 +107,9 @@ pub use binrw_derive::BinWrite;
 /// other attributes that generate code (e.g. `#[derive(Debug)]`) to ensure that
 /// the deleted temporary fields aren’t visible to those macros.
 ///
+/// With the `async` feature, `#[binwrite(async)]` also generates an impl of
+/// `BinWriteAsync`, which can use temporary variables too.
+///
 /// See the [directives glossary](docs::attribute) for usage details.
 pub use binrw_derive::binwrite;
 
@@ -88,9 +121,28 @@ pub use binrw_derive::binwrite;
 /// other attributes that generate code (e.g. `#[derive(Debug)]`) to ensure that
 /// the deleted temporary fields aren’t visible to those macros.
 ///
+/// With the `async` feature, `#[binrw(async)]` also generates impls of
+/// `BinReadAsync` and `BinWriteAsync`, which can use temporary variables too.
+///
 /// See the [directives glossary](docs::attribute) for usage details.
 pub use binrw_derive::binrw;
 
+/// Derive macro generating an impl of the trait [`BinReadAsync`].
+///
+/// The type must also implement [`BinRead`]. See the
+/// [`asynchronous`] module for the directives which are supported.
+#[cfg(feature = "async")]
+#[cfg_attr(all(doc, nightly), doc(cfg(feature = "async")))]
+pub use binrw_derive::BinReadAsync;
+
+/// Derive macro generating an impl of the trait [`BinWriteAsync`].
+///
+/// The type must also implement [`BinWrite`]. See the
+/// [`asynchronous`] module for the directives which are supported.
+#[cfg(feature = "async")]
+#[cfg_attr(all(doc, nightly), doc(cfg(feature = "async")))]
+pub use binrw_derive::BinWriteAsync;
+
 /// Derive macro generating an impl of the trait [`NamedArgs`].
 ///
 /// The use cases for this macro are:
@@ -93,7 +93,7 @@ macro_rules! endian_impl {
     )+)+}
 }
 
-endian_impl!(() i8 u8 core::num::NonZeroU8 core::num::NonZeroI8 crate::strings::NullString => EndianKind::None);
+endian_impl!(() i8 u8 core::num::NonZeroU8 core::num::NonZeroI8 str crate::strings::NullString crate::strings::NullStr<'_> => EndianKind::None);
 
 impl<T: ReadEndian + ?Sized> ReadEndian for Box<T> {
     const ENDIAN: EndianKind = <T as ReadEndian>::ENDIAN;
@@ -103,6 +103,14 @@ impl<T: WriteEndian + ?Sized> WriteEndian for Box<T> {
     const ENDIAN: EndianKind = <T as WriteEndian>::ENDIAN;
 }
 
+impl<T: ReadEndian + ?Sized> ReadEndian for &T {
+    const ENDIAN: EndianKind = <T as ReadEndian>::ENDIAN;
+}
+
+impl<T: WriteEndian + ?Sized> WriteEndian for &T {
+    const ENDIAN: EndianKind = <T as WriteEndian>::ENDIAN;
+}
+
 impl<T: ReadEndian> ReadEndian for [T] {
     const ENDIAN: EndianKind = <T as ReadEndian>::ENDIAN;
 }
@@ -155,3 +163,353 @@ endian_tuple_impl!(
     b1, b2, b3, b4, b5, b6, b7, b8, b9, b10, b11, b12, b13, b14, b15, b16, b17, b18, b19, b20, b21,
     b22, b23, b24, b25, b26, b27, b28, b29, b30, b31, b32
 );
+
+/// Types which always occupy the same number of bytes.
+///
+/// This trait is automatically defined on derived `BinRead` structs when
+/// every field has a static size and every
+/// [padding directive](crate::docs::attribute#padding-and-alignment) uses a
+/// literal value. It is also defined on unit-like enums with a
+/// [`repr`](crate::docs::attribute#repr).
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{prelude::*, meta::StaticSize};
+/// #[derive(BinRead)]
+/// #[br(magic = b"IDX")]
+/// struct Entry {
+///     offset: u32,
+///     #[br(pad_after = 2)]
+///     flags: [u8; 2],
+/// }
+///
+/// assert_eq!(Entry::SIZE, 11);
+/// ```
+pub trait StaticSize {
+    /// The size of the type, in bytes.
+    const SIZE: usize;
+}
+
+macro_rules! static_size_impl {
+    ($($Ty:ty)+) => {$(
+        impl StaticSize for $Ty {
+            const SIZE: usize = core::mem::size_of::<$Ty>();
+        }
+    )+}
+}
+
+static_size_impl!(
+    () u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64
+    core::num::NonZeroU8 core::num::NonZeroU16 core::num::NonZeroU32 core::num::NonZeroU64
+    core::num::NonZeroU128 core::num::NonZeroI8 core::num::NonZeroI16 core::num::NonZeroI32
+    core::num::NonZeroI64 core::num::NonZeroI128
+);
+
+impl<T: ?Sized> StaticSize for PhantomData<T> {
+    const SIZE: usize = 0;
+}
+
+impl<T: StaticSize, const N: usize> StaticSize for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+}
+
+macro_rules! static_size_tuple_impl {
+    ($type1:ident $(, $types:ident)*) => {
+        #[allow(non_camel_case_types)]
+        impl<$type1: StaticSize, $($types: StaticSize),*> StaticSize for ($type1, $($types),*) {
+            const SIZE: usize = $type1::SIZE $(+ $types::SIZE)*;
+        }
+
+        static_size_tuple_impl!($($types),*);
+    };
+
+    () => {};
+}
+
+static_size_tuple_impl!(
+    b1, b2, b3, b4, b5, b6, b7, b8, b9, b10, b11, b12, b13, b14, b15, b16, b17, b18, b19, b20, b21,
+    b22, b23, b24, b25, b26, b27, b28, b29, b30, b31, b32
+);
+
+/// Types which describe their own binary layout.
+///
+/// This trait is automatically defined on derived `BinRead` types. It gives
+/// tools like documentation generators and validators access to the layout of
+/// a type without parsing any data. Types and expressions are recorded as
+/// they were written in the source code.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{prelude::*, meta::{Schema, SchemaKind}};
+/// #[derive(BinRead)]
+/// #[br(big, magic = b"PKT")]
+/// struct Packet {
+///     len: u16,
+///     #[br(count = len)]
+///     data: Vec<u8>,
+/// }
+///
+/// assert_eq!(Packet::SCHEMA.name, "Packet");
+/// assert_eq!(Packet::SCHEMA.magic, Some("b\"PKT\""));
+///
+/// let SchemaKind::Struct(fields) = Packet::SCHEMA.kind else { unreachable!() };
+/// assert_eq!(fields[1].name, "data");
+/// assert_eq!(fields[1].count, Some("len"));
+/// ```
+pub trait Schema {
+    /// The layout of the type.
+    const SCHEMA: TypeSchema;
+}
+
+/// The layout of a type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct TypeSchema {
+    /// The name of the type.
+    pub name: &'static str,
+    /// The endianness of the type.
+    ///
+    /// This is [`EndianKind::Runtime`] when the endianness is inherited from
+    /// the caller.
+    pub endian: EndianKind,
+    /// The magic number of the type.
+    pub magic: Option<&'static str>,
+    /// The contents of the type.
+    pub kind: SchemaKind,
+}
+
+impl TypeSchema {
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn new(name: &'static str, endian: EndianKind, kind: SchemaKind) -> Self {
+        Self {
+            name,
+            endian,
+            magic: None,
+            kind,
+        }
+    }
+}
+
+/// The contents of a type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SchemaKind {
+    /// A struct with the given fields.
+    Struct(&'static [FieldSchema]),
+    /// A type which is read as another type and then converted using the
+    /// given [`map`](crate::docs::attribute#map) function or
+    /// [`repr`](crate::docs::attribute#repr) type.
+    Map(&'static str),
+    /// An enum with at least one data variant.
+    Enum(&'static [VariantSchema]),
+    /// An enum containing only unit variants.
+    UnitEnum {
+        /// The [`repr`](crate::docs::attribute#repr) type of the enum.
+        repr: Option<&'static str>,
+        /// The variants of the enum.
+        variants: &'static [UnitVariantSchema],
+    },
+    /// A union with the given fields.
+    Union(&'static [FieldSchema]),
+}
+
+/// The layout of an enum variant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct VariantSchema {
+    /// The name of the variant.
+    pub name: &'static str,
+    /// The endianness of the variant.
+    pub endian: EndianKind,
+    /// The magic number of the variant.
+    pub magic: Option<&'static str>,
+    /// The fields of the variant.
+    pub fields: &'static [FieldSchema],
+}
+
+impl VariantSchema {
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn new(
+        name: &'static str,
+        endian: EndianKind,
+        fields: &'static [FieldSchema],
+    ) -> Self {
+        Self {
+            name,
+            endian,
+            magic: None,
+            fields,
+        }
+    }
+}
+
+/// The layout of a variant of a unit-like enum.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct UnitVariantSchema {
+    /// The name of the variant.
+    pub name: &'static str,
+    /// The magic number of the variant.
+    pub magic: Option<&'static str>,
+    /// The explicit discriminant of the variant.
+    pub discriminant: Option<&'static str>,
+}
+
+impl UnitVariantSchema {
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            magic: None,
+            discriminant: None,
+        }
+    }
+}
+
+/// The layout of a field.
+///
+/// Each directive is the source expression from the corresponding
+/// [attribute](crate::docs::attribute), or `None` if it is not used.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct FieldSchema {
+    /// The name of the field, or its index in a tuple struct.
+    pub name: &'static str,
+    /// The type of the field.
+    pub ty: &'static str,
+    /// The endianness of the field.
+    pub endian: EndianKind,
+    /// The magic number of the field.
+    pub magic: Option<&'static str>,
+    /// The [`count`](crate::docs::attribute#count) directive.
+    pub count: Option<&'static str>,
+    /// The condition of an [`if`](crate::docs::attribute#conditional-values)
+    /// directive.
+    pub condition: Option<&'static str>,
+    /// The [`since`](crate::docs::attribute#versions) directive.
+    pub since: Option<&'static str>,
+    /// The [`until`](crate::docs::attribute#versions) directive.
+    pub until: Option<&'static str>,
+    /// The [`version`](crate::docs::attribute#versions) of the parent which
+    /// is compared with `since` and `until`.
+    pub version: Option<&'static str>,
+    /// The [`offset`](crate::docs::attribute#offset) directive.
+    pub offset: Option<&'static str>,
+    /// The [`calc`](crate::docs::attribute#calculations) or `try_calc`
+    /// directive.
+    pub calc: Option<&'static str>,
+    /// The [`map`](crate::docs::attribute#map) or `try_map` directive.
+    pub map: Option<&'static str>,
+    /// The [`repr`](crate::docs::attribute#repr) directive.
+    pub repr: Option<&'static str>,
+    /// The [`parse_with`](crate::docs::attribute#custom-parserswriters)
+    /// directive.
+    pub parse_with: Option<&'static str>,
+    /// The [`pad_before`](crate::docs::attribute#padding-and-alignment)
+    /// directive.
+    pub pad_before: Option<&'static str>,
+    /// The [`pad_after`](crate::docs::attribute#padding-and-alignment)
+    /// directive.
+    pub pad_after: Option<&'static str>,
+    /// The [`align_before`](crate::docs::attribute#padding-and-alignment)
+    /// directive.
+    pub align_before: Option<&'static str>,
+    /// The [`align_after`](crate::docs::attribute#padding-and-alignment)
+    /// directive.
+    pub align_after: Option<&'static str>,
+    /// The [`pad_size_to`](crate::docs::attribute#padding-and-alignment)
+    /// directive.
+    pub pad_size_to: Option<&'static str>,
+    /// The [`seek_before`](crate::docs::attribute#padding-and-alignment)
+    /// directive.
+    pub seek_before: Option<&'static str>,
+    /// The [`size`](crate::docs::attribute#size) directive.
+    pub size: Option<&'static str>,
+    /// The [`len_prefix`](crate::docs::attribute#length-prefixes) directive.
+    pub len_prefix: Option<&'static str>,
+    /// The name of the field counted by a
+    /// [`count_of`](crate::docs::attribute#count-of) directive.
+    pub count_of: Option<&'static str>,
+    /// The width of a [bit field](crate::docs::attribute#bit-fields) in bits.
+    pub bits: Option<u32>,
+    /// The order in which the bit fields of the struct are packed.
+    pub bit_order: BitOrder,
+    /// The [`checksum`](crate::docs::attribute#checksums) directive.
+    pub checksum: Option<ChecksumSchema>,
+    /// Whether the field is [ignored](crate::docs::attribute#ignore) instead
+    /// of being read.
+    pub ignore: bool,
+    /// Whether the field is [temporary](crate::docs::attribute#temp).
+    pub temp: bool,
+}
+
+impl FieldSchema {
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn new(name: &'static str, ty: &'static str, endian: EndianKind) -> Self {
+        Self {
+            name,
+            ty,
+            endian,
+            magic: None,
+            count: None,
+            condition: None,
+            since: None,
+            until: None,
+            version: None,
+            offset: None,
+            calc: None,
+            map: None,
+            repr: None,
+            parse_with: None,
+            pad_before: None,
+            pad_after: None,
+            align_before: None,
+            align_after: None,
+            pad_size_to: None,
+            seek_before: None,
+            size: None,
+            len_prefix: None,
+            count_of: None,
+            bits: None,
+            bit_order: BitOrder::Lsb,
+            checksum: None,
+            ignore: false,
+            temp: false,
+        }
+    }
+}
+
+/// The order in which consecutive [bit fields](crate::docs::attribute#bit-fields)
+/// are packed into their backing integer.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BitOrder {
+    /// The first field occupies the least significant bits.
+    #[default]
+    Lsb,
+    /// The first field occupies the most significant bits.
+    Msb,
+}
+
+/// A [`checksum`](crate::docs::attribute#checksums) directive on a field.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ChecksumSchema {
+    /// The checksum algorithm type.
+    pub algorithm: &'static str,
+    /// The range of fields or byte positions covered by the checksum.
+    pub range: &'static str,
+}
+
+impl ChecksumSchema {
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn new(algorithm: &'static str, range: &'static str) -> Self {
+        Self { algorithm, range }
+    }
+}
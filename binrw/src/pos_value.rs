@@ -112,3 +112,24 @@ impl<T> From<T> for PosValue<T> {
         }
     }
 }
+
+#[cfg(feature = "serde")]
+impl<T: ::serde::Serialize> ::serde::Serialize for PosValue<T> {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::serde::serialize_pair(
+            serializer,
+            "PosValue",
+            &["val", "pos"],
+            &self.val,
+            &self.pos,
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: ::serde::Deserialize<'de>> ::serde::Deserialize<'de> for PosValue<T> {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (val, pos) = crate::serde::deserialize_pair(deserializer, "PosValue", &["val", "pos"])?;
+        Ok(Self { val, pos })
+    }
+}
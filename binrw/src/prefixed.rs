@@ -0,0 +1,369 @@
+//! Type definitions and helpers for length-prefixed values.
+
+use crate::{
+    BinRead, BinResult, BinWrite, Endian, Error, NullString, NullWideString,
+    io::{Read, Seek, Write},
+};
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+/// A value stored after an integer of type `L` which holds its length.
+///
+/// For a [`Vec`], the length is the number of items. For a [`String`], the
+/// length is the number of bytes of UTF-8 encoded text. For a [`NullString`]
+/// or [`NullWideString`], the length is the number of bytes or 16-bit units,
+/// and there is no null terminator.
+///
+/// When writing, the length prefix is calculated from the value. An error is
+/// returned if the length does not fit in `L`.
+///
+/// To use a length prefix on a field without changing its type, use the
+/// [`len_prefix`](crate::docs::attribute#length-prefixes) directive.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{prelude::*, io::Cursor, Prefixed};
+/// let mut data = Cursor::new(b"\x00\x03abc");
+/// let name = Prefixed::<u16, String>::read_be(&mut data).unwrap();
+/// assert_eq!(*name, "abc");
+///
+/// let mut out = Cursor::new(Vec::new());
+/// Prefixed::<u8, Vec<u16>>::new(vec![1, 2]).write_le(&mut out).unwrap();
+/// assert_eq!(out.into_inner(), b"\x02\x01\x00\x02\x00");
+/// ```
+pub struct Prefixed<L, T> {
+    /// The value.
+    pub value: T,
+    _len: PhantomData<L>,
+}
+
+impl<L, T> Prefixed<L, T> {
+    /// Creates a new length-prefixed value.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            _len: PhantomData,
+        }
+    }
+
+    /// Consumes this object, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<L, T> From<T> for Prefixed<L, T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<L, T> core::ops::Deref for Prefixed<L, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<L, T> core::ops::DerefMut for Prefixed<L, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<L, T: Clone> Clone for Prefixed<L, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+
+impl<L, T: Default> Default for Prefixed<L, T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<L, T: PartialEq> PartialEq for Prefixed<L, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<L, T: Eq> Eq for Prefixed<L, T> {}
+
+impl<L, T: fmt::Debug> fmt::Debug for Prefixed<L, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<L, T> BinRead for Prefixed<L, T>
+where
+    L: for<'a> BinRead<Args<'a> = ()> + TryInto<usize> + Copy + fmt::Display,
+    T: ReadPrefixed,
+{
+    type Args<'a> = T::Args<'a>;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        parse_prefixed::<L, T, R>(reader, endian, args).map(Self::new)
+    }
+}
+
+impl<L, T> BinWrite for Prefixed<L, T>
+where
+    L: for<'a> BinWrite<Args<'a> = ()> + TryFrom<usize>,
+    T: WritePrefixed,
+{
+    type Args<'a> = T::Args<'a>;
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        write_prefixed::<L, T, W>(&self.value, writer, endian, args)
+    }
+}
+
+/// A type which can be read when its length is known in advance.
+pub trait ReadPrefixed: Sized {
+    /// The type of arguments needed to read the payload.
+    type Args<'a>;
+
+    /// Reads a payload of the given length.
+    ///
+    /// # Errors
+    ///
+    /// If reading fails, an [`Error`](crate::Error) variant will be returned.
+    fn read_payload<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        len: usize,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self>;
+}
+
+/// A type which can be written after a prefix containing its length.
+pub trait WritePrefixed {
+    /// The type of arguments needed to write the payload.
+    type Args<'a>;
+
+    /// Returns the length to be written to the prefix.
+    fn payload_len(&self) -> usize;
+
+    /// Writes the payload.
+    ///
+    /// # Errors
+    ///
+    /// If writing fails, an [`Error`](crate::Error) variant will be returned.
+    fn write_payload<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()>;
+}
+
+impl<T> ReadPrefixed for Vec<T>
+where
+    T: BinRead + 'static,
+    for<'a> T::Args<'a>: Clone,
+{
+    type Args<'a> = T::Args<'a>;
+
+    fn read_payload<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        len: usize,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        crate::helpers::count(len)(reader, endian, args)
+    }
+}
+
+impl<T> WritePrefixed for Vec<T>
+where
+    T: BinWrite + 'static,
+    for<'a> T::Args<'a>: Clone,
+{
+    type Args<'a> = T::Args<'a>;
+
+    fn payload_len(&self) -> usize {
+        self.len()
+    }
+
+    fn write_payload<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.write_options(writer, endian, args)
+    }
+}
+
+impl ReadPrefixed for String {
+    type Args<'a> = ();
+
+    fn read_payload<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        len: usize,
+        (): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let pos = reader.stream_position()?;
+        let bytes = Vec::<u8>::read_payload(reader, endian, len, ())?;
+        String::from_utf8(bytes).map_err(|err| Error::Custom {
+            pos,
+            err: Box::new(err),
+        })
+    }
+}
+
+impl WritePrefixed for String {
+    type Args<'a> = ();
+
+    fn payload_len(&self) -> usize {
+        self.len()
+    }
+
+    fn write_payload<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        _: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        writer.write_all(self.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl ReadPrefixed for NullString {
+    type Args<'a> = ();
+
+    fn read_payload<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        len: usize,
+        (): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        Vec::<u8>::read_payload(reader, endian, len, ()).map(Self)
+    }
+}
+
+impl WritePrefixed for NullString {
+    type Args<'a> = ();
+
+    fn payload_len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn write_payload<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        _: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        writer.write_all(&self.0)?;
+        Ok(())
+    }
+}
+
+impl ReadPrefixed for NullWideString {
+    type Args<'a> = ();
+
+    fn read_payload<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        len: usize,
+        (): Self::Args<'_>,
+    ) -> BinResult<Self> {
+        Vec::<u16>::read_payload(reader, endian, len, ()).map(Self)
+    }
+}
+
+impl WritePrefixed for NullWideString {
+    type Args<'a> = ();
+
+    fn payload_len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn write_payload<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        (): Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.0.write_options(writer, endian, ())
+    }
+}
+
+/// Reads a length of type `L` followed by a payload of that length.
+///
+/// This is the function used by the
+/// [`len_prefix`](crate::docs::attribute#length-prefixes) directive when
+/// reading, and can also be used directly with
+/// [`parse_with`](crate::docs::attribute#custom-parserswriters).
+///
+/// # Errors
+///
+/// If reading fails, or the length does not fit in a `usize`, an
+/// [`Error`](crate::Error) variant will be returned.
+pub fn parse_prefixed<L, T, R>(reader: &mut R, endian: Endian, args: T::Args<'_>) -> BinResult<T>
+where
+    L: for<'a> BinRead<Args<'a> = ()> + TryInto<usize> + Copy + fmt::Display,
+    T: ReadPrefixed,
+    R: Read + Seek,
+{
+    let pos = reader.stream_position()?;
+    let len = L::read_options(reader, endian, ())?;
+    let len = len.try_into().map_err(|_| Error::AssertFail {
+        pos,
+        message: format!("length {len} is out of range for usize"),
+    })?;
+    T::read_payload(reader, endian, len, args)
+}
+
+/// Writes the length of a payload as a value of type `L`, followed by the
+/// payload.
+///
+/// This is the function used by the
+/// [`len_prefix`](crate::docs::attribute#length-prefixes) directive when
+/// writing, and can also be used directly with
+/// [`write_with`](crate::docs::attribute#custom-parserswriters).
+///
+/// # Errors
+///
+/// If writing fails, or the length of the payload does not fit in `L`, an
+/// [`Error`](crate::Error) variant will be returned.
+pub fn write_prefixed<L, T, W>(
+    value: &T,
+    writer: &mut W,
+    endian: Endian,
+    args: T::Args<'_>,
+) -> BinResult<()>
+where
+    L: for<'a> BinWrite<Args<'a> = ()> + TryFrom<usize>,
+    T: WritePrefixed + ?Sized,
+    W: Write + Seek,
+{
+    let pos = writer.stream_position()?;
+    let len = value.payload_len();
+    let prefix = L::try_from(len).map_err(|_| Error::AssertFail {
+        pos,
+        message: format!(
+            "length {len} is out of range for {}",
+            core::any::type_name::<L>()
+        ),
+    })?;
+    prefix.write_options(writer, endian, ())?;
+    value.write_payload(writer, endian, args)
+}
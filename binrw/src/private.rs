@@ -1,12 +1,16 @@
 use crate::{
     BinRead, BinResult, BinWrite, Endian, Error,
+    checksum::{Checksum, ChecksumRecorder},
     error::{Backtrace, BacktraceFrame, CustomError},
     io::{self, Read, Seek, SeekFrom, Write},
+    meta::StaticSize,
+    recover::{Recovered, RecoveredError},
 };
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
 #[cfg(not(feature = "std"))]
 pub use alloc::{boxed::Box, format, vec::Vec};
+use core::{marker::PhantomData, ops::Range};
 #[cfg(feature = "std")]
 pub use std::{boxed::Box, format, vec::Vec};
 
@@ -105,6 +109,294 @@ where
     }
 }
 
+// Conversion between bit field values and the raw bits stored in the backing
+// integer of a bit field group. `width` is always between 1 and 128.
+pub trait BitField: Sized {
+    fn from_bits(bits: u128, width: u32) -> Option<Self>;
+    fn to_bits(&self, width: u32) -> Option<u128>;
+}
+
+fn bit_mask(width: u32) -> u128 {
+    u128::MAX >> (u128::BITS - width)
+}
+
+impl BitField for bool {
+    fn from_bits(bits: u128, _: u32) -> Option<Self> {
+        match bits {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn to_bits(&self, _: u32) -> Option<u128> {
+        Some(u128::from(*self))
+    }
+}
+
+macro_rules! bit_field_unsigned_impl {
+    ($($ty:ty),+) => {$(
+        impl BitField for $ty {
+            fn from_bits(bits: u128, _: u32) -> Option<Self> {
+                Self::try_from(bits).ok()
+            }
+
+            fn to_bits(&self, width: u32) -> Option<u128> {
+                let bits = u128::from(*self);
+                (bits & !bit_mask(width) == 0).then_some(bits)
+            }
+        }
+    )+}
+}
+
+bit_field_unsigned_impl!(u8, u16, u32, u64, u128);
+
+macro_rules! bit_field_signed_impl {
+    ($($ty:ty),+) => {$(
+        impl BitField for $ty {
+            fn from_bits(bits: u128, width: u32) -> Option<Self> {
+                let shift = u128::BITS - width;
+                // Lint: Reinterpreting the bits is the point
+                #[allow(clippy::cast_possible_wrap)]
+                let value = ((bits << shift) as i128) >> shift;
+                Self::try_from(value).ok()
+            }
+
+            fn to_bits(&self, width: u32) -> Option<u128> {
+                let value = i128::from(*self);
+                let shift = u128::BITS - width;
+                // Lint: Reinterpreting the bits is the point
+                #[allow(clippy::cast_sign_loss)]
+                ((value << shift) >> shift == value).then_some(value as u128 & bit_mask(width))
+            }
+        }
+    )+}
+}
+
+bit_field_signed_impl!(i8, i16, i32, i64, i128);
+
+pub fn read_bits<R: Read + Seek>(reader: &mut R, endian: Endian, bytes: usize) -> BinResult<u128> {
+    let mut buf = [0; 16];
+    let buf = &mut buf[..bytes];
+    reader.read_exact(buf)?;
+    Ok(match endian {
+        Endian::Big => buf
+            .iter()
+            .fold(0, |value, &byte| value << 8 | u128::from(byte)),
+        Endian::Little => buf
+            .iter()
+            .rev()
+            .fold(0, |value, &byte| value << 8 | u128::from(byte)),
+    })
+}
+
+pub fn write_bits<W: Write + Seek>(
+    writer: &mut W,
+    endian: Endian,
+    value: u128,
+    bytes: usize,
+) -> BinResult<()> {
+    let buf = match endian {
+        Endian::Big => &value.to_be_bytes()[16 - bytes..],
+        Endian::Little => &value.to_le_bytes()[..bytes],
+    };
+    writer.write_all(buf)?;
+    Ok(())
+}
+
+pub fn extract_bits<T: BitField>(
+    bits: u128,
+    pos: u64,
+    shift: u32,
+    width: u32,
+    bit_offset: u32,
+) -> BinResult<T> {
+    let value = (bits >> shift) & bit_mask(width);
+    T::from_bits(value, width).ok_or_else(|| Error::AssertFail {
+        pos,
+        message: format!(
+            "bit field value {value:#x} at bit offset {bit_offset} is out of range for {}",
+            core::any::type_name::<T>()
+        ),
+    })
+}
+
+pub fn insert_bits<T: BitField + core::fmt::Debug>(
+    value: &T,
+    pos: u64,
+    shift: u32,
+    width: u32,
+    bit_offset: u32,
+) -> BinResult<u128> {
+    value
+        .to_bits(width)
+        .map(|bits| bits << shift)
+        .ok_or_else(|| Error::AssertFail {
+            pos,
+            message: format!(
+                "bit field value {value:?} at bit offset {bit_offset} does not fit in {width} bits"
+            ),
+        })
+}
+
+pub fn check_checksum<C: Checksum>(checksum: &C, value: &C::Output, pos: u64) -> BinResult<()> {
+    let actual = checksum.finish();
+    if actual == *value {
+        Ok(())
+    } else {
+        Err(Error::AssertFail {
+            pos,
+            message: format!("checksum mismatch: expected {value:#x?}, found {actual:#x?}"),
+        })
+    }
+}
+
+pub fn write_checksum<C, W>(writer: &mut W, endian: Endian, checksum: &C, pos: u64) -> BinResult<()>
+where
+    C: Checksum,
+    C::Output: for<'a> BinWrite<Args<'a> = ()>,
+    W: Write + Seek,
+{
+    let end = writer.stream_position()?;
+    writer.seek(SeekFrom::Start(pos))?;
+    checksum.finish().write_options(writer, endian, ())?;
+    writer.seek(SeekFrom::Start(end))?;
+    Ok(())
+}
+
+/// Returns an error if a checksum over byte positions is malformed or
+/// includes any of the given checksum fields.
+fn check_checksum_range(range: &Range<u64>, fields: &[Range<u64>]) -> BinResult<()> {
+    let pos = fields.first().map_or(range.start, |field| field.start);
+    let message = if range.start > range.end {
+        format!("checksum range {range:#x?} ends before it starts")
+    } else if let Some(field) = fields
+        .iter()
+        .find(|field| field.start < range.end && range.start < field.end)
+    {
+        format!(
+            "checksum range {range:#x?} includes the checksum field at {:#x}",
+            field.start
+        )
+    } else {
+        return Ok(());
+    };
+
+    Err(Error::AssertFail { pos, message })
+}
+
+/// Verifies a checksum over a range of stream positions. `field` is the
+/// range of positions of the checksum field, which is where the stream is
+/// left afterwards.
+pub fn check_checksum_bytes<C, R>(
+    reader: &mut R,
+    range: Range<u64>,
+    field: Range<u64>,
+    value: &C::Output,
+) -> BinResult<()>
+where
+    C: Checksum,
+    R: Read + Seek,
+{
+    check_checksum_range(&range, core::slice::from_ref(&field))?;
+
+    let end = reader.stream_position()?;
+    reader.seek(SeekFrom::Start(range.start))?;
+    let mut checksum = C::default();
+    let mut buf = [0; 256];
+    let mut remaining = range.end - range.start;
+    while remaining != 0 {
+        let len = usize::try_from(remaining).map_or(buf.len(), |len| len.min(buf.len()));
+        reader.read_exact(&mut buf[..len])?;
+        checksum.update(&buf[..len]);
+        remaining -= len as u64;
+    }
+    reader.seek(SeekFrom::Start(end))?;
+
+    check_checksum(&checksum, value, field.start)
+}
+
+/// Computes a checksum over a range of stream positions which were written
+/// through `writer` and writes it over the placeholder at the start of the
+/// first of `fields`. The range must not include any of `fields`, which are
+/// the checksum field followed by any other checksum fields that have not
+/// been written yet.
+pub fn write_checksum_bytes<C, W>(
+    writer: &mut ChecksumRecorder<'_, W>,
+    endian: Endian,
+    range: Range<u64>,
+    fields: &[Range<u64>],
+) -> BinResult<()>
+where
+    C: Checksum,
+    C::Output: for<'a> BinWrite<Args<'a> = ()>,
+    W: Write + Seek,
+{
+    check_checksum_range(&range, fields)?;
+
+    let pos = fields[0].start;
+    let mut checksum = C::default();
+    if !writer.update(&mut checksum, range.clone()) {
+        return Err(Error::AssertFail {
+            pos,
+            message: format!("checksum range {range:#x?} was not entirely written"),
+        });
+    }
+
+    write_checksum(writer, endian, &checksum, pos)
+}
+
+pub fn check_version<W: Seek>(
+    writer: &mut W,
+    present: bool,
+    in_version: bool,
+    field: &'static str,
+) -> BinResult<()> {
+    if present == in_version {
+        Ok(())
+    } else {
+        Err(version_error(writer.stream_position()?, in_version, field))
+    }
+}
+
+fn version_error(pos: u64, in_version: bool, field: &'static str) -> Error {
+    Error::AssertFail {
+        pos,
+        message: if in_version {
+            format!("`{field}` is required by this version but is `None`")
+        } else {
+            format!("`{field}` is not part of this version but is `Some`")
+        },
+    }
+}
+
+/// The error returned when the length of a field is out of range for the
+/// type of the field which holds its `count_of`.
+#[derive(Debug)]
+pub struct CountOfError {
+    len: usize,
+    field: &'static str,
+    ty: &'static str,
+}
+
+impl core::fmt::Display for CountOfError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "length {} of `{}` is out of range for {}",
+            self.len, self.field, self.ty
+        )
+    }
+}
+
+pub fn count_of<T: TryFrom<usize>>(len: usize, field: &'static str) -> Result<T, CountOfError> {
+    T::try_from(len).map_err(|_| CountOfError {
+        len,
+        field,
+        ty: core::any::type_name::<T>(),
+    })
+}
+
 #[must_use]
 pub fn not_enough_bytes() -> Error {
     Error::Io(io::Error::new(
@@ -300,3 +592,588 @@ macro_rules! eprintln {
 
 #[cfg(not(feature = "std"))]
 pub use crate::eprintln;
+
+/// Starts a span for a field if spans are being recorded, returning the depth
+/// to pass to [`span_exit`].
+#[inline]
+#[allow(unused_variables)]
+pub fn span_enter_field<R: Seek>(reader: &mut R, name: &'static str, ty: &'static str) -> usize {
+    #[cfg(feature = "spans")]
+    {
+        crate::spans::enter(reader, crate::spans::SpanName::Field(name), ty)
+    }
+    #[cfg(not(feature = "spans"))]
+    {
+        usize::MAX
+    }
+}
+
+/// Starts a span for an enum variant if spans are being recorded, returning
+/// the depth to pass to [`span_exit`] or [`span_discard`].
+#[inline]
+#[allow(unused_variables)]
+pub fn span_enter_variant<R: Seek>(reader: &mut R, name: &'static str, ty: &'static str) -> usize {
+    #[cfg(feature = "spans")]
+    {
+        crate::spans::enter(reader, crate::spans::SpanName::Variant(name), ty)
+    }
+    #[cfg(not(feature = "spans"))]
+    {
+        usize::MAX
+    }
+}
+
+/// Reads an element of a collection inside of its own span if spans are being
+/// recorded.
+#[inline]
+pub(crate) fn span_index<R: Seek, T>(
+    reader: &mut R,
+    index: usize,
+    read: impl FnOnce(&mut R) -> BinResult<T>,
+) -> BinResult<T> {
+    #[cfg(feature = "spans")]
+    {
+        let depth = crate::spans::enter(
+            reader,
+            crate::spans::SpanName::Index(index),
+            core::any::type_name::<T>(),
+        );
+        let value = read(reader);
+        // A failed value is ended when its parent is
+        if value.is_ok() {
+            crate::spans::exit(reader, depth);
+        }
+        value
+    }
+    #[cfg(not(feature = "spans"))]
+    {
+        let _ = index;
+        read(reader)
+    }
+}
+
+/// Reads the value pointed to by a pointer inside of its own span if spans are
+/// being recorded.
+#[inline]
+pub(crate) fn span_pointee<R: Seek, T>(
+    reader: &mut R,
+    read: impl FnOnce(&mut R) -> BinResult<T>,
+) -> BinResult<T> {
+    #[cfg(feature = "spans")]
+    {
+        let depth = crate::spans::enter(
+            reader,
+            crate::spans::SpanName::Pointee,
+            core::any::type_name::<T>(),
+        );
+        let value = read(reader);
+        // A failed value is ended when its parent is
+        if value.is_ok() {
+            crate::spans::exit(reader, depth);
+        }
+        value
+    }
+    #[cfg(not(feature = "spans"))]
+    {
+        read(reader)
+    }
+}
+
+/// Ends a span started by one of the `span_enter` functions.
+#[inline]
+#[allow(unused_variables)]
+pub fn span_exit<R: Seek>(reader: &mut R, depth: usize) {
+    #[cfg(feature = "spans")]
+    crate::spans::exit(reader, depth);
+}
+
+/// Removes a span started by one of the `span_enter` functions, for a value
+/// which was not used.
+#[inline]
+#[allow(unused_variables)]
+pub fn span_discard(depth: usize) {
+    #[cfg(feature = "spans")]
+    crate::spans::discard(depth);
+}
+
+/// When a field recovers from errors.
+#[derive(Clone, Copy)]
+pub enum RecoverMode {
+    /// The struct uses the `recover` directive.
+    Always,
+    /// Only inside of `read_recovering`.
+    Session,
+    /// Only if the field holds its error.
+    Held,
+}
+
+/// The value used in place of a field which failed.
+pub enum RecoverFallback<T> {
+    Hold(fn(RecoveredError) -> T),
+    Default(fn() -> T),
+    None,
+}
+
+/// Where a field started, and where to continue reading if it fails.
+#[derive(Clone, Copy)]
+pub struct RecoverPoint {
+    start: u64,
+    resync: Option<u64>,
+}
+
+// Recovery depends on whether a field type holds errors or implements
+// `Default`, which is found using autoref specialisation. Each trait is
+// implemented for one more reference than the next, and the call site uses as
+// many references as the first trait, so the first implementation which
+// applies is chosen.
+pub struct RecoverProbe<T>(PhantomData<T>);
+
+impl<T> RecoverProbe<T> {
+    #[allow(clippy::new_without_default)]
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait RecoverHeld<T> {
+    fn fallback(&self) -> RecoverFallback<T>;
+}
+
+impl<T> RecoverHeld<Recovered<T>> for &&RecoverProbe<Recovered<T>> {
+    #[inline]
+    fn fallback(&self) -> RecoverFallback<Recovered<T>> {
+        RecoverFallback::Hold(Recovered::Failed)
+    }
+}
+
+pub trait RecoverDefault<T> {
+    fn fallback(&self) -> RecoverFallback<T>;
+}
+
+impl<T: Default> RecoverDefault<T> for &RecoverProbe<T> {
+    #[inline]
+    fn fallback(&self) -> RecoverFallback<T> {
+        RecoverFallback::Default(T::default)
+    }
+}
+
+pub trait RecoverNone<T> {
+    fn fallback(&self) -> RecoverFallback<T>;
+}
+
+impl<T> RecoverNone<T> for RecoverProbe<T> {
+    #[inline]
+    fn fallback(&self) -> RecoverFallback<T> {
+        RecoverFallback::None
+    }
+}
+
+pub trait RecoverStaticSize {
+    fn static_size(&self) -> Option<u64>;
+}
+
+impl<T: StaticSize> RecoverStaticSize for &RecoverProbe<T> {
+    #[inline]
+    fn static_size(&self) -> Option<u64> {
+        u64::try_from(T::SIZE).ok()
+    }
+}
+
+pub trait RecoverDynamicSize {
+    fn static_size(&self) -> Option<u64>;
+}
+
+impl<T> RecoverDynamicSize for RecoverProbe<T> {
+    #[inline]
+    fn static_size(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Returns whether a field with the given fallback recovers from errors.
+#[inline]
+fn recovers<T>(mode: RecoverMode, fallback: &RecoverFallback<T>) -> bool {
+    match (fallback, mode) {
+        (RecoverFallback::Hold(_), _) => true,
+        (RecoverFallback::Default(_), RecoverMode::Always) => {
+            #[cfg(feature = "std")]
+            {
+                !crate::recover::is_strict()
+            }
+            #[cfg(not(feature = "std"))]
+            {
+                true
+            }
+        }
+        (RecoverFallback::Default(_), RecoverMode::Session) => {
+            #[cfg(feature = "recover")]
+            {
+                crate::recover::is_active()
+            }
+            #[cfg(not(feature = "recover"))]
+            {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Adds a field to the path of the current recovery session, if there is one,
+/// returning the depth to pass to [`recover_exit`].
+#[inline]
+#[allow(unused_variables)]
+#[must_use]
+pub fn recover_enter(name: &'static str) -> usize {
+    #[cfg(feature = "recover")]
+    {
+        crate::recover::enter(crate::recover::Segment::Field(name))
+    }
+    #[cfg(not(feature = "recover"))]
+    {
+        usize::MAX
+    }
+}
+
+/// Removes a field added by [`recover_enter`] from the path of the current
+/// recovery session.
+#[inline]
+#[allow(unused_variables)]
+pub fn recover_exit(depth: usize) {
+    #[cfg(feature = "recover")]
+    crate::recover::exit(depth);
+}
+
+/// Reads an element of a collection with its index in the path of the current
+/// recovery session.
+#[inline]
+pub(crate) fn recover_index<T>(index: usize, read: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "recover")]
+    {
+        let depth = crate::recover::enter(crate::recover::Segment::Index(index));
+        let value = read();
+        crate::recover::exit(depth);
+        value
+    }
+    #[cfg(not(feature = "recover"))]
+    {
+        let _ = index;
+        read()
+    }
+}
+
+/// Reads something which relies on errors to decide what to read, so must not
+/// recover from errors unless a field holds its error.
+#[inline]
+pub fn recover_strict<T>(read: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "std")]
+    {
+        crate::recover::strict(read)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        read()
+    }
+}
+
+/// Records the start of a field which can recover from errors.
+///
+/// The stream position is only requested if the field can recover.
+#[inline]
+pub fn recover_mark<R: Seek, T>(
+    reader: &mut R,
+    mode: RecoverMode,
+    fallback: &RecoverFallback<T>,
+    size: Option<u64>,
+) -> Option<RecoverPoint> {
+    if !recovers(mode, fallback) {
+        return None;
+    }
+    let start = reader.stream_position().ok()?;
+    Some(RecoverPoint {
+        start,
+        resync: size.and_then(|size| start.checked_add(size)),
+    })
+}
+
+/// Recovers from an error in a field, if it can, by seeking to its resync
+/// point and returning the value to use in its place.
+#[inline]
+pub fn recover_field<R: Seek, T>(
+    reader: &mut R,
+    error: Error,
+    mode: RecoverMode,
+    name: &'static str,
+    depth: usize,
+    point: Option<RecoverPoint>,
+    fallback: &RecoverFallback<T>,
+) -> BinResult<T> {
+    if !recovers(mode, fallback) {
+        return Err(error);
+    }
+
+    let pos = match point {
+        Some(point) => point.start,
+        None => reader.stream_position()?,
+    };
+
+    // A field which fails because it starts at the end of the input fails the
+    // same way every time, so recovering from it would make reads which stop
+    // at an error, like `until_eof`, never end
+    if error.is_eof() {
+        match starts_at_end(reader, pos) {
+            Ok(true) => return Err(error),
+            Ok(false) => {}
+            Err(seek_error) => return Err(restore_position_err(error, seek_error.into())),
+        }
+    }
+    if let Some(resync) = point.and_then(|point| point.resync)
+        && let Err(seek_error) = reader.seek(SeekFrom::Start(resync))
+    {
+        return Err(restore_position_err(error, seek_error.into()));
+    }
+
+    #[cfg(feature = "recover")]
+    let path = crate::recover::path(depth);
+    #[cfg(not(feature = "recover"))]
+    let path = {
+        let _ = depth;
+        None
+    };
+    let error = RecoveredError {
+        path: path.unwrap_or_else(|| name.into()),
+        pos,
+        error,
+    };
+
+    match fallback {
+        RecoverFallback::Hold(hold) => Ok(hold(error)),
+        RecoverFallback::Default(default) => {
+            #[cfg(feature = "recover")]
+            crate::recover::push_error(error);
+            #[cfg(not(feature = "recover"))]
+            drop(error);
+            Ok(default())
+        }
+        RecoverFallback::None => unreachable!("fields without a fallback never recover"),
+    }
+}
+
+/// Returns whether `pos` is at or after the end of the stream, without moving
+/// the stream.
+fn starts_at_end<R: Seek>(reader: &mut R, pos: u64) -> crate::io::Result<bool> {
+    let current = reader.stream_position()?;
+    let end = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(current))?;
+    Ok(pos >= end)
+}
+
+#[cfg(feature = "arbitrary")]
+pub use arbitrary;
+
+/// Generates a collection with exactly `count` elements, as if it was read
+/// with the `count` directive.
+///
+/// Counts which are larger than the remaining input are rejected instead of
+/// generating mostly default elements.
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary_count<'a, C, T, N>(
+    u: &mut arbitrary::Unstructured<'a>,
+    count: N,
+) -> arbitrary::Result<C>
+where
+    C: FromIterator<T>,
+    T: arbitrary::Arbitrary<'a>,
+    usize: TryFrom<N>,
+{
+    let count = usize::try_from(count).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+    if count > u.len() {
+        return Err(arbitrary::Error::NotEnoughData);
+    }
+    (0..count).map(|_| T::arbitrary(u)).collect()
+}
+
+/// Generates a value for a field which is used as the count of a later field,
+/// small enough that the count can be satisfied by the remaining input.
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary_len<T: TryFrom<usize>>(
+    u: &mut arbitrary::Unstructured<'_>,
+) -> arbitrary::Result<T> {
+    let mut len = u.int_in_range(0..=u.len())?;
+    loop {
+        match T::try_from(len) {
+            Ok(len) => return Ok(len),
+            Err(_) if len != 0 => len /= 2,
+            Err(_) => return Err(arbitrary::Error::IncorrectFormat),
+        }
+    }
+}
+
+/// Generates a value for a bit field which fits in `width` bits.
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary_bits<T: BitField>(
+    u: &mut arbitrary::Unstructured<'_>,
+    width: u32,
+) -> arbitrary::Result<T> {
+    let bits = <u128 as arbitrary::Arbitrary<'_>>::arbitrary(u)? & bit_mask(width);
+    T::from_bits(bits, width).ok_or(arbitrary::Error::IncorrectFormat)
+}
+
+/// Rejects a generated value which fails an assertion.
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary_check(condition: bool) -> arbitrary::Result<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(arbitrary::Error::IncorrectFormat)
+    }
+}
+
+/// Rejects a generated value whose `try_calc` field fails.
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary_try_calc<T, E>(value: Result<T, E>) -> arbitrary::Result<T> {
+    value.map_err(|_| arbitrary::Error::IncorrectFormat)
+}
+
+#[cfg(feature = "async")]
+pub async fn seek_async<S>(stream: &mut S, pos: SeekFrom) -> io::Result<u64>
+where
+    S: crate::asynchronous::AsyncSeek + Unpin + ?Sized,
+{
+    core::future::poll_fn(|cx| core::pin::Pin::new(&mut *stream).poll_seek(cx, pos)).await
+}
+
+#[cfg(feature = "async")]
+pub async fn stream_position_async<S>(stream: &mut S) -> io::Result<u64>
+where
+    S: crate::asynchronous::AsyncSeek + Unpin + ?Sized,
+{
+    seek_async(stream, SeekFrom::Current(0)).await
+}
+
+// This returns `impl Future + Send` instead of being an `async fn`, since
+// otherwise the `Send` check for the caller's future trips over the
+// higher-ranked lifetimes of the inner read (rust-lang/rust#100013)
+#[cfg(feature = "async")]
+#[allow(clippy::manual_async_fn)]
+pub fn magic_async<R, B>(
+    reader: &mut R,
+    expected: B,
+    endian: Endian,
+) -> impl core::future::Future<Output = BinResult<()>> + Send
+where
+    B: for<'a> crate::BinReadAsync<Args<'a> = ()>
+        + core::fmt::Debug
+        + PartialEq
+        + Sync
+        + Send
+        + Clone
+        + Copy
+        + 'static,
+    R: crate::asynchronous::AsyncRead + crate::asynchronous::AsyncSeek + Unpin + Send,
+{
+    async move {
+        let pos = stream_position_async(reader).await?;
+        let val = B::read_options_async(reader, endian, ()).await?;
+        if val == expected {
+            Ok(())
+        } else {
+            Err(Error::BadMagic {
+                pos,
+                found: Box::new(val) as _,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+pub fn async_block_type_hint<T, F>(f: F) -> F
+where
+    F: core::future::Future<Output = BinResult<T>>,
+{
+    f
+}
+
+#[cfg(feature = "async")]
+pub fn map_read_async<Input, Output, MapFn, R>(
+    _: &MapFn,
+    reader: &mut R,
+    endian: Endian,
+    args: Input::Args<'_>,
+) -> impl core::future::Future<Output = BinResult<Input>> + Send
+where
+    MapFn: FnOnce(Input) -> Output,
+    Input: crate::BinReadAsync,
+    R: crate::asynchronous::AsyncRead + crate::asynchronous::AsyncSeek + Unpin + Send,
+{
+    Input::read_options_async(reader, endian, args)
+}
+
+#[cfg(feature = "async")]
+pub async fn check_version_async<W>(
+    writer: &mut W,
+    present: bool,
+    in_version: bool,
+    field: &'static str,
+) -> BinResult<()>
+where
+    W: crate::asynchronous::AsyncSeek + Unpin + ?Sized,
+{
+    if present == in_version {
+        Ok(())
+    } else {
+        Err(version_error(
+            stream_position_async(writer).await?,
+            in_version,
+            field,
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+pub async fn restore_position_async<S>(stream: &mut S, pos: u64, error: Error) -> Error
+where
+    S: crate::asynchronous::AsyncSeek + Unpin + ?Sized,
+{
+    match seek_async(stream, SeekFrom::Start(pos)).await {
+        Ok(_) => error,
+        Err(seek_error) => restore_position_err(error, seek_error.into()),
+    }
+}
+
+#[cfg(feature = "async")]
+pub async fn restore_position_variant_async<S>(
+    stream: &mut S,
+    pos: u64,
+    error: Error,
+) -> BinResult<Error>
+where
+    S: crate::asynchronous::AsyncSeek + Unpin + ?Sized,
+{
+    match seek_async(stream, SeekFrom::Start(pos)).await {
+        Ok(_) => Ok(error),
+        Err(seek_error) => Err(restore_position_err(error, seek_error.into())),
+    }
+}
+
+#[cfg(feature = "async")]
+pub async fn write_zeroes_async<W>(writer: &mut W, count: u64) -> BinResult<()>
+where
+    W: crate::asynchronous::AsyncWrite + Unpin + ?Sized,
+{
+    const BUF_SIZE: u16 = 0x20;
+    const ZEROES: [u8; BUF_SIZE as usize] = [0u8; BUF_SIZE as usize];
+
+    let mut remaining = count;
+    while remaining != 0 {
+        let len = remaining.min(BUF_SIZE.into());
+        // Lint: `len` is guaranteed to be <= BUF_SIZE
+        #[allow(clippy::cast_possible_truncation)]
+        crate::asynchronous::write_all(writer, &ZEROES[..len as usize]).await?;
+        remaining -= len;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,354 @@
+//! Recovery from errors in damaged data.
+//!
+//! Normally, reading stops at the first error. When reading damaged data, it
+//! is often more useful to get as much of an object as possible. Derived
+//! `BinRead` implementations can recover from an error in a field by
+//! replacing its value and continuing with the next field:
+//!
+//! * A field of type [`Recovered<T>`] always recovers, and holds the error in
+//!   place of its value. The field type must be written as `Recovered<T>`,
+//!   not as an alias of it.
+//! * Any other field which implements [`Default`] recovers with its default
+//!   value if its struct uses the [`recover`] directive, or if it is read
+//!   inside of [`read_recovering`] (requires feature `recover`), which also
+//!   returns the list of errors which were recovered from.
+//!
+//! After a field recovers, reading continues from its resync point. If the
+//! size of the field is known, either because it uses the `size` directive
+//! or because its type implements [`StaticSize`](crate::meta::StaticSize),
+//! the resync point is the end of the field. Otherwise, reading continues
+//! from wherever the failed read left the stream.
+//!
+//! Recovery happens in the innermost field which can recover, so the errors
+//! in a nested struct replace only the fields of the nested struct which
+//! failed.
+//!
+//! Fields never recover from reaching the end of the input if they start at
+//! the end of the input. Fields other than [`Recovered<T>`] also do not
+//! recover while reading something which relies on errors to decide what to
+//! read, like an enum which tries each of its variants, a field which uses
+//! `try`, or [`until_eof`](crate::helpers::until_eof). Without feature `std`,
+//! fields with the `recover` directive recover in these cases too.
+//!
+//! [`recover`]: crate::docs::attribute#recover
+//!
+//! # Examples
+//!
+//! ```
+//! # use binrw::{prelude::*, io::Cursor, recover::Recovered};
+//! #[derive(BinRead)]
+//! #[br(recover)]
+//! struct Record {
+//!     #[br(assert(kind != 0))]
+//!     kind: u8,
+//!     flags: u8,
+//!     name: Recovered<[u8; 4]>,
+//! }
+//!
+//! // The kind is invalid and the name is cut short
+//! let record = Record::read_le(&mut Cursor::new(b"\0\x01abc")).unwrap();
+//! assert_eq!(record.kind, 0);
+//! assert_eq!(record.flags, 1);
+//! let error = record.name.error().unwrap();
+//! assert_eq!((error.path.as_str(), error.pos), ("name", 2));
+//! ```
+
+use crate::{
+    BinRead, BinResult, Endian, Error,
+    io::{Read, Seek},
+    meta::StaticSize,
+};
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+use core::fmt;
+
+/// An error which a field recovered from.
+#[derive(Debug)]
+pub struct RecoveredError {
+    /// The path to the field which failed, like `header.entries[2].name`.
+    ///
+    /// Outside of [`read_recovering`], the path is only the name of the
+    /// field.
+    pub path: String,
+    /// The position of the start of the field which failed.
+    pub pos: u64,
+    /// The error which was recovered from.
+    pub error: Error,
+}
+
+impl fmt::Display for RecoveredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {:#x}: {}", self.path, self.pos, self.error)
+    }
+}
+
+/// A value which may have failed to read.
+///
+/// A field of this type holds the error instead of failing when its value
+/// fails to read. See the [module documentation](self) for details.
+#[derive(Debug)]
+pub enum Recovered<T> {
+    /// The value was read successfully.
+    Value(T),
+    /// The value failed to read.
+    Failed(RecoveredError),
+}
+
+impl<T> Recovered<T> {
+    /// Returns the value, if it was read successfully.
+    #[must_use]
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Self::Value(value) => Some(value),
+            Self::Failed(_) => None,
+        }
+    }
+
+    /// Returns the error, if the value failed to read.
+    #[must_use]
+    pub fn error(&self) -> Option<&RecoveredError> {
+        match self {
+            Self::Value(_) => None,
+            Self::Failed(error) => Some(error),
+        }
+    }
+
+    /// Converts into a [`Result`].
+    ///
+    /// # Errors
+    ///
+    /// If the value failed to read, its error is returned.
+    pub fn into_result(self) -> Result<T, RecoveredError> {
+        match self {
+            Self::Value(value) => Ok(value),
+            Self::Failed(error) => Err(error),
+        }
+    }
+}
+
+impl<T: BinRead> BinRead for Recovered<T> {
+    type Args<'a> = T::Args<'a>;
+
+    #[inline]
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        // Errors are caught by the field which holds this value, since that is
+        // where the path and position of the field are known
+        T::read_options(reader, endian, args).map(Self::Value)
+    }
+}
+
+impl<T: StaticSize> StaticSize for Recovered<T> {
+    const SIZE: usize = T::SIZE;
+}
+
+#[cfg(feature = "recover")]
+pub use session::read_recovering;
+#[cfg(feature = "recover")]
+pub(crate) use session::{Segment, enter, exit, is_active, path, push_error};
+#[cfg(feature = "std")]
+pub(crate) use strict::{is_strict, strict};
+
+#[cfg(feature = "std")]
+mod strict {
+    use core::cell::Cell;
+
+    std::thread_local! {
+        /// The number of reads on this thread which rely on errors to
+        /// backtrack, and so must not recover.
+        static STRICT: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Returns whether fields must not recover because of a read which relies
+    /// on errors to backtrack.
+    pub(crate) fn is_strict() -> bool {
+        STRICT.get() != 0
+    }
+
+    /// Calls `read` without recovering from errors in fields, unless they
+    /// hold their errors.
+    pub(crate) fn strict<T>(read: impl FnOnce() -> T) -> T {
+        struct Strict;
+
+        impl Drop for Strict {
+            fn drop(&mut self) {
+                STRICT.set(STRICT.get() - 1);
+            }
+        }
+
+        STRICT.set(STRICT.get() + 1);
+        let _strict = Strict;
+        read()
+    }
+}
+
+#[cfg(feature = "recover")]
+mod session {
+    use super::RecoveredError;
+    use crate::{
+        BinRead, BinResult, Endian,
+        io::{Read, Seek},
+    };
+    use core::cell::RefCell;
+
+    /// A segment of the path to a field.
+    #[derive(Clone, Copy)]
+    pub(crate) enum Segment {
+        /// A named or numbered field.
+        Field(&'static str),
+        /// An element of a collection.
+        Index(usize),
+    }
+
+    #[derive(Default)]
+    struct Session {
+        path: Vec<Segment>,
+        errors: Vec<RecoveredError>,
+    }
+
+    std::thread_local! {
+        /// The current recovery session, or `None` if reading is not being
+        /// done inside of `read_recovering`.
+        static SESSION: RefCell<Option<Session>> = const { RefCell::new(None) };
+    }
+
+    /// The depth returned when no segment was added to the path.
+    pub(crate) const NO_SEGMENT: usize = usize::MAX;
+
+    /// Adds a segment to the current path, returning its depth.
+    pub(crate) fn enter(segment: Segment) -> usize {
+        SESSION.with_borrow_mut(|session| {
+            let Some(session) = session else {
+                return NO_SEGMENT;
+            };
+            session.path.push(segment);
+            session.path.len() - 1
+        })
+    }
+
+    /// Removes the segment at `depth` and everything after it from the
+    /// current path.
+    pub(crate) fn exit(depth: usize) {
+        SESSION.with_borrow_mut(|session| {
+            if let Some(session) = session {
+                session.path.truncate(depth);
+            }
+        });
+    }
+
+    /// Returns whether fields are recovering because of the current session.
+    pub(crate) fn is_active() -> bool {
+        SESSION.with_borrow(Option::is_some) && !super::is_strict()
+    }
+
+    /// Returns the path to the segment at `depth`, if it is in the current
+    /// path.
+    pub(crate) fn path(depth: usize) -> Option<String> {
+        SESSION.with_borrow(|session| {
+            let segments = session.as_ref()?.path.get(..=depth)?;
+            let mut path = String::new();
+            for segment in segments {
+                match segment {
+                    Segment::Field(name) => {
+                        if !path.is_empty() {
+                            path.push('.');
+                        }
+                        path.push_str(name);
+                    }
+                    Segment::Index(index) => {
+                        path.push('[');
+                        path.push_str(&index.to_string());
+                        path.push(']');
+                    }
+                }
+            }
+            Some(path)
+        })
+    }
+
+    /// Adds an error to the current session, if there is one.
+    pub(crate) fn push_error(error: RecoveredError) {
+        SESSION.with_borrow_mut(|session| {
+            if let Some(session) = session {
+                session.errors.push(error);
+            }
+        });
+    }
+
+    /// Restores the previous session when dropped, even if reading panics.
+    struct Recovering {
+        previous: Option<Session>,
+    }
+
+    impl Drop for Recovering {
+        fn drop(&mut self) {
+            SESSION.set(self.previous.take());
+        }
+    }
+
+    /// Reads `T` from the reader, recovering from errors in every field which
+    /// can recover.
+    ///
+    /// The errors which were recovered from are returned in the order they
+    /// happened, even if reading still fails. Errors held by
+    /// [`Recovered`](super::Recovered) fields are not included.
+    ///
+    /// Recovery is done per thread, and only objects which are read by the
+    /// current thread are affected. Fields are not recovered while reading
+    /// something which relies on errors to decide what to read, like an enum
+    /// which tries each of its variants, a field which uses `try`, or
+    /// [`until_eof`](crate::helpers::until_eof), or if they start at the end
+    /// of the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use binrw::{prelude::*, io::Cursor, recover::read_recovering, Endian};
+    /// #[derive(BinRead)]
+    /// struct Header {
+    ///     #[br(magic = 1u8)]
+    ///     version: u8,
+    ///     #[br(count = 2)]
+    ///     entries: Vec<Entry>,
+    /// }
+    ///
+    /// #[derive(BinRead)]
+    /// struct Entry {
+    ///     #[br(assert(kind < 4))]
+    ///     kind: u8,
+    ///     len: u16,
+    /// }
+    ///
+    /// let mut data = Cursor::new(b"\x01\x02\x01\x00\x00\x09\x00\x00");
+    /// let (header, errors) = read_recovering::<Header, _>(&mut data, Endian::Little, ());
+    /// let header = header.unwrap();
+    /// assert_eq!(header.version, 2);
+    /// assert_eq!(header.entries[0].len, 0);
+    /// assert_eq!(header.entries[1].kind, 0);
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].path, "entries[1].kind");
+    /// assert_eq!(errors[0].pos, 5);
+    /// ```
+    pub fn read_recovering<T, R>(
+        reader: &mut R,
+        endian: Endian,
+        args: T::Args<'_>,
+    ) -> (BinResult<T>, Vec<RecoveredError>)
+    where
+        T: BinRead,
+        R: Read + Seek,
+    {
+        let recovering = Recovering {
+            previous: SESSION.replace(Some(Session::default())),
+        };
+
+        let result = T::read_options(reader, endian, args);
+
+        let errors = SESSION.take().unwrap_or_default().errors;
+        drop(recovering);
+        (result, errors)
+    }
+}
@@ -0,0 +1,969 @@
+//! Reading and writing types which implement [`serde`] traits.
+//!
+//! [`Deserializer`] and [`Serializer`] convert between serde data and a
+//! compact binary format which uses the byte order of binrw. [`deserialize`]
+//! and [`serialize`] can be used with
+//! [`parse_with`](crate::docs::attribute#custom-parserswriters) and
+//! [`write_with`](crate::docs::attribute#custom-parserswriters) to include a
+//! type which only implements serde traits in a binrw object.
+//!
+//! The format is not self-describing, so the type being deserialized must be
+//! the same as the type which was serialized:
+//!
+//! | Data | Format
+//! |------|-------
+//! | `bool` | One byte which is `0` or `1`.
+//! | Integers and floats | Their size, in the configured byte order.
+//! | `char` | A `u32` containing the code point.
+//! | Strings and bytes | A [length prefix](LenPrefix), followed by the UTF-8 or raw bytes.
+//! | `Option` | One byte which is `0` for `None` or `1` for `Some`, followed by the value.
+//! | Sequences and maps | A [length prefix](LenPrefix), followed by each element or key and value.
+//! | Tuples and structs | Each field in order.
+//! | Enums | The index of the variant as a `u32`, followed by the data of the variant.
+//! | Unit values | Nothing.
+//!
+//! Errors raised by serde itself, like an invalid value for a type, are given
+//! the position of the start of the innermost sequence, struct, or enum which
+//! was being read when the error was raised.
+//!
+//! # Examples
+//!
+//! ```
+//! # use binrw::{prelude::*, io::Cursor, serde::{deserialize, serialize}};
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Debug, PartialEq, Deserialize, Serialize)]
+//! struct Settings {
+//!     name: String,
+//!     level: Option<u16>,
+//! }
+//!
+//! #[binrw]
+//! #[brw(big, magic = b"CFG")]
+//! # #[derive(Debug, PartialEq)]
+//! struct Config {
+//!     version: u8,
+//!     #[br(parse_with = deserialize)]
+//!     #[bw(write_with = serialize)]
+//!     settings: Settings,
+//! }
+//!
+//! let data = b"CFG\x01\0\0\0\x02hi\x01\0\x03";
+//! let config = Config::read(&mut Cursor::new(data)).unwrap();
+//! assert_eq!(config.settings, Settings { name: "hi".into(), level: Some(3) });
+//!
+//! let mut out = Cursor::new(Vec::new());
+//! config.write(&mut out).unwrap();
+//! assert_eq!(out.into_inner(), data);
+//! ```
+
+use crate::{
+    __private::not_enough_bytes,
+    BinRead, BinResult, BinWrite, Endian, Error,
+    io::{Read, Seek, Write},
+};
+use ::serde::{
+    Serialize,
+    de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor},
+    ser,
+};
+use core::fmt;
+
+/// The integer type used to store the lengths of strings, bytes, sequences,
+/// and maps.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LenPrefix {
+    /// A `u8`.
+    U8,
+    /// A `u16`.
+    U16,
+    /// A `u32`. This is the default.
+    #[default]
+    U32,
+    /// A `u64`.
+    U64,
+}
+
+/// A message from serde, which is given a position once it reaches the value
+/// which was being read when it was raised.
+#[derive(Debug)]
+struct Message {
+    text: String,
+    located: bool,
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+fn message(text: impl fmt::Display) -> Error {
+    Error::Custom {
+        pos: 0,
+        err: Box::new(Message {
+            text: text.to_string(),
+            located: false,
+        }),
+    }
+}
+
+/// Gives any error from serde which does not have a position yet the
+/// position `pos`.
+fn locate<T>(result: BinResult<T>, pos: u64) -> BinResult<T> {
+    result.map_err(|mut error| {
+        if let Error::Custom {
+            pos: error_pos,
+            err,
+        } = &mut error
+            && let Some(message) = err.downcast_mut::<Message>()
+            && !message.located
+        {
+            *error_pos = pos;
+            message.located = true;
+        }
+        error
+    })
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        message(msg)
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        message(msg)
+    }
+}
+
+/// Reads a value using a [`Deserializer`] with the default length prefix.
+///
+/// # Errors
+///
+/// If reading or deserializing fails, an [`Error`] variant will be returned.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{prelude::*, io::Cursor, serde::deserialize};
+/// #[derive(BinRead)]
+/// struct Message {
+///     #[br(parse_with = deserialize)]
+///     tags: Vec<(u8, bool)>,
+/// }
+///
+/// let message = Message::read_le(&mut Cursor::new(b"\x01\0\0\0\x07\x01")).unwrap();
+/// assert_eq!(message.tags, [(7, true)]);
+/// ```
+#[binrw::parser(reader, endian)]
+pub fn deserialize<T: DeserializeOwned>() -> BinResult<T> {
+    Deserializer::new(reader, endian).deserialize()
+}
+
+/// Creates a parser which reads a value using a [`Deserializer`] with the
+/// given length prefix.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{prelude::*, io::Cursor, serde::{deserialize_with, LenPrefix}};
+/// #[derive(BinRead)]
+/// struct Message {
+///     #[br(parse_with = deserialize_with(LenPrefix::U8))]
+///     text: String,
+/// }
+///
+/// let message = Message::read_le(&mut Cursor::new(b"\x02hi")).unwrap();
+/// assert_eq!(message.text, "hi");
+/// ```
+pub fn deserialize_with<R, T>(len_prefix: LenPrefix) -> impl Fn(&mut R, Endian, ()) -> BinResult<T>
+where
+    R: Read + Seek,
+    T: DeserializeOwned,
+{
+    move |reader, endian, ()| {
+        Deserializer::new(reader, endian)
+            .with_len_prefix(len_prefix)
+            .deserialize()
+    }
+}
+
+/// Writes a value using a [`Serializer`] with the default length prefix.
+///
+/// # Errors
+///
+/// If serializing or writing fails, an [`Error`] variant will be returned.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{prelude::*, io::Cursor, serde::serialize};
+/// #[derive(BinWrite)]
+/// struct Message {
+///     #[bw(write_with = serialize)]
+///     tags: Vec<(u8, bool)>,
+/// }
+///
+/// let mut out = Cursor::new(Vec::new());
+/// Message { tags: vec![(7, true)] }.write_le(&mut out).unwrap();
+/// assert_eq!(out.into_inner(), b"\x01\0\0\0\x07\x01");
+/// ```
+#[binrw::writer(writer, endian)]
+pub fn serialize<T: Serialize + ?Sized>(value: &T) -> BinResult<()> {
+    Serializer::new(writer, endian).serialize(value)
+}
+
+/// Creates a writer which writes a value using a [`Serializer`] with the given
+/// length prefix.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{prelude::*, io::Cursor, serde::{serialize_with, LenPrefix}};
+/// #[derive(BinWrite)]
+/// struct Message {
+///     #[bw(write_with = serialize_with(LenPrefix::U8))]
+///     text: String,
+/// }
+///
+/// let mut out = Cursor::new(Vec::new());
+/// Message { text: "hi".into() }.write_le(&mut out).unwrap();
+/// assert_eq!(out.into_inner(), b"\x02hi");
+/// ```
+pub fn serialize_with<W, T>(
+    len_prefix: LenPrefix,
+) -> impl Fn(&T, &mut W, Endian, ()) -> BinResult<()>
+where
+    W: Write + Seek,
+    T: Serialize + ?Sized,
+{
+    move |value, writer, endian, ()| {
+        Serializer::new(writer, endian)
+            .with_len_prefix(len_prefix)
+            .serialize(value)
+    }
+}
+
+/// A serde [`Deserializer`](de::Deserializer) which reads from a binrw
+/// reader.
+///
+/// See the [module documentation](self) for the format which is read.
+#[derive(Debug)]
+pub struct Deserializer<'a, R> {
+    reader: &'a mut R,
+    endian: Endian,
+    len_prefix: LenPrefix,
+}
+
+impl<'a, R: Read + Seek> Deserializer<'a, R> {
+    /// Creates a deserializer which reads from `reader` using the given byte
+    /// order and the default length prefix.
+    pub fn new(reader: &'a mut R, endian: Endian) -> Self {
+        Self {
+            reader,
+            endian,
+            len_prefix: LenPrefix::default(),
+        }
+    }
+
+    /// Sets the type used for length prefixes.
+    #[must_use]
+    pub fn with_len_prefix(mut self, len_prefix: LenPrefix) -> Self {
+        self.len_prefix = len_prefix;
+        self
+    }
+
+    /// Deserializes a value of type `T`.
+    ///
+    /// # Errors
+    ///
+    /// If reading or deserializing fails, an [`Error`] variant will be
+    /// returned.
+    pub fn deserialize<T: DeserializeOwned>(&mut self) -> BinResult<T> {
+        T::deserialize(self)
+    }
+
+    fn pos(&mut self) -> u64 {
+        self.reader.stream_position().unwrap_or_default()
+    }
+
+    fn read<T>(&mut self) -> BinResult<T>
+    where
+        T: for<'b> BinRead<Args<'b> = ()>,
+    {
+        T::read_options(self.reader, self.endian, ())
+    }
+
+    fn read_len(&mut self) -> BinResult<usize> {
+        let pos = self.pos();
+        let len = match self.len_prefix {
+            LenPrefix::U8 => u64::from(self.read::<u8>()?),
+            LenPrefix::U16 => u64::from(self.read::<u16>()?),
+            LenPrefix::U32 => u64::from(self.read::<u32>()?),
+            LenPrefix::U64 => self.read::<u64>()?,
+        };
+        usize::try_from(len).map_err(|_| Error::AssertFail {
+            pos,
+            message: format!("length {len} is too large"),
+        })
+    }
+
+    fn read_bytes(&mut self) -> BinResult<Vec<u8>> {
+        let len = self.read_len()?;
+        // The length is not trusted to allocate the buffer up front
+        let mut bytes = Vec::new();
+        let read = self.reader.take(len as u64).read_to_end(&mut bytes)?;
+        if read == len {
+            Ok(bytes)
+        } else {
+            Err(not_enough_bytes())
+        }
+    }
+
+    fn read_tag(&mut self, what: &str) -> BinResult<bool> {
+        let pos = self.pos();
+        match self.read::<u8>()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => Err(Error::AssertFail {
+                pos,
+                message: format!("invalid {what} {tag:#x}"),
+            }),
+        }
+    }
+
+    fn visit_seq<'de, V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> BinResult<V::Value> {
+        let pos = self.pos();
+        locate(
+            visitor.visit_seq(Access {
+                de: self,
+                remaining: len,
+            }),
+            pos,
+        )
+    }
+}
+
+macro_rules! deserialize_primitive {
+    ($($method:ident => $visit:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+                visitor.$visit(self.read::<$ty>()?)
+            }
+        )*
+    };
+}
+
+impl<'de, R: Read + Seek> de::Deserializer<'de> for &mut Deserializer<'_, R> {
+    type Error = Error;
+
+    deserialize_primitive! {
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_i128 => visit_i128(i128),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_u128 => visit_u128(u128),
+        deserialize_f32 => visit_f32(f32),
+        deserialize_f64 => visit_f64(f64),
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> BinResult<V::Value> {
+        Err(Error::AssertFail {
+            pos: self.pos(),
+            message: "the binrw serde format requires the type of each value to be known".into(),
+        })
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        visitor.visit_bool(self.read_tag("bool")?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        let pos = self.pos();
+        let code = self.read::<u32>()?;
+        let c = char::from_u32(code).ok_or_else(|| Error::AssertFail {
+            pos,
+            message: format!("invalid char {code:#x}"),
+        })?;
+        visitor.visit_char(c)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        let pos = self.pos();
+        let bytes = self.read_bytes()?;
+        let text = String::from_utf8(bytes).map_err(|err| Error::Custom {
+            pos,
+            err: Box::new(err),
+        })?;
+        visitor.visit_string(text)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        visitor.visit_byte_buf(self.read_bytes()?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        if self.read_tag("option tag")? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> BinResult<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> BinResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        let len = self.read_len()?;
+        self.visit_seq(len, visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> BinResult<V::Value> {
+        self.visit_seq(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> BinResult<V::Value> {
+        self.visit_seq(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        let pos = self.pos();
+        let len = self.read_len()?;
+        locate(
+            visitor.visit_map(Access {
+                de: self,
+                remaining: len,
+            }),
+            pos,
+        )
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> BinResult<V::Value> {
+        self.visit_seq(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> BinResult<V::Value> {
+        let pos = self.pos();
+        locate(visitor.visit_enum(self), pos)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> BinResult<V::Value> {
+        self.deserialize_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Access to the elements of a sequence, struct, or map.
+struct Access<'s, 'a, R> {
+    de: &'s mut Deserializer<'a, R>,
+    remaining: usize,
+}
+
+impl<'de, R: Read + Seek> de::SeqAccess<'de> for Access<'_, '_, R> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> BinResult<Option<T::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de, R: Read + Seek> de::MapAccess<'de> for Access<'_, '_, R> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> BinResult<Option<K::Value>> {
+        de::SeqAccess::next_element_seed(self, seed)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> BinResult<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de, R: Read + Seek> de::EnumAccess<'de> for &mut Deserializer<'_, R> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> BinResult<(V::Value, Self)> {
+        let index = self.read::<u32>()?;
+        let variant = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(index))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, R: Read + Seek> de::VariantAccess<'de> for &mut Deserializer<'_, R> {
+    type Error = Error;
+
+    fn unit_variant(self) -> BinResult<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> BinResult<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> BinResult<V::Value> {
+        self.visit_seq(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> BinResult<V::Value> {
+        self.visit_seq(fields.len(), visitor)
+    }
+}
+
+/// A serde [`Serializer`](ser::Serializer) which writes to a binrw writer.
+///
+/// See the [module documentation](self) for the format which is written.
+#[derive(Debug)]
+pub struct Serializer<'a, W> {
+    writer: &'a mut W,
+    endian: Endian,
+    len_prefix: LenPrefix,
+}
+
+impl<'a, W: Write + Seek> Serializer<'a, W> {
+    /// Creates a serializer which writes to `writer` using the given byte
+    /// order and the default length prefix.
+    pub fn new(writer: &'a mut W, endian: Endian) -> Self {
+        Self {
+            writer,
+            endian,
+            len_prefix: LenPrefix::default(),
+        }
+    }
+
+    /// Sets the type used for length prefixes.
+    #[must_use]
+    pub fn with_len_prefix(mut self, len_prefix: LenPrefix) -> Self {
+        self.len_prefix = len_prefix;
+        self
+    }
+
+    /// Serializes `value`.
+    ///
+    /// # Errors
+    ///
+    /// If serializing or writing fails, an [`Error`] variant will be
+    /// returned.
+    pub fn serialize<T: Serialize + ?Sized>(&mut self, value: &T) -> BinResult<()> {
+        let pos = self.writer.stream_position().unwrap_or_default();
+        locate(value.serialize(self), pos)
+    }
+
+    fn write<T>(&mut self, value: &T) -> BinResult<()>
+    where
+        T: for<'b> BinWrite<Args<'b> = ()>,
+    {
+        value.write_options(self.writer, self.endian, ())
+    }
+
+    fn write_len(&mut self, len: Option<usize>) -> BinResult<()> {
+        let len = len.ok_or_else(|| message("the length of a sequence or map must be known"))?;
+        let too_large = |_| {
+            message(format!(
+                "length {len} does not fit in {:?}",
+                self.len_prefix
+            ))
+        };
+        match self.len_prefix {
+            LenPrefix::U8 => self.write(&u8::try_from(len).map_err(too_large)?),
+            LenPrefix::U16 => self.write(&u16::try_from(len).map_err(too_large)?),
+            LenPrefix::U32 => self.write(&u32::try_from(len).map_err(too_large)?),
+            LenPrefix::U64 => self.write(&(len as u64)),
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> BinResult<()> {
+        self.write_len(Some(bytes.len()))?;
+        self.writer.write_all(bytes)?;
+        Ok(())
+    }
+}
+
+macro_rules! serialize_primitive {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, value: $ty) -> BinResult<()> {
+                self.write(&value)
+            }
+        )*
+    };
+}
+
+impl<W: Write + Seek> ser::Serializer for &mut Serializer<'_, W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    serialize_primitive! {
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+    }
+
+    fn serialize_bool(self, value: bool) -> BinResult<()> {
+        self.write(&u8::from(value))
+    }
+
+    fn serialize_char(self, value: char) -> BinResult<()> {
+        self.write(&u32::from(value))
+    }
+
+    fn serialize_str(self, value: &str) -> BinResult<()> {
+        self.write_bytes(value.as_bytes())
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> BinResult<()> {
+        self.write_bytes(value)
+    }
+
+    fn serialize_none(self) -> BinResult<()> {
+        self.write(&0u8)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> BinResult<()> {
+        self.write(&1u8)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> BinResult<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> BinResult<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, index: u32, _: &'static str) -> BinResult<()> {
+        self.write(&index)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> BinResult<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> BinResult<()> {
+        self.write(&index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> BinResult<Self> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _: usize) -> BinResult<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> BinResult<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> BinResult<Self> {
+        self.write(&index)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> BinResult<Self> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> BinResult<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> BinResult<Self> {
+        self.write(&index)?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! serialize_compound {
+    ($($trait:ident::$method:ident$(($key:ty))?),* $(,)?) => {
+        $(
+            impl<W: Write + Seek> ser::$trait for &mut Serializer<'_, W> {
+                type Ok = ();
+                type Error = Error;
+
+                fn $method<T: Serialize + ?Sized>(
+                    &mut self,
+                    $(_: $key,)?
+                    value: &T,
+                ) -> BinResult<()> {
+                    value.serialize(&mut **self)
+                }
+
+                fn end(self) -> BinResult<()> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+serialize_compound! {
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+    SerializeStruct::serialize_field(&'static str),
+    SerializeStructVariant::serialize_field(&'static str),
+}
+
+impl<W: Write + Seek> ser::SerializeMap for &mut Serializer<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> BinResult<()> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> BinResult<()> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> BinResult<()> {
+        Ok(())
+    }
+}
+
+/// Serializes a struct with two fields, for the types in this crate which are
+/// serialized as structs.
+pub(crate) fn serialize_pair<S, A, B>(
+    serializer: S,
+    name: &'static str,
+    fields: &'static [&'static str; 2],
+    a: &A,
+    b: &B,
+) -> Result<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+    A: Serialize + ?Sized,
+    B: Serialize + ?Sized,
+{
+    use ser::SerializeStruct;
+
+    let mut state = serializer.serialize_struct(name, 2)?;
+    state.serialize_field(fields[0], a)?;
+    state.serialize_field(fields[1], b)?;
+    state.end()
+}
+
+/// Deserializes a struct with two fields which was serialized by
+/// [`serialize_pair`].
+pub(crate) fn deserialize_pair<'de, D, A, B>(
+    deserializer: D,
+    name: &'static str,
+    fields: &'static [&'static str; 2],
+) -> Result<(A, B), D::Error>
+where
+    D: de::Deserializer<'de>,
+    A: de::Deserialize<'de>,
+    B: de::Deserialize<'de>,
+{
+    deserializer.deserialize_struct(
+        name,
+        fields,
+        PairVisitor {
+            name,
+            fields,
+            marker: core::marker::PhantomData,
+        },
+    )
+}
+
+struct PairVisitor<A, B> {
+    name: &'static str,
+    fields: &'static [&'static str; 2],
+    marker: core::marker::PhantomData<(A, B)>,
+}
+
+impl<'de, A, B> Visitor<'de> for PairVisitor<A, B>
+where
+    A: de::Deserialize<'de>,
+    B: de::Deserialize<'de>,
+{
+    type Value = (A, B);
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "struct {}", self.name)
+    }
+
+    fn visit_seq<S: de::SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        let a = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let b = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok((a, b))
+    }
+
+    fn visit_map<M: de::MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+        let (mut a, mut b) = (None, None);
+        while let Some(index) = map.next_key_seed(FieldVisitor(self.fields))? {
+            match index {
+                Some(0) if a.is_none() => a = Some(map.next_value()?),
+                Some(1) if b.is_none() => b = Some(map.next_value()?),
+                Some(index) => return Err(de::Error::duplicate_field(self.fields[index])),
+                None => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        Ok((
+            a.ok_or_else(|| de::Error::missing_field(self.fields[0]))?,
+            b.ok_or_else(|| de::Error::missing_field(self.fields[1]))?,
+        ))
+    }
+}
+
+/// Finds the index of a field in a [`PairVisitor`], or `None` for an unknown
+/// field.
+#[derive(Clone, Copy)]
+struct FieldVisitor(&'static [&'static str; 2]);
+
+impl<'de> DeserializeSeed<'de> for FieldVisitor {
+    type Value = Option<usize>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl Visitor<'_> for FieldVisitor {
+    type Value = Option<usize>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a field identifier")
+    }
+
+    fn visit_u64<E: de::Error>(self, index: u64) -> Result<Self::Value, E> {
+        Ok(usize::try_from(index).ok().filter(|index| *index < 2))
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        Ok(self.0.iter().position(|field| *field == name))
+    }
+
+    fn visit_bytes<E: de::Error>(self, name: &[u8]) -> Result<Self::Value, E> {
+        Ok(self.0.iter().position(|field| field.as_bytes() == name))
+    }
+}
@@ -0,0 +1,425 @@
+//! Recording of the byte ranges of every value in a parsed object.
+//!
+//! [`read_with_spans`] reads an object while recording a tree of [`Span`]s.
+//! Derived `BinRead` implementations record a span for each field and for the
+//! variant of an enum which was read, [`Vec`] records a span for each
+//! element, and [`FilePtr`](crate::FilePtr) records a span for the value it
+//! points to. Vectors of integers which are read in bulk do not record spans
+//! for their elements.
+//!
+//! [`write_with_spans`] does the same while writing an object, except that
+//! pointers do not record the values they point to.
+//!
+//! A span tree can be printed as an annotated hexdump using
+//! [`Span::hexdump`].
+//!
+//! Recording is done per thread, and only objects which are read or written
+//! by the current thread inside of [`read_with_spans`] or [`write_with_spans`]
+//! are recorded.
+//!
+//! # Examples
+//!
+//! ```
+//! # use binrw::{prelude::*, io::Cursor, spans::read_with_spans, Endian};
+//! #[derive(BinRead)]
+//! #[br(big)]
+//! struct Header {
+//!     len: u16,
+//!     #[br(count = len)]
+//!     points: Vec<Point>,
+//! }
+//!
+//! #[derive(BinRead)]
+//! struct Point(u8, u8);
+//!
+//! let data = b"\0\x02\x01\x02\x03\x04";
+//! let (header, span) =
+//!     read_with_spans::<Header, _>(&mut Cursor::new(data), Endian::Big, ());
+//! assert!(header.is_ok());
+//! assert_eq!(span.path_to(&[1, 1, 0]), Some(("points[1].0".into(), 4..5)));
+//!
+//! // The dump has a line for the offset, bytes, path, and type of each value:
+//! // 00000000  00 02 [...]  len: u16
+//! // 00000002  01    [...]  points[0].0: u8
+//! let dump = span.hexdump(data).to_string();
+//! let line = dump.lines().nth(3).unwrap();
+//! assert!(line.starts_with("00000004  03 "));
+//! assert!(line.ends_with("  points[1].0: u8"));
+//! ```
+
+use crate::{
+    BinRead, BinResult, BinWrite, Endian,
+    io::{Read, Seek, Write},
+};
+use core::{cell::RefCell, fmt, ops::Range};
+
+/// The name of a [`Span`] within its parent.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpanName {
+    /// The top-level object.
+    Root,
+    /// A named or numbered field.
+    Field(&'static str),
+    /// The variant of an enum which was read or written.
+    Variant(&'static str),
+    /// An element of a collection.
+    Index(usize),
+    /// The value pointed to by a [`FilePtr`](crate::FilePtr).
+    Pointee,
+}
+
+/// The byte range of a value which was read or written.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Span {
+    /// The name of the value within its parent.
+    pub name: SpanName,
+    /// The name of the type of the value.
+    pub ty: &'static str,
+    /// The position of the first byte of the value.
+    pub start: u64,
+    /// The position after the last byte of the value.
+    ///
+    /// If reading or writing the value failed, this is the end of the last
+    /// child which was finished.
+    pub end: u64,
+    /// The spans of the values inside of this value.
+    pub children: Vec<Span>,
+}
+
+impl Span {
+    fn new(name: SpanName, ty: &'static str, start: u64) -> Self {
+        Self {
+            name,
+            ty,
+            start,
+            end: start,
+            children: Vec::new(),
+        }
+    }
+
+    /// Returns the byte range of the value.
+    #[must_use]
+    pub fn range(&self) -> Range<u64> {
+        self.start..self.end
+    }
+
+    /// Returns the path and byte range of the descendant found by following
+    /// the given child indexes.
+    #[must_use]
+    pub fn path_to(&self, indexes: &[usize]) -> Option<(String, Range<u64>)> {
+        let mut path = String::new();
+        push_segment(&mut path, self.name);
+        let mut span = self;
+        for &index in indexes {
+            span = span.children.get(index)?;
+            push_segment(&mut path, span.name);
+        }
+        Some((path, span.range()))
+    }
+
+    /// Returns the path and byte range of the innermost value which contains
+    /// the byte at `pos`.
+    #[must_use]
+    pub fn path_at(&self, pos: u64) -> Option<(String, Range<u64>)> {
+        if !self.range().contains(&pos) {
+            return None;
+        }
+
+        let mut indexes = Vec::new();
+        let mut span = self;
+        while let Some((index, child)) = span
+            .children
+            .iter()
+            .enumerate()
+            .find(|(_, child)| child.range().contains(&pos))
+        {
+            indexes.push(index);
+            span = child;
+        }
+        self.path_to(&indexes)
+    }
+
+    /// Returns an object which displays the span tree as an annotated
+    /// hexdump of `data`, which must be the data that was read or written.
+    ///
+    /// Each value without children is printed on its own line with its path
+    /// and type, and values longer than 16 bytes are split over several
+    /// lines.
+    #[must_use]
+    pub fn hexdump<'a>(&'a self, data: &'a [u8]) -> HexDump<'a> {
+        HexDump { span: self, data }
+    }
+
+    fn write_leaves(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        data: &[u8],
+        path: &mut String,
+    ) -> fmt::Result {
+        let len = path.len();
+        push_segment(path, self.name);
+
+        if self.children.is_empty() {
+            write_leaf(f, data, self.range(), path, self.ty)?;
+        } else {
+            for child in &self.children {
+                child.write_leaves(f, data, path)?;
+            }
+        }
+
+        path.truncate(len);
+        Ok(())
+    }
+}
+
+/// An annotated hexdump of a [`Span`] tree.
+///
+/// This type is returned by [`Span::hexdump`].
+#[derive(Clone, Copy, Debug)]
+pub struct HexDump<'a> {
+    span: &'a Span,
+    data: &'a [u8],
+}
+
+impl fmt::Display for HexDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.span.write_leaves(f, self.data, &mut String::new())
+    }
+}
+
+const BYTES_PER_LINE: usize = 16;
+
+fn write_leaf(
+    f: &mut fmt::Formatter<'_>,
+    data: &[u8],
+    range: Range<u64>,
+    path: &str,
+    ty: &str,
+) -> fmt::Result {
+    let start = usize::try_from(range.start).unwrap_or(usize::MAX);
+    let end = usize::try_from(range.end).unwrap_or(usize::MAX);
+    let bytes = data.get(start..end.min(data.len())).unwrap_or_default();
+
+    let mut lines = bytes.chunks(BYTES_PER_LINE);
+    let first = lines.next().unwrap_or_default();
+    write!(f, "{:08x} ", range.start)?;
+    write_bytes(f, first)?;
+    // Pad short lines so that annotations line up
+    let padding = (BYTES_PER_LINE - first.len()) * 3;
+    writeln!(f, "{:padding$}  {path}: {}", "", short_type_name(ty))?;
+
+    for (index, line) in lines.enumerate() {
+        write!(f, "{:08x} ", start + (index + 1) * BYTES_PER_LINE)?;
+        write_bytes(f, line)?;
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+fn write_bytes(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    for byte in bytes {
+        write!(f, " {byte:02x}")?;
+    }
+    Ok(())
+}
+
+fn push_segment(path: &mut String, name: SpanName) {
+    match name {
+        SpanName::Root => {}
+        SpanName::Field(name) | SpanName::Variant(name) => {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(name);
+        }
+        SpanName::Index(index) => {
+            path.push('[');
+            path.push_str(&index.to_string());
+            path.push(']');
+        }
+        SpanName::Pointee => path.push_str(".*"),
+    }
+}
+
+/// Removes the module paths from a type name.
+fn short_type_name(ty: &str) -> String {
+    let mut out = String::with_capacity(ty.len());
+    let mut word = String::new();
+    for c in ty.chars().chain(core::iter::once(' ')) {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            word.push(c);
+        } else {
+            out.push_str(word.rsplit("::").next().unwrap_or(&word));
+            word.clear();
+            out.push(c);
+        }
+    }
+    out.pop();
+    out
+}
+
+std::thread_local! {
+    /// The stack of spans which are currently being read or written, or
+    /// `None` if spans are not being recorded.
+    static STACK: RefCell<Option<Vec<Span>>> = const { RefCell::new(None) };
+}
+
+/// The depth returned when no span was started.
+pub(crate) const NO_SPAN: usize = usize::MAX;
+
+/// Starts a new span at the current position of the reader, returning its
+/// depth in the stack.
+pub(crate) fn enter<R: Seek>(reader: &mut R, name: SpanName, ty: &'static str) -> usize {
+    STACK.with_borrow_mut(|stack| {
+        let Some(stack) = stack else {
+            return NO_SPAN;
+        };
+        let Ok(pos) = reader.stream_position() else {
+            return NO_SPAN;
+        };
+        stack.push(Span::new(name, ty, pos));
+        stack.len() - 1
+    })
+}
+
+/// Ends the span at `depth` at the current position of the reader.
+///
+/// Any spans inside of it which were not ended, because reading or writing
+/// them failed, are ended first.
+pub(crate) fn exit<R: Seek>(reader: &mut R, depth: usize) {
+    STACK.with_borrow_mut(|stack| {
+        let Some(stack) = stack.as_mut().filter(|stack| depth < stack.len()) else {
+            return;
+        };
+        let Ok(pos) = reader.stream_position() else {
+            return;
+        };
+        unwind(stack, depth + 1);
+        if let Some(mut span) = stack.pop() {
+            span.end = pos.max(span.start);
+            if let Some(parent) = stack.last_mut() {
+                parent.children.push(span);
+            }
+        }
+    });
+}
+
+/// Removes the span at `depth`, and everything inside of it.
+pub(crate) fn discard(depth: usize) {
+    STACK.with_borrow_mut(|stack| {
+        if let Some(stack) = stack {
+            stack.truncate(depth);
+        }
+    });
+}
+
+/// Ends every span deeper than `len` at the end of its last child.
+fn unwind(stack: &mut Vec<Span>, len: usize) {
+    while stack.len() > len.max(1) {
+        let Some(mut span) = stack.pop() else {
+            break;
+        };
+        span.end = span
+            .children
+            .last()
+            .map_or(span.start, |child| child.end)
+            .max(span.start);
+        if let Some(parent) = stack.last_mut() {
+            parent.children.push(span);
+        }
+    }
+}
+
+/// Restores the previous recording state when dropped, even if reading or
+/// writing panics.
+struct Recording {
+    previous: Option<Vec<Span>>,
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        STACK.set(self.previous.take());
+    }
+}
+
+/// Reads `T` from the reader while recording the byte range of every value
+/// inside of it.
+///
+/// The span tree is returned even if reading fails, in which case it
+/// contains every value which was read before the failure.
+pub fn read_with_spans<T, R>(
+    reader: &mut R,
+    endian: Endian,
+    args: T::Args<'_>,
+) -> (BinResult<T>, Span)
+where
+    T: BinRead,
+    R: Read + Seek,
+{
+    record::<T, _, _>(reader, |reader| T::read_options(reader, endian, args))
+}
+
+/// Writes `value` to the writer while recording the byte range of every value
+/// inside of it.
+///
+/// The span tree is returned even if writing fails, in which case it
+/// contains every value which was written before the failure.
+///
+/// # Examples
+///
+/// ```
+/// # use binrw::{prelude::*, io::Cursor, spans::write_with_spans, Endian};
+/// #[derive(BinWrite)]
+/// struct Point {
+///     x: u16,
+///     y: u16,
+/// }
+///
+/// let mut data = Cursor::new(Vec::new());
+/// let (result, span) =
+///     write_with_spans(&Point { x: 1, y: 2 }, &mut data, Endian::Little, ());
+/// assert!(result.is_ok());
+/// assert_eq!(span.path_to(&[1]), Some(("y".into(), 2..4)));
+/// ```
+pub fn write_with_spans<T, W>(
+    value: &T,
+    writer: &mut W,
+    endian: Endian,
+    args: T::Args<'_>,
+) -> (BinResult<()>, Span)
+where
+    T: BinWrite + ?Sized,
+    W: Write + Seek,
+{
+    record::<T, _, _>(writer, |writer| value.write_options(writer, endian, args))
+}
+
+/// Records the spans of the values read or written by `f`, with a root span
+/// for the type `T`.
+fn record<T: ?Sized, S: Seek, V>(
+    stream: &mut S,
+    f: impl FnOnce(&mut S) -> BinResult<V>,
+) -> (BinResult<V>, Span) {
+    let start = stream.stream_position().unwrap_or_default();
+    let root = Span::new(SpanName::Root, core::any::type_name::<T>(), start);
+    let recording = Recording {
+        previous: STACK.replace(Some(vec![root])),
+    };
+
+    let result = f(stream);
+    let end = stream.stream_position().ok();
+
+    let mut stack = STACK.take().unwrap_or_default();
+    drop(recording);
+    unwind(&mut stack, 1);
+    let mut root = stack
+        .pop()
+        .unwrap_or_else(|| Span::new(SpanName::Root, core::any::type_name::<T>(), start));
+    root.end = match (&result, end) {
+        (Ok(_), Some(end)) => end,
+        _ => root.children.last().map_or(start, |child| child.end),
+    }
+    .max(start);
+
+    (result, root)
+}
@@ -1,12 +1,14 @@
 //! Type definitions for string readers.
 
 use crate::{
-    BinRead, BinResult, BinWrite, Endian,
+    BinRead, BinResult, BinWrite, Endian, Error, NamedArgs, VecArgs,
     alloc::string::{FromUtf8Error, FromUtf16Error},
+    encoding::Encoding,
     io::{Read, Seek, Write},
+    meta::StaticSize,
 };
 #[cfg(not(feature = "std"))]
-use alloc::{string::String, vec, vec::Vec};
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
 use core::fmt::{self, Write as _};
 
 /// A null-terminated 8-bit string.
@@ -61,15 +63,42 @@ impl BinWrite for NullString {
         &self,
         writer: &mut W,
         endian: Endian,
-        args: Self::Args<'_>,
+        (): Self::Args<'_>,
     ) -> BinResult<()> {
-        self.0.write_options(writer, endian, args)?;
-        0u8.write_options(writer, endian, args)?;
+        self.0.write_options(writer, endian, ())?;
+        0u8.write_options(writer, endian, ())?;
 
         Ok(())
     }
 }
 
+// The string ends at the first null, so the generated string cannot contain
+// any nulls
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for NullString {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let bytes = u.arbitrary_iter::<u8>()?;
+        bytes
+            .filter(|byte| !matches!(byte, Ok(0)))
+            .collect::<arbitrary::Result<_>>()
+            .map(Self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for NullString {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for NullString {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<u8>::deserialize(deserializer).map(Self)
+    }
+}
+
 impl From<&str> for NullString {
     fn from(s: &str) -> Self {
         Self(s.as_bytes().to_vec())
@@ -124,6 +153,219 @@ impl fmt::Display for NullString {
     }
 }
 
+/// A null-terminated string in a text [encoding](crate::encoding), which is
+/// decoded into a [`String`].
+///
+/// The encoding is given in [`EncodedStringArgs`]. The text is decoded when
+/// reading and encoded again when writing. The null terminator is one code
+/// unit of the encoding, and is consumed and not included in the value.
+///
+/// ```
+/// use binrw::{BinRead, BinWrite, EncodedString, encoding::Utf16Le, io::Cursor};
+///
+/// #[derive(BinRead, BinWrite)]
+/// #[brw(little)]
+/// struct Entry {
+///     #[brw(args { encoding: &Utf16Le })]
+///     title: EncodedString,
+/// }
+///
+/// let entry = Entry::read(&mut Cursor::new(b"b\0c\0\0\0")).unwrap();
+/// assert_eq!(entry.title.as_str(), "bc");
+///
+/// let mut out = Cursor::new(Vec::new());
+/// entry.write(&mut out).unwrap();
+/// assert_eq!(out.into_inner(), b"b\0c\0\0\0");
+/// ```
+#[derive(Clone, Eq, PartialEq, Default)]
+pub struct EncodedString(
+    /// The decoded text.
+    pub String,
+);
+
+/// Named arguments for the [`BinRead`] and [`BinWrite`] implementations of
+/// [`EncodedString`].
+#[derive(Clone, Copy, NamedArgs)]
+pub struct EncodedStringArgs {
+    /// The encoding of the text.
+    ///
+    /// The encoding is `'static` so that the arguments do not borrow, which
+    /// lets parsers like [`FilePtr::parse`](crate::FilePtr::parse) read an
+    /// `EncodedString`.
+    pub encoding: &'static dyn Encoding,
+}
+
+impl BinRead for EncodedString {
+    type Args<'a> = EncodedStringArgs;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let encoding = args.encoding;
+        let pos = reader.stream_position()?;
+        let mut unit = vec![0; encoding.unit_len()];
+        let mut bytes = vec![];
+
+        loop {
+            reader.read_exact(&mut unit)?;
+            if unit.iter().all(|byte| *byte == 0) {
+                break;
+            }
+            bytes.extend_from_slice(&unit);
+        }
+
+        decode(encoding, &bytes, endian, pos).map(Self)
+    }
+}
+
+impl BinWrite for EncodedString {
+    type Args<'a> = EncodedStringArgs;
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let encoding = args.encoding;
+        let pos = writer.stream_position()?;
+        writer.write_all(&encode(encoding, &self.0, endian, pos)?)?;
+        writer.write_all(&vec![0; encoding.unit_len()])?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for EncodedString {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for EncodedString {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self)
+    }
+}
+
+impl From<&str> for EncodedString {
+    fn from(s: &str) -> Self {
+        Self(s.into())
+    }
+}
+
+impl From<String> for EncodedString {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<EncodedString> for String {
+    fn from(s: EncodedString) -> Self {
+        s.0
+    }
+}
+
+impl core::ops::Deref for EncodedString {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl core::ops::DerefMut for EncodedString {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl fmt::Debug for EncodedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EncodedString({:?})", self.0)
+    }
+}
+
+impl fmt::Display for EncodedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A borrowed null-terminated 8-bit string.
+///
+/// This is the zero-copy equivalent of [`NullString`], which borrows its bytes
+/// from the buffer being read. See the [`borrowed`](crate::borrowed) module
+/// for more information.
+///
+/// The null terminator is consumed and not included in the value.
+#[derive(Clone, Copy, Eq, PartialEq, Default)]
+pub struct NullStr<'data>(
+    /// The raw byte string.
+    pub &'data [u8],
+);
+
+impl BinWrite for NullStr<'_> {
+    type Args<'a> = ();
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        self.0.write_options(writer, endian, args)?;
+        0u8.write_options(writer, endian, args)?;
+
+        Ok(())
+    }
+}
+
+impl<'data> From<&'data str> for NullStr<'data> {
+    fn from(s: &'data str) -> Self {
+        Self(s.as_bytes())
+    }
+}
+
+impl From<NullStr<'_>> for NullString {
+    fn from(s: NullStr<'_>) -> Self {
+        Self(s.0.to_vec())
+    }
+}
+
+impl<'data> TryFrom<NullStr<'data>> for &'data str {
+    type Error = core::str::Utf8Error;
+
+    fn try_from(value: NullStr<'data>) -> Result<Self, Self::Error> {
+        core::str::from_utf8(value.0)
+    }
+}
+
+impl core::ops::Deref for NullStr<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl fmt::Debug for NullStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NullStr(\"")?;
+        display_utf8(self.0, f, str::escape_debug)?;
+        write!(f, "\")")
+    }
+}
+
+impl fmt::Display for NullStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display_utf8(self.0, f, str::chars)
+    }
+}
+
 /// A null-terminated 16-bit string.
 ///
 /// The null terminator must also be 16-bits, and is consumed and not included
@@ -192,6 +434,31 @@ impl BinWrite for NullWideString {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for NullWideString {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let units = u.arbitrary_iter::<u16>()?;
+        units
+            .filter(|unit| !matches!(unit, Ok(0)))
+            .collect::<arbitrary::Result<_>>()
+            .map(Self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for NullWideString {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for NullWideString {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<u16>::deserialize(deserializer).map(Self)
+    }
+}
+
 impl From<NullWideString> for Vec<u16> {
     fn from(s: NullWideString) -> Self {
         s.0
@@ -246,6 +513,553 @@ impl fmt::Debug for NullWideString {
     }
 }
 
+/// How a fixed-width string is terminated and padded.
+///
+/// This is passed to [`FixedString`] and [`FixedWideString`] in
+/// [`FixedStringArgs`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Padding {
+    /// The string ends at the first null, and is padded with nulls when
+    /// written. Any data after the first null is ignored.
+    #[default]
+    Null,
+    /// Trailing spaces are removed, and the string is padded with spaces when
+    /// written.
+    Space,
+    /// Like [`Null`](Self::Null), but reading fails if anything other than
+    /// nulls follows the first null.
+    Strict,
+}
+
+impl Padding {
+    fn fill<T: From<u8>>(self) -> T {
+        match self {
+            Self::Null | Self::Strict => T::from(0),
+            Self::Space => T::from(b' '),
+        }
+    }
+}
+
+/// Named arguments for the [`BinRead`] and [`BinWrite`] implementations of
+/// [`FixedString`] and [`FixedWideString`].
+///
+/// Without an encoding, the string holds the raw code units. With an
+/// [encoding](crate::encoding), the bytes are decoded when reading and stored
+/// as UTF-8 or UTF-16 text, and the text is encoded again when writing. The
+/// width of the string in bytes does not change, and padding and terminators
+/// use code units of the encoding.
+///
+/// ```
+/// use binrw::{BinRead, FixedString, encoding::Latin1, io::Cursor, strings::Padding};
+///
+/// #[derive(BinRead)]
+/// #[br(little)]
+/// struct Header {
+///     #[br(args { padding: Padding::Space, encoding: Some(&Latin1) })]
+///     name: FixedString<6>,
+/// }
+///
+/// let header = Header::read(&mut Cursor::new(b"Zo\xeb   ")).unwrap();
+/// assert_eq!(header.name.to_string(), "Zoë");
+/// ```
+#[derive(Clone, Copy, Default, NamedArgs)]
+pub struct FixedStringArgs {
+    /// How the string is terminated and padded. Defaults to [`Padding::Null`].
+    #[named_args(default = Padding::Null)]
+    pub padding: Padding,
+    /// The encoding of the text. Defaults to `None`, which reads and writes
+    /// the code units unchanged.
+    #[named_args(default = None)]
+    pub encoding: Option<&'static dyn Encoding>,
+}
+
+/// Reads exactly `len` units and removes the terminator and padding.
+fn read_fixed<T, R>(
+    reader: &mut R,
+    endian: Endian,
+    len: usize,
+    padding: Padding,
+) -> BinResult<Vec<T>>
+where
+    T: for<'a> BinRead<Args<'a> = ()> + Copy + PartialEq + From<u8> + 'static,
+    R: Read + Seek,
+{
+    let pos = reader.stream_position()?;
+    let mut units = Vec::<T>::read_options(
+        reader,
+        endian,
+        VecArgs {
+            count: len,
+            inner: (),
+        },
+    )?;
+
+    let null = T::from(0);
+    let end = match padding {
+        Padding::Null => units.iter().position(|unit| *unit == null),
+        Padding::Space => Some(
+            units
+                .iter()
+                .rposition(|unit| *unit != T::from(b' '))
+                .map_or(0, |last| last + 1),
+        ),
+        Padding::Strict => {
+            let end = units.iter().position(|unit| *unit == null);
+            if let Some(end) = end
+                && let Some(garbage) = units[end..].iter().position(|unit| *unit != null)
+            {
+                return Err(Error::AssertFail {
+                    pos: pos + ((end + garbage) * core::mem::size_of::<T>()) as u64,
+                    message: "unexpected data after the null terminator of a fixed string".into(),
+                });
+            }
+            end
+        }
+    };
+    if let Some(end) = end {
+        units.truncate(end);
+    }
+    Ok(units)
+}
+
+/// Writes `units` followed by enough padding to fill `len` units.
+fn write_fixed<T, W>(
+    units: &[T],
+    writer: &mut W,
+    endian: Endian,
+    len: usize,
+    padding: Padding,
+) -> BinResult<()>
+where
+    T: for<'a> BinWrite<Args<'a> = ()> + Clone + From<u8> + 'static,
+    W: Write + Seek,
+{
+    if units.len() > len {
+        return Err(Error::AssertFail {
+            pos: writer.stream_position()?,
+            message: format!(
+                "string of length {} is too long for a fixed string of length {len}",
+                units.len()
+            ),
+        });
+    }
+
+    units.write_options(writer, endian, ())?;
+    vec![padding.fill::<T>(); len - units.len()].write_options(writer, endian, ())
+}
+
+/// Reads exactly `len` bytes, removes the terminator and padding, and decodes
+/// the rest.
+fn read_fixed_encoded<R: Read + Seek>(
+    reader: &mut R,
+    endian: Endian,
+    len: usize,
+    padding: Padding,
+    encoding: &dyn Encoding,
+) -> BinResult<String> {
+    let pos = reader.stream_position()?;
+    let mut bytes = vec![0; len];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(crate::__private::restore_position(reader, pos))?;
+
+    let unit_len = encoding.unit_len();
+    let null = bytes
+        .chunks_exact(unit_len)
+        .position(|unit| unit.iter().all(|byte| *byte == 0))
+        .map(|end| end * unit_len);
+    if padding == Padding::Strict
+        && let Some(end) = null
+        && let Some(garbage) = bytes[end..].iter().position(|byte| *byte != 0)
+    {
+        return Err(Error::AssertFail {
+            pos: pos + (end + garbage) as u64,
+            message: "unexpected data after the null terminator of a fixed string".into(),
+        });
+    }
+
+    match padding {
+        Padding::Null | Padding::Strict => {
+            bytes.truncate(null.unwrap_or(len));
+            decode(encoding, &bytes, endian, pos)
+        }
+        Padding::Space => {
+            let mut text = decode(encoding, &bytes, endian, pos)?;
+            text.truncate(text.trim_end_matches(' ').len());
+            Ok(text)
+        }
+    }
+}
+
+/// Encodes `text` and writes it followed by enough padding to fill `len`
+/// bytes.
+fn write_fixed_encoded<W: Write + Seek>(
+    text: &str,
+    writer: &mut W,
+    endian: Endian,
+    len: usize,
+    padding: Padding,
+    encoding: &dyn Encoding,
+) -> BinResult<()> {
+    let pos = writer.stream_position()?;
+    let mut bytes = encode(encoding, text, endian, pos)?;
+    if bytes.len() > len {
+        return Err(Error::AssertFail {
+            pos,
+            message: format!(
+                "encoded string of {} bytes is too long for a fixed string of {len} bytes",
+                bytes.len()
+            ),
+        });
+    }
+
+    // Spaces are encoded with the text so that encodings with a byte order
+    // mark only write it once
+    if padding == Padding::Space {
+        let spaces = (len - bytes.len()) / encoding.unit_len();
+        bytes = encode(encoding, &format!("{text}{:spaces$}", ""), endian, pos)?;
+    }
+    bytes.resize(len, 0);
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+// Padding and terminators are removed when reading, so the generated string
+// cannot contain nulls or end with a space
+#[cfg(feature = "arbitrary")]
+fn arbitrary_fixed<'a, T>(
+    u: &mut arbitrary::Unstructured<'a>,
+    len: usize,
+) -> arbitrary::Result<Vec<T>>
+where
+    T: arbitrary::Arbitrary<'a> + PartialEq + From<u8>,
+{
+    let mut units = u
+        .arbitrary_iter::<T>()?
+        .filter(|unit| !matches!(unit, Ok(unit) if *unit == T::from(0)))
+        .take(len)
+        .collect::<arbitrary::Result<Vec<_>>>()?;
+    while units.last().is_some_and(|unit| *unit == T::from(b' ')) {
+        units.pop();
+    }
+    Ok(units)
+}
+
+/// A fixed-width 8-bit string which always takes up `N` bytes.
+///
+/// The [`Padding`] argument controls how the end of the string is found when
+/// reading, and what is used to fill the remaining bytes when writing. A
+/// string which uses all `N` bytes does not need a terminator. Writing a
+/// string longer than `N` bytes returns an error. An optional
+/// [encoding](crate::encoding) can also be given in [`FixedStringArgs`].
+///
+/// ```
+/// use binrw::{BinRead, BinWrite, FixedString, io::Cursor, strings::Padding};
+///
+/// #[derive(BinRead, BinWrite)]
+/// #[brw(little)]
+/// struct Header {
+///     name: FixedString<8>,
+///     #[brw(args { padding: Padding::Space })]
+///     kind: FixedString<4>,
+/// }
+///
+/// let header = Header::read(&mut Cursor::new(b"ship\0\0\0\0A   ")).unwrap();
+/// assert_eq!(header.name.to_string(), "ship");
+/// assert_eq!(header.kind.to_string(), "A");
+///
+/// let mut out = Cursor::new(Vec::new());
+/// Header { name: "boat".into(), kind: "BC".into() }.write(&mut out).unwrap();
+/// assert_eq!(out.into_inner(), b"boat\0\0\0\0BC  ");
+/// ```
+#[derive(Clone, Eq, PartialEq, Default)]
+pub struct FixedString<const N: usize>(
+    /// The raw byte string, without padding. With an
+    /// [encoding](FixedStringArgs::encoding), this is the decoded text as
+    /// UTF-8.
+    pub Vec<u8>,
+);
+
+impl<const N: usize> BinRead for FixedString<N> {
+    type Args<'a> = FixedStringArgs;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        match args.encoding {
+            Some(encoding) => read_fixed_encoded(reader, endian, N, args.padding, encoding)
+                .map(|text| Self(text.into_bytes())),
+            None => read_fixed(reader, endian, N, args.padding).map(Self),
+        }
+    }
+}
+
+impl<const N: usize> BinWrite for FixedString<N> {
+    type Args<'a> = FixedStringArgs;
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let Some(encoding) = args.encoding else {
+            return write_fixed(&self.0, writer, endian, N, args.padding);
+        };
+
+        let pos = writer.stream_position()?;
+        let text = core::str::from_utf8(&self.0).map_err(|err| Error::Custom {
+            pos,
+            err: Box::new(err),
+        })?;
+        write_fixed_encoded(text, writer, endian, N, args.padding, encoding)
+    }
+}
+
+impl<const N: usize> StaticSize for FixedString<N> {
+    const SIZE: usize = N;
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize> arbitrary::Arbitrary<'a> for FixedString<N> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_fixed(u, N).map(Self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> ::serde::Serialize for FixedString<N> {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> ::serde::Deserialize<'de> for FixedString<N> {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<u8>::deserialize(deserializer).map(Self)
+    }
+}
+
+impl<const N: usize> From<&str> for FixedString<N> {
+    fn from(s: &str) -> Self {
+        Self(s.as_bytes().to_vec())
+    }
+}
+
+impl<const N: usize> From<String> for FixedString<N> {
+    fn from(s: String) -> Self {
+        Self(s.into_bytes())
+    }
+}
+
+impl<const N: usize> From<FixedString<N>> for Vec<u8> {
+    fn from(s: FixedString<N>) -> Self {
+        s.0
+    }
+}
+
+impl<const N: usize> TryFrom<FixedString<N>> for String {
+    type Error = FromUtf8Error;
+
+    fn try_from(value: FixedString<N>) -> Result<Self, Self::Error> {
+        String::from_utf8(value.0)
+    }
+}
+
+impl<const N: usize> core::ops::Deref for FixedString<N> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> core::ops::DerefMut for FixedString<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FixedString<{N}>(\"")?;
+        display_utf8(&self.0, f, str::escape_debug)?;
+        write!(f, "\")")
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display_utf8(&self.0, f, str::chars)
+    }
+}
+
+/// A fixed-width 16-bit string which always takes up `N` 16-bit units.
+///
+/// This is the 16-bit version of [`FixedString`]. The padding uses 16-bit
+/// units in the same byte order as the string. With an
+/// [encoding](crate::encoding), the string still takes up `2 * N` bytes.
+///
+/// ```
+/// use binrw::{BinReaderExt, FixedWideString, io::Cursor};
+///
+/// let name = Cursor::new(b"h\0i\0\0\0\0\0").read_le::<FixedWideString<4>>().unwrap();
+/// assert_eq!(name.to_string(), "hi");
+/// ```
+#[derive(Clone, Eq, PartialEq, Default)]
+pub struct FixedWideString<const N: usize>(
+    /// The raw wide byte string, without padding. With an
+    /// [encoding](FixedStringArgs::encoding), this is the decoded text as
+    /// UTF-16.
+    pub Vec<u16>,
+);
+
+impl<const N: usize> BinRead for FixedWideString<N> {
+    type Args<'a> = FixedStringArgs;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        match args.encoding {
+            Some(encoding) => read_fixed_encoded(reader, endian, N * 2, args.padding, encoding)
+                .map(|text| Self(text.encode_utf16().collect())),
+            None => read_fixed(reader, endian, N, args.padding).map(Self),
+        }
+    }
+}
+
+impl<const N: usize> BinWrite for FixedWideString<N> {
+    type Args<'a> = FixedStringArgs;
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let Some(encoding) = args.encoding else {
+            return write_fixed(&self.0, writer, endian, N, args.padding);
+        };
+
+        let pos = writer.stream_position()?;
+        let text = String::from_utf16(&self.0).map_err(|err| Error::Custom {
+            pos,
+            err: Box::new(err),
+        })?;
+        write_fixed_encoded(&text, writer, endian, N * 2, args.padding, encoding)
+    }
+}
+
+impl<const N: usize> StaticSize for FixedWideString<N> {
+    const SIZE: usize = N * 2;
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize> arbitrary::Arbitrary<'a> for FixedWideString<N> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_fixed(u, N).map(Self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> ::serde::Serialize for FixedWideString<N> {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> ::serde::Deserialize<'de> for FixedWideString<N> {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<u16>::deserialize(deserializer).map(Self)
+    }
+}
+
+impl<const N: usize> From<FixedWideString<N>> for Vec<u16> {
+    fn from(s: FixedWideString<N>) -> Self {
+        s.0
+    }
+}
+
+impl<const N: usize> From<&str> for FixedWideString<N> {
+    fn from(s: &str) -> Self {
+        Self(s.encode_utf16().collect())
+    }
+}
+
+impl<const N: usize> From<String> for FixedWideString<N> {
+    fn from(s: String) -> Self {
+        Self(s.encode_utf16().collect())
+    }
+}
+
+impl<const N: usize> TryFrom<FixedWideString<N>> for String {
+    type Error = FromUtf16Error;
+
+    fn try_from(value: FixedWideString<N>) -> Result<Self, Self::Error> {
+        String::from_utf16(&value.0)
+    }
+}
+
+impl<const N: usize> core::ops::Deref for FixedWideString<N> {
+    type Target = Vec<u16>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> core::ops::DerefMut for FixedWideString<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedWideString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display_utf16(&self.0, f, core::iter::once)
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedWideString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FixedWideString<{N}>(\"")?;
+        display_utf16(&self.0, f, char::escape_debug)?;
+        write!(f, "\")")
+    }
+}
+
+/// Decodes `bytes`, which start at `pos` in the stream.
+pub(crate) fn decode(
+    encoding: &dyn Encoding,
+    bytes: &[u8],
+    endian: Endian,
+    pos: u64,
+) -> BinResult<String> {
+    encoding.decode(bytes, endian).map_err(|err| Error::Custom {
+        pos: pos + err.offset as u64,
+        err: Box::new(err),
+    })
+}
+
+/// Encodes `text`, which is written at `pos` in the stream.
+pub(crate) fn encode(
+    encoding: &dyn Encoding,
+    text: &str,
+    endian: Endian,
+    pos: u64,
+) -> BinResult<Vec<u8>> {
+    encoding.encode(text, endian).map_err(|err| Error::Custom {
+        pos,
+        err: Box::new(err),
+    })
+}
+
 fn display_utf16<Transformer: Fn(char) -> O, O: Iterator<Item = char>>(
     input: &[u16],
     f: &mut fmt::Formatter<'_>,
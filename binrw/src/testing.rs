@@ -0,0 +1,289 @@
+//! Assertions for testing binrw objects.
+//!
+//! These functions panic with a hexdump of the differences between the
+//! expected and actual data when a test fails. The dump starts at the first
+//! byte which differs, and names the field which was written at that position:
+//!
+//! ```text
+//! data written for `Header` does not match the expected data
+//! first difference at 0x11, in `entries[1].len` (0x10..0x12)
+//!   00000000  48 44 02 00 00 00 00 00 00 00 00 00 00 00 00 00
+//! - 00000010  01 02 03
+//! + 00000010  01 09 03
+//!                ^^
+//! ```
+//!
+//! Golden files store the expected data of a test on disk. When the format of
+//! an object changes on purpose, the files can be updated by running the tests
+//! with the environment variable `BINRW_BLESS=1`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use binrw::{prelude::*, testing::{assert_roundtrip, assert_writes_to}, Endian};
+//! #[binrw]
+//! #[brw(big)]
+//! #[derive(Debug, PartialEq)]
+//! struct Point {
+//!     x: u16,
+//!     y: u16,
+//! }
+//!
+//! let point = assert_roundtrip::<Point>(b"\0\x01\0\x02", Endian::Big, ());
+//! assert_eq!(point, Point { x: 1, y: 2 });
+//! assert_writes_to(&Point { x: 3, y: 4 }, b"\0\x03\0\x04");
+//! ```
+
+use crate::{
+    __private::Required,
+    BinRead, BinWrite, Endian,
+    io::Cursor,
+    meta::WriteEndian,
+    spans::{Span, write_with_spans},
+};
+use core::{any::type_name, fmt, ops::Range};
+use std::{fs, path::Path};
+
+/// The environment variable which makes golden file assertions update their
+/// files instead of failing.
+pub const BLESS_VAR: &str = "BINRW_BLESS";
+
+/// The number of lines shown before and after the first difference.
+const CONTEXT_LINES: usize = 2;
+
+const BYTES_PER_LINE: usize = 16;
+
+/// Reads `T` from `bytes`, writes it back, and asserts that the written data
+/// is the same as `bytes`.
+///
+/// The same arguments are used for reading and writing. Use
+/// [`assert_roundtrip_with`] if they are different.
+///
+/// Returns the value which was read.
+///
+/// # Panics
+///
+/// Panics if reading or writing fails, or if the written data is different.
+#[track_caller]
+pub fn assert_roundtrip<'a, T>(bytes: &[u8], endian: Endian, args: <T as BinRead>::Args<'a>) -> T
+where
+    T: BinRead + BinWrite<Args<'a> = <T as BinRead>::Args<'a>>,
+    <T as BinRead>::Args<'a>: Clone,
+{
+    assert_roundtrip_with::<T>(bytes, endian, args.clone(), args)
+}
+
+/// Like [`assert_roundtrip`], but with separate arguments for reading and
+/// writing.
+///
+/// # Panics
+///
+/// Panics if reading or writing fails, or if the written data is different.
+#[track_caller]
+pub fn assert_roundtrip_with<T>(
+    bytes: &[u8],
+    endian: Endian,
+    read_args: <T as BinRead>::Args<'_>,
+    write_args: <T as BinWrite>::Args<'_>,
+) -> T
+where
+    T: BinRead + BinWrite,
+{
+    let value = T::read_options(&mut Cursor::new(bytes), endian, read_args)
+        .unwrap_or_else(|error| panic!("failed to read `{}`: {error}", type_name::<T>()));
+    assert_writes_to_with(&value, endian, write_args, bytes);
+    value
+}
+
+/// Writes `value` and asserts that the written data is `expected`.
+///
+/// # Panics
+///
+/// Panics if writing fails, or if the written data is different.
+#[track_caller]
+pub fn assert_writes_to<T>(value: &T, expected: &[u8])
+where
+    T: BinWrite + WriteEndian + ?Sized,
+    for<'a> T::Args<'a>: Required,
+{
+    assert_writes_to_with(value, Endian::Little, T::Args::args(), expected);
+}
+
+/// Like [`assert_writes_to`], but with the given byte order and arguments.
+///
+/// # Panics
+///
+/// Panics if writing fails, or if the written data is different.
+#[track_caller]
+pub fn assert_writes_to_with<T>(value: &T, endian: Endian, args: T::Args<'_>, expected: &[u8])
+where
+    T: BinWrite + ?Sized,
+{
+    let (actual, span) = write(value, endian, args);
+    if let Some(mismatch) = Mismatch::new::<T>(expected, &actual, &span) {
+        panic!("{mismatch}");
+    }
+}
+
+/// Writes `value` and asserts that the written data is the same as the
+/// contents of the golden file at `path`.
+///
+/// If the environment variable [`BINRW_BLESS`](BLESS_VAR) is set to anything
+/// other than `0`, the file is replaced with the written data instead.
+///
+/// # Panics
+///
+/// Panics if writing fails, if the golden file does not exist, or if the
+/// written data is different.
+#[track_caller]
+pub fn assert_golden<T>(value: &T, path: impl AsRef<Path>)
+where
+    T: BinWrite + WriteEndian + ?Sized,
+    for<'a> T::Args<'a>: Required,
+{
+    assert_golden_with(value, Endian::Little, T::Args::args(), path);
+}
+
+/// Like [`assert_golden`], but with the given byte order and arguments.
+///
+/// # Panics
+///
+/// Panics if writing fails, if the golden file does not exist, or if the
+/// written data is different.
+#[track_caller]
+pub fn assert_golden_with<T>(value: &T, endian: Endian, args: T::Args<'_>, path: impl AsRef<Path>)
+where
+    T: BinWrite + ?Sized,
+{
+    let path = path.as_ref();
+    let (actual, span) = write(value, endian, args);
+
+    if std::env::var_os(BLESS_VAR).is_some_and(|bless| bless != "0") {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .unwrap_or_else(|error| panic!("failed to create `{}`: {error}", parent.display()));
+        }
+        fs::write(path, &actual)
+            .unwrap_or_else(|error| panic!("failed to write `{}`: {error}", path.display()));
+        return;
+    }
+
+    let expected = fs::read(path).unwrap_or_else(|error| {
+        panic!(
+            "failed to read golden file `{}`: {error}\nrun with {BLESS_VAR}=1 to create it",
+            path.display()
+        )
+    });
+    if let Some(mismatch) = Mismatch::new::<T>(&expected, &actual, &span) {
+        panic!(
+            "{mismatch}golden file: `{}`\nrun with {BLESS_VAR}=1 to update it",
+            path.display()
+        );
+    }
+}
+
+#[track_caller]
+fn write<T: BinWrite + ?Sized>(value: &T, endian: Endian, args: T::Args<'_>) -> (Vec<u8>, Span) {
+    let mut writer = Cursor::new(Vec::new());
+    let (result, span) = write_with_spans(value, &mut writer, endian, args);
+    if let Err(error) = result {
+        panic!("failed to write `{}`: {error}", type_name::<T>());
+    }
+    (writer.into_inner(), span)
+}
+
+/// The first difference between the expected and written data.
+struct Mismatch<'a> {
+    ty: &'static str,
+    expected: &'a [u8],
+    actual: &'a [u8],
+    offset: usize,
+    field: Option<(String, Range<u64>)>,
+}
+
+impl<'a> Mismatch<'a> {
+    fn new<T: ?Sized>(expected: &'a [u8], actual: &'a [u8], span: &Span) -> Option<Self> {
+        let offset = expected
+            .iter()
+            .zip(actual)
+            .position(|(expected, actual)| expected != actual)
+            .or_else(|| {
+                (expected.len() != actual.len()).then(|| expected.len().min(actual.len()))
+            })?;
+        let field = span
+            .path_at(offset as u64)
+            .filter(|(path, _)| !path.is_empty());
+        Some(Self {
+            ty: type_name::<T>(),
+            expected,
+            actual,
+            offset,
+            field,
+        })
+    }
+
+    fn write_line(
+        f: &mut fmt::Formatter<'_>,
+        marker: char,
+        data: &[u8],
+        line: usize,
+    ) -> fmt::Result {
+        let start = line * BYTES_PER_LINE;
+        write!(f, "{marker} {start:08x} ")?;
+        for byte in line_bytes(data, line).unwrap_or_default() {
+            write!(f, " {byte:02x}")?;
+        }
+        writeln!(f)
+    }
+}
+
+fn line_bytes(data: &[u8], line: usize) -> Option<&[u8]> {
+    data.chunks(BYTES_PER_LINE).nth(line)
+}
+
+impl fmt::Display for Mismatch<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "data written for `{}` does not match the expected data",
+            self.ty
+        )?;
+        write!(f, "first difference at {:#x}", self.offset)?;
+        if let Some((path, range)) = &self.field {
+            write!(f, ", in `{path}` ({:#x}..{:#x})", range.start, range.end)?;
+        }
+        writeln!(f)?;
+        if self.expected.len() != self.actual.len() {
+            writeln!(
+                f,
+                "expected {} bytes, but {} bytes were written",
+                self.expected.len(),
+                self.actual.len()
+            )?;
+        }
+
+        let first_line = self.offset / BYTES_PER_LINE;
+        for line in first_line.saturating_sub(CONTEXT_LINES)..=first_line + CONTEXT_LINES {
+            let expected = line_bytes(self.expected, line);
+            let actual = line_bytes(self.actual, line);
+            if expected == actual {
+                if expected.is_some() {
+                    Self::write_line(f, ' ', self.expected, line)?;
+                }
+                continue;
+            }
+
+            if expected.is_some() {
+                Self::write_line(f, '-', self.expected, line)?;
+            }
+            if actual.is_some() {
+                Self::write_line(f, '+', self.actual, line)?;
+            }
+            if line == first_line {
+                let column = 12 + (self.offset % BYTES_PER_LINE) * 3;
+                writeln!(f, "{:column$}^^", "")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,501 @@
+//! Type definitions for variable-length integers.
+//!
+//! [`VarUint`] and [`VarInt`] store an integer in as few bytes as possible,
+//! using 7 bits of each byte for the value and the remaining bit to mark
+//! whether another byte follows. The [format](VarIntArgs::format) decides how
+//! the bits are arranged:
+//!
+//! | Type | Format | Used by
+//! |------|--------|--------
+//! | `VarUint` | [`UnsignedFormat::Leb128`] (default) | DWARF, WebAssembly, protobuf
+//! | `VarUint` | [`UnsignedFormat::Vlq`] | MIDI
+//! | `VarInt` | [`SignedFormat::Leb128`] (default) | DWARF, WebAssembly
+//! | `VarInt` | [`SignedFormat::ZigZag`] | protobuf `sint32` and `sint64`
+//!
+//! The byte order of the reader or writer is not used, since each format
+//! defines its own.
+//!
+//! # Examples
+//!
+//! ```
+//! # use binrw::{prelude::*, io::Cursor, VarInt, VarUint, varint::UnsignedFormat};
+//! #[binrw]
+//! #[brw(little)]
+//! # #[derive(Debug, PartialEq)]
+//! struct Event {
+//!     #[brw(args { format: UnsignedFormat::Vlq, max_len: 4 })]
+//!     delta_time: VarUint,
+//!     offset: VarInt,
+//! }
+//!
+//! let event = Event::read(&mut Cursor::new(b"\x81\x00\x7f")).unwrap();
+//! assert_eq!(u64::from(event.delta_time), 128);
+//! assert_eq!(i64::from(event.offset), -1);
+//!
+//! let mut out = Cursor::new(Vec::new());
+//! event.write(&mut out).unwrap();
+//! assert_eq!(out.into_inner(), b"\x81\x00\x7f");
+//! ```
+
+use crate::{
+    BinRead, BinResult, BinWrite, Endian, Error, NamedArgs,
+    io::{Read, Seek, Write},
+};
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec::Vec};
+use core::num::TryFromIntError;
+
+/// The most bytes needed to store a 64-bit integer with 7 bits per byte.
+const MAX_LEN: usize = 10;
+
+/// The format of a [`VarUint`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum UnsignedFormat {
+    /// Unsigned [LEB128](https://en.wikipedia.org/wiki/LEB128), which stores
+    /// the least significant group of 7 bits first.
+    #[default]
+    Leb128,
+    /// A [variable-length quantity](https://en.wikipedia.org/wiki/Variable-length_quantity),
+    /// which stores the most significant group of 7 bits first.
+    Vlq,
+}
+
+/// The format of a [`VarInt`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SignedFormat {
+    /// Signed [LEB128](https://en.wikipedia.org/wiki/LEB128), which stores
+    /// the value in two’s complement with the least significant group of 7
+    /// bits first.
+    #[default]
+    Leb128,
+    /// Unsigned LEB128 of the [zigzag encoding](https://protobuf.dev/programming-guides/encoding/#signed-ints)
+    /// of the value, which maps `0, -1, 1, -2, …` to `0, 1, 2, 3, …`.
+    ZigZag,
+}
+
+/// Named arguments for the [`BinRead`] and [`BinWrite`] implementations of
+/// [`VarUint`] and [`VarInt`].
+#[derive(Clone, Copy, Debug, NamedArgs)]
+pub struct VarIntArgs<Format: Default> {
+    /// The format of the integer.
+    #[named_args(default = Format::default())]
+    pub format: Format,
+
+    /// The maximum number of bytes in the integer. Reading or writing an
+    /// integer which needs more bytes returns an error.
+    ///
+    /// The default is 10, which is enough for every 64-bit integer.
+    #[named_args(default = MAX_LEN)]
+    pub max_len: usize,
+
+    /// If true, reading an integer which is not stored in as few bytes as
+    /// possible returns an error.
+    #[named_args(default = false)]
+    pub strict: bool,
+}
+
+impl<Format: Default> Default for VarIntArgs<Format> {
+    fn default() -> Self {
+        Self {
+            format: Format::default(),
+            max_len: MAX_LEN,
+            strict: false,
+        }
+    }
+}
+
+/// A variable-length unsigned integer.
+///
+/// See the [module documentation](self) for the supported formats.
+///
+/// ```
+/// use binrw::{BinReaderExt, VarUint, io::Cursor};
+///
+/// let value = Cursor::new(b"\xe5\x8e\x26").read_le::<VarUint>().unwrap();
+/// assert_eq!(value, VarUint(624_485));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct VarUint(
+    /// The value.
+    pub u64,
+);
+
+/// A variable-length signed integer.
+///
+/// See the [module documentation](self) for the supported formats.
+///
+/// ```
+/// use binrw::{BinReaderExt, VarInt, io::Cursor};
+///
+/// let value = Cursor::new(b"\xc0\xbb\x78").read_le::<VarInt>().unwrap();
+/// assert_eq!(value, VarInt(-123_456));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct VarInt(
+    /// The value.
+    pub i64,
+);
+
+impl BinRead for VarUint {
+    type Args<'a> = VarIntArgs<UnsignedFormat>;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let pos = reader.stream_position()?;
+        read_unsigned(reader, endian, &args, pos)
+            .map(Self)
+            .map_err(crate::__private::restore_position(reader, pos))
+    }
+}
+
+impl BinWrite for VarUint {
+    type Args<'a> = VarIntArgs<UnsignedFormat>;
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let mut bytes = to_leb128(self.0);
+        if args.format == UnsignedFormat::Vlq {
+            // The same groups, most significant first, with the continuation
+            // bit on every byte but the last
+            bytes.reverse();
+            let last = bytes.len() - 1;
+            for (index, byte) in bytes.iter_mut().enumerate() {
+                *byte = if index == last {
+                    *byte & 0x7f
+                } else {
+                    *byte | 0x80
+                };
+            }
+        }
+        write_groups(&bytes, writer, endian, args.max_len)
+    }
+}
+
+impl BinRead for VarInt {
+    type Args<'a> = VarIntArgs<SignedFormat>;
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let pos = reader.stream_position()?;
+        read_signed(reader, endian, &args, pos)
+            .map(Self)
+            .map_err(crate::__private::restore_position(reader, pos))
+    }
+}
+
+impl BinWrite for VarInt {
+    type Args<'a> = VarIntArgs<SignedFormat>;
+
+    fn write_options<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<()> {
+        let bytes = match args.format {
+            SignedFormat::Leb128 => to_sleb128(self.0),
+            SignedFormat::ZigZag => to_leb128(to_zigzag(self.0)),
+        };
+        write_groups(&bytes, writer, endian, args.max_len)
+    }
+}
+
+/// Reads bytes up to and including the first byte without a continuation bit.
+fn read_unsigned<R: Read + Seek>(
+    reader: &mut R,
+    endian: Endian,
+    args: &VarIntArgs<UnsignedFormat>,
+    pos: u64,
+) -> BinResult<u64> {
+    let bytes = read_groups(reader, endian, args.max_len, pos)?;
+    let value = match args.format {
+        UnsignedFormat::Leb128 => {
+            if args.strict && bytes.len() > 1 && bytes[bytes.len() - 1] == 0 {
+                return Err(overlong(pos));
+            }
+            from_leb128(&bytes, pos)?
+        }
+        UnsignedFormat::Vlq => {
+            if args.strict && bytes.len() > 1 && bytes[0] == 0x80 {
+                return Err(overlong(pos));
+            }
+            bytes.iter().try_fold(0u64, |value, byte| {
+                if value >> 57 == 0 {
+                    Ok((value << 7) | u64::from(byte & 0x7f))
+                } else {
+                    Err(too_large(pos))
+                }
+            })?
+        }
+    };
+    Ok(value)
+}
+
+fn read_signed<R: Read + Seek>(
+    reader: &mut R,
+    endian: Endian,
+    args: &VarIntArgs<SignedFormat>,
+    pos: u64,
+) -> BinResult<i64> {
+    let bytes = read_groups(reader, endian, args.max_len, pos)?;
+    let value = match args.format {
+        SignedFormat::Leb128 => {
+            if args.strict
+                && let [.., prev, last] = bytes[..]
+                && (last == 0 && prev & 0x40 == 0 || last == 0x7f && prev & 0x40 != 0)
+            {
+                return Err(overlong(pos));
+            }
+            from_sleb128(&bytes, pos)?
+        }
+        SignedFormat::ZigZag => {
+            if args.strict && bytes.len() > 1 && bytes[bytes.len() - 1] == 0 {
+                return Err(overlong(pos));
+            }
+            from_zigzag(from_leb128(&bytes, pos)?)
+        }
+    };
+    Ok(value)
+}
+
+fn read_groups<R: Read + Seek>(
+    reader: &mut R,
+    endian: Endian,
+    max_len: usize,
+    pos: u64,
+) -> BinResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    loop {
+        if bytes.len() == max_len {
+            return Err(Error::AssertFail {
+                pos,
+                message: format!("variable-length integer is longer than {max_len} bytes"),
+            });
+        }
+        let byte = u8::read_options(reader, endian, ())?;
+        bytes.push(byte);
+        if byte & 0x80 == 0 {
+            return Ok(bytes);
+        }
+    }
+}
+
+fn write_groups<W: Write + Seek>(
+    bytes: &[u8],
+    writer: &mut W,
+    endian: Endian,
+    max_len: usize,
+) -> BinResult<()> {
+    if bytes.len() > max_len {
+        return Err(Error::AssertFail {
+            pos: writer.stream_position()?,
+            message: format!(
+                "variable-length integer needs {} bytes, but the maximum is {max_len}",
+                bytes.len()
+            ),
+        });
+    }
+    bytes.write_options(writer, endian, ())
+}
+
+fn overlong(pos: u64) -> Error {
+    Error::AssertFail {
+        pos,
+        message: "variable-length integer is not stored in as few bytes as possible".into(),
+    }
+}
+
+fn too_large(pos: u64) -> Error {
+    Error::AssertFail {
+        pos,
+        message: "variable-length integer does not fit in 64 bits".into(),
+    }
+}
+
+// Each group of 7 bits is at a multiple of 7, so the first nine groups hold
+// bits 0 to 62, and every later group only holds bits from 63 onwards
+
+fn from_leb128(bytes: &[u8], pos: u64) -> BinResult<u64> {
+    let mut value = 0;
+    for (index, byte) in bytes.iter().enumerate() {
+        let group = u64::from(byte & 0x7f);
+        match index {
+            0..9 => value |= group << (index * 7),
+            9 if group <= 1 => value |= group << 63,
+            _ if group == 0 => {}
+            _ => return Err(too_large(pos)),
+        }
+    }
+    Ok(value)
+}
+
+fn from_sleb128(bytes: &[u8], pos: u64) -> BinResult<i64> {
+    let mut value = 0;
+    for (index, byte) in bytes.iter().enumerate().take(9) {
+        value |= u64::from(byte & 0x7f) << (index * 7);
+    }
+
+    let last = bytes[bytes.len() - 1];
+    let negative = if let Some(high) = bytes.get(9..).filter(|high| !high.is_empty()) {
+        // Every bit from 63 onwards must be a copy of the sign bit
+        let mut groups = high.iter().map(|byte| byte & 0x7f);
+        if groups.clone().all(|group| group == 0) {
+            false
+        } else if groups.all(|group| group == 0x7f) {
+            true
+        } else {
+            return Err(too_large(pos));
+        }
+    } else {
+        last & 0x40 != 0
+    };
+
+    if negative {
+        value |= !0 << (bytes.len().min(9) * 7);
+    }
+    #[allow(clippy::cast_possible_wrap)]
+    Ok(value as i64)
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn from_zigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn to_zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn to_leb128(mut value: u64) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(MAX_LEN);
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+fn to_sleb128(mut value: i64) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(MAX_LEN);
+    loop {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+macro_rules! conversions {
+    (
+        $ty:ident($inner:ty),
+        from: [$($from:ty),*],
+        try_from: [$($try_from:ty),*],
+        into: [$($into:ty),*],
+        try_into: [$($try_into:ty),*] $(,)?
+    ) => {
+        $(impl From<$from> for $ty {
+            fn from(value: $from) -> Self {
+                Self(value.into())
+            }
+        })*
+
+        $(impl TryFrom<$try_from> for $ty {
+            type Error = TryFromIntError;
+
+            fn try_from(value: $try_from) -> Result<Self, Self::Error> {
+                <$inner>::try_from(value).map(Self)
+            }
+        })*
+
+        $(impl From<$ty> for $into {
+            fn from(value: $ty) -> Self {
+                value.0.into()
+            }
+        })*
+
+        $(impl TryFrom<$ty> for $try_into {
+            type Error = TryFromIntError;
+
+            fn try_from(value: $ty) -> Result<Self, Self::Error> {
+                <$try_into>::try_from(value.0)
+            }
+        })*
+    };
+}
+
+conversions!(
+    VarUint(u64),
+    from: [u8, u16, u32, u64],
+    try_from: [u128, usize, i8, i16, i32, i64, i128, isize],
+    into: [u64, u128, i128],
+    try_into: [u8, u16, u32, usize, i8, i16, i32, i64, isize],
+);
+
+conversions!(
+    VarInt(i64),
+    from: [u8, u16, u32, i8, i16, i32, i64],
+    try_from: [u64, u128, usize, i128, isize],
+    into: [i64, i128],
+    try_into: [u8, u16, u32, u64, u128, usize, i8, i16, i32, isize],
+);
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for VarUint {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u64::arbitrary(u).map(Self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for VarUint {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for VarUint {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u64::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for VarInt {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        i64::arbitrary(u).map(Self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for VarInt {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for VarInt {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(Self)
+    }
+}
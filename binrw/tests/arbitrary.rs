@@ -0,0 +1,129 @@
+use arbitrary::{Arbitrary, Unstructured};
+use binrw::{
+    BinRead, BinWrite, FixedString, NullString, binrw,
+    io::Cursor,
+    meta::{ReadEndian, WriteEndian},
+    strings::Padding,
+};
+use core::fmt::Debug;
+
+/// Generates values from pseudo-random input and checks that each one reads
+/// back the same as it was written.
+fn round_trip<T>()
+where
+    T: for<'a> Arbitrary<'a> + BinWrite + ReadEndian + WriteEndian + Debug + PartialEq,
+    for<'a> T: BinRead<Args<'a> = ()>,
+    for<'a> T: BinWrite<Args<'a> = ()>,
+{
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut generated = 0;
+    for len in 0..500 {
+        let data = (0..len % 64)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state.to_le_bytes()[0]
+            })
+            .collect::<Vec<_>>();
+
+        let Ok(value) = T::arbitrary(&mut Unstructured::new(&data)) else {
+            continue;
+        };
+
+        let mut out = Cursor::new(Vec::new());
+        value.write(&mut out).unwrap();
+        let read = T::read(&mut Cursor::new(out.into_inner())).unwrap();
+        assert_eq!(read, value);
+        generated += 1;
+    }
+    assert!(generated > 10, "only {generated} values were generated");
+}
+
+#[binrw]
+#[br(arbitrary)]
+#[brw(big, magic = b"REC")]
+#[derive(Debug, PartialEq)]
+struct Record {
+    #[brw(count_of = items)]
+    len: u16,
+    #[br(assert(kind < 4))]
+    kind: u8,
+    items: Vec<u16>,
+    name: NullString,
+    #[brw(args { padding: Padding::Space })]
+    label: FixedString<4>,
+}
+
+#[test]
+fn magic_count_of_and_assert() {
+    round_trip::<Record>();
+}
+
+#[derive(BinRead, BinWrite, Debug, PartialEq)]
+#[br(arbitrary)]
+#[brw(little)]
+#[br(assert(flags != 0 || data.is_empty()))]
+struct Packet {
+    flags: u8,
+    len: u8,
+    #[br(count = len)]
+    data: Vec<u8>,
+    #[br(calc = u16::from(len) * 2)]
+    #[bw(ignore)]
+    double_len: u16,
+    #[br(if(flags & 1 != 0))]
+    extra: Option<u32>,
+    #[br(try_calc = u8::try_from(data.len() % 7))]
+    #[bw(ignore)]
+    rem: u8,
+}
+
+#[test]
+fn count_calc_and_condition() {
+    round_trip::<Packet>();
+}
+
+#[derive(BinRead, BinWrite, Debug, PartialEq)]
+#[br(arbitrary)]
+#[brw(little)]
+enum Command {
+    #[brw(magic = 1u8)]
+    Move { x: i16, y: i16 },
+    #[brw(magic = 2u8)]
+    Say(u8, #[br(count = self_0)] Vec<u8>),
+    #[brw(magic = 3u8)]
+    Stop,
+}
+
+#[test]
+fn variant_magic() {
+    round_trip::<Command>();
+}
+
+#[derive(BinRead, BinWrite, Debug, PartialEq)]
+#[br(arbitrary)]
+#[brw(big)]
+struct Flags {
+    #[brw(bits = 3)]
+    mode: u8,
+    #[brw(bits = 1)]
+    enabled: bool,
+    #[brw(bits = 12)]
+    id: u16,
+    kind: Kind,
+}
+
+#[derive(BinRead, BinWrite, Debug, PartialEq)]
+#[br(arbitrary)]
+#[brw(repr = u8)]
+enum Kind {
+    A = 1,
+    B = 5,
+    C = 9,
+}
+
+#[test]
+fn bits_and_repr() {
+    round_trip::<Flags>();
+}
@@ -0,0 +1,353 @@
+use binrw::{
+    BinRead, BinReadAsync, BinResult, BinWrite, BinWriteAsync, EncodedString, Endian, FilePtr8,
+    NullString, NullWideString, binrw, binwrite,
+    encoding::Utf16Bom,
+    file_ptr::{PointeeQueue, write_with_pointees, write_with_pointees_async},
+    strings::EncodedStringArgs,
+};
+use futures_executor::block_on;
+use futures_util::io::Cursor;
+
+fn read<T>(data: &[u8], endian: Endian) -> (BinResult<T>, u64)
+where
+    T: BinReadAsync,
+    for<'a> T::Args<'a>: Default,
+{
+    let mut reader = Cursor::new(data);
+    let result = block_on(T::read_options_async(
+        &mut reader,
+        endian,
+        Default::default(),
+    ));
+    (result, reader.position())
+}
+
+fn write<T>(value: &T, endian: Endian) -> Vec<u8>
+where
+    T: BinWriteAsync + Sync,
+    for<'a> T::Args<'a>: Default,
+{
+    let mut writer = Cursor::new(Vec::new());
+    block_on(value.write_options_async(&mut writer, endian, Default::default())).unwrap();
+    writer.into_inner()
+}
+
+#[derive(BinRead, BinReadAsync, BinWrite, BinWriteAsync, Debug, PartialEq)]
+#[brw(big, magic = b"HD")]
+struct Header {
+    count: u8,
+    #[br(count = count)]
+    items: Vec<Item>,
+    #[brw(align_after = 4)]
+    name: NullString,
+    #[br(map = |value: u8| value != 0)]
+    #[bw(map = |value: &bool| u8::from(*value))]
+    flag: bool,
+    #[brw(pad_before = 2, restore_position)]
+    peek: u16,
+    #[brw(pad_before = 2)]
+    tail: u16,
+    #[br(calc = count * 2)]
+    #[bw(ignore)]
+    doubled: u8,
+}
+
+#[derive(BinRead, BinReadAsync, BinWrite, BinWriteAsync, Debug, PartialEq)]
+enum Item {
+    #[brw(magic = 1u8)]
+    Short(u8),
+    #[brw(magic = 2u8)]
+    Long { value: u32 },
+    #[brw(magic = 3u8)]
+    Empty,
+}
+
+#[derive(BinRead, BinReadAsync, BinWrite, BinWriteAsync, Clone, Copy, Debug, PartialEq)]
+#[brw(repr = u16)]
+enum Kind {
+    A = 1,
+    B = 0x200,
+}
+
+#[derive(BinRead, BinReadAsync, BinWrite, BinWriteAsync, Debug, PartialEq)]
+#[br(map = |raw: u32| Self(raw.swap_bytes()))]
+#[bw(map = |this: &Self| this.0.swap_bytes())]
+struct Swapped(u32);
+
+#[derive(BinRead, BinReadAsync, BinWrite, BinWriteAsync, Debug, PartialEq)]
+#[br(import(offset: u8))]
+struct Imported {
+    #[br(calc = offset)]
+    #[bw(ignore)]
+    offset: u8,
+    #[br(if(offset > 0))]
+    value: Option<u8>,
+}
+
+#[test]
+fn primitives() {
+    assert_eq!(
+        read::<u32>(b"\x01\x02\x03\x04", Endian::Big).0.unwrap(),
+        0x0102_0304
+    );
+    assert_eq!(
+        read::<[u16; 2]>(b"\x01\x00\x02\x00", Endian::Little)
+            .0
+            .unwrap(),
+        [1, 2]
+    );
+    assert_eq!(write(&0x0102u16, Endian::Little), b"\x02\x01");
+    assert_eq!(write(&[1u8, 2, 3], Endian::Little), b"\x01\x02\x03");
+    assert_eq!(write(&vec![1u16, 2], Endian::Big), b"\x00\x01\x00\x02");
+}
+
+#[test]
+fn primitive_restores_position_on_failure() {
+    let (result, pos) = read::<u32>(b"\x01\x02", Endian::Big);
+    assert!(result.is_err());
+    assert_eq!(pos, 0);
+}
+
+#[test]
+fn byte_vec_with_untrusted_count() {
+    let mut reader = Cursor::new(b"\x01\x02\x03");
+    let err = block_on(Vec::<u8>::read_options_async(
+        &mut reader,
+        Endian::Little,
+        binrw::args! { count: usize::MAX },
+    ))
+    .unwrap_err();
+    assert!(err.is_eof());
+    assert_eq!(reader.position(), 0);
+
+    let mut reader = Cursor::new(vec![7; 0x2001]);
+    let bytes = block_on(Vec::<u8>::read_options_async(
+        &mut reader,
+        Endian::Little,
+        binrw::args! { count: 0x2001 },
+    ))
+    .unwrap();
+    assert_eq!(bytes, vec![7; 0x2001]);
+}
+
+#[test]
+fn strings() {
+    let (value, pos) = read::<NullString>(b"abc\0def", Endian::Little);
+    assert_eq!(value.unwrap(), NullString::from("abc"));
+    assert_eq!(pos, 4);
+
+    let value = read::<NullWideString>(b"a\0b\0\0\0", Endian::Little).0;
+    assert_eq!(value.unwrap(), NullWideString::from("ab"));
+
+    assert_eq!(write(&NullString::from("ab"), Endian::Little), b"ab\0");
+    assert_eq!(
+        write(&NullWideString::from("ab"), Endian::Big),
+        b"\0a\0b\0\0"
+    );
+
+    let mut reader = Cursor::new(b"\xff\xfeh\0\xe9\0\0\0");
+    let args = EncodedStringArgs {
+        encoding: &Utf16Bom,
+    };
+    let value = block_on(EncodedString::read_options_async(
+        &mut reader,
+        Endian::Big,
+        args,
+    ))
+    .unwrap();
+    assert_eq!(value.as_str(), "hé");
+
+    let mut writer = Cursor::new(Vec::new());
+    block_on(value.write_options_async(&mut writer, Endian::Big, args)).unwrap();
+    assert_eq!(writer.into_inner(), b"\xfe\xff\0h\0\xe9\0\0");
+}
+
+#[test]
+fn file_ptr() {
+    let mut reader = Cursor::new(b"\x02\xff\x34\x12");
+    let value = block_on(FilePtr8::<u16>::read_le_async(&mut reader)).unwrap();
+    assert_eq!(*value, 0x1234);
+    assert_eq!(reader.position(), 1);
+}
+
+#[test]
+fn file_ptr_write() {
+    #[binwrite(async)]
+    #[bw(little, import(queue: &PointeeQueue))]
+    struct Table {
+        #[bw(args { queue })]
+        first: FilePtr8<u8>,
+        #[bw(args { queue, align: 4, inner: (queue,) })]
+        nested: FilePtr8<Nested>,
+    }
+
+    #[binwrite(async)]
+    #[bw(little, import(queue: &PointeeQueue))]
+    struct Nested {
+        #[bw(args { queue })]
+        value: FilePtr8<u8>,
+    }
+
+    let table = Table {
+        first: FilePtr8 {
+            ptr: 0,
+            value: 0x12,
+        },
+        nested: FilePtr8 {
+            ptr: 0,
+            value: Nested {
+                value: FilePtr8 { ptr: 0, value: 9 },
+            },
+        },
+    };
+
+    let mut sync_output = binrw::io::Cursor::new(Vec::new());
+    write_with_pointees(&table, &mut sync_output, Endian::Little, |queue| (queue,)).unwrap();
+
+    let mut output = Cursor::new(Vec::new());
+    block_on(write_with_pointees_async(
+        &table,
+        &mut output,
+        Endian::Little,
+        |queue| (queue,),
+    ))
+    .unwrap();
+    assert_eq!(output.position(), 6);
+    let output = output.into_inner();
+    assert_eq!(output, b"\x02\x04\x12\0\x05\x09");
+    assert_eq!(output, sync_output.into_inner());
+
+    // A pointer which cannot hold the offset is an error
+    let mut output = Cursor::new(vec![0; 0x100]);
+    let error = block_on(write_with_pointees_async(
+        &table,
+        &mut output,
+        Endian::Little,
+        |queue| (queue,),
+    ))
+    .unwrap_err();
+    assert!(
+        matches!(error, binrw::Error::AssertFail { pos: 0, .. }),
+        "{error}"
+    );
+}
+
+#[test]
+fn temp_fields() {
+    #[binrw(async)]
+    #[brw(big)]
+    #[derive(Debug, PartialEq)]
+    struct Message {
+        #[br(temp)]
+        #[bw(calc = body.len() as u8)]
+        len: u8,
+        #[br(count = len)]
+        body: Vec<u8>,
+    }
+
+    let (message, pos) = read::<Message>(b"\x02ab", Endian::Little);
+    assert_eq!(
+        message.unwrap(),
+        Message {
+            body: b"ab".to_vec()
+        }
+    );
+    assert_eq!(pos, 3);
+    assert_eq!(
+        write(
+            &Message {
+                body: b"xyz".to_vec()
+            },
+            Endian::Little
+        ),
+        b"\x03xyz"
+    );
+}
+
+#[test]
+fn derived_round_trip() {
+    let data = b"HD\x03\x01\x05\x02\0\0\0\x07\x03ab\0\0\0\x01\0\0\x56\x78";
+    let (header, pos) = read::<Header>(data, Endian::Little);
+    let header = header.unwrap();
+    assert_eq!(
+        header,
+        Header {
+            count: 3,
+            items: vec![Item::Short(5), Item::Long { value: 7 }, Item::Empty],
+            name: NullString::from("ab"),
+            flag: true,
+            peek: 0x5678,
+            tail: 0x5678,
+            doubled: 6,
+        }
+    );
+    assert_eq!(pos, data.len() as u64);
+
+    // The sync and async impls agree with each other
+    let mut sync_output = binrw::io::Cursor::new(Vec::new());
+    header.write(&mut sync_output).unwrap();
+    let async_output = write(&header, Endian::Little);
+    assert_eq!(async_output, sync_output.into_inner());
+    assert_eq!(async_output, data);
+}
+
+#[test]
+fn derived_restores_position_on_failure() {
+    let (result, pos) = read::<Header>(b"HD\x01\x04", Endian::Little);
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        binrw::Error::EnumErrors { pos: 3, .. }
+    ));
+    assert_eq!(pos, 0);
+
+    let (result, pos) = read::<Header>(b"XX", Endian::Little);
+    assert!(matches!(
+        result.unwrap_err(),
+        binrw::Error::BadMagic { pos: 0, .. }
+    ));
+    assert_eq!(pos, 0);
+}
+
+#[test]
+fn unit_enum() {
+    assert_eq!(read::<Kind>(b"\x02\x00", Endian::Big).0.unwrap(), Kind::B);
+    assert!(read::<Kind>(b"\x00\x03", Endian::Big).0.is_err());
+    assert_eq!(write(&Kind::A, Endian::Big), b"\x00\x01");
+}
+
+#[test]
+fn top_level_map() {
+    let value = read::<Swapped>(b"\x01\x02\x03\x04", Endian::Big).0;
+    assert_eq!(value.unwrap(), Swapped(0x0403_0201));
+    assert_eq!(
+        write(&Swapped(0x0403_0201), Endian::Big),
+        b"\x01\x02\x03\x04"
+    );
+}
+
+#[test]
+fn args() {
+    let mut reader = Cursor::new(b"\x09");
+    let value = block_on(Imported::read_options_async(
+        &mut reader,
+        Endian::Little,
+        (1,),
+    ))
+    .unwrap();
+    assert_eq!(
+        value,
+        Imported {
+            offset: 1,
+            value: Some(9)
+        }
+    );
+
+    let mut reader = Cursor::new(b"\x09");
+    let value = block_on(Imported::read_options_async(
+        &mut reader,
+        Endian::Little,
+        (0,),
+    ))
+    .unwrap();
+    assert_eq!(value.value, None);
+}
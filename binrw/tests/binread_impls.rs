@@ -127,3 +127,56 @@ fn count_with_correctness() {
         vec![0, 0xffffff]
     );
 }
+
+#[test]
+fn borrowed() {
+    use binrw::{NullStr, args, borrowed::SliceReader};
+
+    let data = b"ab\xffcd\0ef";
+    let reader = SliceReader::new(data);
+    let source = reader.source();
+    let bytes = <&[u8]>::read_args(&mut &reader, args! { source, count: 2 }).unwrap();
+    assert_eq!(bytes, b"ab");
+    assert_eq!(bytes.as_ptr(), data.as_ptr());
+    assert_eq!(reader.position(), 2);
+
+    let error = <&str>::read_args(&mut &reader, args! { source, count: 2 }).unwrap_err();
+    assert!(matches!(error, binrw::Error::Custom { pos: 2, .. }));
+    assert_eq!(reader.position(), 2);
+
+    reader.set_position(3);
+    let string = NullStr::read_args(&mut &reader, args! { source }).unwrap();
+    assert_eq!(string, NullStr(b"cd"));
+    assert_eq!(reader.position(), 6);
+
+    assert!(
+        NullStr::read_args(&mut &reader, args! { source })
+            .unwrap_err()
+            .is_eof()
+    );
+    assert!(
+        <&[u8; 3]>::read_args(&mut &reader, args! { source })
+            .unwrap_err()
+            .is_eof()
+    );
+    assert_eq!(reader.position(), 6);
+    assert_eq!(
+        <&[u8; 2]>::read_args(&mut &reader, args! { source }).unwrap(),
+        b"ef"
+    );
+
+    // The position of the reader is past the end of the data
+    reader.set_position(9);
+    assert!(
+        <&[u8]>::read_args(&mut &reader, args! { source, count: 0 })
+            .unwrap_err()
+            .is_eof()
+    );
+
+    // Reading from another reader cannot borrow from the source
+    reader.set_position(0);
+    let mut other = Cursor::new(data);
+    let error = <&[u8]>::read_args(&mut other, args! { source, count: 2 }).unwrap_err();
+    assert!(matches!(error, binrw::Error::AssertFail { pos: 0, .. }));
+    assert_eq!(other.position(), 0);
+}
@@ -0,0 +1,68 @@
+use binrw::{
+    checksum::{
+        Adler32, Checksum, ChecksumRecorder, ChecksumStream, Crc16, Crc32, Sum8, Sum16, Sum32,
+    },
+    io::{Cursor, Read, Seek, SeekFrom, Write},
+};
+
+fn digest<C: Checksum>(bytes: &[u8]) -> C::Output {
+    let mut checksum = C::default();
+    checksum.update(bytes);
+    checksum.finish()
+}
+
+#[test]
+fn check_values() {
+    const CHECK: &[u8] = b"123456789";
+    assert_eq!(digest::<Crc32>(CHECK), 0xcbf4_3926);
+    assert_eq!(digest::<Crc16>(CHECK), 0xbb3d);
+    assert_eq!(digest::<Adler32>(CHECK), 0x091e_01de);
+    assert_eq!(digest::<Sum8>(CHECK), 0xdd);
+    assert_eq!(digest::<Sum16>(CHECK), 0x1dd);
+    assert_eq!(digest::<Sum32>(CHECK), 0x1dd);
+}
+
+#[test]
+fn adler32_large_input() {
+    let bytes = [0xff; 10000];
+    assert_eq!(digest::<Adler32>(&bytes), 0xb623_eb2b);
+}
+
+#[test]
+fn checksum_stream() {
+    let mut checksum = Crc32::default();
+    let mut reader = Cursor::new(b"123456789");
+    let mut buf = [0; 9];
+    ChecksumStream::new(&mut reader, &mut checksum)
+        .read_exact(&mut buf)
+        .unwrap();
+    assert_eq!(checksum.finish(), 0xcbf4_3926);
+
+    let mut checksum = Sum8::default();
+    let mut writer = Cursor::new(Vec::new());
+    ChecksumStream::new(&mut writer, &mut checksum)
+        .write_all(b"\x01\x02\x03")
+        .unwrap();
+    assert_eq!(checksum.finish(), 6);
+    assert_eq!(writer.into_inner(), b"\x01\x02\x03");
+}
+
+#[test]
+fn checksum_recorder() {
+    let mut writer = Cursor::new(Vec::new());
+    writer.write_all(b"\xff").unwrap();
+    let mut recorder = ChecksumRecorder::new(&mut writer).unwrap();
+    recorder.write_all(b"\x01\x02\x03").unwrap();
+    recorder.seek(SeekFrom::Start(2)).unwrap();
+    recorder.write_all(b"\x04").unwrap();
+    recorder.seek(SeekFrom::Start(0)).unwrap();
+    recorder.write_all(b"\x80").unwrap();
+
+    let mut checksum = Sum8::default();
+    assert!(recorder.update(&mut checksum, 1..4));
+    assert_eq!(checksum.finish(), 8);
+    assert!(!recorder.update(&mut checksum, 0..2));
+    assert!(!recorder.update(&mut checksum, 2..5));
+    assert_eq!(checksum.finish(), 8);
+    assert_eq!(writer.into_inner(), b"\x80\x01\x04\x03");
+}
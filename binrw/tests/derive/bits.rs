@@ -0,0 +1,206 @@
+extern crate binrw;
+use super::t;
+
+#[test]
+fn bits_lsb_first() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(little)]
+    struct Test {
+        #[brw(bits = 3)]
+        a: u8,
+        #[brw(bits = 5)]
+        b: u8,
+        #[brw(bits = 12)]
+        c: u16,
+        #[brw(bits = 1)]
+        d: bool,
+        #[brw(bits = 3)]
+        e: u8,
+        tail: u8,
+    }
+
+    let bytes = [0b1010_1101, 0x21, 0b1010_0100, 0xff];
+    let value = Test {
+        a: 0b101,
+        b: 0b10101,
+        c: 0x421,
+        d: false,
+        e: 0b101,
+        tail: 0xff,
+    };
+
+    let mut data = binrw::io::Cursor::new(&bytes);
+    t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+}
+
+#[test]
+fn bits_msb_first() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(big, bit_order = msb)]
+    struct Test {
+        #[brw(bits = 4)]
+        version: u8,
+        #[brw(bits = 4)]
+        ihl: u8,
+        #[brw(bits = 6)]
+        dscp: u8,
+        #[brw(bits = 2)]
+        ecn: u8,
+    }
+
+    let bytes = [0x45, 0b1011_1001];
+    let value = Test {
+        version: 4,
+        ihl: 5,
+        dscp: 0b10_1110,
+        ecn: 1,
+    };
+
+    let mut data = binrw::io::Cursor::new(&bytes);
+    t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+}
+
+#[test]
+fn bits_endian() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        #[brw(bits = 4)]
+        a: u8,
+        #[brw(bits = 12)]
+        b: u16,
+    }
+
+    let value = Test { a: 0x1, b: 0x234 };
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write_be(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), [0x23, 0x41]);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write_le(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), [0x41, 0x23]);
+
+    let mut data = binrw::io::Cursor::new(b"\x23\x41");
+    t::assert_eq!(<Test as binrw::BinRead>::read_be(&mut data).unwrap(), value);
+}
+
+#[test]
+fn bits_signed() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        #[brw(bits = 4)]
+        a: i8,
+        #[brw(bits = 4)]
+        b: i8,
+    }
+
+    let value = Test { a: -2, b: 7 };
+
+    let mut data = binrw::io::Cursor::new(b"\x7e");
+    t::assert_eq!(<Test as binrw::BinRead>::read_le(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write_le(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), [0x7e]);
+}
+
+#[test]
+fn bits_with_calc_and_temp() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        #[br(temp)]
+        #[bw(calc = <u8 as t::From<bool>>::from(*flag))]
+        #[brw(bits = 1)]
+        raw_flag: u8,
+        #[brw(bits = 7)]
+        value: u8,
+        #[br(calc = raw_flag != 0)]
+        #[bw(ignore)]
+        flag: bool,
+    }
+
+    let value = Test {
+        flag: true,
+        value: 0x40,
+    };
+
+    let mut data = binrw::io::Cursor::new(b"\x81");
+    t::assert_eq!(<Test as binrw::BinRead>::read_le(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write_le(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), [0x81]);
+}
+
+#[test]
+fn bits_out_of_range() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        lead: u8,
+        #[brw(bits = 3)]
+        a: u8,
+        #[brw(bits = 5)]
+        b: u8,
+    }
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    let error = binrw::BinWrite::write_le(
+        &Test {
+            lead: 0,
+            a: 1,
+            b: 32,
+        },
+        &mut out,
+    )
+    .unwrap_err();
+    match error {
+        binrw::Error::AssertFail { pos, message } => {
+            t::assert_eq!(pos, 1);
+            t::assert_eq!(
+                message,
+                "bit field value 32 at bit offset 3 does not fit in 5 bits"
+            );
+        }
+        error => t::panic!("unexpected error {error:?}"),
+    }
+}
+
+#[test]
+fn bits_read_error_offset() {
+    #[allow(dead_code)]
+    #[derive(binrw::BinRead, Debug)]
+    struct Test {
+        lead: u8,
+        #[br(bits = 7)]
+        a: u8,
+        #[br(bits = 9)]
+        b: u8,
+    }
+
+    let mut data = binrw::io::Cursor::new(b"\0\xff\xff");
+    let error = <Test as binrw::BinRead>::read_le(&mut data).unwrap_err();
+    match error.root_cause() {
+        binrw::Error::AssertFail { pos, message } => {
+            t::assert_eq!(*pos, 1);
+            t::assert_eq!(
+                message,
+                "bit field value 0x1ff at bit offset 7 is out of range for u8"
+            );
+        }
+        error => t::panic!("unexpected error {error:?}"),
+    }
+}
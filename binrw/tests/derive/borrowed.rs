@@ -0,0 +1,97 @@
+extern crate binrw;
+use super::t;
+use binrw::borrowed::{BorrowSource, SliceReader};
+
+#[test]
+fn borrowed_round_trip() {
+    #[derive(binrw::BinRead, binrw::BinWrite, Debug, PartialEq)]
+    #[brw(big)]
+    #[br(import(source: BorrowSource<'_, 'data>))]
+    struct Test<'data> {
+        len: u8,
+        #[br(count = len, args { source })]
+        bytes: &'data [u8],
+        #[br(args { source })]
+        magic: &'data [u8; 2],
+        #[br(args(source))]
+        inner: Inner<'data>,
+    }
+
+    #[derive(binrw::BinRead, binrw::BinWrite, Debug, PartialEq)]
+    #[br(import(source: BorrowSource<'_, 'data>))]
+    enum Inner<'data> {
+        #[brw(magic = 0u8)]
+        Name(#[br(args { source })] binrw::NullStr<'data>),
+        #[brw(magic = 1u8)]
+        Text {
+            len: u8,
+            #[br(count = len, args { source })]
+            text: &'data str,
+        },
+    }
+
+    let bytes = b"\x02\xaa\xbbOK\x01\x03abc";
+    let reader = SliceReader::new(bytes);
+    let value = <Test<'_> as binrw::BinRead>::read_args(&mut &reader, (reader.source(),)).unwrap();
+    t::assert_eq!(
+        value,
+        Test {
+            len: 2,
+            bytes: &[0xaa, 0xbb],
+            magic: b"OK",
+            inner: Inner::Text {
+                len: 3,
+                text: "abc"
+            },
+        }
+    );
+    t::assert_eq!(value.bytes.as_ptr(), bytes[1..].as_ptr());
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+
+    let bytes = b"\0OK\0name\0";
+    let reader = SliceReader::new(bytes);
+    let value = <Test<'_> as binrw::BinRead>::read_args(&mut &reader, (reader.source(),)).unwrap();
+    t::assert_eq!(value.inner, Inner::Name(binrw::NullStr(b"name")));
+}
+
+#[test]
+fn borrowed_outlives_reader() {
+    #[derive(binrw::BinRead, Debug, PartialEq)]
+    #[br(import(source: BorrowSource<'_, 'data>))]
+    struct Test<'data> {
+        #[br(args { source })]
+        name: binrw::NullStr<'data>,
+    }
+
+    fn parse(bytes: &[u8]) -> Test<'_> {
+        let reader = SliceReader::new(bytes);
+        <Test<'_> as binrw::BinRead>::read_le_args(&mut &reader, (reader.source(),)).unwrap()
+    }
+
+    t::assert_eq!(parse(b"abc\0").name, binrw::NullStr(b"abc"));
+}
+
+#[test]
+fn borrowed_in_size_window() {
+    #[derive(binrw::BinRead, Debug, PartialEq)]
+    #[br(little, import(source: BorrowSource<'_, 'data>))]
+    struct Test<'data> {
+        #[br(size = 4, args { source })]
+        first: &'data [u8; 2],
+        #[br(size = 4, args { source })]
+        second: &'data [u8; 2],
+    }
+
+    // Values are borrowed at the position of the source, so they are correct
+    // inside of a window whose positions are relative to its start
+    let bytes = b"ab\0\0cd\0\0";
+    let reader = SliceReader::new(bytes);
+    let value = <Test<'_> as binrw::BinRead>::read_args(&mut &reader, (reader.source(),)).unwrap();
+    t::assert_eq!(value.first, b"ab");
+    t::assert_eq!(value.second, b"cd");
+    t::assert_eq!(value.second.as_ptr(), bytes[4..].as_ptr());
+    t::assert_eq!(reader.position(), 8);
+}
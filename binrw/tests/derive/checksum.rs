@@ -0,0 +1,243 @@
+extern crate binrw;
+use super::t;
+
+#[test]
+fn checksum_after_range() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(little)]
+    struct Test {
+        lead: u8,
+        data: [u8; 9],
+        #[brw(checksum(binrw::checksum::Crc32, data))]
+        crc: u32,
+    }
+
+    let bytes = b"\xff123456789\x26\x39\xf4\xcb";
+    let value = Test {
+        lead: 0xff,
+        data: *b"123456789",
+        crc: 0xcbf4_3926,
+    };
+
+    let mut data = binrw::io::Cursor::new(bytes);
+    t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&Test { crc: 0, ..value }, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+}
+
+#[test]
+fn checksum_before_range() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(big)]
+    struct Test {
+        #[br(temp)]
+        #[brw(checksum(binrw::checksum::Sum16, len..=payload))]
+        sum: u16,
+        #[br(temp)]
+        #[bw(calc = payload.len() as u8)]
+        len: u8,
+        #[br(count = len)]
+        payload: t::Vec<u8>,
+        tail: u8,
+    }
+
+    let bytes = b"\x02\x01\x03\xff\x7f\x80\xaa";
+    let value = Test {
+        payload: t::vec![0xff, 0x7f, 0x80],
+        tail: 0xaa,
+    };
+
+    let mut data = binrw::io::Cursor::new(bytes);
+    t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+}
+
+#[test]
+fn checksum_tuple_struct() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(little)]
+    struct Test(
+        u16,
+        #[brw(checksum(binrw::checksum::Sum8, 0))] u8,
+        u8,
+        #[brw(checksum(binrw::checksum::Crc16, 2))] u16,
+    );
+
+    let bytes = b"\x01\x02\x03\x03\x40\x01";
+    let value = Test(0x0201, 0x03, 0x03, 0x0140);
+
+    let mut data = binrw::io::Cursor::new(bytes);
+    t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&Test(0x0201, 0, 0x03, 0), &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+}
+
+#[test]
+fn checksum_bits() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        #[brw(bits = 4)]
+        a: u8,
+        #[brw(bits = 12)]
+        b: u16,
+        #[brw(checksum(binrw::checksum::Sum8, a..=b))]
+        sum: u8,
+    }
+
+    let bytes = b"\x41\x23\x64";
+    let value = Test {
+        a: 1,
+        b: 0x234,
+        sum: 0x64,
+    };
+
+    let mut data = binrw::io::Cursor::new(bytes);
+    t::assert_eq!(<Test as binrw::BinRead>::read_le(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write_le(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+}
+
+#[test]
+fn checksum_mismatch() {
+    #[allow(dead_code)]
+    #[derive(binrw::BinRead, Debug)]
+    #[br(little)]
+    struct Test {
+        data: [u8; 4],
+        #[br(checksum(binrw::checksum::Adler32, data))]
+        adler: u32,
+    }
+
+    let mut data = binrw::io::Cursor::new(b"abcd\x00\x00\x00\x00");
+    let error = <Test as binrw::BinRead>::read(&mut data).unwrap_err();
+    match error {
+        binrw::Error::AssertFail { pos, message } => {
+            t::assert_eq!(pos, 4);
+            t::assert_eq!(message, "checksum mismatch: expected 0x0, found 0x3d8018b");
+        }
+        error => t::panic!("unexpected error {error:?}"),
+    }
+}
+
+#[test]
+fn checksum_offsets() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(little, magic = b"HD")]
+    struct Test {
+        #[br(temp)]
+        #[bw(calc = payload.len() as u8)]
+        len: u8,
+        #[br(temp)]
+        #[brw(checksum(binrw::checksum::Sum8, offset(0..3)))]
+        sum: u8,
+        #[br(count = len)]
+        payload: t::Vec<u8>,
+        #[br(temp)]
+        #[brw(checksum(binrw::checksum::Crc32, offset(4..4 + len)))]
+        crc: u32,
+    }
+
+    let bytes = b"\xeeHD\x02\x8e12\xcd\x44\x53\x4f";
+    let value = Test {
+        payload: t::vec![b'1', b'2'],
+    };
+
+    let mut data = binrw::io::Cursor::new(bytes);
+    data.set_position(1);
+    t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+    t::assert_eq!(data.position(), 11);
+
+    let mut out = binrw::io::Cursor::new(t::vec![0xee]);
+    out.set_position(1);
+    binrw::BinWrite::write(&value, &mut out).unwrap();
+    t::assert_eq!(out.position(), 11);
+    t::assert_eq!(out.into_inner(), bytes);
+}
+
+#[test]
+fn checksum_positions() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(big)]
+    struct Test {
+        #[brw(checksum(binrw::checksum::Sum16, pos(2..=3)))]
+        sum: u16,
+        a: u8,
+        b: u8,
+    }
+
+    let bytes = b"\x00\x30\x10\x20";
+    let value = Test {
+        sum: 0x30,
+        a: 0x10,
+        b: 0x20,
+    };
+
+    let mut data = binrw::io::Cursor::new(bytes);
+    t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&Test { sum: 0, ..value }, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+
+    let mut data = binrw::io::Cursor::new(b"\x00\x31\x10\x20");
+    let error = <Test as binrw::BinRead>::read(&mut data).unwrap_err();
+    t::assert!(
+        t::matches!(error, binrw::Error::AssertFail { pos: 0, ref message } if message == "checksum mismatch: expected 0x31, found 0x30"),
+        "unexpected error {error:?}"
+    );
+}
+
+#[test]
+fn checksum_bad_positions() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    struct Overlap {
+        a: u8,
+        #[brw(checksum(binrw::checksum::Sum8, pos(0..2)))]
+        sum: u8,
+    }
+
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    struct Unwritten {
+        a: u8,
+        #[brw(checksum(binrw::checksum::Sum8, offset(2..4)))]
+        sum: u8,
+    }
+
+    let error =
+        <Overlap as binrw::BinRead>::read_le(&mut binrw::io::Cursor::new(b"\x01\x01")).unwrap_err();
+    t::assert!(
+        t::matches!(error, binrw::Error::AssertFail { pos: 1, ref message } if message == "checksum range 0x0..0x2 includes the checksum field at 0x1"),
+        "unexpected error {error:?}"
+    );
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    let error = binrw::BinWrite::write_le(&Overlap { a: 1, sum: 0 }, &mut out).unwrap_err();
+    t::assert!(
+        t::matches!(error, binrw::Error::AssertFail { pos: 1, .. }),
+        "unexpected error {error:?}"
+    );
+
+    let mut out = binrw::io::Cursor::new(t::vec![0; 4]);
+    let error = binrw::BinWrite::write_le(&Unwritten { a: 1, sum: 0 }, &mut out).unwrap_err();
+    t::assert!(
+        t::matches!(error, binrw::Error::AssertFail { pos: 1, ref message } if message == "checksum range 0x2..0x4 was not entirely written"),
+        "unexpected error {error:?}"
+    );
+}
@@ -0,0 +1,78 @@
+extern crate binrw;
+use super::t;
+
+#[test]
+fn count_of_round_trip() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(big)]
+    struct Test {
+        #[brw(count_of = values)]
+        count: u16,
+        flags: u8,
+        values: t::Vec<u16>,
+    }
+
+    let bytes = b"\0\x02\x80\0\x01\0\x02";
+    let value = Test {
+        flags: 0x80,
+        values: t::vec![1, 2],
+    };
+
+    let mut data = binrw::io::Cursor::new(bytes);
+    t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+}
+
+#[test]
+fn count_of_read_only() {
+    #[derive(binrw::BinRead, Debug, PartialEq)]
+    #[br(little)]
+    struct Test {
+        #[br(count_of = values)]
+        count: u8,
+        values: t::Vec<u8>,
+    }
+
+    let mut data = binrw::io::Cursor::new(b"\x03\x01\x02\x03\x04");
+    t::assert_eq!(
+        <Test as binrw::BinRead>::read(&mut data).unwrap(),
+        Test {
+            count: 3,
+            values: t::vec![1, 2, 3],
+        }
+    );
+}
+
+#[test]
+fn count_of_overflow() {
+    #[binrw::binwrite]
+    #[bw(little)]
+    struct Test {
+        #[bw(count_of = items)]
+        count: u8,
+        items: t::Vec<u8>,
+    }
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    let error = binrw::BinWrite::write(
+        &Test {
+            items: t::vec![0; 256],
+        },
+        &mut out,
+    )
+    .unwrap_err();
+    match error.root_cause() {
+        binrw::Error::Custom { pos, err } => {
+            t::assert_eq!(*pos, 0);
+            t::assert_eq!(
+                t::ToString::to_string(err),
+                "length 256 of `items` is out of range for u8"
+            );
+        }
+        error => t::panic!("unexpected error: {error:?}"),
+    }
+}
@@ -366,3 +366,41 @@ fn mixed_enum() {
         <Test as binrw::BinRead>::read(&mut binrw::io::Cursor::new(b"\x02\0\x03\0\x04")).unwrap();
     t::assert_eq!(result, Test::Two { a: 3, b: 4 });
 }
+
+#[test]
+fn enum_other() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(big)]
+    enum Test {
+        #[br(other)]
+        Unknown {
+            kind: u8,
+            #[br(parse_with = binrw::helpers::until_eof)]
+            data: t::Vec<u8>,
+        },
+        #[brw(magic(0u8))]
+        Zero { a: u16 },
+        #[brw(magic(1u8))]
+        One,
+    }
+
+    for (bytes, value) in [
+        (&b"\0\x01\x02"[..], Test::Zero { a: 0x102 }),
+        (b"\x01", Test::One),
+        (
+            b"\x02\x01\x02\x03",
+            Test::Unknown {
+                kind: 2,
+                data: t::vec![1, 2, 3],
+            },
+        ),
+    ] {
+        let mut data = binrw::io::Cursor::new(bytes);
+        t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+
+        let mut out = binrw::io::Cursor::new(t::Vec::new());
+        binrw::BinWrite::write(&value, &mut out).unwrap();
+        t::assert_eq!(out.into_inner(), bytes);
+    }
+}
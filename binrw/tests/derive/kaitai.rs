@@ -0,0 +1,228 @@
+extern crate binrw;
+use super::t;
+
+#[test]
+fn kaitai_export() {
+    use binrw::{BinRead, NullString, io::SeekFrom, kaitai::Ksy};
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    #[br(big, magic = b"FMT\x01")]
+    struct Header {
+        count: u16,
+        #[br(count = count)]
+        entries: t::Vec<Entry>,
+        #[br(little, if(count > 0 && count < 10))]
+        checksum: t::Option<u32>,
+        #[br(seek_before = SeekFrom::Start(0x40))]
+        trailer: u8,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Entry {
+        kind: Kind,
+        #[br(align_before = 4, pad_after = 2)]
+        name: NullString,
+        body: Body,
+        #[br(parse_with = binrw::helpers::until_eof)]
+        rest: t::Vec<u8>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    #[br(repr = u8)]
+    enum Kind {
+        File = 1,
+        Directory,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    enum Body {
+        #[br(magic = 0u8)]
+        Empty,
+        #[br(magic = 1u8)]
+        Data {
+            len: u32,
+            #[br(map = |value: u16| value != 0)]
+            compressed: bool,
+        },
+    }
+
+    let ksy = Ksy::new::<Header>("fmt")
+        .with_type::<Entry>()
+        .with_type::<Kind>()
+        .with_type::<Body>();
+    t::assert_eq!(
+        ksy.to_yaml().unwrap(),
+        "\
+meta:
+  id: fmt
+  endian: be
+  ks-opaque-types: true
+seq:
+  - id: magic
+    contents: [0x46, 0x4d, 0x54, 0x01]
+  - id: count
+    type: u2
+  - id: entries
+    type: entry
+    repeat: expr
+    repeat-expr: count
+  - id: checksum
+    type: u4le
+    if: \"count > 0 and count < 10\"
+instances:
+  trailer:
+    pos: 0x40
+    type: u1
+types:
+  entry:
+    seq:
+      - id: kind
+        type: u1
+        enum: kind
+      - size: \"(4 - _io.pos % 4) % 4\"
+      - id: name
+        type: strz
+        encoding: ASCII
+      - size: 2
+      - id: body
+        type: body
+      - id: rest
+        type: vec_u8
+        doc: \"parse_with = binrw::helpers::until_eof\"
+  body:
+    seq:
+      - id: tag
+        type: u1
+      - id: body
+        type:
+          switch-on: tag
+          cases:
+            0: body_empty
+            1: body_data
+  body_empty: {}
+  body_data:
+    seq:
+      - id: len
+        type: u4
+      - id: compressed
+        type: bool
+        doc: \"map = |value: u16| value != 0\"
+enums:
+  kind:
+    1: file
+    2: directory
+"
+    );
+}
+
+#[test]
+fn kaitai_export_directives() {
+    use binrw::{BinRead, NullString, kaitai::Ksy};
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    #[br(big, version = version, bit_order = msb)]
+    struct Packet {
+        version: u8,
+        #[br(bits = 3)]
+        kind: u8,
+        #[br(bits = 5)]
+        flags: u8,
+        #[br(size = 8)]
+        id: u16,
+        #[br(since = 2)]
+        extra: t::Option<u32>,
+        #[br(until = 3, if(kind > 1))]
+        legacy: t::Option<u8>,
+        #[br(len_prefix = u8)]
+        name: NullString,
+        #[br(count_of = items)]
+        len: u16,
+        items: t::Vec<u8>,
+    }
+
+    t::assert_eq!(
+        Ksy::new::<Packet>("packet").to_yaml().unwrap(),
+        "\
+meta:
+  id: packet
+  endian: be
+seq:
+  - id: version
+    type: u1
+  - id: kind
+    type: b3
+  - id: flags
+    type: b5
+  - id: id
+    type: u2
+  - size: 6
+  - id: extra
+    type: u4
+    if: \"version >= 2\"
+  - id: legacy
+    type: u1
+    if: \"version < 3 and (kind > 1)\"
+  - id: name_len
+    type: u1
+  - id: name
+    type: str
+    size: name_len
+    encoding: ASCII
+  - id: len
+    type: u2
+  - id: items
+    type: u1
+    repeat: expr
+    repeat-expr: len
+"
+    );
+}
+
+#[test]
+fn kaitai_export_unsupported() {
+    use binrw::{BinRead, checksum::Sum8, kaitai::Ksy};
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    #[br(big)]
+    struct Flags {
+        #[br(bits = 4)]
+        a: u8,
+        #[br(bits = 12)]
+        b: u16,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Summed {
+        a: u8,
+        #[br(checksum(Sum8, a))]
+        sum: u8,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Window {
+        #[br(size = 8, count = 2)]
+        items: t::Vec<u16>,
+    }
+
+    let error = Ksy::new::<Flags>("flags").to_yaml().unwrap_err();
+    t::assert_eq!(error.field, t::Some("a"));
+    t::assert_eq!(
+        t::ToString::to_string(&error),
+        "cannot export `Flags` field `a` to Kaitai Struct: a 16-bit group of bit fields with `bit_order = lsb` cannot be read from a big-endian integer"
+    );
+
+    let error = Ksy::new::<Summed>("summed").to_yaml().unwrap_err();
+    t::assert_eq!(error.ty, "Summed");
+    t::assert_eq!(error.field, t::Some("sum"));
+
+    let error = Ksy::new::<Window>("window").to_yaml().unwrap_err();
+    t::assert_eq!(error.field, t::Some("items"));
+}
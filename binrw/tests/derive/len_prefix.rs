@@ -0,0 +1,92 @@
+extern crate binrw;
+use super::t;
+
+#[test]
+fn len_prefix_round_trip() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(big)]
+    struct Test {
+        #[brw(len_prefix = u16)]
+        name: t::String,
+        #[brw(len_prefix = u8)]
+        values: t::Vec<u16>,
+        #[brw(len_prefix = u8, args(3))]
+        nested: t::Vec<Inner>,
+    }
+
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(import(len: usize))]
+    struct Inner(#[br(count = len)] t::Vec<u8>);
+
+    let bytes = b"\0\x03abc\x02\0\x01\0\x02\x01xyz";
+    let value = Test {
+        name: t::ToString::to_string("abc"),
+        values: t::vec![1, 2],
+        nested: t::vec![Inner(t::vec![b'x', b'y', b'z'])],
+    };
+
+    let mut data = binrw::io::Cursor::new(bytes);
+    t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+}
+
+#[test]
+fn len_prefix_overflow() {
+    #[binrw::binwrite]
+    #[bw(little)]
+    struct Test {
+        a: u8,
+        #[bw(len_prefix = u8)]
+        values: t::Vec<u8>,
+    }
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    let error = binrw::BinWrite::write(
+        &Test {
+            a: 0,
+            values: t::vec![0; 256],
+        },
+        &mut out,
+    )
+    .unwrap_err();
+    match error.root_cause() {
+        binrw::Error::AssertFail { pos, message } => {
+            t::assert_eq!(*pos, 1);
+            t::assert_eq!(message, "length 256 is out of range for u8");
+        }
+        error => t::panic!("unexpected error: {error:?}"),
+    }
+}
+
+#[test]
+fn len_prefix_null_string() {
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(little)]
+    struct Test {
+        #[brw(len_prefix = u8)]
+        name: binrw::NullString,
+        #[brw(len_prefix = u16)]
+        wide: binrw::NullWideString,
+    }
+
+    let bytes = b"\x02hi\x01\0!\0";
+    let mut data = binrw::io::Cursor::new(bytes);
+    let value = <Test as binrw::BinRead>::read(&mut data).unwrap();
+    t::assert_eq!(
+        value,
+        Test {
+            name: <binrw::NullString as t::From<&str>>::from("hi"),
+            wide: <binrw::NullWideString as t::From<&str>>::from("!"),
+        }
+    );
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&value, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), bytes);
+}
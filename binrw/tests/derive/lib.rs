@@ -13,12 +13,23 @@ mod t {
 }
 
 mod binwrite_temp;
+mod bits;
+mod borrowed;
+mod checksum;
+mod count_of;
 mod r#enum;
 mod fn_helper;
+mod kaitai;
+mod len_prefix;
 mod map_args;
+mod recover;
+mod size;
+mod stream;
 mod r#struct;
 mod struct_generic;
 mod struct_map;
+mod union;
 mod unit_enum;
 mod unit_struct;
+mod version;
 mod write;
@@ -0,0 +1,177 @@
+extern crate binrw;
+use super::t;
+use binrw::{BinRead, NullString, io::Cursor, recover::Recovered};
+
+#[derive(BinRead, Debug, Default, PartialEq)]
+struct Inner {
+    #[br(assert(x < 10))]
+    x: u8,
+    y: u8,
+}
+
+#[test]
+fn recover_struct() {
+    #[derive(BinRead, Debug)]
+    #[br(big, recover)]
+    struct Test {
+        #[br(assert(a != 0))]
+        a: u16,
+        inner: Inner,
+        #[br(size = 3)]
+        name: NullString,
+        tail: u8,
+        rest: Recovered<u32>,
+    }
+
+    let test = Test::read(&mut Cursor::new(b"\0\0\x20\x01abc\x07\x01\x02")).unwrap();
+    t::assert_eq!(test.a, 0);
+    // The failed fields are skipped using their sizes
+    t::assert_eq!(test.inner, <Inner as t::Default>::default());
+    t::assert_eq!(test.name, <NullString as t::Default>::default());
+    t::assert_eq!(test.tail, 7);
+    let error = test.rest.error().unwrap();
+    t::assert_eq!(error.path, "rest");
+    t::assert_eq!(error.pos, 8);
+    t::assert!(error.error.is_eof());
+}
+
+#[test]
+fn recover_held_only() {
+    #[derive(BinRead, Debug)]
+    #[br(little)]
+    struct Test {
+        held: Recovered<Inner>,
+        value: u8,
+        other: u8,
+    }
+
+    // Fields which hold their errors recover without the `recover` directive
+    let test = Test::read(&mut Cursor::new(b"\x0a\x01\x02\x03")).unwrap();
+    t::assert_eq!(test.held.error().unwrap().pos, 0);
+    t::assert_eq!(test.value, 2);
+    t::assert_eq!(test.other, 3);
+
+    let test = Test::read(&mut Cursor::new(b"\x09\x01\x02\x03")).unwrap();
+    t::assert_eq!(test.held.into_result().unwrap(), Inner { x: 9, y: 1 });
+
+    // Other fields still fail
+    t::assert!(Test::read(&mut Cursor::new(b"\x09\x01\x02")).is_err());
+}
+
+#[test]
+fn recover_without_default() {
+    #[derive(BinRead, Debug)]
+    #[br(repr = u8)]
+    enum Kind {
+        A = 1,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead, Debug)]
+    #[br(recover)]
+    struct Test {
+        kind: Kind,
+    }
+
+    let error = Test::read_le(&mut Cursor::new(b"\x02")).unwrap_err();
+    t::assert!(t::matches!(
+        error.root_cause(),
+        binrw::Error::NoVariantMatch { .. }
+    ));
+}
+
+#[test]
+fn recover_variant() {
+    #[derive(BinRead, Debug, PartialEq)]
+    enum Single {
+        #[br(magic = 1u8, recover)]
+        A {
+            #[br(assert(value != 0))]
+            value: u8,
+            next: u8,
+        },
+    }
+
+    t::assert_eq!(
+        Single::read_le(&mut Cursor::new(b"\x01\0\x05")).unwrap(),
+        Single::A { value: 0, next: 5 }
+    );
+
+    #[derive(BinRead, Debug, PartialEq)]
+    enum Test {
+        #[br(magic = 1u8, recover)]
+        A {
+            #[br(assert(value != 0))]
+            value: u8,
+            next: u8,
+        },
+        #[br(magic = 2u8)]
+        B {
+            #[br(assert(value != 0))]
+            value: u8,
+        },
+    }
+
+    // Fields do not recover while the variants of an enum are being tried,
+    // since a variant which recovered would be chosen even if it was wrong
+    t::assert!(Test::read_le(&mut Cursor::new(b"\x01\0\x05")).is_err());
+    t::assert_eq!(
+        Test::read_le(&mut Cursor::new(b"\x01\x04\x05")).unwrap(),
+        Test::A { value: 4, next: 5 }
+    );
+    t::assert!(Test::read_le(&mut Cursor::new(b"\x02\0")).is_err());
+}
+
+#[derive(BinRead, Debug, Default, PartialEq)]
+#[br(little, recover)]
+struct Item {
+    #[br(assert(a != 0))]
+    a: u8,
+    b: u8,
+}
+
+#[test]
+fn recover_at_eof() {
+    // A field which starts at the end of the input fails instead of
+    // recovering
+    t::assert!(Item::read(&mut Cursor::new(b"")).unwrap_err().is_eof());
+    t::assert!(Item::read(&mut Cursor::new(b"\x01")).unwrap_err().is_eof());
+    t::assert_eq!(
+        Item::read(&mut Cursor::new(b"\0\x02")).unwrap(),
+        Item { a: 0, b: 2 }
+    );
+}
+
+#[test]
+fn recover_until_eof() {
+    #[derive(BinRead, Debug)]
+    struct List {
+        #[br(parse_with = binrw::helpers::until_eof)]
+        items: t::Vec<Item>,
+    }
+
+    let list = List::read_le(&mut Cursor::new(b"\x01\x02\x03\x04")).unwrap();
+    t::assert_eq!(list.items, [Item { a: 1, b: 2 }, Item { a: 3, b: 4 }]);
+
+    // Items do not recover inside of `until_eof`, which stops at errors
+    t::assert!(List::read_le(&mut Cursor::new(b"\x01\x02\0\x04")).is_err());
+}
+
+#[test]
+fn recover_enum_backtracking() {
+    #[derive(BinRead, Debug, PartialEq)]
+    #[br(little)]
+    enum Choice {
+        Item(Item),
+        Raw(u16),
+    }
+
+    t::assert_eq!(
+        Choice::read(&mut Cursor::new(b"\x01\x02")).unwrap(),
+        Choice::Item(Item { a: 1, b: 2 })
+    );
+    t::assert_eq!(
+        Choice::read(&mut Cursor::new(b"\0\x05")).unwrap(),
+        Choice::Raw(0x0500)
+    );
+}
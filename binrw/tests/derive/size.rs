@@ -0,0 +1,130 @@
+extern crate binrw;
+use super::t;
+
+#[binrw::binrw]
+#[brw(big)]
+#[derive(Debug, PartialEq)]
+struct Body {
+    offset: u8,
+    #[br(seek_before = binrw::io::SeekFrom::Start(<u64 as t::From<u8>>::from(offset)))]
+    #[bw(seek_before = binrw::io::SeekFrom::Start(<u64 as t::From<u8>>::from(*offset)))]
+    value: u16,
+}
+
+#[binrw::binrw]
+#[brw(big)]
+#[derive(Debug, PartialEq)]
+struct Chunk {
+    #[bw(calc = 5)]
+    len: u8,
+    #[brw(size = len)]
+    body: Body,
+    trailer: u8,
+}
+
+#[test]
+fn size_round_trip() {
+    let data = b"\x05\x02\xff\x12\x34\xff\x09";
+    let chunk = <Chunk as binrw::BinRead>::read(&mut binrw::io::Cursor::new(data)).unwrap();
+    t::assert_eq!(
+        chunk,
+        Chunk {
+            body: Body {
+                offset: 2,
+                value: 0x1234,
+            },
+            trailer: 9,
+        }
+    );
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    binrw::BinWrite::write(&chunk, &mut out).unwrap();
+    t::assert_eq!(out.into_inner(), b"\x05\x02\0\x12\x34\0\x09");
+}
+
+#[test]
+fn size_overread() {
+    #[binrw::binread]
+    #[br(big)]
+    #[derive(Debug)]
+    struct Test {
+        #[br(size = 2)]
+        _value: u32,
+    }
+
+    let error =
+        <Test as binrw::BinRead>::read(&mut binrw::io::Cursor::new(b"\0\0\0\0")).unwrap_err();
+    t::assert!(error.is_eof());
+}
+
+#[test]
+fn size_overwrite() {
+    #[binrw::binwrite]
+    #[bw(big)]
+    struct Test {
+        #[bw(size = 2)]
+        value: u32,
+    }
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    let error = binrw::BinWrite::write(&Test { value: 1 }, &mut out).unwrap_err();
+    t::assert!(t::matches!(
+        error,
+        binrw::Error::Io(error) if error.kind() == binrw::io::ErrorKind::WriteZero
+    ));
+}
+
+#[test]
+fn size_map_stream() {
+    use binrw::io::TakeSeekExt;
+
+    #[binrw::binread]
+    #[br(big)]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        #[br(size = 3, map_stream = |reader| reader.take_seek(2))]
+        value: u16,
+        next: u8,
+    }
+
+    let test =
+        <Test as binrw::BinRead>::read(&mut binrw::io::Cursor::new(b"\x01\x02\x03\x04")).unwrap();
+    t::assert_eq!(
+        test,
+        Test {
+            value: 0x102,
+            next: 4
+        }
+    );
+}
+
+#[test]
+fn size_out_of_range() {
+    #[binrw::binrw]
+    #[brw(little)]
+    #[derive(Debug)]
+    struct Test {
+        len: i32,
+        #[br(size = len)]
+        #[bw(size = *len)]
+        value: u8,
+    }
+
+    let error =
+        <Test as binrw::BinRead>::read(&mut binrw::io::Cursor::new(b"\xff\xff\xff\xff\x01"))
+            .unwrap_err();
+    match error {
+        binrw::Error::AssertFail { pos, message } => {
+            t::assert_eq!(pos, 4);
+            t::assert_eq!(message, "size -1 out of range of u64");
+        }
+        _ => t::panic!("bad error type"),
+    }
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    let error = binrw::BinWrite::write(&Test { len: -1, value: 1 }, &mut out).unwrap_err();
+    t::assert!(
+        t::matches!(error, binrw::Error::AssertFail { pos: 4, .. }),
+        "{error}"
+    );
+}
@@ -0,0 +1,221 @@
+extern crate binrw;
+extern crate core;
+use super::t;
+use binrw::{BinRead, BinReadStream};
+
+/// Evaluates to whether a type implements `BinReadStream`. The inherent
+/// constant is only visible when its bounds are satisfied, otherwise the trait
+/// constant is used.
+macro_rules! is_stream {
+    ($ty:ty) => {{
+        #[allow(dead_code)]
+        trait NotStream {
+            const STREAM: bool = false;
+        }
+        impl<T> NotStream for Check<T> {}
+        struct Check<T>(core::marker::PhantomData<T>);
+        #[allow(dead_code)]
+        impl<T: BinReadStream> Check<T> {
+            const STREAM: bool = true;
+        }
+        Check::<$ty>::STREAM
+    }};
+}
+
+#[test]
+fn stream_struct() {
+    #[derive(BinRead, Debug, PartialEq)]
+    #[br(big, magic = b"ST")]
+    struct Test {
+        len: u8,
+        #[br(count = len, align_after = 4)]
+        data: t::Vec<u8>,
+        #[br(pad_before = 2, map = |value: u8| t::Into::into(value * 2))]
+        doubled: u16,
+        #[br(if(len > 1))]
+        extra: t::Option<Inner>,
+        #[br(bits = 4)]
+        lo: u8,
+        #[br(bits = 4)]
+        hi: u8,
+        #[br(calc = len + 1)]
+        calc: u8,
+        #[br(size = 3)]
+        sized: u16,
+        kind: Kind,
+    }
+
+    #[derive(BinRead, Debug, PartialEq)]
+    struct Inner(u16);
+
+    #[derive(BinRead, Debug, PartialEq)]
+    #[br(repr = u8)]
+    enum Kind {
+        A = 1,
+        B = 2,
+    }
+
+    // `&[u8]` implements `Read` but not `Seek`
+    let mut input: &[u8] = b"ST\x02ab\0\xff\xff\0\0\x03\x12\x34\x56\x12\x34\xff\x02tail";
+    let value = Test::read_stream(&mut input).unwrap();
+    t::assert_eq!(
+        value,
+        Test {
+            len: 2,
+            data: t::vec![b'a', b'b'],
+            doubled: 6,
+            extra: t::Some(Inner(0x1234)),
+            lo: 6,
+            hi: 5,
+            calc: 3,
+            sized: 0x1234,
+            kind: Kind::B,
+        }
+    );
+    t::assert_eq!(input, b"tail");
+}
+
+#[test]
+fn stream_enums() {
+    #[derive(BinRead, Debug, PartialEq)]
+    enum Single {
+        #[br(magic = 1u8)]
+        Only { value: u16 },
+    }
+
+    #[derive(BinRead, Debug, PartialEq)]
+    enum Magic {
+        #[br(magic = b"AA")]
+        A,
+        #[br(magic = b"BB")]
+        B,
+    }
+
+    #[derive(BinRead, Debug, PartialEq)]
+    #[br(map = |raw: u8| if raw == 0 { Self::Zero } else { Self::Other(raw) })]
+    enum Mapped {
+        Zero,
+        Other(u8),
+    }
+
+    let mut input: &[u8] = b"\x01\x02\x00BB\x05";
+    t::assert_eq!(
+        Single::read_stream_le(&mut input).unwrap(),
+        Single::Only { value: 2 }
+    );
+    t::assert_eq!(Magic::read_stream_le(&mut input).unwrap(), Magic::B);
+    t::assert_eq!(
+        Mapped::read_stream_le(&mut input).unwrap(),
+        Mapped::Other(5)
+    );
+}
+
+#[test]
+fn stream_errors() {
+    #[allow(dead_code)]
+    #[derive(BinRead, Debug)]
+    #[br(little)]
+    struct Test {
+        #[br(pad_before = 1)]
+        a: u8,
+        #[br(magic = 7u8)]
+        b: u16,
+    }
+
+    // The original error is returned even though the stream cannot rewind
+    let mut input: &[u8] = b"\0\x01\x07\x02";
+    let error = Test::read_stream(&mut input).unwrap_err();
+    t::assert!(t::matches!(
+        error.root_cause(),
+        binrw::Error::Io(error) if error.kind() == binrw::io::ErrorKind::UnexpectedEof
+    ));
+
+    let mut input: &[u8] = b"\0\x01\x08\0\0";
+    let error = Test::read_stream(&mut input).unwrap_err();
+    t::assert!(t::matches!(
+        error.root_cause(),
+        binrw::Error::BadMagic { pos: 2, .. }
+    ));
+}
+
+#[test]
+fn stream_detection() {
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Generic<T: for<'a> BinRead<Args<'a> = ()>> {
+        value: T,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Pointer {
+        ptr: binrw::FilePtr8<u8>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Restore {
+        #[br(restore_position)]
+        peek: u8,
+        value: u8,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Seek {
+        #[br(seek_before = binrw::io::SeekFrom::Start(0))]
+        value: u8,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Try {
+        #[br(try)]
+        value: t::Option<u8>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct ParseWith {
+        #[br(parse_with = binrw::helpers::until_eof)]
+        value: t::Vec<u8>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct UntypedMap {
+        #[br(map = widen)]
+        value: u16,
+    }
+
+    fn widen(value: u8) -> u16 {
+        t::Into::into(value)
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    enum Backtrack {
+        A(u8),
+        B(u16),
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    enum MixedMagic {
+        #[br(magic = 1u8)]
+        A,
+        #[br(magic = 2u16)]
+        B,
+    }
+
+    t::assert!(is_stream!(Generic<u32>));
+    t::assert!(!is_stream!(Generic<Pointer>));
+    t::assert!(!is_stream!(Pointer));
+    t::assert!(!is_stream!(Restore));
+    t::assert!(!is_stream!(Seek));
+    t::assert!(!is_stream!(Try));
+    t::assert!(!is_stream!(ParseWith));
+    t::assert!(!is_stream!(UntypedMap));
+    t::assert!(!is_stream!(Backtrack));
+    t::assert!(!is_stream!(MixedMagic));
+}
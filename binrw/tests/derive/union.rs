@@ -0,0 +1,78 @@
+extern crate binrw;
+use super::t;
+
+#[binrw::binrw]
+#[brw(little, import(kind: u8))]
+#[derive(Clone, Copy)]
+union Value {
+    #[brw(select = unsafe { kind == 0 })]
+    int: u32,
+    #[brw(select = unsafe { kind == 1 })]
+    float: f32,
+    #[brw(select = unsafe { kind == 2 }, magic = b"B")]
+    bytes: [u8; 2],
+}
+
+#[binrw::binrw]
+#[brw(little)]
+struct Record {
+    kind: u8,
+    #[br(args(kind))]
+    #[bw(args(*kind))]
+    value: Value,
+}
+
+#[test]
+fn union_select() {
+    let mut data = binrw::io::Cursor::new(b"\0\x01\x02\x03\x04\x01\0\0\x80\x3f\x02B\x05\x06");
+
+    let int = <Record as binrw::BinRead>::read(&mut data).unwrap();
+    t::assert_eq!(int.kind, 0);
+    t::assert_eq!(unsafe { int.value.int }, 0x0403_0201);
+
+    let float = <Record as binrw::BinRead>::read(&mut data).unwrap();
+    t::assert_eq!(float.kind, 1);
+    t::assert_eq!(unsafe { float.value.float }, 1.0);
+
+    let bytes = <Record as binrw::BinRead>::read(&mut data).unwrap();
+    t::assert_eq!(bytes.kind, 2);
+    t::assert_eq!(unsafe { bytes.value.bytes }, [5, 6]);
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    for record in [int, float, bytes] {
+        binrw::BinWrite::write(&record, &mut out).unwrap();
+    }
+    t::assert_eq!(out.into_inner(), data.into_inner());
+}
+
+#[test]
+fn union_no_selection() {
+    let result =
+        <Value as binrw::BinRead>::read_args(&mut binrw::io::Cursor::new(b"\0\0\0\0"), (3,));
+    t::assert!(t::matches!(
+        result,
+        t::Err(binrw::Error::NoVariantMatch { pos: 0 })
+    ));
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    let error = binrw::BinWrite::write_args(&Value { int: 0 }, &mut out, (3,)).unwrap_err();
+    t::assert!(t::matches!(error, binrw::Error::NoVariantMatch { pos: 0 }));
+}
+
+#[test]
+fn union_default_field() {
+    #[binrw::binread]
+    #[br(big, import(wide: bool))]
+    union Number {
+        #[br(select = wide)]
+        wide: u16,
+        narrow: u8,
+    }
+
+    let mut data = binrw::io::Cursor::new(b"\x01\x02");
+    let narrow = <Number as binrw::BinRead>::read_args(&mut data, (false,)).unwrap();
+    t::assert_eq!(unsafe { narrow.narrow }, 1);
+    data.set_position(0);
+    let wide = <Number as binrw::BinRead>::read_args(&mut data, (true,)).unwrap();
+    t::assert_eq!(unsafe { wide.wide }, 0x102);
+}
@@ -291,3 +291,53 @@ fn unit_enum_eof_when_all_magic_eof() {
             .is_eof()
     );
 }
+
+#[test]
+fn unit_enum_repr_other() {
+    const BASE: u16 = 0x10;
+
+    #[binrw::binrw]
+    #[derive(Debug, Eq, PartialEq)]
+    #[brw(big, repr(u16))]
+    #[repr(u16)]
+    enum Test {
+        Zero,
+        #[br(other)]
+        Unknown(u16),
+        Two,
+        Sixteen = BASE,
+        Seventeen,
+    }
+
+    for (bytes, value) in [
+        (b"\0\0", Test::Zero),
+        (b"\0\x02", Test::Two),
+        (b"\0\x10", Test::Sixteen),
+        (b"\0\x11", Test::Seventeen),
+        (b"\0\x01", Test::Unknown(1)),
+        (b"\x12\x34", Test::Unknown(0x1234)),
+    ] {
+        let mut data = binrw::io::Cursor::new(bytes);
+        t::assert_eq!(<Test as binrw::BinRead>::read(&mut data).unwrap(), value);
+
+        let mut out = binrw::io::Cursor::new(t::Vec::new());
+        binrw::BinWrite::write(&value, &mut out).unwrap();
+        t::assert_eq!(out.into_inner(), bytes);
+    }
+
+    // A value which belongs to another variant would read back as that
+    // variant, so it cannot be written
+    for value in [0, 2, BASE, BASE + 1] {
+        let mut out = binrw::io::Cursor::new(t::Vec::new());
+        let error = binrw::BinWrite::write(&Test::Unknown(value), &mut out).unwrap_err();
+        t::assert!(
+            t::matches!(
+                &error,
+                binrw::Error::AssertFail { pos: 0, message }
+                    if message == &t::format!("Unknown({value}) cannot be written because its value belongs to another variant")
+            ),
+            "{error}"
+        );
+        t::assert!(out.into_inner().is_empty());
+    }
+}
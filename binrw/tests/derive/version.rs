@@ -0,0 +1,118 @@
+extern crate binrw;
+use super::t;
+
+#[binrw::binrw]
+#[brw(big, version = version)]
+#[derive(Debug, PartialEq)]
+struct Header {
+    version: u8,
+    #[brw(since = 2)]
+    flags: t::Option<u16>,
+    #[brw(until = 3)]
+    legacy: u8,
+    #[brw(since = 2, until = 4)]
+    extra: t::Option<u8>,
+}
+
+#[test]
+fn version_read_write() {
+    for (data, expected) in [
+        (
+            &b"\x01\x07"[..],
+            Header {
+                version: 1,
+                flags: t::None,
+                legacy: 7,
+                extra: t::None,
+            },
+        ),
+        (
+            &b"\x02\x00\x05\x07\x08"[..],
+            Header {
+                version: 2,
+                flags: t::Some(5),
+                legacy: 7,
+                extra: t::Some(8),
+            },
+        ),
+        (
+            &b"\x04\x00\x05"[..],
+            Header {
+                version: 4,
+                flags: t::Some(5),
+                legacy: 0,
+                extra: t::None,
+            },
+        ),
+    ] {
+        let header = <Header as binrw::BinRead>::read(&mut binrw::io::Cursor::new(data)).unwrap();
+        t::assert_eq!(header, expected);
+
+        let mut out = binrw::io::Cursor::new(t::Vec::new());
+        binrw::BinWrite::write(&header, &mut out).unwrap();
+        t::assert_eq!(out.into_inner(), data);
+    }
+}
+
+#[test]
+fn version_write_mismatch() {
+    for header in [
+        Header {
+            version: 1,
+            flags: t::Some(5),
+            legacy: 7,
+            extra: t::None,
+        },
+        Header {
+            version: 2,
+            flags: t::None,
+            legacy: 7,
+            extra: t::Some(8),
+        },
+    ] {
+        let mut out = binrw::io::Cursor::new(t::Vec::new());
+        let error = binrw::BinWrite::write(&header, &mut out).unwrap_err();
+        t::assert!(t::matches!(error, binrw::Error::AssertFail { pos: 1, .. }));
+    }
+}
+
+#[test]
+fn version_import() {
+    #[binrw::binrw]
+    #[brw(little, import(version: u32), version = version)]
+    #[derive(Debug, PartialEq)]
+    struct Record {
+        #[brw(since = 10, if(version != 11))]
+        kind: u8,
+    }
+
+    for (version, expected) in [(9, 0), (10, 3), (11, 0)] {
+        let record =
+            <Record as binrw::BinRead>::read_args(&mut binrw::io::Cursor::new(b"\x03"), (version,))
+                .unwrap();
+        t::assert_eq!(record, Record { kind: expected });
+    }
+}
+
+#[test]
+fn version_enum_variant() {
+    #[binrw::binread]
+    #[br(big, import(version: u8))]
+    #[derive(Debug, PartialEq)]
+    enum Message {
+        #[br(magic = 1u8, version = version)]
+        Ping {
+            #[br(since = 2)]
+            id: t::Option<u8>,
+        },
+    }
+
+    let message =
+        <Message as binrw::BinRead>::read_args(&mut binrw::io::Cursor::new(b"\x01\x05"), (1,))
+            .unwrap();
+    t::assert_eq!(message, Message::Ping { id: t::None });
+    let message =
+        <Message as binrw::BinRead>::read_args(&mut binrw::io::Cursor::new(b"\x01\x05"), (2,))
+            .unwrap();
+    t::assert_eq!(message, Message::Ping { id: t::Some(5) });
+}
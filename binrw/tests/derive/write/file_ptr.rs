@@ -0,0 +1,135 @@
+extern crate binrw;
+use super::t;
+use binrw::file_ptr::{FilePtr, FilePtr8, FilePtr16, PointeeQueue, write_with_pointees};
+
+#[test]
+fn file_ptr_round_trip() {
+    // Pointed-to values are borrowed, so they do not need to be `Clone`
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(big)]
+    #[bw(import(queue: &PointeeQueue))]
+    struct Inner {
+        x: u8,
+        #[bw(args { queue })]
+        name: FilePtr16<binrw::NullString>,
+    }
+
+    #[binrw::binrw]
+    #[derive(Debug, PartialEq)]
+    #[brw(big)]
+    #[bw(import(queue: &PointeeQueue))]
+    struct Outer {
+        #[bw(args { queue, inner: (queue,) })]
+        inner: FilePtr16<Inner>,
+        tail: u8,
+    }
+
+    let mut data = binrw::io::Cursor::new(b"\0\x03\xaa\x01\0\x06hi\0");
+    let mut value = <Outer as binrw::BinRead>::read(&mut data).unwrap();
+    t::assert_eq!(t::ToString::to_string(&*value.inner.name), "hi");
+
+    value.inner.x = 2;
+    *value.inner.name = <binrw::NullString as t::From<&str>>::from("hello");
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    write_with_pointees(&value, &mut out, binrw::Endian::Big, |queue| (queue,)).unwrap();
+    t::assert_eq!(out.position(), 12);
+    t::assert_eq!(out.get_ref(), b"\0\x03\xaa\x02\0\x06hello\0");
+
+    out.set_position(0);
+    t::assert_eq!(<Outer as binrw::BinRead>::read(&mut out).unwrap(), value);
+}
+
+#[test]
+fn file_ptr_nested_order() {
+    #[binrw::binwrite]
+    #[bw(import(queue: &PointeeQueue))]
+    struct Inner {
+        x: u8,
+        #[bw(args { queue })]
+        y: FilePtr8<u8>,
+    }
+
+    #[binrw::binwrite]
+    #[bw(import(queue: &PointeeQueue))]
+    struct Outer {
+        #[bw(args { queue, inner: (queue,) })]
+        a: FilePtr8<Inner>,
+        #[bw(args { queue, inner: (queue,) })]
+        b: FilePtr8<Inner>,
+    }
+
+    let value = Outer {
+        a: FilePtr8 {
+            ptr: 0,
+            value: Inner {
+                x: 0xa0,
+                y: FilePtr8 {
+                    ptr: 0,
+                    value: 0xa1,
+                },
+            },
+        },
+        b: FilePtr8 {
+            ptr: 0,
+            value: Inner {
+                x: 0xb0,
+                y: FilePtr8 {
+                    ptr: 0,
+                    value: 0xb1,
+                },
+            },
+        },
+    };
+
+    // Each pointed-to value is followed by the values that it points to
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    write_with_pointees(&value, &mut out, binrw::Endian::Little, |queue| (queue,)).unwrap();
+    t::assert_eq!(out.into_inner(), b"\x02\x05\xa0\x04\xa1\xb0\x07\xb1");
+}
+
+#[test]
+fn file_ptr_offset_and_align() {
+    let value = FilePtr::<::core::num::NonZeroU8, u16> {
+        ptr: ::core::num::NonZeroU8::MIN,
+        value: 0x1234,
+    };
+
+    let mut out = binrw::io::Cursor::new(t::Vec::new());
+    write_with_pointees(&value, &mut out, binrw::Endian::Little, |queue| {
+        binrw::args! { queue, offset: 1, align: 4 }
+    })
+    .unwrap();
+    t::assert_eq!(out.get_ref(), b"\x03\0\0\0\x34\x12");
+
+    out.set_position(0);
+    let read = <FilePtr<::core::num::NonZeroU8, u16> as binrw::BinRead>::read_le_args(
+        &mut out,
+        binrw::args! { offset: 1 },
+    )
+    .unwrap();
+    t::assert_eq!(read.ptr.get(), 3);
+    t::assert_eq!(*read, 0x1234);
+}
+
+#[test]
+fn file_ptr_out_of_range() {
+    let mut out = binrw::io::Cursor::new(t::vec![0; 300]);
+    out.set_position(300);
+    let error = write_with_pointees(
+        &FilePtr8 { ptr: 0, value: 1u8 },
+        &mut out,
+        binrw::Endian::Little,
+        |queue| binrw::args! { queue },
+    )
+    .unwrap_err();
+
+    match error {
+        binrw::Error::AssertFail { pos, message } => {
+            t::assert_eq!(pos, 300);
+            t::assert_eq!(message, "file pointer offset 301 is out of range for u8");
+        }
+        error => t::panic!("unexpected error {error:?}"),
+    }
+}
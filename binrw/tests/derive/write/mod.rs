@@ -8,6 +8,7 @@ mod calc;
 mod custom_writer;
 mod endian;
 mod r#enum;
+mod file_ptr;
 mod if_cond;
 mod ignore;
 mod import;
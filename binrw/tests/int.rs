@@ -0,0 +1,148 @@
+use binrw::{
+    BinRead, BinReaderExt, BinWrite, Endian, FilePtr, I24, I40, I48, I56, U24, U40, U48, U56,
+    file_ptr::{PointeeQueue, write_with_pointees},
+    io::Cursor,
+};
+
+/// Reads a value from `data`, and checks that writing it produces `data`
+/// again.
+fn round_trip<T>(data: &[u8], endian: Endian) -> T
+where
+    T: for<'a> BinRead<Args<'a> = ()> + for<'a> BinWrite<Args<'a> = ()>,
+{
+    let value = T::read_options(&mut Cursor::new(data), endian, ()).unwrap();
+    let mut out = Cursor::new(Vec::new());
+    value.write_options(&mut out, endian, ()).unwrap();
+    assert_eq!(out.into_inner(), data);
+    value
+}
+
+#[test]
+fn read_write() {
+    assert_eq!(
+        round_trip::<U24>(b"\0\x01\x02", Endian::Big),
+        U24::from(0x0102_u16)
+    );
+    assert_eq!(round_trip::<U24>(b"\xff\xff\xff", Endian::Little), U24::MAX);
+    assert_eq!(
+        round_trip::<U24>(b"\x0c\x0b\x0a", Endian::Little),
+        U24::try_from(0x0a_0b0c).unwrap()
+    );
+    assert_eq!(round_trip::<I24>(b"\x80\0\0", Endian::Big), I24::MIN);
+    assert_eq!(
+        round_trip::<I24>(b"\xfe\xff\xff", Endian::Little),
+        I24::from(-2_i8)
+    );
+    assert_eq!(round_trip::<I24>(b"\xff\xff\x7f", Endian::Little), I24::MAX);
+
+    assert_eq!(
+        round_trip::<U40>(b"\0\0\0\0\x01", Endian::Big),
+        U40::from(1_u32)
+    );
+    assert_eq!(
+        round_trip::<U40>(b"\xff\xff\xff\xff\xff", Endian::Little),
+        U40::MAX
+    );
+    assert_eq!(
+        round_trip::<I40>(b"\xff\xff\xff\xff\xff", Endian::Big),
+        I40::from(-1_i32)
+    );
+    assert_eq!(round_trip::<I40>(b"\0\0\0\0\x80", Endian::Little), I40::MIN);
+
+    assert_eq!(
+        round_trip::<U48>(b"\x01\x02\x03\x04\x05\x06", Endian::Big),
+        U48::try_from(0x0102_0304_0506_u64).unwrap()
+    );
+    assert_eq!(round_trip::<I48>(b"\x80\0\0\0\0\0", Endian::Big), I48::MIN);
+    assert_eq!(
+        round_trip::<I48>(b"\0\xff\xff\xff\xff\xff", Endian::Little),
+        I48::from(-256_i16)
+    );
+
+    assert_eq!(
+        round_trip::<U56>(b"\x07\x06\x05\x04\x03\x02\x01", Endian::Little),
+        U56::try_from(0x0001_0203_0405_0607_u64).unwrap()
+    );
+    assert_eq!(
+        round_trip::<I56>(b"\x7f\xff\xff\xff\xff\xff\xff", Endian::Big),
+        I56::MAX
+    );
+    assert_eq!(
+        round_trip::<I56>(b"\xff\xff\xff\xff\xff\xff\xfd", Endian::Big),
+        I56::from(-3_i8)
+    );
+}
+
+#[test]
+fn not_enough_data() {
+    let mut data = Cursor::new(b"\x01\x02\x03");
+    data.set_position(1);
+    assert!(data.read_be::<U24>().unwrap_err().is_eof());
+    assert_eq!(data.position(), 1);
+
+    let mut data = Cursor::new(b"\x01\x02\x03\x04");
+    assert!(data.read_le::<I40>().unwrap_err().is_eof());
+    assert_eq!(data.position(), 0);
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(U24::BITS, 24);
+    assert_eq!(U24::MAX.get(), 0xff_ffff);
+    assert_eq!(I24::MIN.get(), -0x80_0000);
+    assert_eq!(U56::MAX.get(), (1 << 56) - 1);
+    assert_eq!(I40::MAX.get(), (1 << 39) - 1);
+
+    assert_eq!(U24::new(0xff_ffff), Some(U24::MAX));
+    assert_eq!(U24::new(0x100_0000), None);
+    assert_eq!(I24::new(-0x80_0001), None);
+    assert_eq!(U24::new_wrapping(0x0123_4567).get(), 0x23_4567);
+    assert_eq!(I24::new_wrapping(0x00ff_ffff).get(), -1);
+    assert_eq!(I48::new_wrapping(-1).get(), -1);
+
+    assert_eq!(U24::try_from(-1_i8).ok(), None);
+    assert_eq!(U24::try_from(0x100_0000_u32).ok(), None);
+    assert_eq!(U24::try_from(u128::MAX).ok(), None);
+    assert_eq!(I24::try_from(0x80_0000_u32).ok(), None);
+    assert_eq!(I24::try_from(-0x80_0000_i64), Ok(I24::MIN));
+    assert_eq!(U40::try_from(1_u64 << 40).ok(), None);
+    assert_eq!(I56::try_from(1_i64 << 55).ok(), None);
+    assert_eq!(I56::try_from(-(1_i64 << 55)), Ok(I56::MIN));
+    assert_eq!(
+        U48::try_from(-1_isize).unwrap_err().to_string(),
+        "out of range integral type conversion attempted"
+    );
+
+    assert_eq!(u32::from(U24::MAX), 0xff_ffff);
+    assert_eq!(i128::from(I56::MIN), -(1 << 55));
+    assert_eq!(u8::try_from(U24::from(0x100_u16)).ok(), None);
+    assert_eq!(u64::try_from(I40::from(-1_i8)).ok(), None);
+    assert_eq!(u16::try_from(U40::from(5_u8)), Ok(5));
+
+    assert_eq!(I24::from(-42_i8).to_string(), "-42");
+    assert_eq!(U24::default().get(), 0);
+}
+
+#[test]
+fn file_ptr_offset() {
+    #[derive(BinRead, BinWrite, Debug, PartialEq)]
+    #[brw(big)]
+    #[bw(import(queue: &PointeeQueue))]
+    struct Header {
+        #[bw(args { queue })]
+        first: FilePtr<U24, u8>,
+        #[bw(args { queue })]
+        second: FilePtr<I40, u16>,
+    }
+
+    let data = b"\0\0\x08\0\0\0\0\x09\x01\x02\x03";
+    let header = Header::read(&mut Cursor::new(data)).unwrap();
+    assert_eq!(header.first.ptr, U24::from(8_u8));
+    assert_eq!(*header.first, 1);
+    assert_eq!(header.second.ptr, I40::from(9_u8));
+    assert_eq!(*header.second, 0x0203);
+
+    let mut out = Cursor::new(Vec::new());
+    write_with_pointees(&header, &mut out, Endian::Big, |queue| (queue,)).unwrap();
+    assert_eq!(out.into_inner(), data);
+}
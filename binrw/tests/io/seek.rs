@@ -9,8 +9,6 @@ fn read() {
     assert_eq!(stream.stream_position().unwrap(), 0);
     assert_eq!(stream.seek(SeekFrom::Start(0)).unwrap(), 0);
     assert_eq!(stream.stream_position().unwrap(), 0);
-    stream.seek(SeekFrom::Start(1)).unwrap_err();
-    stream.seek(SeekFrom::Current(1)).unwrap_err();
     stream.seek(SeekFrom::Current(-1)).unwrap_err();
     stream.seek(SeekFrom::End(0)).unwrap_err();
 
@@ -83,7 +81,6 @@ fn write() {
     assert_eq!(stream.seek(SeekFrom::Start(10)).unwrap(), 10);
     assert_eq!(stream.get_ref(), b"helloworld");
     stream.seek(SeekFrom::Start(1)).unwrap_err();
-    stream.seek(SeekFrom::Current(1)).unwrap_err();
     stream.seek(SeekFrom::Current(-1)).unwrap_err();
     stream.seek(SeekFrom::End(0)).unwrap_err();
 
@@ -114,3 +111,54 @@ fn write_vectored() {
     assert_eq!(stream.get_ref(), b"aaaaaaaaaa");
     assert_eq!(stream.stream_position().unwrap(), 10);
 }
+
+#[test]
+fn read_skip_forward() {
+    let mut stream = NoSeek::new(b"helloworld".as_slice());
+    let mut buf = [0; 3];
+
+    assert_eq!(stream.seek(SeekFrom::Current(2)).unwrap(), 2);
+    assert_eq!(stream.seek(SeekFrom::Start(3)).unwrap(), 3);
+    // Seeking back within a skip which has not happened yet is allowed
+    assert_eq!(stream.seek(SeekFrom::Current(-1)).unwrap(), 2);
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"llo");
+    assert_eq!(stream.stream_position().unwrap(), 5);
+    stream.seek(SeekFrom::Start(4)).unwrap_err();
+
+    assert_eq!(stream.seek(SeekFrom::Start(7)).unwrap(), 7);
+    assert_eq!(stream.read(&mut buf).unwrap(), 3);
+    assert_eq!(&buf, b"rld");
+    assert_eq!(stream.stream_position().unwrap(), 10);
+
+    // Skipping past the end fails on the next read
+    assert_eq!(stream.seek(SeekFrom::Current(1)).unwrap(), 11);
+    stream.read_exact(&mut buf).unwrap_err();
+
+    let mut data = vec![0; 0x300];
+    data.push(b'!');
+    let mut stream = NoSeek::new(data.as_slice());
+    stream.seek(SeekFrom::Start(0x300)).unwrap();
+    let mut buf = Vec::new();
+    stream.read_to_end(&mut buf).unwrap();
+    assert_eq!(buf, b"!");
+    assert_eq!(stream.stream_position().unwrap(), 0x301);
+}
+
+#[test]
+fn write_skip_forward() {
+    let mut stream = NoSeek::new(Vec::new());
+    stream.write_all(b"ab").unwrap();
+    assert_eq!(stream.seek(SeekFrom::Current(2)).unwrap(), 4);
+    // Nothing is written until the next write
+    assert_eq!(stream.get_ref(), b"ab");
+    stream.write_all(b"cd").unwrap();
+    assert_eq!(stream.get_ref(), b"ab\0\0cd");
+    assert_eq!(stream.stream_position().unwrap(), 6);
+
+    stream.seek(SeekFrom::Start(0x206)).unwrap();
+    assert_eq!(stream.write(b"e").unwrap(), 1);
+    assert_eq!(stream.get_ref().len(), 0x207);
+    assert!(stream.get_ref()[6..0x206].iter().all(|&b| b == 0));
+    assert_eq!(stream.stream_position().unwrap(), 0x207);
+}
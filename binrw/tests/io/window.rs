@@ -0,0 +1,56 @@
+#![allow(clippy::seek_to_start_instead_of_rewind)]
+use binrw::io::{Cursor, ErrorKind, Read, Seek, SeekFrom, Window, Write};
+
+#[test]
+fn window_read() {
+    let mut data = Cursor::new(b"hello world".to_vec());
+    data.seek(SeekFrom::Start(2)).unwrap();
+    let mut window = Window::new(&mut data, 6).unwrap();
+    let mut buf = [0; 4];
+
+    assert_eq!(window.size(), 6);
+    assert_eq!(window.stream_position().unwrap(), 0, "position not rebased");
+    assert_eq!(window.read(&mut buf).unwrap(), 4);
+    assert_eq!(&buf, b"llo ");
+    assert_eq!(window.stream_position().unwrap(), 4);
+    assert_eq!(window.remaining(), 2);
+    assert_eq!(window.read(&mut buf).unwrap(), 2, "read past end of window");
+    assert_eq!(&buf[..2], b"wo");
+    assert_eq!(window.read(&mut buf).unwrap(), 0, "read past end of window");
+
+    assert_eq!(window.seek(SeekFrom::Start(1)).unwrap(), 1);
+    assert_eq!(window.get_ref().position(), 3, "seek not rebased");
+    assert_eq!(window.seek(SeekFrom::End(-1)).unwrap(), 5);
+    assert_eq!(window.get_ref().position(), 7);
+    assert_eq!(window.seek(SeekFrom::Current(-5)).unwrap(), 0);
+    assert_eq!(
+        window.seek(SeekFrom::Current(-1)).unwrap_err().kind(),
+        ErrorKind::InvalidInput,
+        "seek before start of window"
+    );
+
+    window.skip_remaining().unwrap();
+    assert_eq!(data.position(), 8, "`skip_remaining` did not skip to end");
+}
+
+#[test]
+fn window_write() {
+    let mut data = Cursor::new(vec![0xff; 8]);
+    data.seek(SeekFrom::Start(1)).unwrap();
+    let mut window = Window::new(&mut data, 5).unwrap();
+
+    window.write_all(b"ab").unwrap();
+    window.seek(SeekFrom::Start(0)).unwrap();
+    window.write_all(b"c").unwrap();
+    window.pad_remaining().unwrap();
+    assert_eq!(data.position(), 6);
+    assert_eq!(data.get_ref(), b"\xffcb\0\0\0\xff\xff");
+
+    data.seek(SeekFrom::Start(0)).unwrap();
+    let mut window = Window::new(&mut data, 2).unwrap();
+    assert_eq!(
+        window.write_all(b"abc").unwrap_err().kind(),
+        ErrorKind::WriteZero,
+        "write past end of window"
+    );
+}
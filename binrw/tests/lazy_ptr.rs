@@ -0,0 +1,58 @@
+use binrw::{
+    BinRead, BinWrite, LazyPtr,
+    file_ptr::{FilePtrArgs, LazyPtr16},
+    io::Cursor,
+};
+
+#[derive(BinRead, BinWrite)]
+#[brw(big)]
+struct Index {
+    count: u8,
+    #[br(count = count, args { inner: FilePtrArgs { offset: 1, inner: () } })]
+    entries: Vec<LazyPtr<u8, u16>>,
+}
+
+#[test]
+fn lazy_ptr() {
+    let data = b"\x02\x03\x01\xff\xff\x00\x01";
+    let mut reader = Cursor::new(data);
+    let index = Index::read(&mut reader).unwrap();
+    assert_eq!(reader.position(), 3);
+
+    let entry = &index.entries[0];
+    assert_eq!(entry.ptr, 3);
+    assert_eq!(entry.pos(), 4);
+    assert_eq!(entry.get_cached(), None);
+
+    reader.set_position(6);
+    assert_eq!(*entry.get(&mut reader).unwrap(), 0xff00);
+    assert_eq!(reader.position(), 6);
+    assert_eq!(entry.get_cached(), Some(&0xff00));
+
+    // The cached value is returned without reading
+    assert_eq!(*entry.get(&mut Cursor::new(b"")).unwrap(), 0xff00);
+    assert_eq!(index.entries[1].pos(), 2);
+    assert_eq!(entry.clone().into_inner(), Some(0xff00));
+
+    let mut output = Cursor::new(Vec::new());
+    index.write(&mut output).unwrap();
+    assert_eq!(output.into_inner(), b"\x02\x03\x01");
+}
+
+#[test]
+fn lazy_ptr_error() {
+    #[derive(BinRead)]
+    #[br(little)]
+    struct Test {
+        value: LazyPtr16<u32>,
+    }
+
+    let mut reader = Cursor::new(b"\x02\x00\x01\x02");
+    let test = Test::read(&mut reader).unwrap();
+    assert!(test.value.get(&mut reader).is_err());
+    assert_eq!(reader.position(), 2);
+    assert_eq!(test.value.get_cached(), None);
+
+    let mut reader = Cursor::new(b"\x02\x00\x01\x02\x03\x04");
+    assert_eq!(*test.value.get(&mut reader).unwrap(), 0x0403_0201);
+}
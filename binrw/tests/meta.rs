@@ -58,3 +58,254 @@ fn write_endian() {
     assert_eq!(<(u8, Big)>::ENDIAN, EndianKind::Mixed);
     assert_eq!(<(u8, Big)>::ENDIAN.endian(), None);
 }
+
+#[test]
+fn static_size() {
+    use binrw::{BinRead, binread, meta::StaticSize};
+    use core::marker::PhantomData;
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    #[br(magic = b"HDR")]
+    struct Header {
+        #[br(pad_before = 1, pad_after = 2)]
+        a: u16,
+        #[br(magic = 1u8)]
+        b: [u32; 2],
+        c: (u8, i64),
+        #[br(calc = 5)]
+        d: u8,
+        #[br(bits = 4)]
+        e: u8,
+        #[br(bits = 12)]
+        f: u16,
+        #[br(pad_size_to = 6)]
+        g: Inner,
+        #[br(pad_size_to = 1)]
+        h: Inner,
+        #[br(size = 7)]
+        i: u8,
+        #[br(repr = u16)]
+        j: u32,
+        k: Kind,
+        l: PhantomData<u64>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Inner(u16, u8);
+
+    #[derive(BinRead)]
+    #[br(repr = u16)]
+    enum Kind {
+        A,
+    }
+
+    // These must still compile even though they do not implement the trait
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Dynamic {
+        len: u8,
+        #[br(count = len)]
+        data: Vec<u8>,
+    }
+
+    #[allow(dead_code)]
+    #[binread]
+    struct Padded {
+        len: u8,
+        #[br(pad_before = len)]
+        value: u8,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Generic<T: for<'a> BinRead<Args<'a> = ()>>(u8, T);
+
+    // The backing integer is still read when the first field is not
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct IgnoredBits {
+        #[br(ignore, bits = 4)]
+        a: u8,
+        #[br(bits = 4)]
+        b: u8,
+    }
+
+    assert_eq!(u32::SIZE, 4);
+    assert_eq!(<[u16; 3]>::SIZE, 6);
+    assert_eq!(<(u8, u32, ())>::SIZE, 5);
+    assert_eq!(PhantomData::<u64>::SIZE, 0);
+    assert_eq!(Inner::SIZE, 3);
+    assert_eq!(Kind::SIZE, 2);
+    assert_eq!(Generic::<u32>::SIZE, 5);
+    assert_eq!(IgnoredBits::SIZE, 1);
+    let mut data = binrw::io::Cursor::new(b"\x12");
+    IgnoredBits::read_le(&mut data).unwrap();
+    assert_eq!(data.position(), 1);
+    // magic, a, b, c, e + f, g, h, i, j, k
+    assert_eq!(Header::SIZE, 3 + 5 + 9 + 9 + 2 + 6 + 3 + 7 + 2 + 2);
+}
+
+#[test]
+fn static_size_odd_width_int() {
+    use binrw::{BinRead, I24, I48, U24, U40, U56, meta::StaticSize};
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Sample {
+        left: I24,
+        right: I24,
+        timestamp: U40,
+    }
+
+    assert_eq!(U24::SIZE, 3);
+    assert_eq!(U40::SIZE, 5);
+    assert_eq!(I48::SIZE, 6);
+    assert_eq!(U56::SIZE, 7);
+    assert_eq!(<[I24; 2]>::SIZE, 6);
+    assert_eq!(Sample::SIZE, 11);
+}
+
+#[test]
+fn schema() {
+    use binrw::{
+        BinRead, Endian, binread,
+        meta::{EndianKind, Schema, SchemaKind},
+    };
+
+    #[allow(dead_code)]
+    #[binread]
+    #[br(big, magic = b"HDR")]
+    struct Header {
+        #[br(temp)]
+        len: u16,
+        #[br(little, count = len, pad_after = 2)]
+        data: Vec<u8>,
+        #[br(if(len > 0), align_before = 4)]
+        extra: Option<u32>,
+        #[br(calc = len + 1)]
+        total: u16,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    #[br(little)]
+    enum Message {
+        #[br(magic = 0u8)]
+        Ping,
+        #[br(magic = 1u8)]
+        Data(#[br(big)] u32, u8),
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    #[br(repr = u8)]
+    enum Kind {
+        A = 1,
+        B,
+    }
+
+    let header = Header::SCHEMA;
+    assert_eq!(header.name, "Header");
+    assert_eq!(header.endian, EndianKind::Endian(Endian::Big));
+    assert_eq!(header.magic, Some("b\"HDR\""));
+    let SchemaKind::Struct(fields) = header.kind else {
+        panic!("expected a struct, got {:?}", header.kind);
+    };
+    assert_eq!(fields.len(), 4);
+    assert_eq!(fields[0].name, "len");
+    assert_eq!(fields[0].ty, "u16");
+    assert!(fields[0].temp);
+    assert_eq!(fields[1].ty, "Vec<u8>");
+    assert_eq!(fields[1].endian, EndianKind::Endian(Endian::Little));
+    assert_eq!(fields[1].count, Some("len"));
+    assert_eq!(fields[1].pad_after, Some("2"));
+    assert_eq!(fields[2].ty, "Option<u32>");
+    assert_eq!(fields[2].endian, EndianKind::Endian(Endian::Big));
+    assert_eq!(fields[2].condition, Some("len > 0"));
+    assert_eq!(fields[2].align_before, Some("4"));
+    assert_eq!(fields[3].calc, Some("len + 1"));
+    assert_eq!(fields[3].count, None);
+
+    let SchemaKind::Enum(variants) = Message::SCHEMA.kind else {
+        panic!("expected an enum, got {:?}", Message::SCHEMA.kind);
+    };
+    assert_eq!(variants[0].name, "Ping");
+    assert_eq!(variants[0].magic, Some("0u8"));
+    assert!(variants[0].fields.is_empty());
+    assert_eq!(variants[1].fields[0].name, "0");
+    assert_eq!(
+        variants[1].fields[0].endian,
+        EndianKind::Endian(Endian::Big)
+    );
+    assert_eq!(
+        variants[1].fields[1].endian,
+        EndianKind::Endian(Endian::Little)
+    );
+
+    let SchemaKind::UnitEnum { repr, variants } = Kind::SCHEMA.kind else {
+        panic!("expected a unit enum, got {:?}", Kind::SCHEMA.kind);
+    };
+    assert_eq!(repr, Some("u8"));
+    assert_eq!(variants[0].discriminant, Some("1"));
+    assert_eq!(variants[1].name, "B");
+    assert_eq!(variants[1].discriminant, None);
+    assert_eq!(Kind::SCHEMA.endian, EndianKind::None);
+}
+
+#[test]
+fn schema_directives() {
+    use binrw::{
+        NullString, binread,
+        checksum::Sum8,
+        meta::{BitOrder, ChecksumSchema, Schema, SchemaKind},
+    };
+
+    #[allow(dead_code)]
+    #[binread]
+    #[br(big, version = version, bit_order = msb)]
+    struct Header {
+        version: u8,
+        #[br(bits = 3)]
+        kind: u8,
+        #[br(bits = 5)]
+        flags: u8,
+        #[br(count_of = items)]
+        len: u8,
+        items: Vec<u8>,
+        #[br(len_prefix = u8)]
+        name: NullString,
+        #[br(size = 8, since = 2, if(len > 0))]
+        extra: Option<u32>,
+        #[br(until = 3)]
+        legacy: u8,
+        #[br(checksum(Sum8, version..=legacy))]
+        sum: u8,
+    }
+
+    let SchemaKind::Struct(fields) = Header::SCHEMA.kind else {
+        panic!("expected a struct, got {:?}", Header::SCHEMA.kind);
+    };
+    assert_eq!(fields[0].bits, None);
+    assert_eq!(fields[1].bits, Some(3));
+    assert_eq!(fields[1].bit_order, BitOrder::Msb);
+    assert_eq!(fields[2].bits, Some(5));
+    assert_eq!(fields[3].count_of, Some("items"));
+    assert_eq!(fields[5].len_prefix, Some("u8"));
+    assert_eq!(fields[5].parse_with, None);
+    assert_eq!(fields[6].size, Some("8"));
+    assert_eq!(fields[6].since, Some("2"));
+    assert_eq!(fields[6].until, None);
+    assert_eq!(fields[6].condition, Some("len > 0"));
+    assert_eq!(fields[7].until, Some("3"));
+    assert_eq!(fields[7].condition, None);
+    let Some(ChecksumSchema {
+        algorithm, range, ..
+    }) = fields[8].checksum
+    else {
+        panic!("expected a checksum, got {:?}", fields[8].checksum);
+    };
+    assert_eq!(algorithm, "Sum8");
+    assert_eq!(range, "version..=legacy");
+}
@@ -0,0 +1,79 @@
+use binrw::{BinRead, BinWrite, Endian, Error, NullString, NullWideString, Prefixed, io::Cursor};
+
+#[test]
+fn prefixed_vec() {
+    let mut data = Cursor::new(b"\x00\x02\x00\x01\x00\x02");
+    let value = Prefixed::<u16, Vec<u16>>::read_be(&mut data).unwrap();
+    assert_eq!(*value, [1, 2]);
+
+    let mut out = Cursor::new(Vec::new());
+    value.write_be(&mut out).unwrap();
+    assert_eq!(out.into_inner(), data.into_inner());
+}
+
+#[test]
+fn prefixed_string() {
+    let mut data = Cursor::new(b"\x06\x00\x00\x00h\xc3\xa9llo");
+    let value = Prefixed::<u32, String>::read_le(&mut data).unwrap();
+    assert_eq!(value.into_inner(), "héllo");
+
+    let mut out = Cursor::new(Vec::new());
+    Prefixed::<u32, String>::new("héllo".into())
+        .write_le(&mut out)
+        .unwrap();
+    assert_eq!(out.into_inner(), data.into_inner());
+}
+
+#[test]
+fn prefixed_null_strings() {
+    let mut data = Cursor::new(b"\x02hi");
+    let value = Prefixed::<u8, NullString>::read_le(&mut data).unwrap();
+    assert_eq!(value.to_string(), "hi");
+
+    let mut out = Cursor::new(Vec::new());
+    value.write_le(&mut out).unwrap();
+    assert_eq!(out.into_inner(), data.into_inner());
+
+    let mut data = Cursor::new(b"\0\x02\0h\0i");
+    let value = Prefixed::<u16, NullWideString>::read_be(&mut data).unwrap();
+    assert_eq!(value.to_string(), "hi");
+
+    let mut out = Cursor::new(Vec::new());
+    value.write_be(&mut out).unwrap();
+    assert_eq!(out.into_inner(), data.into_inner());
+}
+
+#[test]
+fn prefixed_string_invalid_utf8() {
+    let error = Prefixed::<u8, String>::read_le(&mut Cursor::new(b"\x02\xff\xfe")).unwrap_err();
+    assert!(matches!(error, Error::Custom { pos: 1, .. }));
+}
+
+#[test]
+fn prefixed_length_out_of_range() {
+    let mut out = Cursor::new(vec![0; 2]);
+    out.set_position(2);
+    let error = Prefixed::<u8, Vec<u8>>::new(vec![0; 256])
+        .write_options(&mut out, Endian::Little, ())
+        .unwrap_err();
+    match error {
+        Error::AssertFail { pos, message } => {
+            assert_eq!(pos, 2);
+            assert_eq!(message, "length 256 is out of range for u8");
+        }
+        error => panic!("unexpected error: {error:?}"),
+    }
+    assert_eq!(out.into_inner(), [0; 2]);
+}
+
+#[test]
+fn prefixed_negative_length() {
+    let error = Prefixed::<i8, Vec<u8>>::read_le(&mut Cursor::new(b"\xff")).unwrap_err();
+    match error {
+        Error::AssertFail { pos, message } => {
+            assert_eq!(pos, 0);
+            assert_eq!(message, "length -1 is out of range for usize");
+        }
+        error => panic!("unexpected error: {error:?}"),
+    }
+}
@@ -0,0 +1,127 @@
+use binrw::{
+    BinRead, Endian,
+    helpers::until_eof,
+    io::Cursor,
+    recover::{Recovered, read_recovering},
+};
+
+#[derive(BinRead, Debug, Default, PartialEq)]
+#[br(little)]
+struct Entry {
+    #[br(assert(kind < 4))]
+    kind: u8,
+    len: u16,
+}
+
+#[allow(dead_code)]
+#[derive(BinRead, Debug)]
+#[br(little)]
+struct Header {
+    #[br(magic = b"HD")]
+    version: u8,
+    #[br(count = 3)]
+    entries: Vec<Entry>,
+    checksum: Recovered<u32>,
+}
+
+#[test]
+fn paths_and_positions() {
+    let data = b"HD\x01\x01\x02\x00\x07\x03\x00\x09\x04\x00\xff";
+    let (header, errors) = read_recovering::<Header, _>(&mut Cursor::new(data), Endian::Little, ());
+    let header = header.unwrap();
+    assert_eq!(
+        header.entries,
+        [
+            Entry { kind: 1, len: 2 },
+            Entry { kind: 0, len: 3 },
+            Entry { kind: 0, len: 4 },
+        ]
+    );
+    assert_eq!(
+        errors
+            .iter()
+            .map(|error| (error.path.as_str(), error.pos))
+            .collect::<Vec<_>>(),
+        [("entries[1].kind", 6), ("entries[2].kind", 9)]
+    );
+    assert!(
+        errors[0]
+            .to_string()
+            .starts_with("entries[1].kind at 0x6: ")
+    );
+
+    // Held errors are not collected
+    let error = header.checksum.error().unwrap();
+    assert_eq!((error.path.as_str(), error.pos), ("checksum", 12));
+
+    // Outside of a session, nothing recovers
+    assert!(Header::read(&mut Cursor::new(data)).is_err());
+}
+
+#[test]
+fn failed_read() {
+    #[derive(BinRead, Debug)]
+    #[br(repr = u8)]
+    enum Kind {
+        A = 1,
+    }
+
+    #[allow(dead_code)]
+    #[derive(BinRead, Debug)]
+    #[br(little)]
+    struct Test {
+        entry: Entry,
+        kind: Kind,
+    }
+
+    // Errors which were recovered from are returned even if reading fails
+    let (test, errors) =
+        read_recovering::<Test, _>(&mut Cursor::new(b"\x07\0\0\x02"), Endian::Little, ());
+    assert!(test.is_err());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, "entry.kind");
+}
+
+#[test]
+fn backtracking() {
+    #[derive(BinRead, Debug, PartialEq)]
+    #[br(little)]
+    enum Choice {
+        Entry(Entry),
+        Raw(u8, u8),
+    }
+
+    #[derive(BinRead, Debug)]
+    #[br(little)]
+    struct Test {
+        choice: Choice,
+        #[br(try)]
+        maybe: Option<Entry>,
+        tail: u8,
+    }
+
+    #[derive(BinRead, Debug)]
+    #[br(little)]
+    struct Rest {
+        #[br(parse_with = until_eof)]
+        entries: Vec<Entry>,
+    }
+
+    // Variants and `try` still see errors
+    let data = b"\x09\x01\x08\0\0";
+    let (test, errors) = read_recovering::<Test, _>(&mut Cursor::new(data), Endian::Little, ());
+    let test = test.unwrap();
+    assert_eq!(test.choice, Choice::Raw(9, 1));
+    assert_eq!(test.maybe, None);
+    assert_eq!(test.tail, 8);
+    assert!(errors.is_empty());
+
+    // `until_eof` still stops at the end of the input
+    let data = b"\0\x01\0\x01\x02\0\x03";
+    let (rest, errors) = read_recovering::<Rest, _>(&mut Cursor::new(data), Endian::Little, ());
+    assert_eq!(
+        rest.unwrap().entries,
+        [Entry { kind: 0, len: 1 }, Entry { kind: 1, len: 2 }]
+    );
+    assert!(errors.is_empty());
+}
@@ -0,0 +1,214 @@
+use binrw::{
+    BinRead, BinWrite, Endian, Error, FilePtr, NullString, NullWideString, PosValue, binrw,
+    io::Cursor,
+    serde::{
+        Deserializer, LenPrefix, Serializer, deserialize, deserialize_with, serialize,
+        serialize_with,
+    },
+};
+use serde::{
+    Deserialize, Serialize,
+    de::value::{Error as ValueError, MapDeserializer},
+};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Settings {
+    name: String,
+    level: Option<u16>,
+    flags: (bool, char),
+    weights: Vec<f32>,
+    shape: Shape,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+enum Shape {
+    Empty,
+    Circle(u8),
+    Rect { w: u8, h: u8 },
+}
+
+#[test]
+fn endian_and_len_prefix() {
+    let settings = Settings {
+        name: "ab".into(),
+        level: Some(0x102),
+        flags: (true, 'x'),
+        weights: vec![1.0],
+        shape: Shape::Rect { w: 3, h: 4 },
+    };
+
+    let mut out = Cursor::new(Vec::new());
+    Serializer::new(&mut out, Endian::Big)
+        .with_len_prefix(LenPrefix::U16)
+        .serialize(&settings)
+        .unwrap();
+    assert_eq!(
+        out.get_ref(),
+        b"\0\x02ab\x01\x01\x02\x01\0\0\0x\0\x01\x3f\x80\0\0\0\0\0\x02\x03\x04"
+    );
+    out.set_position(0);
+    let value = Deserializer::new(&mut out, Endian::Big)
+        .with_len_prefix(LenPrefix::U16)
+        .deserialize::<Settings>()
+        .unwrap();
+    assert_eq!(value, settings);
+
+    let mut out = Cursor::new(Vec::new());
+    Serializer::new(&mut out, Endian::Little)
+        .with_len_prefix(LenPrefix::U8)
+        .serialize(&settings)
+        .unwrap();
+    assert_eq!(
+        out.get_ref(),
+        b"\x02ab\x01\x02\x01\x01x\0\0\0\x01\0\0\x80\x3f\x02\0\0\0\x03\x04"
+    );
+    out.set_position(0);
+    let value = Deserializer::new(&mut out, Endian::Little)
+        .with_len_prefix(LenPrefix::U8)
+        .deserialize::<Settings>()
+        .unwrap();
+    assert_eq!(value, settings);
+
+    let map = BTreeMap::from([(1u8, Shape::Empty), (2, Shape::Circle(9))]);
+    let mut out = Cursor::new(Vec::new());
+    Serializer::new(&mut out, Endian::Little)
+        .serialize(&map)
+        .unwrap();
+    assert_eq!(out.get_ref(), b"\x02\0\0\0\x01\0\0\0\0\x02\x01\0\0\0\x09");
+    out.set_position(0);
+    let value = Deserializer::new(&mut out, Endian::Little)
+        .deserialize::<BTreeMap<u8, Shape>>()
+        .unwrap();
+    assert_eq!(value, map);
+}
+
+#[binrw]
+#[brw(big, magic = b"CFG")]
+#[derive(Debug, PartialEq)]
+struct Config {
+    version: u8,
+    #[br(parse_with = deserialize)]
+    #[bw(write_with = serialize)]
+    shape: Shape,
+    #[br(parse_with = deserialize_with(LenPrefix::U8))]
+    #[bw(write_with = serialize_with(LenPrefix::U8))]
+    names: Vec<String>,
+}
+
+#[test]
+fn parse_with_and_write_with() {
+    let data = b"CFG\x01\0\0\0\x01\x07\x01\x02hi";
+    let config = Config::read(&mut Cursor::new(data)).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            version: 1,
+            shape: Shape::Circle(7),
+            names: vec!["hi".into()],
+        }
+    );
+
+    let mut out = Cursor::new(Vec::new());
+    config.write(&mut out).unwrap();
+    assert_eq!(out.into_inner(), data);
+}
+
+#[test]
+fn errors() {
+    fn read<T: for<'de> Deserialize<'de>>(data: &[u8]) -> Error {
+        Deserializer::new(&mut Cursor::new(data), Endian::Little)
+            .deserialize::<T>()
+            .map(drop)
+            .unwrap_err()
+    }
+
+    let error = read::<(u8, bool)>(b"\x01\x02");
+    assert!(matches!(error, Error::AssertFail { pos: 1, .. }), "{error}");
+
+    // An invalid variant index is reported by serde, at the start of the enum
+    let error = read::<(u8, Shape)>(b"\x01\x05\0\0\0");
+    assert!(matches!(error, Error::Custom { pos: 1, .. }), "{error}");
+    assert!(error.to_string().contains("variant index"), "{error}");
+
+    let error = read::<String>(b"\x05\0\0\0abc");
+    assert!(error.is_eof(), "{error}");
+
+    let error = read::<String>(b"\x01\0\0\0\xff");
+    assert!(matches!(error, Error::Custom { pos: 0, .. }), "{error}");
+
+    let mut out = Cursor::new(Vec::new());
+    let error = Serializer::new(&mut out, Endian::Little)
+        .with_len_prefix(LenPrefix::U8)
+        .serialize(&vec![0u8; 256])
+        .unwrap_err();
+    assert!(error.to_string().contains("does not fit in U8"), "{error}");
+}
+
+#[test]
+fn binrw_types() {
+    let mut out = Cursor::new(Vec::new());
+    Serializer::new(&mut out, Endian::Little)
+        .with_len_prefix(LenPrefix::U8)
+        .serialize(&NullString::from("hi"))
+        .unwrap();
+    assert_eq!(out.get_ref(), b"\x02hi");
+    out.set_position(0);
+    let value = Deserializer::new(&mut out, Endian::Little)
+        .with_len_prefix(LenPrefix::U8)
+        .deserialize::<NullString>()
+        .unwrap();
+    assert_eq!(value, NullString::from("hi"));
+
+    let mut out = Cursor::new(Vec::new());
+    Serializer::new(&mut out, Endian::Big)
+        .with_len_prefix(LenPrefix::U8)
+        .serialize(&NullWideString::from("hi"))
+        .unwrap();
+    assert_eq!(out.get_ref(), b"\x02\0h\0i");
+    out.set_position(0);
+    let value = Deserializer::new(&mut out, Endian::Big)
+        .with_len_prefix(LenPrefix::U8)
+        .deserialize::<NullWideString>()
+        .unwrap();
+    assert_eq!(value, NullWideString::from("hi"));
+
+    let mut out = Cursor::new(Vec::new());
+    Serializer::new(&mut out, Endian::Little)
+        .serialize(&PosValue { val: 1u8, pos: 2 })
+        .unwrap();
+    assert_eq!(out.get_ref(), b"\x01\x02\0\0\0\0\0\0\0");
+    out.set_position(0);
+    let value = Deserializer::new(&mut out, Endian::Little)
+        .deserialize::<PosValue<u8>>()
+        .unwrap();
+    assert_eq!((value.val, value.pos), (1, 2));
+
+    let ptr = FilePtr {
+        ptr: 4u16,
+        value: 5u8,
+    };
+    let mut out = Cursor::new(Vec::new());
+    Serializer::new(&mut out, Endian::Big)
+        .serialize(&ptr)
+        .unwrap();
+    assert_eq!(out.get_ref(), b"\0\x04\x05");
+    out.set_position(0);
+    let value = Deserializer::new(&mut out, Endian::Big)
+        .deserialize::<FilePtr<u16, u8>>()
+        .unwrap();
+    assert_eq!((value.ptr, value.value), (4, 5));
+
+    // Self-describing formats use the field names
+    let value = PosValue::<u64>::deserialize(MapDeserializer::<_, ValueError>::new(
+        [("pos", 2u64), ("extra", 0), ("val", 1)].into_iter(),
+    ))
+    .unwrap();
+    assert_eq!((value.val, value.pos), (1, 2));
+    let error = PosValue::<u64>::deserialize(MapDeserializer::<_, ValueError>::new(
+        [("val", 1u64)].into_iter(),
+    ))
+    .map(drop)
+    .unwrap_err();
+    assert_eq!(error.to_string(), "missing field `pos`");
+}
@@ -0,0 +1,188 @@
+use binrw::{
+    BinRead, BinWrite, Endian, FilePtr8,
+    io::Cursor,
+    spans::{Span, SpanName, read_with_spans, write_with_spans},
+};
+
+#[allow(dead_code)]
+#[derive(BinRead, Debug)]
+#[br(little)]
+struct Header {
+    kind: Kind,
+    offset: FilePtr8<u16>,
+    #[br(count = 2)]
+    points: Vec<Point>,
+    #[br(calc = 1)]
+    calc: u8,
+}
+
+#[allow(dead_code)]
+#[derive(BinRead, Debug)]
+enum Kind {
+    #[br(magic = 1u8)]
+    One(u8),
+    #[br(magic = 2u8)]
+    Two { value: u16 },
+}
+
+#[allow(dead_code)]
+#[derive(BinRead, Debug)]
+struct Point(u8, u8);
+
+fn names(span: &Span) -> Vec<SpanName> {
+    span.children.iter().map(|child| child.name).collect()
+}
+
+#[test]
+fn tree() {
+    let data = b"\x02\x34\x12\x08\x01\x02\x03\x04\xcd\xab";
+    let (result, span) = read_with_spans::<Header, _>(&mut Cursor::new(data), Endian::Little, ());
+    result.unwrap();
+
+    assert_eq!(span.name, SpanName::Root);
+    assert_eq!(span.range(), 0..8);
+    assert_eq!(
+        names(&span),
+        [
+            SpanName::Field("kind"),
+            SpanName::Field("offset"),
+            SpanName::Field("points")
+        ]
+    );
+
+    let kind = &span.children[0];
+    assert_eq!(kind.range(), 0..3);
+    assert_eq!(names(kind), [SpanName::Variant("Two")]);
+    assert_eq!(kind.children[0].range(), 0..3);
+    assert_eq!(
+        kind.children[0].children[0],
+        Span {
+            name: SpanName::Field("value"),
+            ty: "u16",
+            start: 1,
+            end: 3,
+            children: Vec::new(),
+        }
+    );
+
+    let offset = &span.children[1];
+    assert_eq!(offset.range(), 3..4);
+    assert_eq!(names(offset), [SpanName::Pointee]);
+    assert_eq!(offset.children[0].range(), 8..10);
+
+    let points = &span.children[2];
+    assert_eq!(names(points), [SpanName::Index(0), SpanName::Index(1)]);
+    assert_eq!(points.children[1].range(), 6..8);
+    assert_eq!(span.path_to(&[2, 1, 0]), Some(("points[1].0".into(), 6..7)));
+    assert_eq!(span.path_at(7), Some(("points[1].1".into(), 7..8)));
+    assert_eq!(span.path_at(8), None);
+}
+
+#[test]
+fn hexdump() {
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Test {
+        #[br(count = 18)]
+        data: Vec<u8>,
+        end: u8,
+    }
+
+    let data = (0..19).collect::<Vec<u8>>();
+    let (result, span) = read_with_spans::<Test, _>(&mut Cursor::new(&data), Endian::Little, ());
+    assert!(result.is_ok());
+    assert_eq!(
+        span.hexdump(&data).to_string(),
+        "\
+00000000  00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f  data: Vec<u8>
+00000010  10 11
+00000012  12                                               end: u8
+"
+    );
+}
+
+#[test]
+fn failed_read() {
+    let data = b"\x01\x05\x03\x01\x02\x03";
+    let (result, span) = read_with_spans::<Header, _>(&mut Cursor::new(data), Endian::Little, ());
+    assert!(result.is_err());
+
+    // Everything read before the failure is kept, including the field which
+    // failed, and values which failed end where their last child ended
+    assert_eq!(span.range(), 0..6);
+    assert_eq!(span.children[0].children[0].name, SpanName::Variant("One"));
+    let points = &span.children[2];
+    assert_eq!(points.range(), 3..6);
+    assert_eq!(names(points), [SpanName::Index(0), SpanName::Index(1)]);
+    assert_eq!(points.children[1].range(), 5..6);
+    assert_eq!(points.children[1].children[1].name, SpanName::Field("1"));
+    assert_eq!(points.children[1].children[1].range(), 6..6);
+}
+
+#[test]
+fn failed_variant() {
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    enum Test {
+        A(u8, #[br(assert(self_1 == 0))] u8),
+        B(u16),
+    }
+
+    let data = b"\x01\x02";
+    let (result, span) = read_with_spans::<Test, _>(&mut Cursor::new(data), Endian::Little, ());
+    assert!(result.is_ok());
+    assert_eq!(names(&span), [SpanName::Variant("B")]);
+    assert_eq!(names(&span.children[0]), [SpanName::Field("0")]);
+}
+
+#[test]
+fn not_recording() {
+    let data = b"\x01\x05\x01\x02\x03\x04\x05";
+    Header::read_le(&mut Cursor::new(data)).unwrap();
+
+    let (_, span) = read_with_spans::<Point, _>(&mut Cursor::new(data), Endian::Little, ());
+    assert_eq!(names(&span), [SpanName::Field("0"), SpanName::Field("1")]);
+}
+
+#[test]
+fn written() {
+    #[derive(BinWrite)]
+    enum Shape {
+        #[bw(magic = 1u8)]
+        Line { len: u16 },
+    }
+
+    #[derive(BinWrite)]
+    #[bw(little)]
+    struct Drawing {
+        #[bw(magic = 0xffu8)]
+        id: u8,
+        shape: Shape,
+        points: Vec<u16>,
+    }
+
+    let drawing = Drawing {
+        id: 7,
+        shape: Shape::Line { len: 2 },
+        points: vec![3, 4],
+    };
+    let (result, span) = write_with_spans(&drawing, &mut Cursor::new(Vec::new()), Endian::Big, ());
+    result.unwrap();
+
+    assert_eq!(span.range(), 0..9);
+    assert_eq!(
+        names(&span),
+        [
+            SpanName::Field("id"),
+            SpanName::Field("shape"),
+            SpanName::Field("points")
+        ]
+    );
+    assert_eq!(span.children[0].range(), 1..2);
+    assert_eq!(names(&span.children[1]), [SpanName::Variant("Line")]);
+    assert_eq!(
+        span.path_to(&[1, 0, 0]),
+        Some(("shape.Line.len".into(), 3..5))
+    );
+    assert_eq!(span.path_to(&[2, 1]), Some(("points[1]".into(), 7..9)));
+}
@@ -152,3 +152,303 @@ fn null_wide_string_round_trip() {
 
     assert_eq!(&s2.to_string(), data);
 }
+
+#[test]
+fn fixed_strings() {
+    use binrw::{BinRead, BinWrite, Endian, FixedString, io::Cursor, strings::Padding};
+
+    let read = |data: &[u8], padding| {
+        FixedString::<6>::read_options(
+            &mut Cursor::new(data),
+            Endian::Little,
+            binrw::args! { padding },
+        )
+    };
+
+    assert_eq!(read(b"abc\0de", Padding::Null).unwrap().to_string(), "abc");
+    assert_eq!(
+        read(b"abcdef", Padding::Null).unwrap().to_string(),
+        "abcdef"
+    );
+    assert_eq!(read(b"a c   ", Padding::Space).unwrap().to_string(), "a c");
+    assert_eq!(read(b"      ", Padding::Space).unwrap().to_string(), "");
+    assert_eq!(
+        read(b"abc\0\0\0", Padding::Strict).unwrap().to_string(),
+        "abc"
+    );
+    assert!(matches!(
+        read(b"abc\0\0d", Padding::Strict),
+        Err(binrw::Error::AssertFail { pos: 5, .. })
+    ));
+    assert!(read(b"abc", Padding::Null).unwrap_err().is_eof());
+
+    assert_eq!(
+        format!("{:?}", read(b"hey\n\0\0", Padding::Null).unwrap()),
+        "FixedString<6>(\"hey\\n\")"
+    );
+
+    let write = |s: &str, padding| {
+        let mut out = Cursor::new(Vec::new());
+        FixedString::<6>::from(s)
+            .write_options(&mut out, Endian::Little, binrw::args! { padding })
+            .map(|()| out.into_inner())
+    };
+
+    assert_eq!(write("abc", Padding::Null).unwrap(), b"abc\0\0\0");
+    assert_eq!(write("abc", Padding::Space).unwrap(), b"abc   ");
+    assert_eq!(write("abcdef", Padding::Strict).unwrap(), b"abcdef");
+    assert!(matches!(
+        write("abcdefg", Padding::Null),
+        Err(binrw::Error::AssertFail { pos: 0, .. })
+    ));
+}
+
+#[test]
+fn fixed_wide_strings() {
+    use binrw::{BinReaderExt, BinWrite, Endian, FixedWideString, io::Cursor, strings::Padding};
+
+    let s = Cursor::new(b"\0h\0i\0\0\0!")
+        .read_be::<FixedWideString<4>>()
+        .unwrap();
+    assert_eq!(s.to_string(), "hi");
+    assert_eq!(format!("{s:?}"), "FixedWideString<4>(\"hi\")");
+
+    let mut out = Cursor::new(Vec::new());
+    s.write_options(
+        &mut out,
+        Endian::Little,
+        binrw::args! { padding: Padding::Space },
+    )
+    .unwrap();
+    assert_eq!(out.into_inner(), b"h\0i\0 \0 \0");
+
+    let mut out = Cursor::new(Vec::new());
+    FixedWideString::<1>::from("hi")
+        .write_options(
+            &mut out,
+            Endian::Little,
+            binrw::args! { padding: Padding::Null },
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn fixed_string_static_size() {
+    use binrw::{BinRead, FixedString, FixedWideString, meta::StaticSize};
+
+    #[allow(dead_code)]
+    #[derive(BinRead)]
+    struct Header {
+        name: FixedString<8>,
+        title: FixedWideString<4>,
+        version: u16,
+    }
+
+    assert_eq!(FixedString::<6>::SIZE, 6);
+    assert_eq!(FixedWideString::<6>::SIZE, 12);
+    assert_eq!(Header::SIZE, 18);
+}
+
+#[test]
+fn encoded_strings() {
+    use binrw::{
+        BinRead, BinWrite, EncodedString, Endian, Error, NullString,
+        encoding::{Ascii, Encoding, Latin1, Utf16Be, Utf16Bom, Utf16Le, Utf32},
+        io::Cursor,
+    };
+
+    fn read(
+        data: &[u8],
+        endian: Endian,
+        encoding: &'static dyn Encoding,
+    ) -> binrw::BinResult<String> {
+        EncodedString::read_options(&mut Cursor::new(data), endian, binrw::args! { encoding })
+            .map(String::from)
+    }
+
+    fn write(
+        text: &str,
+        endian: Endian,
+        encoding: &'static dyn Encoding,
+    ) -> binrw::BinResult<Vec<u8>> {
+        let mut out = Cursor::new(Vec::new());
+        EncodedString::from(text)
+            .write_options(&mut out, endian, binrw::args! { encoding })
+            .map(|()| out.into_inner())
+    }
+
+    let cases: [(&'static dyn Encoding, Endian, &[u8]); 7] = [
+        (&Latin1, Endian::Big, b"caf\xe9\0"),
+        (&Ascii, Endian::Big, b"cafe\0"),
+        (&Utf16Le, Endian::Big, b"c\0a\0f\0\xe9\0\0\0"),
+        (&Utf16Be, Endian::Little, b"\0c\0a\0f\0\xe9\0\0"),
+        (&Utf16Bom, Endian::Big, b"\xfe\xff\0c\0a\0f\0\xe9\0\0"),
+        (&Utf16Bom, Endian::Little, b"\xff\xfec\0a\0f\0\xe9\0\0\0"),
+        (
+            &Utf32,
+            Endian::Big,
+            b"\0\0\0c\0\0\0a\0\0\0f\0\0\0\xe9\0\0\0\0",
+        ),
+    ];
+    for (encoding, endian, data) in cases {
+        let text = read(data, endian, encoding).unwrap();
+        assert!(text == "café" || text == "cafe", "{text}");
+        assert_eq!(write(&text, endian, encoding).unwrap(), data);
+    }
+
+    // The byte order mark takes priority over the byte order of the field
+    assert_eq!(
+        read(b"\xfe\xff\0h\0i\0\0", Endian::Little, &Utf16Bom).unwrap(),
+        "hi"
+    );
+    assert_eq!(
+        read(b"h\0i\0\0\0", Endian::Little, &Utf16Bom).unwrap(),
+        "hi"
+    );
+
+    // Without an encoding, the bytes are unchanged
+    let mut data = Cursor::new(b"caf\xe9\0");
+    assert_eq!(NullString::read_le(&mut data).unwrap().0, b"caf\xe9");
+
+    let error = read(b"ab\xc3\0", Endian::Little, &binrw::encoding::Utf8).unwrap_err();
+    assert!(matches!(error, Error::Custom { pos: 2, .. }), "{error}");
+    let error = read(b"ab\x80\0", Endian::Little, &Ascii).unwrap_err();
+    assert_eq!(error.to_string(), "invalid ASCII byte 0x80 at 0x2");
+    let error = read(b"a\0\0\xdc\0\0", Endian::Little, &Utf16Le).unwrap_err();
+    assert_eq!(error.to_string(), "unpaired UTF-16 surrogate at 0x2");
+    assert!(read(b"abc", Endian::Little, &Latin1).unwrap_err().is_eof());
+
+    let error = write("a☺", Endian::Little, &Latin1).unwrap_err();
+    assert_eq!(error.to_string(), "'☺' cannot be encoded as Latin-1 at 0x0");
+    write("é", Endian::Little, &Ascii).unwrap_err();
+}
+
+#[test]
+fn encoded_fixed_strings() {
+    use binrw::{
+        BinRead, BinWrite, Endian, FixedString, FixedWideString,
+        encoding::{Latin1, Utf16Bom, Utf16Le},
+        io::Cursor,
+        strings::Padding,
+    };
+
+    let read = |data: &[u8], padding| {
+        FixedString::<6>::read_options(
+            &mut Cursor::new(data),
+            Endian::Little,
+            binrw::args! { padding, encoding: Some(&Latin1) },
+        )
+    };
+    let write = |s: &str, padding| {
+        let mut out = Cursor::new(Vec::new());
+        FixedString::<6>::from(s)
+            .write_options(
+                &mut out,
+                Endian::Little,
+                binrw::args! { padding, encoding: Some(&Latin1) },
+            )
+            .map(|()| out.into_inner())
+    };
+
+    let s = read(b"caf\xe9\0x", Padding::Null).unwrap();
+    assert_eq!(s.to_string(), "café");
+    assert_eq!(
+        write(&s.to_string(), Padding::Null).unwrap(),
+        b"caf\xe9\0\0"
+    );
+    let s = read(b"\xe9t\xe9   ", Padding::Space).unwrap();
+    assert_eq!(s.to_string(), "été");
+    assert_eq!(
+        write(&s.to_string(), Padding::Space).unwrap(),
+        b"\xe9t\xe9   "
+    );
+    assert!(matches!(
+        read(b"ab\0\0c\0", Padding::Strict),
+        Err(binrw::Error::AssertFail { pos: 4, .. })
+    ));
+    // The limit is in bytes of encoded text
+    assert!(matches!(
+        write("ééééééé", Padding::Null),
+        Err(binrw::Error::AssertFail { pos: 0, .. })
+    ));
+
+    // Wide strings take up the same number of bytes with an encoding, and the
+    // padding uses code units of the encoding
+    let s = FixedWideString::<4>::read_options(
+        &mut Cursor::new(b"\xfe\xff\0h\0i\0\0"),
+        Endian::Little,
+        binrw::args! { encoding: Some(&Utf16Bom) },
+    )
+    .unwrap();
+    assert_eq!(s.to_string(), "hi");
+
+    let mut out = Cursor::new(Vec::new());
+    s.write_options(
+        &mut out,
+        Endian::Little,
+        binrw::args! { padding: Padding::Space, encoding: Some(&Utf16Bom) },
+    )
+    .unwrap();
+    assert_eq!(out.into_inner(), b"\xff\xfeh\0i\0 \0");
+
+    let mut out = Cursor::new(Vec::new());
+    FixedWideString::<2>::from("hé")
+        .write_options(
+            &mut out,
+            Endian::Big,
+            binrw::args! { encoding: Some(&Utf16Le) },
+        )
+        .unwrap();
+    assert_eq!(out.into_inner(), b"h\0\xe9\0");
+}
+
+#[cfg(feature = "codepages")]
+#[test]
+fn codepage_strings() {
+    use binrw::{
+        BinRead, BinWrite, EncodedString, Endian,
+        encoding::encoding_rs::{SHIFT_JIS, UTF_16LE, WINDOWS_1252},
+        io::Cursor,
+    };
+
+    let text = EncodedString::read_options(
+        &mut Cursor::new(b"\x82\xb1\x82\xf1\0"),
+        Endian::Little,
+        binrw::args! { encoding: &SHIFT_JIS },
+    )
+    .unwrap();
+    assert_eq!(text.as_str(), "こん");
+
+    let mut out = Cursor::new(Vec::new());
+    EncodedString::from("€5")
+        .write_options(
+            &mut out,
+            Endian::Little,
+            binrw::args! { encoding: &WINDOWS_1252 },
+        )
+        .unwrap();
+    assert_eq!(out.into_inner(), b"\x805\0");
+
+    let error = EncodedString::read_options(
+        &mut Cursor::new(b"a\x82\0"),
+        Endian::Little,
+        binrw::args! { encoding: &SHIFT_JIS },
+    )
+    .unwrap_err();
+    assert!(error.to_string().ends_with("at 0x1"), "{error}");
+
+    let text = EncodedString::read_options(
+        &mut Cursor::new(b"h\0i\0\0\0"),
+        Endian::Little,
+        binrw::args! { encoding: &UTF_16LE },
+    )
+    .unwrap();
+    assert_eq!(text.as_str(), "hi");
+    EncodedString::from("hi")
+        .write_options(
+            &mut Cursor::new(Vec::new()),
+            Endian::Little,
+            binrw::args! { encoding: &UTF_16LE },
+        )
+        .unwrap_err();
+}
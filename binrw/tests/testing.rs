@@ -0,0 +1,96 @@
+use binrw::{
+    Endian, binrw,
+    testing::{assert_golden, assert_roundtrip, assert_writes_to},
+};
+use std::panic::{UnwindSafe, catch_unwind};
+
+#[binrw]
+#[brw(little, magic = b"HD")]
+#[derive(Debug, PartialEq)]
+struct Header {
+    version: u8,
+    #[br(count = 2)]
+    entries: Vec<Entry>,
+}
+
+#[binrw]
+#[brw(little)]
+#[derive(Debug, PartialEq)]
+struct Entry {
+    kind: u8,
+    // Writes a different value than was read, to make the data mismatch
+    #[bw(map = |len: &u16| if *len == 9 { 8 } else { *len })]
+    len: u16,
+}
+
+fn panic_message(f: impl FnOnce() + UnwindSafe) -> String {
+    let payload = catch_unwind(f).unwrap_err();
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => (*payload.downcast::<&str>().unwrap()).to_owned(),
+    }
+}
+
+#[test]
+fn roundtrip() {
+    let header = assert_roundtrip::<Header>(b"HD\x01\x01\x02\x00\x03\x04\x00", Endian::Little, ());
+    assert_eq!(header.entries[1], Entry { kind: 3, len: 4 });
+}
+
+#[test]
+fn roundtrip_mismatch() {
+    let message = panic_message(|| {
+        assert_roundtrip::<Header>(b"HD\x01\x01\x02\x00\x03\x09\x00", Endian::Little, ());
+    });
+    assert_eq!(
+        message,
+        "data written for `testing::Header` does not match the expected data\n\
+         first difference at 0x7, in `entries[1].len` (0x7..0x9)\n\
+         - 00000000  48 44 01 01 02 00 03 09 00\n\
+         + 00000000  48 44 01 01 02 00 03 08 00\n\
+         \x20                                ^^\n"
+    );
+}
+
+#[test]
+fn writes_to_length_mismatch() {
+    let entry = Entry { kind: 1, len: 2 };
+    let message = panic_message(|| assert_writes_to(&entry, b"\x01\x02\x00\x00"));
+    assert!(message.contains("first difference at 0x3\n"), "{message}");
+    assert!(
+        message.contains("expected 4 bytes, but 3 bytes were written\n"),
+        "{message}"
+    );
+
+    let message = panic_message(|| assert_writes_to(&entry, b"\x01"));
+    assert!(
+        message.contains("first difference at 0x1, in `len` (0x1..0x3)\n"),
+        "{message}"
+    );
+}
+
+#[test]
+fn golden() {
+    let dir = std::env::temp_dir().join(format!("binrw-testing-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("entry.bin");
+    std::fs::write(&path, b"\x01\x02\x00").unwrap();
+
+    assert_golden(&Entry { kind: 1, len: 2 }, &path);
+
+    let message = panic_message(|| assert_golden(&Entry { kind: 1, len: 3 }, &path));
+    assert!(message.contains("in `len`"), "{message}");
+    assert!(
+        message.contains("run with BINRW_BLESS=1 to update it"),
+        "{message}"
+    );
+
+    let missing = dir.join("missing.bin");
+    let message = panic_message(|| assert_golden(&Entry { kind: 1, len: 2 }, &missing));
+    assert!(
+        message.contains("run with BINRW_BLESS=1 to create it"),
+        "{message}"
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,287 @@
+use binrw::{
+    BinRead, BinWrite, Endian, Error, VarInt, VarUint,
+    io::Cursor,
+    varint::{SignedFormat, UnsignedFormat, VarIntArgs},
+};
+
+/// Reads a value from `data`, and checks that writing it produces `data`
+/// again.
+fn round_trip_with<T, Args: Clone>(data: &[u8], args: Args) -> T
+where
+    T: for<'a> BinRead<Args<'a> = Args> + for<'a> BinWrite<Args<'a> = Args>,
+{
+    let value = T::read_options(&mut Cursor::new(data), Endian::Little, args.clone()).unwrap();
+    let mut out = Cursor::new(Vec::new());
+    value.write_options(&mut out, Endian::Little, args).unwrap();
+    assert_eq!(out.into_inner(), data);
+    value
+}
+
+fn unsigned(format: UnsignedFormat) -> VarIntArgs<UnsignedFormat> {
+    VarIntArgs {
+        format,
+        ..Default::default()
+    }
+}
+
+fn signed(format: SignedFormat) -> VarIntArgs<SignedFormat> {
+    VarIntArgs {
+        format,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn round_trip() {
+    let unsigned_cases: &[(UnsignedFormat, u64, &[u8])] = &[
+        (UnsignedFormat::Leb128, 0, b"\0"),
+        (UnsignedFormat::Leb128, 127, b"\x7f"),
+        (UnsignedFormat::Leb128, 128, b"\x80\x01"),
+        (UnsignedFormat::Leb128, 624_485, b"\xe5\x8e\x26"),
+        (
+            UnsignedFormat::Leb128,
+            u64::MAX,
+            b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01",
+        ),
+        (UnsignedFormat::Vlq, 0, b"\0"),
+        (UnsignedFormat::Vlq, 0x40, b"\x40"),
+        (UnsignedFormat::Vlq, 0x2000, b"\xc0\x00"),
+        (UnsignedFormat::Vlq, 0x0fff_ffff, b"\xff\xff\xff\x7f"),
+        (
+            UnsignedFormat::Vlq,
+            u64::MAX,
+            b"\x81\xff\xff\xff\xff\xff\xff\xff\xff\x7f",
+        ),
+    ];
+    for &(format, value, data) in unsigned_cases {
+        let read = round_trip_with::<VarUint, _>(data, unsigned(format));
+        assert_eq!(read.0, value);
+    }
+
+    let signed_cases: &[(SignedFormat, i64, &[u8])] = &[
+        (SignedFormat::Leb128, 0, b"\0"),
+        (SignedFormat::Leb128, -1, b"\x7f"),
+        (SignedFormat::Leb128, 63, b"\x3f"),
+        (SignedFormat::Leb128, 64, b"\xc0\x00"),
+        (SignedFormat::Leb128, -65, b"\xbf\x7f"),
+        (SignedFormat::Leb128, -123_456, b"\xc0\xbb\x78"),
+        (
+            SignedFormat::Leb128,
+            i64::MAX,
+            b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x00",
+        ),
+        (
+            SignedFormat::Leb128,
+            -(1 << 56),
+            b"\x80\x80\x80\x80\x80\x80\x80\x80\x7f",
+        ),
+        (
+            SignedFormat::Leb128,
+            -(1 << 60),
+            b"\x80\x80\x80\x80\x80\x80\x80\x80\x70",
+        ),
+        (
+            SignedFormat::Leb128,
+            -(1 << 62),
+            b"\x80\x80\x80\x80\x80\x80\x80\x80\x40",
+        ),
+        (
+            SignedFormat::Leb128,
+            i64::MIN,
+            b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x7f",
+        ),
+        (SignedFormat::ZigZag, 0, b"\0"),
+        (SignedFormat::ZigZag, -1, b"\x01"),
+        (SignedFormat::ZigZag, 1, b"\x02"),
+        (SignedFormat::ZigZag, -65, b"\x81\x01"),
+        (
+            SignedFormat::ZigZag,
+            i64::MIN,
+            b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01",
+        ),
+    ];
+    for &(format, value, data) in signed_cases {
+        let read = round_trip_with::<VarInt, _>(data, signed(format));
+        assert_eq!(read.0, value);
+    }
+
+    // A redundant sign extension which fills the ninth byte
+    assert_eq!(
+        VarInt::read_le(&mut Cursor::new(b"\xff\xff\xff\xff\xff\xff\xff\xff\x7f"))
+            .unwrap()
+            .0,
+        -1
+    );
+}
+
+#[test]
+fn max_len_and_overflow() {
+    let args = VarIntArgs {
+        max_len: 2,
+        ..Default::default()
+    };
+    assert!(matches!(
+        VarUint::read_le_args(&mut Cursor::new(b"\x80\x80\x01"), args),
+        Err(Error::AssertFail { pos: 0, .. })
+    ));
+    let error = VarUint(0x4000)
+        .write_le_args(&mut Cursor::new(Vec::new()), args)
+        .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("needs 3 bytes, but the maximum is 2"),
+        "{error}"
+    );
+    let mut out = Cursor::new(Vec::new());
+    VarUint(0x3fff).write_le_args(&mut out, args).unwrap();
+    assert_eq!(out.into_inner(), b"\xff\x7f");
+
+    let args = VarIntArgs {
+        max_len: 12,
+        ..Default::default()
+    };
+    VarUint::read_le_args(
+        &mut Cursor::new(b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x02"),
+        args,
+    )
+    .unwrap_err();
+    VarUint::read_le_args(
+        &mut Cursor::new(b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x80\x01"),
+        args,
+    )
+    .unwrap_err();
+    assert_eq!(
+        VarUint::read_le_args(
+            &mut Cursor::new(b"\x81\x80\x80\x80\x80\x80\x80\x80\x80\x80\x00"),
+            args
+        )
+        .unwrap()
+        .0,
+        1
+    );
+    VarUint::read_le_args(
+        &mut Cursor::new(b"\x82\xff\xff\xff\xff\xff\xff\xff\xff\x7f"),
+        unsigned(UnsignedFormat::Vlq),
+    )
+    .unwrap_err();
+
+    // Bits past the 64th must all be copies of the sign bit
+    let args = VarIntArgs {
+        max_len: 12,
+        ..Default::default()
+    };
+    assert_eq!(
+        VarInt::read_le_args(
+            &mut Cursor::new(b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\x7f"),
+            args
+        )
+        .unwrap()
+        .0,
+        -1
+    );
+    VarInt::read_le_args(
+        &mut Cursor::new(b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x01"),
+        args,
+    )
+    .unwrap_err();
+    VarInt::read_le_args(
+        &mut Cursor::new(b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x7e"),
+        args,
+    )
+    .unwrap_err();
+
+    assert!(
+        VarUint::read_le_args(&mut Cursor::new(b"\x80"), VarIntArgs::default())
+            .unwrap_err()
+            .is_eof()
+    );
+
+    // Failed reads do not consume anything
+    let mut data = Cursor::new(b"\x80\x80");
+    assert!(
+        VarUint::read_le_args(&mut data, VarIntArgs::default())
+            .unwrap_err()
+            .is_eof()
+    );
+    assert_eq!(data.position(), 0);
+}
+
+#[test]
+fn strict() {
+    fn strict<Format: Default>(format: Format) -> VarIntArgs<Format> {
+        VarIntArgs {
+            format,
+            strict: true,
+            ..Default::default()
+        }
+    }
+
+    assert_eq!(
+        VarUint::read_le_args(&mut Cursor::new(b"\x80\x00"), VarIntArgs::default())
+            .unwrap()
+            .0,
+        0
+    );
+    assert!(matches!(
+        VarUint::read_le_args(
+            &mut Cursor::new(b"\x80\x00"),
+            strict(UnsignedFormat::Leb128)
+        ),
+        Err(Error::AssertFail { pos: 0, .. })
+    ));
+    VarUint::read_le_args(
+        &mut Cursor::new(b"\x80\x01"),
+        strict(UnsignedFormat::Leb128),
+    )
+    .unwrap();
+    VarUint::read_le_args(&mut Cursor::new(b"\x80\x7f"), strict(UnsignedFormat::Vlq)).unwrap_err();
+    VarUint::read_le_args(&mut Cursor::new(b"\x81\x00"), strict(UnsignedFormat::Vlq)).unwrap();
+
+    VarInt::read_le_args(&mut Cursor::new(b"\xff\x7f"), strict(SignedFormat::Leb128)).unwrap_err();
+    VarInt::read_le_args(&mut Cursor::new(b"\x80\x00"), strict(SignedFormat::Leb128)).unwrap_err();
+    VarInt::read_le_args(&mut Cursor::new(b"\xc0\x00"), strict(SignedFormat::Leb128)).unwrap();
+    VarInt::read_le_args(&mut Cursor::new(b"\xbf\x7f"), strict(SignedFormat::Leb128)).unwrap();
+    VarInt::read_le_args(&mut Cursor::new(b"\x81\x00"), strict(SignedFormat::ZigZag)).unwrap_err();
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(VarUint::from(5u8), VarUint(5));
+    assert_eq!(VarUint::try_from(-1i32).ok(), None);
+    assert_eq!(u8::try_from(VarUint(300)).ok(), None);
+    assert_eq!(u128::from(VarUint(300)), 300);
+
+    assert_eq!(VarInt::from(-5i8), VarInt(-5));
+    assert_eq!(VarInt::try_from(u64::MAX).ok(), None);
+    assert_eq!(u32::try_from(VarInt(-1)).ok(), None);
+    assert_eq!(i64::from(VarInt(-1)), -1);
+}
+
+#[test]
+fn derive() {
+    #[derive(BinRead, BinWrite, Debug, PartialEq)]
+    #[brw(little)]
+    struct Record {
+        len: VarUint,
+        #[brw(args { format: SignedFormat::ZigZag, strict: true })]
+        delta: VarInt,
+        #[br(count = u64::from(len))]
+        data: Vec<u8>,
+    }
+
+    let data = b"\x02\x03ab";
+    let record = Record::read(&mut Cursor::new(data)).unwrap();
+    assert_eq!(
+        record,
+        Record {
+            len: VarUint(2),
+            delta: VarInt(-2),
+            data: b"ab".to_vec(),
+        }
+    );
+
+    let mut out = Cursor::new(Vec::new());
+    record.write(&mut out).unwrap();
+    assert_eq!(out.into_inner(), data);
+}
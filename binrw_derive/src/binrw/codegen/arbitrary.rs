@@ -0,0 +1,311 @@
+use super::sanitization::{
+    ARBITRARY_BITS, ARBITRARY_CHECK, ARBITRARY_COUNT, ARBITRARY_LEN, ARBITRARY_LIFETIME,
+    ARBITRARY_RESULT, ARBITRARY_TRAIT, ARBITRARY_TRY_CALC, THIS, UNSTRUCTURED, UNSTRUCTURED_VAR,
+};
+use crate::binrw::parser::{
+    Assert, EnumVariant, FieldMode, Imports, Input, Map, Struct, StructField,
+};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::Ident;
+
+/// Generates an implementation of `Arbitrary` which only produces values that
+/// can be written and then read back, if the `arbitrary` directive is used.
+pub(super) fn generate(input: &Input, derive_input: &syn::DeriveInput) -> Option<TokenStream> {
+    let span = input.arbitrary()?.span();
+
+    if !cfg!(feature = "arbitrary") {
+        return Some(error(
+            span,
+            "`arbitrary` requires the `arbitrary` feature of binrw",
+        ));
+    }
+
+    if !matches!(input.imports(), Imports::None) {
+        return Some(error(span, "`arbitrary` cannot be used with `import`"));
+    }
+
+    let body = match input {
+        Input::Struct(st) | Input::UnitStruct(st) => {
+            if st.map.is_some() {
+                return Some(error(
+                    span,
+                    "`arbitrary` cannot be used with `map` or `try_map`",
+                ));
+            }
+            struct_body(st, &quote! { Self }, &[])
+        }
+        Input::Union(_) => return Some(error(span, "`arbitrary` cannot be used on a union")),
+        Input::Enum(en) => {
+            if en.map.is_some() {
+                return Some(error(
+                    span,
+                    "`arbitrary` cannot be used with `map` or `try_map`",
+                ));
+            }
+            let variants = en
+                .variants
+                .iter()
+                .filter(|variant| variant.other().is_none())
+                .map(|variant| match variant {
+                    EnumVariant::Variant { ident, options } => {
+                        let pre_assertions = checks(&options.pre_assertions, &[]);
+                        let body = struct_body(options, &quote! { Self::#ident }, &en.assertions);
+                        quote! {
+                            #(#pre_assertions)*
+                            #body
+                        }
+                    }
+                    EnumVariant::Unit(field) => {
+                        let ident = &field.ident;
+                        let pre_assertions = checks(&field.pre_assertions, &[]);
+                        quote! {
+                            #(#pre_assertions)*
+                            ::core::result::Result::Ok(Self::#ident)
+                        }
+                    }
+                });
+            choose(variants)
+        }
+        Input::UnitOnlyEnum(en) => {
+            if matches!(en.map, Map::Map(_) | Map::Try(_)) {
+                return Some(error(
+                    span,
+                    "`arbitrary` cannot be used with `map` or `try_map`",
+                ));
+            }
+            // The `other` variant holds values which match no other variant,
+            // so generating it could produce a value which reads back as a
+            // different variant
+            let variants = en
+                .fields
+                .iter()
+                .filter(|field| field.other.is_none() && !field.holds_value)
+                .map(|field| {
+                    let ident = &field.ident;
+                    quote! { ::core::result::Result::Ok(Self::#ident) }
+                });
+            choose(variants)
+        }
+    };
+
+    let lifetime = syn::Lifetime::new(&format!("'{ARBITRARY_LIFETIME}"), Span::call_site());
+    let mut generics = derive_input.generics.clone();
+    let mut lifetime_param = syn::LifetimeParam::new(lifetime.clone());
+    for param in &mut generics.params {
+        match param {
+            syn::GenericParam::Type(ty) => {
+                ty.bounds
+                    .push(syn::parse_quote! { #ARBITRARY_TRAIT<#lifetime> });
+            }
+            syn::GenericParam::Lifetime(param) => {
+                lifetime_param.bounds.push(param.lifetime.clone());
+            }
+            syn::GenericParam::Const(_) => {}
+        }
+    }
+    let (_, ty_generics, where_clause) = derive_input.generics.split_for_impl();
+    generics
+        .params
+        .insert(0, syn::GenericParam::Lifetime(lifetime_param));
+    let (impl_generics, _, _) = generics.split_for_impl();
+
+    let name = &derive_input.ident;
+    Some(quote! {
+        #[automatically_derived]
+        #[allow(non_snake_case, unknown_lints)]
+        impl #impl_generics #ARBITRARY_TRAIT<#lifetime> for #name #ty_generics #where_clause {
+            fn arbitrary(#UNSTRUCTURED_VAR: &mut #UNSTRUCTURED<#lifetime>) -> #ARBITRARY_RESULT<Self> {
+                #body
+            }
+        }
+    })
+}
+
+fn error(span: Span, message: &str) -> TokenStream {
+    syn::Error::new(span, message).to_compile_error()
+}
+
+/// Chooses one of the given variant bodies.
+fn choose(variants: impl Iterator<Item = TokenStream>) -> TokenStream {
+    let variants = variants.collect::<Vec<_>>();
+    let count = variants.len();
+    let indexes = 0..count;
+    quote! {
+        match #UNSTRUCTURED_VAR.choose_index(#count)? {
+            #(#indexes => { #variants })*
+            _ => ::core::unreachable!(),
+        }
+    }
+}
+
+/// Generates the fields of a struct or data variant in order, so that later
+/// fields can use the values of earlier fields just like when reading.
+fn struct_body(st: &Struct, constructor: &TokenStream, extra_assertions: &[Assert]) -> TokenStream {
+    // Temporary fields are not stored, so they cannot be generated in a way
+    // that is consistent with what is written. Anything which depends on them
+    // is treated as unconstrained.
+    let temps = st
+        .fields
+        .iter()
+        .filter(|field| field.is_temp(false))
+        .map(|field| &field.ident)
+        .collect::<Vec<_>>();
+
+    let fields = st
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| !field.is_temp(false))
+        .map(|(index, field)| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let value = field_value(field, &st.fields[index + 1..], &temps);
+            let value = match &field.if_cond {
+                Some(cond) if !uses_any(&cond.condition, &temps) => {
+                    let condition = &cond.condition;
+                    let alternate = cond.alternate.clone().unwrap_or_else(|| {
+                        quote! { ::core::default::Default::default() }
+                    });
+                    quote! {
+                        if #condition {
+                            #value
+                        } else {
+                            #alternate
+                        }
+                    }
+                }
+                _ => value,
+            };
+            let assertions = checks(&field.assertions, &temps);
+            quote! {
+                let #ident: #ty = #value;
+                #(#assertions)*
+            }
+        });
+
+    let idents = st.iter_permanent_idents();
+    let value = if st.is_tuple() {
+        quote! { #constructor(#(#idents),*) }
+    } else {
+        quote! { #constructor { #(#idents),* } }
+    };
+
+    let assertions = checks(&st.assertions, &temps)
+        .chain(checks(extra_assertions, &temps))
+        .collect::<Vec<_>>();
+    let uses_self = st
+        .assertions
+        .iter()
+        .chain(extra_assertions)
+        .any(|assert| assert.condition_uses_self);
+    let (before, after) = if uses_self {
+        (Vec::new(), assertions)
+    } else {
+        (assertions, Vec::new())
+    };
+
+    quote! {
+        #(#fields)*
+        #(#before)*
+        let #THIS = #value;
+        #(#after)*
+        ::core::result::Result::Ok(#THIS)
+    }
+}
+
+fn field_value(field: &StructField, later_fields: &[StructField], temps: &[&Ident]) -> TokenStream {
+    let ty = &field.ty;
+    match &field.field_mode {
+        FieldMode::Default => return quote! { ::core::default::Default::default() },
+        FieldMode::Calc(calc) if !uses_any(calc, temps) => return calc.clone(),
+        FieldMode::TryCalc(calc) if !uses_any(calc, temps) => {
+            return quote! { #ARBITRARY_TRY_CALC(#calc)? };
+        }
+        FieldMode::Normal if field.map.is_none() => {}
+        _ => return quote! { <#ty as #ARBITRARY_TRAIT>::arbitrary(#UNSTRUCTURED_VAR)? },
+    }
+
+    if let Some(bits) = &field.bits {
+        let width = **bits;
+        quote! { #ARBITRARY_BITS(#UNSTRUCTURED_VAR, #width)? }
+    } else if let Some(count) = field.count.as_ref().filter(|count| !uses_any(count, temps)) {
+        quote! { #ARBITRARY_COUNT(#UNSTRUCTURED_VAR, #count)? }
+    } else if later_fields
+        .iter()
+        .any(|later| is_count_of(later, &field.ident))
+    {
+        quote! { #ARBITRARY_LEN(#UNSTRUCTURED_VAR)? }
+    } else if let Some(inner) = option_inner(ty) {
+        // An `Option` is always read as `Some`, unless it is skipped by a
+        // condition
+        quote! {
+            ::core::option::Option::Some(<#inner as #ARBITRARY_TRAIT>::arbitrary(#UNSTRUCTURED_VAR)?)
+        }
+    } else {
+        quote! { <#ty as #ARBITRARY_TRAIT>::arbitrary(#UNSTRUCTURED_VAR)? }
+    }
+}
+
+/// Converts assertions into checks which reject the generated value, skipping
+/// any which depend on temporary fields.
+fn checks<'a>(
+    assertions: &'a [Assert],
+    temps: &'a [&Ident],
+) -> impl Iterator<Item = TokenStream> + 'a {
+    assertions
+        .iter()
+        .filter(|assert| !uses_any(&assert.condition, temps))
+        .map(|assert| {
+            let condition = &assert.condition;
+            quote! { #ARBITRARY_CHECK(#condition)?; }
+        })
+}
+
+/// Returns true if `later` reads exactly as many items as the value of `ident`.
+fn is_count_of(later: &StructField, ident: &Ident) -> bool {
+    fn is_ident(expr: &syn::Expr, ident: &Ident) -> bool {
+        match expr {
+            syn::Expr::Path(path) => path.qself.is_none() && path.path.is_ident(ident),
+            syn::Expr::Cast(cast) => is_ident(&cast.expr, ident),
+            syn::Expr::Paren(paren) => is_ident(&paren.expr, ident),
+            syn::Expr::Group(group) => is_ident(&group.expr, ident),
+            _ => false,
+        }
+    }
+
+    !later.is_temp(false)
+        && matches!(later.field_mode, FieldMode::Normal)
+        && later.map.is_none()
+        && later
+            .count
+            .as_ref()
+            .and_then(|count| syn::parse2::<syn::Expr>(count.clone()).ok())
+            .is_some_and(|count| is_ident(&count, ident))
+}
+
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(ty) = ty else {
+        return None;
+    };
+    let segment = ty.path.segments.last()?;
+    if ty.qself.is_some() || segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+fn uses_any(tokens: &TokenStream, idents: &[&Ident]) -> bool {
+    !idents.is_empty()
+        && tokens.clone().into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => idents.contains(&&ident),
+            TokenTree::Group(group) => uses_any(&group.stream(), idents),
+            TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+        })
+}
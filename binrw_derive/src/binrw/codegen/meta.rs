@@ -1,7 +1,17 @@
-use super::sanitization::{META_ENDIAN_KIND, READ_ENDIAN, READ_MAGIC, WRITE_ENDIAN, WRITE_MAGIC};
-use crate::binrw::parser::{CondEndian, Input, Map};
+use super::sanitization::{
+    BINREAD_STREAM_TRAIT, META_BIT_ORDER, META_CHECKSUM_SCHEMA, META_ENDIAN_KIND,
+    META_FIELD_SCHEMA, META_SCHEMA, META_SCHEMA_KIND, META_TYPE_SCHEMA, META_UNIT_VARIANT_SCHEMA,
+    META_VARIANT_SCHEMA, READ_ENDIAN, READ_MAGIC, STATIC_SIZE, WRITE_ENDIAN, WRITE_MAGIC,
+};
+use crate::{
+    binrw::parser::{
+        BitOrder, BitSlot, CondEndian, EnumVariant, FieldMode, Input, Magic, Map, Struct,
+        StructField, UnitEnumField, UnitOnlyEnum,
+    },
+    util::tokens_to_string,
+};
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{ToTokens, quote};
 
 pub(crate) fn generate<const WRITE: bool>(
     input: &Input,
@@ -53,8 +63,466 @@ pub(crate) fn generate<const WRITE: bool>(
         }
     });
 
+    // The read and write layouts of a type can only differ in ways which
+    // change the size, so only the read side is described
+    let (static_size, read_stream, schema) = if WRITE {
+        (None, None, None)
+    } else {
+        (
+            generate_static_size(input, derive_input),
+            generate_read_stream(input, derive_input),
+            Some(generate_schema(input, derive_input, endian.is_some())),
+        )
+    };
+
     quote! {
         #magic
         #endian
+        #static_size
+        #read_stream
+        #schema
+    }
+}
+
+fn generate_schema(
+    input: &Input,
+    derive_input: &syn::DeriveInput,
+    has_endian: bool,
+) -> TokenStream {
+    let name = &derive_input.ident;
+    let endian = if has_endian {
+        quote! { <Self as #READ_ENDIAN>::ENDIAN }
+    } else {
+        quote! { #META_ENDIAN_KIND::Runtime }
+    };
+    let parent_endian = endian_kind(input.endian()).unwrap_or_else(|| endian.clone());
+
+    let kind = match input {
+        Input::Struct(st) | Input::UnitStruct(st) => match &st.map {
+            Map::Map(map) | Map::Try(map) | Map::Repr(map) => {
+                let map = source(map);
+                quote! { #META_SCHEMA_KIND::Map(#map) }
+            }
+            Map::None => {
+                let fields = struct_schema(st, &parent_endian);
+                quote! { #META_SCHEMA_KIND::Struct(#fields) }
+            }
+        },
+        Input::Union(st) => {
+            let fields = struct_schema(st, &parent_endian);
+            quote! { #META_SCHEMA_KIND::Union(#fields) }
+        }
+        Input::Enum(en) => {
+            let variants = en.variants.iter().map(|variant| match variant {
+                EnumVariant::Variant { ident, options } => {
+                    let name = source(ident);
+                    let endian = endian_kind(&options.endian).unwrap_or(parent_endian.clone());
+                    let magic =
+                        option_source(options.magic.as_ref().map(|magic| magic.match_value()));
+                    let fields = struct_schema(options, &endian);
+                    quote! {{
+                        let mut variant = #META_VARIANT_SCHEMA::new(#name, #endian, #fields);
+                        variant.magic = #magic;
+                        variant
+                    }}
+                }
+                EnumVariant::Unit(field) => {
+                    let name = source(&field.ident);
+                    let magic =
+                        option_source(field.magic.as_ref().map(|magic| magic.match_value()));
+                    quote! {{
+                        let mut variant = #META_VARIANT_SCHEMA::new(#name, #parent_endian, &[]);
+                        variant.magic = #magic;
+                        variant
+                    }}
+                }
+            });
+            quote! { #META_SCHEMA_KIND::Enum(const { &[#(#variants),*] }) }
+        }
+        Input::UnitOnlyEnum(en) => {
+            let repr = option_source(en.map.as_repr());
+            let variants = en.fields.iter().map(unit_variant_schema);
+            quote! {
+                #META_SCHEMA_KIND::UnitEnum {
+                    repr: #repr,
+                    variants: const { &[#(#variants),*] },
+                }
+            }
+        }
+    };
+
+    let type_name = source(name);
+    let magic = option_source(input.magic().as_ref().map(|magic| magic.match_value()));
+    let (impl_generics, ty_generics, where_clause) = derive_input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics #META_SCHEMA for #name #ty_generics #where_clause {
+            const SCHEMA: #META_TYPE_SCHEMA = {
+                let mut schema = #META_TYPE_SCHEMA::new(#type_name, #endian, #kind);
+                schema.magic = #magic;
+                schema
+            };
+        }
     }
 }
+
+fn struct_schema(st: &Struct, parent_endian: &TokenStream) -> TokenStream {
+    let fields = st.fields.iter().map(|field| {
+        let name = field.name();
+        let ty = tokens_to_string(field.ty.to_token_stream(), true);
+        let endian = endian_kind(&field.endian).unwrap_or_else(|| parent_endian.clone());
+        let magic = option_source(field.magic.as_ref().map(|magic| magic.match_value()));
+        let count = option_source(field.count.as_ref());
+        let condition = option_source(if field.version_cond.is_some() {
+            field.if_source.as_ref()
+        } else {
+            field.if_cond.as_ref().map(|cond| &cond.condition)
+        });
+        let since = option_source(field.since.as_deref());
+        let until = option_source(field.until.as_deref());
+        let version = option_source(st.version.as_ref().filter(|_| field.version_cond.is_some()));
+        let offset = option_source(field.offset.as_ref());
+        let calc = option_source(match &field.field_mode {
+            FieldMode::Calc(calc) | FieldMode::TryCalc(calc) => Some(calc),
+            _ => None,
+        });
+        let (map, repr) = match &field.map {
+            Map::None => (None, None),
+            Map::Map(map) | Map::Try(map) => (Some(map), None),
+            Map::Repr(repr) => (None, Some(repr)),
+        };
+        let map = option_source(map);
+        let repr = option_source(repr);
+        // `len_prefix` is implemented as a `parse_with` function, which is
+        // described by `len_prefix` instead
+        let parse_with = option_source(match &field.field_mode {
+            FieldMode::Function(parse_with) if field.len_prefix.is_none() => Some(parse_with),
+            _ => None,
+        });
+        let pad_before = option_source(field.pad_before.as_ref());
+        let pad_after = option_source(field.pad_after.as_ref());
+        let align_before = option_source(field.align_before.as_ref());
+        let align_after = option_source(field.align_after.as_ref());
+        let pad_size_to = option_source(field.pad_size_to.as_ref());
+        let seek_before = option_source(field.seek_before.as_ref());
+        let size = option_source(field.size.as_deref());
+        let len_prefix = option_source(field.len_prefix.as_deref());
+        let count_of = option_source(field.count_of.as_ref());
+        let bits = option(field.bits.as_deref().map(|bits| quote! { #bits }));
+        let bit_order = match st.bit_order.unwrap_or_default() {
+            BitOrder::Lsb => quote! { #META_BIT_ORDER::Lsb },
+            BitOrder::Msb => quote! { #META_BIT_ORDER::Msb },
+        };
+        let checksum = option(field.checksum.as_ref().map(|checksum| {
+            let algorithm = source(&checksum.algorithm);
+            let range = source(&checksum.range);
+            quote! { #META_CHECKSUM_SCHEMA::new(#algorithm, #range) }
+        }));
+        let ignore = matches!(field.field_mode, FieldMode::Default);
+        let temp = field.is_temp(false);
+        // The schema types are non-exhaustive, so they are created with a
+        // constructor and then filled in
+        quote! {{
+            let mut field = #META_FIELD_SCHEMA::new(#name, #ty, #endian);
+            field.magic = #magic;
+            field.count = #count;
+            field.condition = #condition;
+            field.since = #since;
+            field.until = #until;
+            field.version = #version;
+            field.offset = #offset;
+            field.calc = #calc;
+            field.map = #map;
+            field.repr = #repr;
+            field.parse_with = #parse_with;
+            field.pad_before = #pad_before;
+            field.pad_after = #pad_after;
+            field.align_before = #align_before;
+            field.align_after = #align_after;
+            field.pad_size_to = #pad_size_to;
+            field.seek_before = #seek_before;
+            field.size = #size;
+            field.len_prefix = #len_prefix;
+            field.count_of = #count_of;
+            field.bits = #bits;
+            field.bit_order = #bit_order;
+            field.checksum = #checksum;
+            field.ignore = #ignore;
+            field.temp = #temp;
+            field
+        }}
+    });
+    quote! { const { &[#(#fields),*] } }
+}
+
+fn unit_variant_schema(field: &UnitEnumField) -> TokenStream {
+    let name = source(&field.ident);
+    let magic = option_source(field.magic.as_ref().map(|magic| magic.match_value()));
+    let discriminant = option_source(field.discriminant.as_ref());
+    quote! {{
+        let mut variant = #META_UNIT_VARIANT_SCHEMA::new(#name);
+        variant.magic = #magic;
+        variant.discriminant = #discriminant;
+        variant
+    }}
+}
+
+fn endian_kind(endian: &CondEndian) -> Option<TokenStream> {
+    match endian {
+        CondEndian::Inherited => None,
+        CondEndian::Fixed(endian) => Some(quote! { #META_ENDIAN_KIND::Endian(#endian) }),
+        CondEndian::Cond(..) => Some(quote! { #META_ENDIAN_KIND::Runtime }),
+    }
+}
+
+/// Converts tokens into a string literal of their source code.
+fn source(tokens: impl ToTokens) -> TokenStream {
+    let text = tokens_to_string(tokens.to_token_stream(), false);
+    quote! { #text }
+}
+
+fn option_source(tokens: Option<impl ToTokens>) -> TokenStream {
+    option(tokens.map(source))
+}
+
+/// Converts an optional value into an `Option` expression.
+fn option(tokens: Option<TokenStream>) -> TokenStream {
+    tokens.map_or_else(
+        || quote! { ::core::option::Option::None },
+        |tokens| quote! { ::core::option::Option::Some(#tokens) },
+    )
+}
+
+fn generate_static_size(input: &Input, derive_input: &syn::DeriveInput) -> Option<TokenStream> {
+    let mut types = Vec::new();
+    let size = match input {
+        Input::Struct(st) | Input::UnitStruct(st)
+            if st.map.is_none() && st.map_stream.is_none() =>
+        {
+            let fields = st
+                .fields
+                .iter()
+                .zip(BitSlot::layout(&st.fields))
+                .map(|(field, slot)| field_size(field, slot.as_ref(), &mut types))
+                .collect::<Option<Vec<_>>>()?;
+            let magic = magic_size(&st.magic);
+            quote! { #magic #(+ #fields)* }
+        }
+        Input::UnitOnlyEnum(en) if en.map_stream.is_none() => {
+            let repr = en.map.as_repr()?;
+            let magic = magic_size(&en.magic);
+            quote! { #magic + <#repr as #STATIC_SIZE>::SIZE }
+        }
+        _ => return None,
+    };
+
+    // Bounds for field types are higher-ranked so that they are allowed even
+    // when they are not satisfied, in which case the type simply does not
+    // implement the trait
+    let mut generics = derive_input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for ty in types {
+        where_clause
+            .predicates
+            .push(syn::parse_quote! { for<'__binrw_size> #ty: #STATIC_SIZE });
+    }
+
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Some(quote! {
+        impl #impl_generics #STATIC_SIZE for #name #ty_generics #where_clause {
+            const SIZE: usize = #size;
+        }
+    })
+}
+
+fn field_size(
+    field: &StructField,
+    slot: Option<&BitSlot>,
+    types: &mut Vec<TokenStream>,
+) -> Option<TokenStream> {
+    if field.if_cond.is_some()
+        || field.do_try.is_some()
+        || field.map_stream.is_some()
+        || field.len_prefix.is_some()
+        || field.count.is_some()
+        || field.offset.is_some()
+        || field.restore_position.is_some()
+        || field.seek_before.is_some()
+        || field.align_before.is_some()
+        || field.align_after.is_some()
+    {
+        return None;
+    }
+
+    let value = if let Some(size) = &field.size {
+        let size = literal_size(Some(size.as_ref()))?;
+        quote! { #size }
+    } else if let Some(slot) = slot {
+        // The backing integer belongs to the first field of the group, and is
+        // read even if that field is not
+        let bytes = if slot.first {
+            slot.group_bytes() as usize
+        } else {
+            0
+        };
+        quote! { #bytes }
+    } else {
+        match (&field.field_mode, &field.map) {
+            (FieldMode::Default | FieldMode::Calc(_) | FieldMode::TryCalc(_), _) => {
+                quote! { 0 }
+            }
+            (FieldMode::Normal, Map::None) => {
+                let ty = &field.ty;
+                types.push(quote! { #ty });
+                quote! { <#ty as #STATIC_SIZE>::SIZE }
+            }
+            (FieldMode::Normal, Map::Repr(ty)) => {
+                types.push(ty.clone());
+                quote! { <#ty as #STATIC_SIZE>::SIZE }
+            }
+            _ => return None,
+        }
+    };
+
+    let value = if let Some(pad_size_to) = &field.pad_size_to {
+        let pad_size_to = literal_size(Some(pad_size_to))?;
+        quote! {{
+            let size = #value;
+            if size > #pad_size_to { size } else { #pad_size_to }
+        }}
+    } else {
+        value
+    };
+
+    let pad_before = literal_size(field.pad_before.as_ref())?;
+    let pad_after = literal_size(field.pad_after.as_ref())?;
+    let magic = magic_size(&field.magic);
+    Some(quote! { (#magic + #pad_before + #value + #pad_after) })
+}
+
+fn generate_read_stream(input: &Input, derive_input: &syn::DeriveInput) -> Option<TokenStream> {
+    let mut types = Vec::new();
+    match input {
+        Input::Struct(st) | Input::UnitStruct(st) => struct_stream_types(st, &mut types)?,
+        // Enums with more than one variant rewind to try each variant
+        Input::Enum(en) if en.map_stream.is_none() => match (&en.map, en.variants.as_slice()) {
+            (Map::None, [EnumVariant::Variant { options, .. }]) => {
+                struct_stream_types(options, &mut types)?;
+            }
+            (Map::None, [EnumVariant::Unit(_)]) => {}
+            (Map::None, _) => return None,
+            (map, _) => types.push(map_stream_type(map)?),
+        },
+        Input::UnitOnlyEnum(en) if en.map_stream.is_none() => match &en.map {
+            Map::None if magic_groups(en) <= 1 => {}
+            Map::None => return None,
+            map => types.push(map_stream_type(map)?),
+        },
+        _ => return None,
+    }
+
+    // Bounds for field types are higher-ranked for the same reason as those
+    // of `StaticSize`
+    let mut generics = derive_input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for ty in types {
+        where_clause
+            .predicates
+            .push(syn::parse_quote! { for<'__binrw_stream> #ty: #BINREAD_STREAM_TRAIT });
+    }
+
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Some(quote! {
+        impl #impl_generics #BINREAD_STREAM_TRAIT for #name #ty_generics #where_clause {}
+    })
+}
+
+/// Collects the types read by a struct, or returns `None` if reading the
+/// struct may need to seek backwards.
+fn struct_stream_types(st: &Struct, types: &mut Vec<TokenStream>) -> Option<()> {
+    if st.map_stream.is_some() {
+        return None;
+    }
+
+    if st.map.is_some() {
+        types.push(map_stream_type(&st.map)?);
+        return Some(());
+    }
+
+    for (field, slot) in st.fields.iter().zip(BitSlot::layout(&st.fields)) {
+        if field.do_try.is_some()
+            || field.map_stream.is_some()
+            || field.restore_position.is_some()
+            || field.seek_before.is_some()
+            || matches!(field.field_mode, FieldMode::Function(_))
+        {
+            return None;
+        }
+
+        // Bit fields are extracted from an integer which is read directly
+        if field.generated_value() || slot.is_some() {
+            continue;
+        }
+
+        types.push(match &field.map {
+            Map::None => {
+                let ty = &field.ty;
+                quote! { #ty }
+            }
+            map => map_stream_type(map)?,
+        });
+    }
+
+    Some(())
+}
+
+/// Returns the type which is read before it is mapped, if it is known.
+fn map_stream_type(map: &Map) -> Option<TokenStream> {
+    match map {
+        Map::None => None,
+        Map::Repr(ty) => Some(ty.clone()),
+        Map::Map(map) | Map::Try(map) => {
+            let closure = syn::parse2::<syn::ExprClosure>(map.clone()).ok()?;
+            match closure.inputs.iter().collect::<Vec<_>>().as_slice() {
+                [syn::Pat::Type(input)] => Some(input.ty.to_token_stream()),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Returns the number of times a unit-like enum reads its magic, which happens
+/// once for each run of variants with the same type of magic.
+fn magic_groups(en: &UnitOnlyEnum) -> usize {
+    let mut groups = Vec::new();
+    for field in &en.fields {
+        let kind = field.magic.as_ref().map(|magic| magic.kind());
+        match groups.last() {
+            Some(last) if kind.is_none() || *last == kind => {}
+            _ => groups.push(kind),
+        }
+    }
+    groups.len()
+}
+
+/// Returns the value of an optional size or padding directive, if it is a
+/// literal.
+fn literal_size(value: Option<&TokenStream>) -> Option<usize> {
+    value.map_or(Some(0), |value| {
+        syn::parse2::<syn::LitInt>(value.clone())
+            .ok()?
+            .base10_parse()
+            .ok()
+    })
+}
+
+fn magic_size(magic: &Magic) -> TokenStream {
+    magic.as_ref().map_or_else(
+        || quote! { 0 },
+        |magic| {
+            let ty = TokenStream::from(magic.kind());
+            quote! { <#ty as #STATIC_SIZE>::SIZE }
+        },
+    )
+}
@@ -1,3 +1,4 @@
+mod arbitrary;
 mod meta;
 mod read_options;
 pub(crate) mod sanitization;
@@ -5,7 +6,8 @@ mod write_options;
 
 use crate::{
     binrw::parser::{
-        Assert, AssertionError, CondEndian, Imports, Input, ParseResult, PassedArgs, StructField,
+        Assert, AssertionError, Checksum, ChecksumCover, ChecksumRange, CondEndian, Imports, Input,
+        ParseResult, PassedArgs, StructField, UnitEnumField,
     },
     named_args::{arg_type_name, derive_from_imports},
     util::{IdentStr, quote_spanned_any},
@@ -13,16 +15,24 @@ use crate::{
 use proc_macro2::{Span, TokenStream};
 use quote::{ToTokens, quote, quote_spanned};
 use sanitization::{
-    ARGS, ARGS_LIFETIME, ARGS_MACRO, ASSERT, ASSERT_ERROR_FN, BIN_ERROR, BIN_RESULT, BINREAD_TRAIT,
-    BINWRITE_TRAIT, BOX, ENDIAN_ENUM, FORMAT, OPT, POS, READ_TRAIT, READER, SEEK_TRAIT, TEMP,
-    WRITE_TRAIT, WRITER,
+    ARGS, ARGS_LIFETIME, ARGS_MACRO, ASSERT, ASSERT_ERROR_FN, ASYNC_BLOCK_TYPE_HINT,
+    ASYNC_READ_TRAIT, ASYNC_SEEK_TRAIT, ASYNC_WRITE_TRAIT, BIN_ERROR, BIN_RESULT,
+    BINREAD_ASYNC_TRAIT, BINREAD_TRAIT, BINWRITE_ASYNC_TRAIT, BINWRITE_TRAIT, BOX, CHECKSUM_START,
+    CHECKSUM_STREAM, ENDIAN_ENUM, FORMAT, OPT, POS, READ_TRAIT, READER, SEEK_ASYNC, SEEK_TRAIT,
+    STREAM_POSITION_ASYNC, TEMP, WRITE_TRAIT, WRITER, make_ident,
 };
 use syn::{DeriveInput, Ident, Type, spanned::Spanned};
 
-pub(crate) fn generate_impl<const WRITE: bool>(
+pub(crate) fn generate_impl<const WRITE: bool, const ASYNC: bool>(
     derive_input: &DeriveInput,
     binrw_input: &ParseResult<Input>,
 ) -> TokenStream {
+    // The async traits share the arguments and metadata of the synchronous
+    // traits, which are generated by their own derives
+    if ASYNC {
+        return generate_trait_impl::<WRITE, ASYNC>(binrw_input, derive_input, None);
+    }
+
     let (arg_type, arg_type_declaration) = match binrw_input {
         ParseResult::Ok(binrw_input) | ParseResult::Partial(binrw_input, _) => generate_imports(
             binrw_input.imports(),
@@ -33,7 +43,8 @@ pub(crate) fn generate_impl<const WRITE: bool>(
         ParseResult::Err(_) => (quote! { () }, None),
     };
 
-    let trait_impl = generate_trait_impl::<WRITE>(binrw_input, derive_input, &arg_type);
+    let trait_impl =
+        generate_trait_impl::<WRITE, ASYNC>(binrw_input, derive_input, Some(&arg_type));
 
     let meta_impls = match binrw_input {
         ParseResult::Ok(binrw_input) | ParseResult::Partial(binrw_input, _) => {
@@ -42,9 +53,15 @@ pub(crate) fn generate_impl<const WRITE: bool>(
         ParseResult::Err(_) => None,
     };
 
+    let arbitrary_impl = match binrw_input {
+        ParseResult::Ok(binrw_input) if !WRITE => arbitrary::generate(binrw_input, derive_input),
+        _ => None,
+    };
+
     quote! {
         #trait_impl
         #meta_impls
+        #arbitrary_impl
         #arg_type_declaration
     }
 }
@@ -136,13 +153,13 @@ fn generate_imports(
     }
 }
 
-fn generate_trait_impl<const WRITE: bool>(
+fn generate_trait_impl<const WRITE: bool, const ASYNC: bool>(
     binrw_input: &ParseResult<Input>,
     derive_input: &DeriveInput,
-    arg_type: &TokenStream,
+    arg_type: Option<&TokenStream>,
 ) -> TokenStream {
-    let (trait_name, fn_sig) = if WRITE {
-        (
+    let (trait_name, fn_sig) = match (WRITE, ASYNC) {
+        (true, false) => (
             BINWRITE_TRAIT,
             quote! {
                 fn write_options<W: #WRITE_TRAIT + #SEEK_TRAIT>(
@@ -152,24 +169,46 @@ fn generate_trait_impl<const WRITE: bool>(
                     #ARGS: Self::Args<'_>
                 ) -> #BIN_RESULT<()>
             },
-        )
-    } else {
-        (
+        ),
+        (false, false) => (
             BINREAD_TRAIT,
             quote! {
                 fn read_options<R: #READ_TRAIT + #SEEK_TRAIT>
                     (#READER: &mut R, #OPT: #ENDIAN_ENUM, #ARGS: Self::Args<'_>)
                     -> #BIN_RESULT<Self>
             },
-        )
+        ),
+        (true, true) => (
+            BINWRITE_ASYNC_TRAIT,
+            quote! {
+                async fn write_options_async<W>(
+                    &self,
+                    #WRITER: &mut W,
+                    #OPT: #ENDIAN_ENUM,
+                    #ARGS: Self::Args<'_>
+                ) -> #BIN_RESULT<()>
+                where
+                    W: #ASYNC_WRITE_TRAIT + #ASYNC_SEEK_TRAIT + ::core::marker::Unpin + ::core::marker::Send
+            },
+        ),
+        (false, true) => (
+            BINREAD_ASYNC_TRAIT,
+            quote! {
+                async fn read_options_async<R>
+                    (#READER: &mut R, #OPT: #ENDIAN_ENUM, #ARGS: Self::Args<'_>)
+                    -> #BIN_RESULT<Self>
+                where
+                    R: #ASYNC_READ_TRAIT + #ASYNC_SEEK_TRAIT + ::core::marker::Unpin + ::core::marker::Send
+            },
+        ),
     };
 
     let fn_impl = match binrw_input {
         ParseResult::Ok(binrw_input) => {
             if WRITE {
-                write_options::generate(binrw_input, derive_input)
+                write_options::generate(binrw_input, derive_input, ASYNC)
             } else {
-                read_options::generate(binrw_input, derive_input)
+                read_options::generate(binrw_input, derive_input, ASYNC)
             }
         }
         // If there is a parsing error, an impl for the trait still needs to be
@@ -182,13 +221,16 @@ fn generate_trait_impl<const WRITE: bool>(
     let name = &derive_input.ident;
     let (impl_generics, ty_generics, where_clause) = derive_input.generics.split_for_impl();
 
-    let args_lifetime = get_args_lifetime(Span::call_site());
+    let args_decl = arg_type.map(|arg_type| {
+        let args_lifetime = get_args_lifetime(Span::call_site());
+        quote! { type Args<#args_lifetime> = #arg_type; }
+    });
     quote! {
         #[automatically_derived]
         #[allow(non_snake_case, unknown_lints)]
         #[allow(clippy::redundant_closure_call)]
         impl #impl_generics #trait_name for #name #ty_generics #where_clause {
-            type Args<#args_lifetime> = #arg_type;
+            #args_decl
 
             #fn_sig {
                 #fn_impl
@@ -257,6 +299,119 @@ fn get_destructured_imports(
     }
 }
 
+/// Generates the checksum state for each checksum which begins at the field
+/// with the given index, and the wrapped stream for each checksum whose range
+/// begins there.
+fn get_checksum_setup(
+    stream: &TokenStream,
+    fields: &[StructField],
+    checksums: &[ChecksumRange],
+    index: usize,
+) -> TokenStream {
+    let states = checksums
+        .iter()
+        .filter(|checksum| checksum.first() == index)
+        .map(|checksum| {
+            let field = &fields[checksum.field];
+            let state = make_ident(&field.ident, "checksum");
+            let algorithm = &field.checksum.as_ref().unwrap().algorithm;
+            quote_spanned! {algorithm.span()=>
+                let mut #state: #algorithm = <#algorithm as ::core::default::Default>::default();
+            }
+        });
+
+    let streams = checksums
+        .iter()
+        .filter(|checksum| checksum.start == index)
+        .map(|checksum| {
+            let ident = &fields[checksum.field].ident;
+            let state = make_ident(ident, "checksum");
+            let stream_var = make_ident(ident, "checksum_stream");
+            quote! {
+                let #stream_var = &mut #CHECKSUM_STREAM::new(#stream, &mut #state);
+            }
+        });
+
+    quote! {
+        #(#states)*
+        #(#streams)*
+    }
+}
+
+/// Returns an expression for the range of stream positions covered by a
+/// checksum over byte positions. Relative positions are offsets from
+/// [`CHECKSUM_START`].
+fn get_checksum_bytes(checksum: &Checksum, stream: &TokenStream) -> TokenStream {
+    let ChecksumCover::Bytes {
+        start,
+        end,
+        inclusive,
+        relative,
+    } = &checksum.cover
+    else {
+        unreachable!("checksum over fields has no byte range")
+    };
+
+    let base = if *relative {
+        quote! { #CHECKSUM_START }
+    } else {
+        quote! { 0_u64 }
+    };
+    let start = get_u64(&start.to_token_stream(), stream, "checksum position");
+    let end = get_u64(&end.to_token_stream(), stream, "checksum position");
+    let inclusive = inclusive.then(|| quote! { .saturating_add(1) });
+
+    quote! {
+        #base.saturating_add(#start)..#base.saturating_add(#end)#inclusive
+    }
+}
+
+/// Returns the stream which should be used by the field with the given index,
+/// which is a checksum stream if the field is covered by a checksum.
+fn get_checksum_stream(
+    stream: &TokenStream,
+    fields: &[StructField],
+    checksums: &[ChecksumRange],
+    index: usize,
+) -> TokenStream {
+    checksums
+        .iter()
+        .find(|checksum| checksum.covers(index))
+        .map_or_else(
+            || stream.clone(),
+            |checksum| {
+                make_ident(&fields[checksum.field].ident, "checksum_stream").into_token_stream()
+            },
+        )
+}
+
+/// Returns an expression for the discriminant of each variant of a unit-like
+/// enum, following the same numbering rules as the compiler.
+///
+/// These are needed for enums with an `other` variant, which cannot be cast
+/// with `as` because the `other` variant has a field.
+fn get_discriminants(variants: &[UnitEnumField]) -> Vec<TokenStream> {
+    let mut base = None;
+    let mut offset = 0_usize;
+    variants
+        .iter()
+        .map(|variant| {
+            if let Some(discriminant) = &variant.discriminant {
+                base = Some(discriminant);
+                offset = 0;
+            }
+
+            let value = proc_macro2::Literal::usize_unsuffixed(offset);
+            offset += 1;
+            match base {
+                Some(base) if offset == 1 => quote! { (#base) },
+                Some(base) => quote! { (#base) + #value },
+                None => quote! { #value },
+            }
+        })
+        .collect()
+}
+
 fn get_endian(endian: &CondEndian) -> TokenStream {
     match endian {
         CondEndian::Inherited => OPT.to_token_stream(),
@@ -280,12 +435,16 @@ fn get_map_err(pos: IdentStr, span: Span) -> TokenStream {
     }
 }
 
-fn get_passed_args(field: &StructField, stream: &TokenStream) -> Option<TokenStream> {
+fn get_passed_args(
+    field: &StructField,
+    stream: &TokenStream,
+    asynchronous: bool,
+) -> Option<TokenStream> {
     let args = &field.args;
     let span = args.span().unwrap_or_else(|| field.ty.span());
     match args {
         PassedArgs::Named(fields) => Some({
-            let extra_args = directives_to_args(field, stream);
+            let extra_args = directives_to_args(field, stream, asynchronous);
             quote_spanned_any! { span=>
                 #ARGS_MACRO! { #extra_args #(#fields, )* }
             }
@@ -293,7 +452,7 @@ fn get_passed_args(field: &StructField, stream: &TokenStream) -> Option<TokenStr
         PassedArgs::List(list) => Some(quote_spanned! {span=> (#(#list,)*) }),
         PassedArgs::Tuple(tuple) => Some(tuple.as_ref().clone()),
         PassedArgs::None => {
-            let extra_args = directives_to_args(field, stream);
+            let extra_args = directives_to_args(field, stream, asynchronous);
             (!extra_args.is_empty()).then(|| {
                 quote_spanned_any! { span=> #ARGS_MACRO! { #extra_args } }
             })
@@ -301,6 +460,62 @@ fn get_passed_args(field: &StructField, stream: &TokenStream) -> Option<TokenStr
     }
 }
 
+/// Returns an expression which gets the current position of the stream.
+fn get_stream_position(stream: &impl ToTokens, asynchronous: bool) -> TokenStream {
+    if asynchronous {
+        quote! { #STREAM_POSITION_ASYNC(#stream).await }
+    } else {
+        quote! { #SEEK_TRAIT::stream_position(#stream) }
+    }
+}
+
+/// Returns an expression which converts the value of a `size` directive to a
+/// `u64`, returning an error at the current position of the stream if it is
+/// out of range.
+fn get_window_size(size: &TokenStream, stream: &TokenStream) -> TokenStream {
+    get_u64(size, stream, "size")
+}
+
+/// Returns an expression which converts `value` to a `u64`, returning an
+/// error which refers to the value as `name` if it is out of range.
+fn get_u64(value: &TokenStream, stream: &TokenStream, name: &str) -> TokenStream {
+    let message = format!("{name} {{:?}} out of range of u64");
+    quote_spanned_any! {value.span()=>
+        {
+            let #TEMP = #value;
+            #[allow(clippy::useless_conversion, clippy::unnecessary_fallible_conversions)]
+            match <::core::primitive::u64 as ::core::convert::TryFrom<_>>::try_from(#TEMP) {
+                ::core::result::Result::Ok(size) => size,
+                ::core::result::Result::Err(_) => {
+                    return ::core::result::Result::Err(#BIN_ERROR::AssertFail {
+                        pos: #SEEK_TRAIT::stream_position(#stream).unwrap_or_default(),
+                        message: #FORMAT!(#message, #TEMP)
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Returns an expression which seeks the stream to the given position.
+fn get_seek(stream: &impl ToTokens, pos: &impl ToTokens, asynchronous: bool) -> TokenStream {
+    if asynchronous {
+        quote! { #SEEK_ASYNC(#stream, #pos).await }
+    } else {
+        quote! { #SEEK_TRAIT::seek(#stream, #pos) }
+    }
+}
+
+/// Returns an expression which evaluates `body` with its own scope for `?`
+/// and `return`, which is an async block when generating an async impl.
+fn get_try_scope(body: &TokenStream, ty: &TokenStream, asynchronous: bool) -> TokenStream {
+    if asynchronous {
+        quote! { #ASYNC_BLOCK_TYPE_HINT::<#ty, _>(async { #body }).await }
+    } else {
+        quote! { (|| { #body })() }
+    }
+}
+
 fn get_try_calc(pos: IdentStr, ty: &Type, calc: &TokenStream) -> TokenStream {
     let map_err = get_map_err(pos, calc.span());
     quote_spanned! {ty.span()=> {
@@ -309,7 +524,12 @@ fn get_try_calc(pos: IdentStr, ty: &Type, calc: &TokenStream) -> TokenStream {
     }}
 }
 
-fn directives_to_args(field: &StructField, stream: &TokenStream) -> TokenStream {
+fn directives_to_args(
+    field: &StructField,
+    stream: &TokenStream,
+    asynchronous: bool,
+) -> TokenStream {
+    let stream_position = get_stream_position(stream, asynchronous);
     let args = field
         .count
         .as_ref()
@@ -317,19 +537,23 @@ fn directives_to_args(field: &StructField, stream: &TokenStream) -> TokenStream
             quote_spanned_any! {count.span()=>
                 count: {
                     let #TEMP = #count;
+                    // This is a `match` instead of `map_err` so that the
+                    // stream position can be awaited in async impls
                     #[allow(clippy::useless_conversion, clippy::unnecessary_fallible_conversions)]
-                    <::core::primitive::usize as ::core::convert::TryFrom<_>>::try_from(#TEMP).map_err(|_| {
-                        #BIN_ERROR::AssertFail {
-                            pos: #SEEK_TRAIT::stream_position(#stream)
-                                .unwrap_or_default(),
-                            // This is using debug formatting instead of display
-                            // formatting to reduce the chance of some
-                            // additional confusing error complaining about
-                            // Display not being implemented if someone tries
-                            // using a bogus type with `count`
-                            message: #FORMAT!("count {:?} out of range of usize", #TEMP)
+                    match <::core::primitive::usize as ::core::convert::TryFrom<_>>::try_from(#TEMP) {
+                        ::core::result::Result::Ok(count) => count,
+                        ::core::result::Result::Err(_) => {
+                            return ::core::result::Result::Err(#BIN_ERROR::AssertFail {
+                                pos: #stream_position.unwrap_or_default(),
+                                // This is using debug formatting instead of
+                                // display formatting to reduce the chance of
+                                // some additional confusing error complaining
+                                // about Display not being implemented if
+                                // someone tries using a bogus type with `count`
+                                message: #FORMAT!("count {:?} out of range of usize", #TEMP)
+                            });
                         }
-                    })?
+                    }
                 }
             }
         })
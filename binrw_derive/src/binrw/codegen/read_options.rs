@@ -1,15 +1,16 @@
 mod r#enum;
 mod map;
 mod r#struct;
+mod union;
 
-use super::{get_assertions, get_destructured_imports};
+use super::{get_assertions, get_destructured_imports, get_stream_position, get_try_scope};
 use crate::{
     binrw::{
         codegen::{
             get_endian,
             sanitization::{
-                ARGS, ASSERT_MAGIC, MAP_READER_TYPE_HINT, OPT, POS, READER, RESTORE_POSITION,
-                SEEK_TRAIT,
+                ARGS, ASSERT_MAGIC, ASSERT_MAGIC_ASYNC, MAP_READER_TYPE_HINT, OPT, POS, READER,
+                RESTORE_POSITION, RESTORE_POSITION_ASYNC, TEMP,
             },
         },
         parser::{Input, Magic, Map},
@@ -21,28 +22,35 @@ use proc_macro2::TokenStream;
 use quote::{ToTokens, quote};
 use r#struct::{generate_struct, generate_unit_struct};
 use syn::{Ident, spanned::Spanned};
+use union::generate_union;
 
-pub(crate) fn generate(input: &Input, derive_input: &syn::DeriveInput) -> TokenStream {
+pub(crate) fn generate(
+    input: &Input,
+    derive_input: &syn::DeriveInput,
+    asynchronous: bool,
+) -> TokenStream {
     let name = Some(&derive_input.ident);
     let (inner, needs_rewind) = match input.map() {
         Map::None => match input {
-            Input::UnitStruct(_) => (generate_unit_struct(input, name, None), false),
-            Input::Struct(s) => (generate_struct(input, name, s), true),
-            Input::Enum(e) => (generate_data_enum(input, name, e), false),
+            Input::UnitStruct(_) => (generate_unit_struct(input, name, None, asynchronous), false),
+            Input::Struct(s) => (generate_struct(input, name, s, asynchronous), true),
+            Input::Enum(e) => (generate_data_enum(input, name, e, asynchronous), false),
+            Input::Union(u) => (generate_union(input, name, u, asynchronous), true),
             Input::UnitOnlyEnum(e) => (
-                generate_unit_enum(input, name, e),
+                generate_unit_enum(input, name, e, asynchronous),
                 e.map.as_repr().is_some(),
             ),
         },
-        Map::Try(map) => (map::generate_try_map(input, name, map), true),
-        Map::Map(map) => (map::generate_map(input, name, map), true),
+        Map::Try(map) => (map::generate_try_map(input, name, map, asynchronous), true),
+        Map::Map(map) => (map::generate_map(input, name, map, asynchronous), true),
         Map::Repr(ty) => match input {
-            Input::UnitOnlyEnum(e) => (generate_unit_enum(input, name, e), true),
+            Input::UnitOnlyEnum(e) => (generate_unit_enum(input, name, e, asynchronous), true),
             _ => (
                 map::generate_try_map(
                     input,
                     name,
                     &quote! { <#ty as ::core::convert::TryInto<_>>::try_into },
+                    asynchronous,
                 ),
                 true,
             ),
@@ -50,8 +58,32 @@ pub(crate) fn generate(input: &Input, derive_input: &syn::DeriveInput) -> TokenS
     };
 
     let reader_var = input.stream_ident_or(READER);
+    let stream_position = get_stream_position(&reader_var, asynchronous);
+    let needs_rewind = needs_rewind || input.magic().is_some();
+
+    if asynchronous {
+        let value = get_try_scope(&inner, &quote! { Self }, true);
+        let value = if needs_rewind {
+            quote! {
+                match #value {
+                    ::core::result::Result::Err(#TEMP) => {
+                        ::core::result::Result::Err(#RESTORE_POSITION_ASYNC(#reader_var, #POS, #TEMP).await)
+                    }
+                    #TEMP => #TEMP,
+                }
+            }
+        } else {
+            value
+        };
+
+        return quote! {
+            let #reader_var = #READER;
+            let #POS = #stream_position?;
+            #value
+        };
+    }
 
-    let rewind = (needs_rewind || input.magic().is_some()).then(|| {
+    let rewind = needs_rewind.then(|| {
         quote! {
             .map_err(#RESTORE_POSITION::<binrw::Error, _>(#reader_var, #POS))
         }
@@ -59,7 +91,7 @@ pub(crate) fn generate(input: &Input, derive_input: &syn::DeriveInput) -> TokenS
 
     quote! {
         let #reader_var = #READER;
-        let #POS = #SEEK_TRAIT::stream_position(#reader_var)?;
+        let #POS = #stream_position?;
         (|| {
             #inner
         })()#rewind
@@ -69,15 +101,17 @@ pub(crate) fn generate(input: &Input, derive_input: &syn::DeriveInput) -> TokenS
 struct PreludeGenerator<'input> {
     input: &'input Input,
     reader_var: TokenStream,
+    asynchronous: bool,
     out: TokenStream,
 }
 
 impl<'input> PreludeGenerator<'input> {
-    fn new(input: &'input Input) -> Self {
+    fn new(input: &'input Input, asynchronous: bool) -> Self {
         let reader_var = input.stream_ident_or(READER);
         Self {
             input,
             reader_var,
+            asynchronous,
             out: TokenStream::new(),
         }
     }
@@ -110,7 +144,7 @@ impl<'input> PreludeGenerator<'input> {
 
     fn add_magic_pre_assertion(mut self) -> Self {
         let head = self.out;
-        let magic = get_magic(self.input.magic(), &self.reader_var, OPT);
+        let magic = get_magic(self.input.magic(), &self.reader_var, OPT, self.asynchronous);
         let pre_assertions = get_assertions(self.input.pre_assertions());
         self.out = quote! {
             #head
@@ -137,11 +171,11 @@ impl<'input> PreludeGenerator<'input> {
 
     fn reset_position_after_magic(mut self) -> Self {
         if self.input.magic().is_some() {
-            let reader_var = &self.reader_var;
+            let stream_position = get_stream_position(&self.reader_var, self.asynchronous);
             let head = self.out;
             self.out = quote! {
                 #head
-                let #POS = #SEEK_TRAIT::stream_position(#reader_var)?;
+                let #POS = #stream_position?;
             };
         }
 
@@ -153,11 +187,18 @@ fn get_magic(
     magic: &Magic,
     reader_var: impl ToTokens,
     endian_var: impl ToTokens,
+    asynchronous: bool,
 ) -> Option<TokenStream> {
     magic.as_ref().map(|magic| {
         let magic = magic.deref_value();
-        quote! {
-            #ASSERT_MAGIC(#reader_var, #magic, #endian_var)?;
+        if asynchronous {
+            quote! {
+                #ASSERT_MAGIC_ASYNC(#reader_var, #magic, #endian_var).await?;
+            }
+        } else {
+            quote! {
+                #ASSERT_MAGIC(#reader_var, #magic, #endian_var)?;
+            }
         }
     })
 }
@@ -1,35 +1,39 @@
 use super::{
     PreludeGenerator,
-    r#struct::{StructGenerator, generate_unit_struct},
+    r#struct::{StructGenerator, generate_unit_struct, may_recover},
 };
 use crate::binrw::{
     codegen::{
-        FORMAT,
+        FORMAT, get_discriminants, get_try_scope,
         sanitization::{
-            ALL_EOF, BACKTRACE_FRAME, BIN_ERROR, ERROR_BASKET, NOT_ENOUGH_BYTES, OPT, POS,
-            READ_METHOD, READER, RESTORE_POSITION_VARIANT, TEMP, VEC, WITH_CONTEXT,
+            ALL_EOF, BACKTRACE_FRAME, BIN_ERROR, BINREAD_ASYNC_TRAIT, ERROR_BASKET,
+            NOT_ENOUGH_BYTES, OPT, POS, READ_METHOD, READER, RECOVER_STRICT,
+            RESTORE_POSITION_VARIANT, RESTORE_POSITION_VARIANT_ASYNC, SPAN_DEPTH, SPAN_DISCARD,
+            SPAN_ENTER_VARIANT, SPAN_EXIT, TEMP, VEC, WITH_CONTEXT,
         },
     },
     parser::{Enum, EnumErrorMode, EnumVariant, Input, UnitEnumField, UnitOnlyEnum},
 };
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{ToTokens, quote};
 use syn::Ident;
 
 pub(super) fn generate_unit_enum(
     input: &Input,
     name: Option<&Ident>,
     en: &UnitOnlyEnum,
+    asynchronous: bool,
 ) -> TokenStream {
-    let prelude = PreludeGenerator::new(input)
+    let prelude = PreludeGenerator::new(input, asynchronous)
         .add_imports(name)
         .add_endian()
         .add_magic_pre_assertion()
         .finish();
 
+    let reader_var = input.stream_ident_or(READER);
     let read = match en.map.as_repr() {
-        Some(repr) => generate_unit_enum_repr(&input.stream_ident_or(READER), repr, &en.fields),
-        None => generate_unit_enum_magic(&input.stream_ident_or(READER), &en.fields),
+        Some(repr) => generate_unit_enum_repr(&reader_var, repr, &en.fields, asynchronous),
+        None => generate_unit_enum_magic(&reader_var, &en.fields, asynchronous),
     };
 
     quote! {
@@ -42,8 +46,33 @@ fn generate_unit_enum_repr(
     reader_var: &TokenStream,
     repr: &TokenStream,
     variants: &[UnitEnumField],
+    asynchronous: bool,
 ) -> TokenStream {
-    let clauses = variants.iter().map(|variant| {
+    let other = variants.iter().find(|variant| variant.holds_value);
+    let discriminants = other.map(|_| get_discriminants(variants));
+    let clauses = variants
+        .iter()
+        .enumerate()
+        .filter(|(_, variant)| !variant.holds_value)
+        .map(|(index, variant)| {
+            let ident = &variant.ident;
+            let pre_assertions = variant
+                .pre_assertions
+                .iter()
+                .map(|assert| &assert.condition);
+            let value = discriminants.as_ref().map_or_else(
+                || quote! { Self::#ident as #repr },
+                |discriminants| discriminants[index].clone(),
+            );
+
+            quote! {
+                if #TEMP == #value #(&& (#pre_assertions))* {
+                    ::core::result::Result::Ok(Self::#ident)
+                }
+            }
+        });
+
+    let fallback = other.map(|variant| {
         let ident = &variant.ident;
         let pre_assertions = variant
             .pre_assertions
@@ -51,15 +80,21 @@ fn generate_unit_enum_repr(
             .map(|assert| &assert.condition);
 
         quote! {
-            if #TEMP == Self::#ident as #repr #(&& (#pre_assertions))* {
-                ::core::result::Result::Ok(Self::#ident)
-            }
+            if true #(&& (#pre_assertions))* {
+                ::core::result::Result::Ok(Self::#ident(#TEMP))
+            } else
         }
     });
 
+    let read = if asynchronous {
+        quote! { <#repr as #BINREAD_ASYNC_TRAIT>::read_options_async(#reader_var, #OPT, ()).await }
+    } else {
+        quote! { #READ_METHOD(#reader_var, #OPT, ()) }
+    };
+
     quote! {
-        let #TEMP: #repr = #READ_METHOD(#reader_var, #OPT, ())?;
-        #(#clauses else)* {
+        let #TEMP: #repr = #read?;
+        #(#clauses else)* #fallback {
             ::core::result::Result::Err(#WITH_CONTEXT(
                 #BIN_ERROR::NoVariantMatch {
                     pos: #POS,
@@ -72,7 +107,11 @@ fn generate_unit_enum_repr(
     }
 }
 
-fn generate_unit_enum_magic(reader_var: &TokenStream, variants: &[UnitEnumField]) -> TokenStream {
+fn generate_unit_enum_magic(
+    reader_var: &TokenStream,
+    variants: &[UnitEnumField],
+    asynchronous: bool,
+) -> TokenStream {
     // group fields by the type (Kind) of their magic value, preserve the order
     let group_by_magic_type = variants.iter().fold(
         Vec::new(),
@@ -97,7 +136,7 @@ fn generate_unit_enum_magic(reader_var: &TokenStream, variants: &[UnitEnumField]
     );
 
     // for each type (Kind), read and try to match the magic of each field
-    let try_each_magic_type = group_by_magic_type.into_iter().map(|(_kind, fields)| {
+    let try_each_magic_type = group_by_magic_type.into_iter().map(|(kind, fields)| {
         let amp = fields[0].magic.as_ref().map(|magic| magic.add_ref());
 
         let matches = fields.iter().map(|field| {
@@ -119,21 +158,28 @@ fn generate_unit_enum_magic(reader_var: &TokenStream, variants: &[UnitEnumField]
             }
         });
 
+        let read = if let Some(kind) = kind.as_ref().filter(|_| asynchronous) {
+            let ty = TokenStream::from(*kind);
+            quote! { <#ty as #BINREAD_ASYNC_TRAIT>::read_options_async(#reader_var, #OPT, ()).await }
+        } else {
+            quote! { #READ_METHOD(#reader_var, #OPT, ()) }
+        };
+
         let body = quote! {
-            match #amp #READ_METHOD(#reader_var, #OPT, ())? {
+            match #amp #read? {
                 #(#matches,)*
                 _ => ::core::result::Result::Err(#BIN_ERROR::NoVariantMatch { pos: #POS })
             }
         };
+        let value = get_try_scope(&body, &quote! { Self }, asynchronous);
+        let restore_position = get_restore_position_variant(reader_var, &TEMP, asynchronous);
 
         quote! {
-            match (|| {
-                #body
-            })() {
+            match #value {
                 v @ ::core::result::Result::Ok(_) => return v,
                 ::core::result::Result::Err(#TEMP) => {
                     #ALL_EOF &= #TEMP.is_eof();
-                    #RESTORE_POSITION_VARIANT(#reader_var, #POS, #TEMP)?;
+                    #restore_position?;
                 }
             }
         }
@@ -156,7 +202,12 @@ fn generate_unit_enum_magic(reader_var: &TokenStream, variants: &[UnitEnumField]
     }
 }
 
-pub(super) fn generate_data_enum(input: &Input, name: Option<&Ident>, en: &Enum) -> TokenStream {
+pub(super) fn generate_data_enum(
+    input: &Input,
+    name: Option<&Ident>,
+    en: &Enum,
+    asynchronous: bool,
+) -> TokenStream {
     let return_all_errors = en.error_mode != EnumErrorMode::ReturnUnexpectedError;
 
     let (create_error_basket, return_error) = if return_all_errors {
@@ -182,7 +233,7 @@ pub(super) fn generate_data_enum(input: &Input, name: Option<&Ident>, en: &Enum)
         )
     };
 
-    let prelude = PreludeGenerator::new(input)
+    let prelude = PreludeGenerator::new(input, asynchronous)
         .add_imports(name)
         .add_endian()
         .add_magic_pre_assertion()
@@ -191,8 +242,22 @@ pub(super) fn generate_data_enum(input: &Input, name: Option<&Ident>, en: &Enum)
 
     let reader_var = input.stream_ident_or(READER);
 
-    let try_each_variant = en.variants.iter().map(|variant| {
-        let body = generate_variant_impl(en, variant);
+    // The `other` variant is a fallback, so it is only tried once every other
+    // variant has failed
+    let (others, variants) = en
+        .variants
+        .iter()
+        .partition::<Vec<_>, _>(|variant| variant.other().is_some());
+
+    let may_recover = en.variants.iter().any(|variant| match variant {
+        EnumVariant::Variant { options, .. } => {
+            options.recover.is_some() || options.fields.iter().any(may_recover)
+        }
+        EnumVariant::Unit(_) => false,
+    });
+
+    let try_each_variant = variants.into_iter().chain(others).map(|variant| {
+        let body = generate_variant_impl(en, variant, asynchronous);
 
         let handle_error = if return_all_errors {
             let name = variant.ident().to_string();
@@ -203,13 +268,42 @@ pub(super) fn generate_data_enum(input: &Input, name: Option<&Ident>, en: &Enum)
             TokenStream::new()
         };
 
+        let value = get_try_scope(&body, &quote! { Self }, asynchronous);
+        let restore_position =
+            get_restore_position_variant(&reader_var, &quote! { error }, asynchronous);
+
+        // Spans are recorded using the synchronous stream traits
+        if asynchronous {
+            return quote! {
+                match #value {
+                    ok @ ::core::result::Result::Ok(_) => return ok,
+                    ::core::result::Result::Err(error) => {
+                        #restore_position.map(|#TEMP| {
+                            #handle_error
+                        })?;
+                    }
+                }
+            };
+        }
+
+        // A variant which recovers from an error would be chosen even if it
+        // is the wrong variant
+        let value = if en.variants.len() > 1 && may_recover {
+            quote! { #RECOVER_STRICT(|| #value) }
+        } else {
+            value
+        };
+        let name = variant.ident().to_string();
         quote! {
-            match (|| {
-                #body
-            })() {
-                ok @ ::core::result::Result::Ok(_) => return ok,
+            let #SPAN_DEPTH = #SPAN_ENTER_VARIANT(#reader_var, #name, ::core::any::type_name::<Self>());
+            match #value {
+                ok @ ::core::result::Result::Ok(_) => {
+                    #SPAN_EXIT(#reader_var, #SPAN_DEPTH);
+                    return ok;
+                }
                 ::core::result::Result::Err(error) => {
-                    #RESTORE_POSITION_VARIANT(#reader_var, #POS, error).map(|#TEMP| {
+                    #SPAN_DISCARD(#SPAN_DEPTH);
+                    #restore_position.map(|#TEMP| {
                         #handle_error
                     })?;
                 }
@@ -225,19 +319,36 @@ pub(super) fn generate_data_enum(input: &Input, name: Option<&Ident>, en: &Enum)
     }
 }
 
-fn generate_variant_impl(en: &Enum, variant: &EnumVariant) -> TokenStream {
+fn generate_variant_impl(en: &Enum, variant: &EnumVariant, asynchronous: bool) -> TokenStream {
     let input = Input::Struct(variant.clone().into());
 
     match variant {
-        EnumVariant::Variant { ident, options } => StructGenerator::new(&input, options)
-            .read_fields(
-                None,
-                Some(&format!("{}::{}", en.ident.as_ref().unwrap(), ident)),
-            )
-            .initialize_value_with_assertions(Some(ident), &en.assertions)
-            .return_value()
-            .finish(),
-
-        EnumVariant::Unit(options) => generate_unit_struct(&input, None, Some(&options.ident)),
+        EnumVariant::Variant { ident, options } => {
+            StructGenerator::new(&input, options, asynchronous)
+                .read_fields(
+                    None,
+                    Some(&format!("{}::{}", en.ident.as_ref().unwrap(), ident)),
+                )
+                .initialize_value_with_assertions(Some(ident), &en.assertions)
+                .return_value()
+                .finish()
+        }
+
+        EnumVariant::Unit(options) => {
+            generate_unit_struct(&input, None, Some(&options.ident), asynchronous)
+        }
+    }
+}
+
+/// Returns an expression which rewinds the stream after a variant fails.
+fn get_restore_position_variant(
+    reader_var: &TokenStream,
+    error: &impl ToTokens,
+    asynchronous: bool,
+) -> TokenStream {
+    if asynchronous {
+        quote! { #RESTORE_POSITION_VARIANT_ASYNC(#reader_var, #POS, #error).await }
+    } else {
+        quote! { #RESTORE_POSITION_VARIANT(#reader_var, #POS, #error) }
     }
 }
@@ -2,7 +2,7 @@ use super::PreludeGenerator;
 use crate::binrw::{
     codegen::{
         get_assertions, get_map_err,
-        sanitization::{ARGS, OPT, POS, READ_METHOD, READER, THIS},
+        sanitization::{ARGS, MAP_FUNCTION, MAP_READ_ASYNC, OPT, POS, READ_METHOD, READER, THIS},
     },
     parser::Input,
 };
@@ -10,8 +10,13 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Ident, spanned::Spanned};
 
-pub(crate) fn generate_map(input: &Input, name: Option<&Ident>, map: &TokenStream) -> TokenStream {
-    let prelude = PreludeGenerator::new(input)
+pub(crate) fn generate_map(
+    input: &Input,
+    name: Option<&Ident>,
+    map: &TokenStream,
+    asynchronous: bool,
+) -> TokenStream {
+    let prelude = PreludeGenerator::new(input, asynchronous)
         .add_imports(name)
         .add_endian()
         .add_magic_pre_assertion()
@@ -21,6 +26,20 @@ pub(crate) fn generate_map(input: &Input, name: Option<&Ident>, map: &TokenStrea
     let assertions = field_asserts(input).chain(get_assertions(input.assertions()));
     let reader_var = input.stream_ident_or(READER);
 
+    if asynchronous {
+        return quote! {
+            #prelude
+
+            let #MAP_FUNCTION = #map;
+            let #THIS = #MAP_FUNCTION(#MAP_READ_ASYNC(&#MAP_FUNCTION, #reader_var, #OPT, ()).await?);
+            {
+                #destructure_ref
+                #(#assertions)*
+            }
+            ::core::result::Result::Ok(#THIS)
+        };
+    }
+
     // TODO: replace args with top-level arguments and only
     // use `()` as a default
     quote! {
@@ -46,9 +65,10 @@ pub(crate) fn generate_try_map(
     input: &Input,
     name: Option<&Ident>,
     map: &TokenStream,
+    asynchronous: bool,
 ) -> TokenStream {
     let map_err = get_map_err(POS, map.span());
-    let prelude = PreludeGenerator::new(input)
+    let prelude = PreludeGenerator::new(input, asynchronous)
         .add_imports(name)
         .add_endian()
         .add_magic_pre_assertion()
@@ -58,6 +78,20 @@ pub(crate) fn generate_try_map(
     let assertions = field_asserts(input).chain(get_assertions(input.assertions()));
     let reader_var = input.stream_ident_or(READER);
 
+    if asynchronous {
+        return quote! {
+            #prelude
+
+            let #MAP_FUNCTION = #map;
+            let #THIS = #MAP_FUNCTION(#MAP_READ_ASYNC(&#MAP_FUNCTION, #reader_var, #OPT, #ARGS).await?)#map_err?;
+            {
+                #destructure_ref
+                #(#assertions)*
+            }
+            ::core::result::Result::Ok(#THIS)
+        };
+    }
+
     // TODO: replace args with top-level arguments and only
     // use `()` as a default
     quote! {
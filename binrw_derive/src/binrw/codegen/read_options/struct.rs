@@ -6,15 +6,25 @@ use crate::binrw::parser::Assert;
 use crate::{
     binrw::{
         codegen::{
-            get_assertions, get_endian, get_map_err, get_passed_args, get_try_calc,
+            get_assertions, get_checksum_bytes, get_checksum_setup, get_checksum_stream,
+            get_endian, get_map_err, get_passed_args, get_seek, get_stream_position, get_try_calc,
+            get_window_size,
             sanitization::{
-                ARGS_TYPE_HINT, BACKTRACE_FRAME, BINREAD_TRAIT, COERCE_FN, DBG_EPRINTLN,
-                MAP_ARGS_TYPE_HINT, MAP_READER_TYPE_HINT, OPT, PARSE_FN_TYPE_HINT, POS,
-                READ_FUNCTION, READ_METHOD, READER, REQUIRED_ARG_TRAIT, SAVED_POSITION, SEEK_FROM,
-                SEEK_TRAIT, TEMP, THIS, WITH_CONTEXT, make_ident,
+                ARGS_TYPE_HINT, BACKTRACE_FRAME, BIN_RESULT, BINREAD_ASYNC_TRAIT, BINREAD_TRAIT,
+                CHECK_CHECKSUM, CHECK_CHECKSUM_BYTES, CHECKSUM_START, COERCE_FN, DBG_EPRINTLN,
+                EXTRACT_BITS, MAP_ARGS_TYPE_HINT, MAP_READ_ASYNC, MAP_READER_TYPE_HINT, OPT,
+                PARSE_FN_TYPE_HINT, POS, READ_BITS, READ_FUNCTION, READ_METHOD, READER,
+                RECOVER_DEFAULT, RECOVER_DYNAMIC_SIZE, RECOVER_ENTER, RECOVER_EXIT, RECOVER_FIELD,
+                RECOVER_HELD, RECOVER_MARK, RECOVER_MODE, RECOVER_NONE, RECOVER_PROBE,
+                RECOVER_STATIC_SIZE, RECOVER_STRICT, REQUIRED_ARG_TRAIT, SAVED_POSITION, SEEK_FROM,
+                SEEK_TRAIT, SPAN_ENTER_FIELD, SPAN_EXIT, TEMP, THIS, WINDOW, WITH_CONTEXT,
+                make_ident,
             },
         },
-        parser::{ErrContext, FieldMode, Input, Map, Struct, StructField},
+        parser::{
+            BitOrder, BitSlot, ChecksumRange, ErrContext, FieldMode, Input, Map, Struct,
+            StructField,
+        },
     },
     util::quote_spanned_any,
 };
@@ -27,8 +37,9 @@ pub(super) fn generate_unit_struct(
     input: &Input,
     name: Option<&Ident>,
     variant_ident: Option<&Ident>,
+    asynchronous: bool,
 ) -> TokenStream {
-    let prelude = get_prelude(input, name);
+    let prelude = get_prelude(input, name, asynchronous);
     let return_type = get_return_type(variant_ident);
     quote! {
         #prelude
@@ -36,8 +47,13 @@ pub(super) fn generate_unit_struct(
     }
 }
 
-pub(super) fn generate_struct(input: &Input, name: Option<&Ident>, st: &Struct) -> TokenStream {
-    StructGenerator::new(input, st)
+pub(super) fn generate_struct(
+    input: &Input,
+    name: Option<&Ident>,
+    st: &Struct,
+    asynchronous: bool,
+) -> TokenStream {
+    StructGenerator::new(input, st, asynchronous)
         .read_fields(name, None)
         .initialize_value_with_assertions(None, &[])
         .return_value()
@@ -47,14 +63,16 @@ pub(super) fn generate_struct(input: &Input, name: Option<&Ident>, st: &Struct)
 pub(super) struct StructGenerator<'input> {
     input: &'input Input,
     st: &'input Struct,
+    asynchronous: bool,
     out: TokenStream,
 }
 
 impl<'input> StructGenerator<'input> {
-    pub(super) fn new(input: &'input Input, st: &'input Struct) -> Self {
+    pub(super) fn new(input: &'input Input, st: &'input Struct, asynchronous: bool) -> Self {
         Self {
             input,
             st,
+            asynchronous,
             out: TokenStream::new(),
         }
     }
@@ -98,15 +116,102 @@ impl<'input> StructGenerator<'input> {
     }
 
     pub(super) fn read_fields(mut self, name: Option<&Ident>, variant_name: Option<&str>) -> Self {
-        let prelude = get_prelude(self.input, name);
-        let read_fields = self
-            .st
-            .fields
+        let prelude = get_prelude(self.input, name, self.asynchronous);
+        let bit_order = self.st.bit_order.unwrap_or_default();
+        let reader_var = self.input.stream_ident_or(READER);
+        let fields = &self.st.fields;
+        let recover = RecoverMode::new(self.st, variant_name.is_some());
+        // Invalid checksums were already rejected by the parser
+        let checksums = ChecksumRange::resolve(fields).unwrap_or_default();
+        let read_fields =
+            fields
+                .iter()
+                .zip(BitSlot::layout(fields))
+                .enumerate()
+                .map(|(index, (field, slot))| {
+                    let bits = slot.map(|slot| (slot, bit_order));
+                    let setup = get_checksum_setup(&reader_var, fields, &checksums, index);
+                    let field_reader_var =
+                        get_checksum_stream(&reader_var, fields, &checksums, index);
+                    let field_pos = field.checksum.as_ref().map(|_| {
+                        let pos_var = make_ident(&field.ident, "checksum_pos");
+                        quote! {
+                            let #pos_var = #SEEK_TRAIT::stream_position(#reader_var)?;
+                        }
+                    });
+                    let field_code = generate_field(
+                        field,
+                        bits,
+                        &field_reader_var,
+                        name,
+                        variant_name,
+                        recover,
+                        self.asynchronous,
+                    );
+                    let field_end = field
+                        .checksum
+                        .as_ref()
+                        .filter(|checksum| checksum.is_bytes())
+                        .map(|_| {
+                            let end_var = make_ident(&field.ident, "checksum_end");
+                            quote! {
+                                let #end_var = #SEEK_TRAIT::stream_position(#reader_var)?;
+                            }
+                        });
+                    let checks = checksums
+                        .iter()
+                        .filter(|checksum| checksum.last() == index)
+                        .map(|checksum| {
+                            let ident = &fields[checksum.field].ident;
+                            let state = make_ident(ident, "checksum");
+                            let pos_var = make_ident(ident, "checksum_pos");
+                            quote! {
+                                #CHECK_CHECKSUM(&#state, &#ident, #pos_var)?;
+                            }
+                        });
+
+                    quote! {
+                        #setup
+                        #field_pos
+                        #field_code
+                        #field_end
+                        #(#checks)*
+                    }
+                });
+
+        // Checksums over byte positions are checked once every field has been
+        // read, so that their ranges can refer to any field
+        let byte_checksums = fields
             .iter()
-            .map(|field| generate_field(self.input, field, name, variant_name));
+            .filter_map(|field| {
+                field
+                    .checksum
+                    .as_ref()
+                    .filter(|checksum| checksum.is_bytes())
+                    .map(|checksum| (field, checksum))
+            })
+            .collect::<Vec<_>>();
+        let checksum_start = (!byte_checksums.is_empty()).then(|| {
+            quote! {
+                let #CHECKSUM_START = #SEEK_TRAIT::stream_position(#reader_var)?;
+            }
+        });
+        let byte_checks = byte_checksums.iter().map(|(field, checksum)| {
+            let ident = &field.ident;
+            let algorithm = &checksum.algorithm;
+            let range = get_checksum_bytes(checksum, &reader_var);
+            let pos_var = make_ident(ident, "checksum_pos");
+            let end_var = make_ident(ident, "checksum_end");
+            quote_spanned! {algorithm.span()=>
+                #CHECK_CHECKSUM_BYTES::<#algorithm, _>(#reader_var, #range, #pos_var..#end_var, &#ident)?;
+            }
+        });
+
         self.out = quote! {
+            #checksum_start
             #prelude
             #(#read_fields)*
+            #(#byte_checks)*
         };
 
         self
@@ -142,23 +247,141 @@ impl<'input> StructGenerator<'input> {
     }
 }
 
-fn generate_field(
-    input: &Input,
+/// When the fields of a struct recover from errors.
+#[derive(Clone, Copy)]
+pub(super) enum RecoverMode {
+    /// The struct uses the `recover` directive.
+    Always,
+    /// Only when reading inside of a recovery session.
+    Session,
+    /// Only fields which hold their errors.
+    Held,
+}
+
+impl RecoverMode {
+    /// Enum variants only recover when asked, since recovering would stop a
+    /// variant from failing so that the next variant can be tried. Recovery
+    /// sessions only exist with feature `recover`.
+    pub(super) fn new(st: &Struct, is_variant: bool) -> Self {
+        if st.recover.is_some() {
+            Self::Always
+        } else if is_variant || !cfg!(feature = "recover") {
+            Self::Held
+        } else {
+            Self::Session
+        }
+    }
+
+    /// Returns whether a field in this mode needs to be read inside of the
+    /// recovery wrapper.
+    fn wraps(self, field: &StructField) -> bool {
+        match self {
+            Self::Always | Self::Session => true,
+            Self::Held => is_recovered(&field.ty),
+        }
+    }
+}
+
+/// Returns whether a type is spelled as [`Recovered<T>`], which holds its
+/// error.
+fn is_recovered(ty: &syn::Type) -> bool {
+    matches!(
+        ty,
+        syn::Type::Path(syn::TypePath { qself: None, path })
+            if path.segments.last().is_some_and(|segment| {
+                segment.ident == "Recovered"
+                    && matches!(segment.arguments, syn::PathArguments::AngleBracketed(_))
+            })
+    )
+}
+
+/// Returns whether a type is built only from numbers, so that reading it can
+/// never recover from an error.
+fn is_plain_number(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            path.get_ident().is_some_and(|ident| {
+                matches!(
+                    ident.to_string().as_str(),
+                    "u8" | "u16"
+                        | "u32"
+                        | "u64"
+                        | "u128"
+                        | "i8"
+                        | "i16"
+                        | "i32"
+                        | "i64"
+                        | "i128"
+                        | "f32"
+                        | "f64"
+                )
+            })
+        }
+        syn::Type::Array(array) => is_plain_number(&array.elem),
+        syn::Type::Tuple(tuple) => tuple.elems.iter().all(is_plain_number),
+        syn::Type::Paren(paren) => is_plain_number(&paren.elem),
+        _ => false,
+    }
+}
+
+/// Returns whether reading a field might recover from an error, in which case
+/// a read which relies on errors to backtrack has to stop it from recovering.
+pub(super) fn may_recover(field: &StructField) -> bool {
+    cfg!(feature = "recover")
+        || !field.generated_value()
+            && (!matches!(field.field_mode, FieldMode::Normal)
+                || !matches!(field.map, Map::None)
+                || !is_plain_number(&field.ty))
+}
+
+impl ToTokens for RecoverMode {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Self::Always => quote! { #RECOVER_MODE::Always },
+            Self::Session => quote! { #RECOVER_MODE::Session },
+            Self::Held => quote! { #RECOVER_MODE::Held },
+        });
+    }
+}
+
+pub(super) fn generate_field(
     field: &StructField,
+    bits: Option<(BitSlot, BitOrder)>,
+    reader_var: &TokenStream,
     name: Option<&Ident>,
     variant_name: Option<&str>,
+    recover: RecoverMode,
+    asynchronous: bool,
 ) -> TokenStream {
+    // The backing integer is read even if the first field of the group is
+    // skipped, since the other fields in the group still need it
+    let read_bits = bits
+        .as_ref()
+        .filter(|(slot, _)| slot.first)
+        .map(|(slot, _)| {
+            let bits_var = make_ident(&slot.group, "bits");
+            let pos_var = make_ident(&slot.group, "bits_pos");
+            let bytes = slot.group_bytes() as usize;
+            quote! {
+                let #pos_var = #SEEK_TRAIT::stream_position(#reader_var)?;
+                let #bits_var = #READ_BITS(#reader_var, #OPT, #bytes)?;
+            }
+        });
+
     // temp + ignore == just don't bother
     if field.is_temp(false) && matches!(field.field_mode, FieldMode::Default) {
-        return TokenStream::new();
+        return quote! { #read_bits };
     }
 
-    FieldGenerator::new(input, field)
+    let field = FieldGenerator::new(field, bits, reader_var, recover, asynchronous)
         .read_value()
         .wrap_map_stream()
         .try_conversion(name, variant_name)
+        .wrap_size()
+        .wrap_span()
         .map_value()
         .wrap_debug()
+        .mark_recover_point()
         .wrap_seek()
         .wrap_condition()
         .assign_to_var()
@@ -168,29 +391,51 @@ fn generate_field(
         .prefix_args_and_options()
         .prefix_map_function()
         .prefix_read_function()
-        .finish()
+        .wrap_recover()
+        .finish();
+
+    quote! {
+        #read_bits
+        #field
+    }
 }
 
 struct FieldGenerator<'field> {
     field: &'field StructField,
+    bits: Option<(BitSlot, BitOrder)>,
     out: TokenStream,
     outer_reader_var: TokenStream,
     reader_var: TokenStream,
     endian_var: TokenStream,
     args_var: Option<Ident>,
+    recover: Option<RecoverMode>,
+    asynchronous: bool,
 }
 
 impl<'field> FieldGenerator<'field> {
-    fn new(input: &Input, field: &'field StructField) -> Self {
-        let (reader_var, endian_var, args_var) = make_field_vars(input, field);
+    fn new(
+        field: &'field StructField,
+        bits: Option<(BitSlot, BitOrder)>,
+        outer_reader_var: &TokenStream,
+        recover: RecoverMode,
+        asynchronous: bool,
+    ) -> Self {
+        let (reader_var, endian_var, args_var) = make_field_vars(field, outer_reader_var);
+        // Recovery uses the synchronous stream traits
+        let recover =
+            (!field.generated_value() && bits.is_none() && !asynchronous && recover.wraps(field))
+                .then_some(recover);
 
         Self {
             field,
+            bits,
             out: TokenStream::new(),
-            outer_reader_var: input.stream_ident_or(READER),
+            outer_reader_var: outer_reader_var.clone(),
             reader_var,
             endian_var,
             args_var,
+            recover,
+            asynchronous,
         }
     }
 
@@ -212,7 +457,7 @@ impl<'field> FieldGenerator<'field> {
             }
 
             let head = self.out;
-            let reader_var = &self.outer_reader_var;
+            let stream_position = get_stream_position(&self.outer_reader_var, self.asynchronous);
             let ident = &self.field.ident;
             let start_line = ident.span().start().line;
             let at = if start_line == 0 {
@@ -230,7 +475,7 @@ impl<'field> FieldGenerator<'field> {
             self.out = quote! {{
                 #dbg_pad_before
                 #dbg_align_before
-                let #SAVED_POSITION = #SEEK_TRAIT::stream_position(#reader_var)?;
+                let #SAVED_POSITION = #stream_position?;
                 let #TEMP = #head;
                 #DBG_EPRINTLN!(
                     "[{}:{} | offset {:#x}] {} = {:#x?}",
@@ -271,21 +516,33 @@ impl<'field> FieldGenerator<'field> {
     }
 
     fn map_value(mut self) -> Self {
+        if self.field.map.is_none() {
+            return self;
+        }
+
         let map_func = make_ident(&self.field.ident, "map_func");
+        // The async read borrows the map function to infer its type, so it has
+        // to finish before the map function is called
+        let (read, value) = if self.asynchronous {
+            let value = self.out;
+            (Some(quote! { let #TEMP = #value; }), TEMP.to_token_stream())
+        } else {
+            (None, self.out)
+        };
 
         self.out = match &self.field.map {
-            Map::None => return self,
+            Map::None => unreachable!(),
             Map::Map(m) => {
-                let value = self.out;
-                quote_spanned! {m.span()=> #map_func(#value) }
+                quote_spanned! {m.span()=> { #read #map_func(#value) } }
             }
             Map::Try(t) | Map::Repr(t) => {
                 // TODO: Position should always just be saved once for a field if used
-                let value = self.out;
                 let map_err = get_map_err(SAVED_POSITION, t.span());
-                let reader_var = &self.outer_reader_var;
+                let stream_position =
+                    get_stream_position(&self.outer_reader_var, self.asynchronous);
                 quote_spanned! {t.span()=> {
-                    let #SAVED_POSITION = #SEEK_TRAIT::stream_position(#reader_var)?;
+                    let #SAVED_POSITION = #stream_position?;
+                    #read
 
                     #map_func(#value)#map_err?
                 }}
@@ -335,9 +592,14 @@ impl<'field> FieldGenerator<'field> {
         if let Some(map_stream) = &self.field.map_stream {
             let rest = self.out;
             let reader_var = &self.reader_var;
-            let outer_reader_var = &self.outer_reader_var;
+            let (outer_reader_ty, outer_reader_var) = if self.field.size.is_some() {
+                let window_var = make_ident(&self.field.ident, "window");
+                (quote! { #WINDOW<&mut R> }, quote! { &mut #window_var })
+            } else {
+                (quote! { R }, self.outer_reader_var.clone())
+            };
             self.out = quote_spanned_any! { map_stream.span()=> {
-                let #reader_var = &mut #MAP_READER_TYPE_HINT::<R, _, _>(#map_stream)(#outer_reader_var);
+                let #reader_var = &mut #MAP_READER_TYPE_HINT::<#outer_reader_ty, _, _>(#map_stream)(#outer_reader_var);
                 #rest
             }};
         }
@@ -345,6 +607,51 @@ impl<'field> FieldGenerator<'field> {
         self
     }
 
+    fn wrap_size(mut self) -> Self {
+        if let Some(size) = &self.field.size {
+            let window_var = make_ident(&self.field.ident, "window");
+            let outer_reader_var = &self.outer_reader_var;
+            // If there is a `map_stream`, it already maps the window into the
+            // reader for the field
+            let reader_var = self.field.map_stream.is_none().then(|| {
+                let reader_var = &self.reader_var;
+                quote! { let #reader_var = &mut #window_var; }
+            });
+            let size = get_window_size(size.as_ref(), outer_reader_var);
+            let rest = self.out;
+            self.out = quote! {{
+                let mut #window_var = #WINDOW::new(&mut *#outer_reader_var, #size)?;
+                let #TEMP = {
+                    #reader_var
+                    #rest
+                };
+                #WINDOW::skip_remaining(#window_var)?;
+                #TEMP
+            }};
+        }
+
+        self
+    }
+
+    fn wrap_span(mut self) -> Self {
+        // Spans are recorded using the synchronous stream traits
+        if !self.field.generated_value() && self.bits.is_none() && !self.asynchronous {
+            let depth_var = make_ident(&self.field.ident, "span");
+            let outer_reader_var = &self.outer_reader_var;
+            let name = self.field.name();
+            let ty = &self.field.ty;
+            let rest = self.out;
+            self.out = quote! {{
+                let #depth_var = #SPAN_ENTER_FIELD(#outer_reader_var, #name, ::core::any::type_name::<#ty>());
+                let #TEMP = #rest;
+                #SPAN_EXIT(#outer_reader_var, #depth_var);
+                #TEMP
+            }};
+        }
+
+        self
+    }
+
     fn prefix_read_function(mut self) -> Self {
         let read_function = match &self.field.field_mode {
             FieldMode::Function(parser) => {
@@ -352,7 +659,7 @@ impl<'field> FieldGenerator<'field> {
                     let #READ_FUNCTION = #PARSE_FN_TYPE_HINT(#parser);
                 }
             }
-            FieldMode::Normal => quote! {
+            FieldMode::Normal if self.bits.is_none() && !self.asynchronous => quote! {
                 let #READ_FUNCTION = #READ_METHOD;
             },
             _ => return self,
@@ -370,7 +677,7 @@ impl<'field> FieldGenerator<'field> {
     fn prefix_args_and_options(mut self) -> Self {
         let args = self.args_var.as_ref().map(|args_var| {
             let map_func = make_ident(&self.field.ident, "map_func");
-            let args = get_passed_args(self.field, &self.outer_reader_var);
+            let args = get_passed_args(self.field, &self.outer_reader_var, self.asynchronous);
             let ty = &self.field.ty;
 
             if let FieldMode::Function(_) = &self.field.field_mode {
@@ -411,8 +718,12 @@ impl<'field> FieldGenerator<'field> {
     }
 
     fn prefix_magic(mut self) -> Self {
-        if let Some(magic) = get_magic(&self.field.magic, &self.outer_reader_var, &self.endian_var)
-        {
+        if let Some(magic) = get_magic(
+            &self.field.magic,
+            &self.outer_reader_var,
+            &self.endian_var,
+            self.asynchronous,
+        ) {
             let tail = self.out;
             self.out = quote! {
                 #magic
@@ -428,6 +739,17 @@ impl<'field> FieldGenerator<'field> {
             FieldMode::Default => quote! { ::core::default::Default::default() },
             FieldMode::Calc(calc) => quote! { #calc },
             FieldMode::TryCalc(calc) => get_try_calc(POS, &self.field.ty, calc),
+            FieldMode::Normal if self.bits.is_some() => {
+                let (slot, bit_order) = self.bits.as_ref().unwrap();
+                let bits_var = make_ident(&slot.group, "bits");
+                let pos_var = make_ident(&slot.group, "bits_pos");
+                let shift = slot.shift(*bit_order);
+                let width = slot.width;
+                let offset = slot.offset;
+                quote_spanned! {self.field.ty.span()=>
+                    #EXTRACT_BITS(#bits_var, #pos_var, #shift, #width, #offset)
+                }
+            }
             read_mode @ (FieldMode::Normal | FieldMode::Function(_)) => {
                 let args_arg = self.args_var.as_ref().map_or_else(
                     || quote_spanned! {self.field.ty.span()=> <_ as #REQUIRED_ARG_TRAIT>::args() },
@@ -453,6 +775,20 @@ impl<'field> FieldGenerator<'field> {
                         (|| #READ_FUNCTION)()(#reader_var, #endian_var, #args_arg)
                         #map
                     }
+                } else if self.asynchronous {
+                    // The type being read has to be named since it cannot be
+                    // inferred through the returned future
+                    if self.field.map.is_none() {
+                        let ty = &self.field.ty;
+                        quote! {
+                            <#ty as #BINREAD_ASYNC_TRAIT>::read_options_async(#reader_var, #endian_var, #args_arg).await
+                        }
+                    } else {
+                        let map_func = make_ident(&self.field.ident, "map_func");
+                        quote! {
+                            #MAP_READ_ASYNC(&#map_func, #reader_var, #endian_var, #args_arg).await
+                        }
+                    }
                 } else {
                     quote! {
                         #READ_FUNCTION(#reader_var, #endian_var, #args_arg)
@@ -468,7 +804,11 @@ impl<'field> FieldGenerator<'field> {
         if !self.field.generated_value() {
             let result = self.out;
             self.out = if self.field.do_try.is_some() {
-                quote! { #result.unwrap_or_default() }
+                if self.asynchronous || !may_recover(self.field) {
+                    quote! { #result.unwrap_or_default() }
+                } else {
+                    quote! { #RECOVER_STRICT(|| #result).unwrap_or_default() }
+                }
             } else {
                 let span = match &self.field.field_mode {
                     FieldMode::Function(f) => f.span(),
@@ -503,17 +843,102 @@ impl<'field> FieldGenerator<'field> {
         self
     }
 
+    fn mark_recover_point(mut self) -> Self {
+        if let Some(mode) = self.recover {
+            let point_var = make_ident(&self.field.ident, "recover_point");
+            let fallback_var = make_ident(&self.field.ident, "recover_fallback");
+            let outer_reader_var = &self.outer_reader_var;
+            let size = self.get_recover_size();
+            let value = self.out;
+            self.out = quote! {{
+                #point_var = #RECOVER_MARK(#outer_reader_var, #mode, &#fallback_var, #size);
+                let #TEMP = #value;
+                #TEMP
+            }};
+        }
+
+        self
+    }
+
+    /// Returns an expression for the size of the field, if it is known before
+    /// the field is read.
+    fn get_recover_size(&self) -> TokenStream {
+        if let Some(size) = &self.field.size {
+            let size = size.as_ref();
+            return quote! {
+                #[allow(clippy::useless_conversion, clippy::unnecessary_fallible_conversions)]
+                <::core::primitive::u64 as ::core::convert::TryFrom<_>>::try_from(#size).ok()
+            };
+        }
+
+        let read_ty = match (&self.field.field_mode, &self.field.map) {
+            (FieldMode::Normal, Map::None) => self.field.ty.to_token_stream(),
+            (FieldMode::Normal, Map::Repr(repr)) => repr.clone(),
+            _ => return quote! { ::core::option::Option::None },
+        };
+        quote! {{
+            use #RECOVER_STATIC_SIZE as _;
+            use #RECOVER_DYNAMIC_SIZE as _;
+            (&&#RECOVER_PROBE::<#read_ty>::new()).static_size()
+        }}
+    }
+
+    fn wrap_recover(mut self) -> Self {
+        if let Some(mode) = self.recover {
+            let ident = &self.field.ident;
+            let ty = &self.field.ty;
+            let name = self.field.name();
+            let depth_var = make_ident(ident, "recover_depth");
+            let point_var = make_ident(ident, "recover_point");
+            let fallback_var = make_ident(ident, "recover_fallback");
+            let outer_reader_var = &self.outer_reader_var;
+            let read = self.out;
+            // The field is read inside of a closure so that any error it
+            // returns can be caught
+            self.out = quote! {
+                let mut #ident: #ty = {
+                    let #depth_var = #RECOVER_ENTER(#name);
+                    let #fallback_var = {
+                        use #RECOVER_HELD as _;
+                        use #RECOVER_DEFAULT as _;
+                        use #RECOVER_NONE as _;
+                        (&&&#RECOVER_PROBE::<#ty>::new()).fallback()
+                    };
+                    let mut #point_var = ::core::option::Option::None;
+                    let #TEMP = (|| -> #BIN_RESULT<#ty> {
+                        #read
+                        ::core::result::Result::Ok(#ident)
+                    })();
+                    let #TEMP = #TEMP.or_else(|error| #RECOVER_FIELD(
+                        #outer_reader_var,
+                        error,
+                        #mode,
+                        #name,
+                        #depth_var,
+                        #point_var,
+                        &#fallback_var,
+                    ));
+                    #RECOVER_EXIT(#depth_var);
+                    #TEMP?
+                };
+            };
+        }
+
+        self
+    }
+
     fn wrap_restore_position(mut self) -> Self {
         if self.field.restore_position.is_some() {
-            self.out = wrap_save_restore(&self.outer_reader_var, self.out);
+            self.out = wrap_save_restore(&self.outer_reader_var, self.out, self.asynchronous);
         }
 
         self
     }
 
     fn wrap_seek(mut self) -> Self {
-        let seek_before = generate_seek_before(&self.outer_reader_var, self.field);
-        let seek_after = generate_seek_after(&self.outer_reader_var, self.field);
+        let seek_before =
+            generate_seek_before(&self.outer_reader_var, self.field, self.asynchronous);
+        let seek_after = generate_seek_after(&self.outer_reader_var, self.field, self.asynchronous);
         if !seek_before.is_empty() || !seek_after.is_empty() {
             let value = self.out;
             self.out = quote! {{
@@ -580,8 +1005,8 @@ fn get_err_context(
     }
 }
 
-fn get_prelude(input: &Input, name: Option<&Ident>) -> TokenStream {
-    PreludeGenerator::new(input)
+pub(super) fn get_prelude(input: &Input, name: Option<&Ident>, asynchronous: bool) -> TokenStream {
+    PreludeGenerator::new(input, asynchronous)
         .add_imports(name)
         .add_endian()
         .add_magic_pre_assertion()
@@ -589,24 +1014,34 @@ fn get_prelude(input: &Input, name: Option<&Ident>) -> TokenStream {
         .finish()
 }
 
-fn generate_seek_after(reader_var: &TokenStream, field: &StructField) -> TokenStream {
+fn generate_seek_after(
+    reader_var: &TokenStream,
+    field: &StructField,
+    asynchronous: bool,
+) -> TokenStream {
     let pad_size_to = field.pad_size_to.as_ref().map(|pad| {
+        let stream_position = get_stream_position(reader_var, asynchronous);
+        let seek = get_seek(
+            reader_var,
+            &quote! { #SEEK_FROM::Current(pad - size) },
+            asynchronous,
+        );
         quote! {{
             let pad = (#pad) as ::core::primitive::i64;
-            let size = (#SEEK_TRAIT::stream_position(#reader_var)? - #POS) as ::core::primitive::i64;
+            let size = (#stream_position? - #POS) as ::core::primitive::i64;
             if size < pad {
-                #SEEK_TRAIT::seek(#reader_var, #SEEK_FROM::Current(pad - size))?;
+                #seek?;
             }
         }}
     });
     let pad_after = field
         .pad_after
         .as_ref()
-        .map(|value| map_pad(reader_var, value));
+        .map(|value| map_pad(reader_var, value, asynchronous));
     let align_after = field
         .align_after
         .as_ref()
-        .map(|value| map_align(reader_var, value));
+        .map(|value| map_align(reader_var, value, asynchronous));
 
     quote! {
         #pad_size_to
@@ -615,23 +1050,29 @@ fn generate_seek_after(reader_var: &TokenStream, field: &StructField) -> TokenSt
     }
 }
 
-fn generate_seek_before(reader_var: &TokenStream, field: &StructField) -> TokenStream {
+fn generate_seek_before(
+    reader_var: &TokenStream,
+    field: &StructField,
+    asynchronous: bool,
+) -> TokenStream {
     let seek_before = field.seek_before.as_ref().map(|seek| {
+        let seek = get_seek(reader_var, seek, asynchronous);
         quote! {
-            #SEEK_TRAIT::seek(#reader_var, #seek)?;
+            #seek?;
         }
     });
     let pad_before = field
         .pad_before
         .as_ref()
-        .map(|value| map_pad(reader_var, value));
+        .map(|value| map_pad(reader_var, value, asynchronous));
     let align_before = field
         .align_before
         .as_ref()
-        .map(|value| map_align(reader_var, value));
+        .map(|value| map_align(reader_var, value, asynchronous));
     let pad_size_to_before = field.pad_size_to.as_ref().map(|_| {
+        let stream_position = get_stream_position(reader_var, asynchronous);
         quote! {
-            let #POS = #SEEK_TRAIT::stream_position(#reader_var)?;
+            let #POS = #stream_position?;
         }
     });
 
@@ -648,13 +1089,13 @@ fn get_return_type(variant_ident: Option<&Ident>) -> TokenStream {
 }
 
 fn make_field_vars(
-    input: &Input,
     field: &StructField,
+    outer_reader_var: &TokenStream,
 ) -> (TokenStream, TokenStream, Option<Ident>) {
-    let reader_var = if field.map_stream.is_some() {
+    let reader_var = if field.map_stream.is_some() || field.size.is_some() {
         make_ident(&field.ident, "reader").into_token_stream()
     } else {
-        input.stream_ident_or(READER)
+        outer_reader_var.clone()
     };
 
     let endian_var = if field.needs_endian() {
@@ -672,28 +1113,49 @@ fn make_field_vars(
     (reader_var, endian_var, args_var)
 }
 
-fn map_align(reader_var: &TokenStream, align: &TokenStream) -> TokenStream {
+fn map_align(reader_var: &TokenStream, align: &TokenStream, asynchronous: bool) -> TokenStream {
+    let stream_position = get_stream_position(reader_var, asynchronous);
+    let seek = get_seek(
+        reader_var,
+        &quote! { #SEEK_FROM::Current((align - (pos % align)) % align) },
+        asynchronous,
+    );
     quote! {{
         let align = (#align) as ::core::primitive::i64;
-        let pos = #SEEK_TRAIT::stream_position(#reader_var)? as ::core::primitive::i64;
-        #SEEK_TRAIT::seek(#reader_var, #SEEK_FROM::Current((align - (pos % align)) % align))?;
+        let pos = #stream_position? as ::core::primitive::i64;
+        #seek?;
     }}
 }
 
-fn map_pad(reader_var: &TokenStream, pad: &TokenStream) -> TokenStream {
+fn map_pad(reader_var: &TokenStream, pad: &TokenStream, asynchronous: bool) -> TokenStream {
+    let seek = get_seek(
+        reader_var,
+        &quote! { #SEEK_FROM::Current((#pad) as ::core::primitive::i64) },
+        asynchronous,
+    );
     quote! {
-        #SEEK_TRAIT::seek(#reader_var, #SEEK_FROM::Current((#pad) as ::core::primitive::i64))?;
+        #seek?;
     }
 }
 
-fn wrap_save_restore(reader_var: &TokenStream, value: TokenStream) -> TokenStream {
+fn wrap_save_restore(
+    reader_var: &TokenStream,
+    value: TokenStream,
+    asynchronous: bool,
+) -> TokenStream {
     if value.is_empty() {
         value
     } else {
+        let stream_position = get_stream_position(reader_var, asynchronous);
+        let seek = get_seek(
+            reader_var,
+            &quote! { #SEEK_FROM::Start(#SAVED_POSITION) },
+            asynchronous,
+        );
         quote! {
-            let #SAVED_POSITION = #SEEK_TRAIT::stream_position(#reader_var)?;
+            let #SAVED_POSITION = #stream_position?;
             #value
-            #SEEK_TRAIT::seek(#reader_var, #SEEK_FROM::Start(#SAVED_POSITION))?;
+            #seek?;
         }
     }
 }
@@ -0,0 +1,50 @@
+use super::r#struct::{RecoverMode, generate_field, get_prelude};
+use crate::binrw::{
+    codegen::{
+        get_assertions,
+        sanitization::{BIN_ERROR, POS, READER, THIS},
+    },
+    parser::{Input, Struct},
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+pub(super) fn generate_union(
+    input: &Input,
+    name: Option<&Ident>,
+    un: &Struct,
+    asynchronous: bool,
+) -> TokenStream {
+    let prelude = get_prelude(input, name, asynchronous);
+    let reader_var = input.stream_ident_or(READER);
+    let recover = RecoverMode::new(un, false);
+
+    // Fields are tried in order, and the first field whose condition is true
+    // is the one which is read
+    let read_fields = un.fields.iter().map(|field| {
+        let ident = &field.ident;
+        let select = field
+            .select
+            .as_ref()
+            .map_or_else(|| quote! { true }, |select| select.condition.clone());
+        let read_field =
+            generate_field(field, None, &reader_var, name, None, recover, asynchronous);
+        let assertions = get_assertions(&un.assertions);
+
+        quote! {
+            if #select {
+                #read_field
+                let #THIS = Self { #ident };
+                #(#assertions)*
+                return ::core::result::Result::Ok(#THIS);
+            }
+        }
+    });
+
+    quote! {
+        #prelude
+        #(#read_fields)*
+        ::core::result::Result::Err(#BIN_ERROR::NoVariantMatch { pos: #POS })
+    }
+}
@@ -21,19 +21,28 @@ macro_rules! from_write_trait {
 }
 
 pub(crate) const ARGS_LIFETIME: &str = "__binrw_generated_args_lifetime";
+pub(crate) const ARBITRARY_LIFETIME: &str = "__binrw_generated_arbitrary_lifetime";
 
 ident_str! {
     pub(crate) BINREAD_TRAIT = from_read_trait!();
     pub(crate) BINWRITE_TRAIT = from_write_trait!();
+    pub(crate) BINREAD_ASYNC_TRAIT = from_crate!(BinReadAsync);
+    pub(crate) BINREAD_STREAM_TRAIT = from_crate!(BinReadStream);
+    pub(crate) BINWRITE_ASYNC_TRAIT = from_crate!(BinWriteAsync);
     pub(crate) BIN_ERROR = from_crate!(Error);
     pub(crate) READ_TRAIT = from_crate!(io::Read);
     pub(crate) WRITE_TRAIT = from_crate!(io::Write);
     pub(crate) SEEK_TRAIT = from_crate!(io::Seek);
+    pub(crate) ASYNC_READ_TRAIT = from_crate!(asynchronous::AsyncRead);
+    pub(crate) ASYNC_WRITE_TRAIT = from_crate!(asynchronous::AsyncWrite);
+    pub(crate) ASYNC_SEEK_TRAIT = from_crate!(asynchronous::AsyncSeek);
     pub(crate) SEEK_FROM = from_crate!(io::SeekFrom);
+    pub(crate) WINDOW = from_crate!(io::Window);
     pub(crate) BIN_RESULT = from_crate!(BinResult);
     pub(crate) ENDIAN_ENUM = from_crate!(Endian);
     pub(crate) READ_METHOD = from_read_trait!(read_options);
     pub(crate) WRITE_METHOD = from_write_trait!(write_options);
+    pub(crate) WRITE_ASYNC_METHOD = from_crate!(BinWriteAsync::write_options_async);
     pub(crate) READER = "__binrw_generated_var_reader";
     pub(crate) WRITER = "__binrw_generated_var_writer";
     pub(crate) OPT = "__binrw_generated_var_endian";
@@ -41,6 +50,11 @@ ident_str! {
     pub(crate) SAVED_POSITION = "__binrw_generated_saved_position";
     pub(crate) NOT_ENOUGH_BYTES = from_crate!(__private::not_enough_bytes);
     pub(crate) ASSERT_MAGIC = from_crate!(__private::magic);
+    pub(crate) ASSERT_MAGIC_ASYNC = from_crate!(__private::magic_async);
+    pub(crate) ASYNC_BLOCK_TYPE_HINT = from_crate!(__private::async_block_type_hint);
+    pub(crate) MAP_READ_ASYNC = from_crate!(__private::map_read_async);
+    pub(crate) STREAM_POSITION_ASYNC = from_crate!(__private::stream_position_async);
+    pub(crate) SEEK_ASYNC = from_crate!(__private::seek_async);
     pub(crate) ASSERT = from_crate!(__private::assert);
     pub(crate) ASSERT_ERROR_FN = from_crate!(__private::AssertErrorFn);
     pub(crate) COERCE_FN = from_crate!(__private::coerce_fn);
@@ -57,31 +71,92 @@ ident_str! {
     pub(crate) WRITE_MAP_INPUT_TYPE_HINT = from_crate!(__private::write_map_fn_input_type_hint);
     pub(crate) WRITE_FN_MAP_OUTPUT_TYPE_HINT = from_crate!(__private::write_fn_map_output_type_hint);
     pub(crate) WRITE_FN_TRY_MAP_OUTPUT_TYPE_HINT = from_crate!(__private::write_fn_try_map_output_type_hint);
+    pub(crate) PARSE_PREFIXED = from_crate!(prefixed::parse_prefixed);
+    pub(crate) WRITE_PREFIXED = from_crate!(prefixed::write_prefixed);
     pub(crate) RESTORE_POSITION = from_crate!(__private::restore_position);
     pub(crate) RESTORE_POSITION_VARIANT = from_crate!(__private::restore_position_variant);
+    pub(crate) RESTORE_POSITION_ASYNC = from_crate!(__private::restore_position_async);
+    pub(crate) RESTORE_POSITION_VARIANT_ASYNC = from_crate!(__private::restore_position_variant_async);
     pub(crate) WRITE_ZEROES = from_crate!(__private::write_zeroes);
+    pub(crate) WRITE_ZEROES_ASYNC = from_crate!(__private::write_zeroes_async);
+    pub(crate) READ_BITS = from_crate!(__private::read_bits);
+    pub(crate) WRITE_BITS = from_crate!(__private::write_bits);
+    pub(crate) EXTRACT_BITS = from_crate!(__private::extract_bits);
+    pub(crate) INSERT_BITS = from_crate!(__private::insert_bits);
+    pub(crate) CHECKSUM_TRAIT = from_crate!(checksum::Checksum);
+    pub(crate) CHECKSUM_STREAM = from_crate!(checksum::ChecksumStream);
+    pub(crate) CHECK_CHECKSUM = from_crate!(__private::check_checksum);
+    pub(crate) WRITE_CHECKSUM = from_crate!(__private::write_checksum);
+    pub(crate) CHECK_VERSION = from_crate!(__private::check_version);
+    pub(crate) CHECK_VERSION_ASYNC = from_crate!(__private::check_version_async);
+    pub(crate) COUNT_OF = from_crate!(__private::count_of);
+    pub(crate) SPAN_ENTER_FIELD = from_crate!(__private::span_enter_field);
+    pub(crate) SPAN_ENTER_VARIANT = from_crate!(__private::span_enter_variant);
+    pub(crate) SPAN_EXIT = from_crate!(__private::span_exit);
+    pub(crate) SPAN_DISCARD = from_crate!(__private::span_discard);
+    pub(crate) RECOVER_MODE = from_crate!(__private::RecoverMode);
+    pub(crate) RECOVER_PROBE = from_crate!(__private::RecoverProbe);
+    pub(crate) RECOVER_HELD = from_crate!(__private::RecoverHeld);
+    pub(crate) RECOVER_DEFAULT = from_crate!(__private::RecoverDefault);
+    pub(crate) RECOVER_NONE = from_crate!(__private::RecoverNone);
+    pub(crate) RECOVER_STATIC_SIZE = from_crate!(__private::RecoverStaticSize);
+    pub(crate) RECOVER_DYNAMIC_SIZE = from_crate!(__private::RecoverDynamicSize);
+    pub(crate) RECOVER_ENTER = from_crate!(__private::recover_enter);
+    pub(crate) RECOVER_EXIT = from_crate!(__private::recover_exit);
+    pub(crate) RECOVER_MARK = from_crate!(__private::recover_mark);
+    pub(crate) RECOVER_FIELD = from_crate!(__private::recover_field);
+    pub(crate) RECOVER_STRICT = from_crate!(__private::recover_strict);
+    pub(crate) ARBITRARY_TRAIT = from_crate!(__private::arbitrary::Arbitrary);
+    pub(crate) ARBITRARY_RESULT = from_crate!(__private::arbitrary::Result);
+    pub(crate) UNSTRUCTURED = from_crate!(__private::arbitrary::Unstructured);
+    pub(crate) ARBITRARY_COUNT = from_crate!(__private::arbitrary_count);
+    pub(crate) ARBITRARY_LEN = from_crate!(__private::arbitrary_len);
+    pub(crate) ARBITRARY_BITS = from_crate!(__private::arbitrary_bits);
+    pub(crate) ARBITRARY_CHECK = from_crate!(__private::arbitrary_check);
+    pub(crate) ARBITRARY_TRY_CALC = from_crate!(__private::arbitrary_try_calc);
     pub(crate) ARGS_MACRO = from_crate!(args);
     pub(crate) META_ENDIAN_KIND = from_crate!(meta::EndianKind);
     pub(crate) READ_ENDIAN = from_crate!(meta::ReadEndian);
     pub(crate) READ_MAGIC = from_crate!(meta::ReadMagic);
+    pub(crate) META_SCHEMA = from_crate!(meta::Schema);
+    pub(crate) META_SCHEMA_KIND = from_crate!(meta::SchemaKind);
+    pub(crate) META_TYPE_SCHEMA = from_crate!(meta::TypeSchema);
+    pub(crate) META_VARIANT_SCHEMA = from_crate!(meta::VariantSchema);
+    pub(crate) META_UNIT_VARIANT_SCHEMA = from_crate!(meta::UnitVariantSchema);
+    pub(crate) META_FIELD_SCHEMA = from_crate!(meta::FieldSchema);
+    pub(crate) STATIC_SIZE = from_crate!(meta::StaticSize);
     pub(crate) WRITE_ENDIAN = from_crate!(meta::WriteEndian);
     pub(crate) WRITE_MAGIC = from_crate!(meta::WriteMagic);
     pub(crate) WITH_CONTEXT = from_crate!(error::ContextExt::with_context);
     pub(crate) BACKTRACE_FRAME = from_crate!(error::BacktraceFrame);
     pub(crate) TEMP = "__binrw_temp";
     pub(crate) THIS = "__binrw_this";
+    pub(crate) UNSTRUCTURED_VAR = "__binrw_generated_unstructured";
     pub(crate) POS = "__binrw_generated_position_temp";
     pub(crate) ERROR_BASKET = "__binrw_generated_error_basket";
     pub(crate) READ_FUNCTION = "__binrw_generated_read_function";
     pub(crate) WRITE_FUNCTION = "__binrw_generated_write_function";
+    pub(crate) MAP_FUNCTION = "__binrw_generated_map_function";
     pub(crate) BEFORE_POS = "__binrw_generated_before_pos";
     pub(crate) ALL_EOF = "__binrw_generated_all_eof";
+    pub(crate) SPAN_DEPTH = "__binrw_generated_span_depth";
     pub(crate) BOX = from_crate!(__private::Box);
     pub(crate) DBG_EPRINTLN = from_crate!(__private::eprintln);
     pub(crate) FORMAT = from_crate!(__private::format);
     pub(crate) VEC = from_crate!(__private::Vec);
 }
 
+// Split from the list above to stay within the macro recursion limit
+ident_str! {
+    pub(crate) CHECKSUM_RECORDER = from_crate!(checksum::ChecksumRecorder);
+    pub(crate) META_BIT_ORDER = from_crate!(meta::BitOrder);
+    pub(crate) META_CHECKSUM_SCHEMA = from_crate!(meta::ChecksumSchema);
+    pub(crate) CHECK_CHECKSUM_BYTES = from_crate!(__private::check_checksum_bytes);
+    pub(crate) WRITE_CHECKSUM_BYTES = from_crate!(__private::write_checksum_bytes);
+    pub(crate) CHECKSUM_START = "__binrw_generated_checksum_start";
+    pub(crate) CHECKSUM_RECORDER_VAR = "__binrw_generated_checksum_recorder";
+}
+
 pub(crate) fn make_ident(ident: &Ident, kind: &str) -> Ident {
     format_ident!("__binrw_generated_{}_{}", kind, ident)
 }
@@ -2,45 +2,60 @@ mod r#enum;
 mod prelude;
 mod r#struct;
 mod struct_field;
+mod union;
 
-use super::get_map_err;
+use super::{get_map_err, get_stream_position};
 use crate::binrw::{
-    codegen::sanitization::{OPT, POS, SEEK_TRAIT, WRITE_METHOD, WRITER},
+    codegen::sanitization::{OPT, POS, WRITE_ASYNC_METHOD, WRITE_METHOD, WRITER},
     parser::{Input, Map},
 };
 use r#enum::{generate_data_enum, generate_unit_enum};
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{ToTokens, quote};
 use r#struct::generate_struct;
 use syn::{Ident, spanned::Spanned};
+use union::generate_union;
 
-pub(crate) fn generate(input: &Input, derive_input: &syn::DeriveInput) -> TokenStream {
+pub(crate) fn generate(
+    input: &Input,
+    derive_input: &syn::DeriveInput,
+    asynchronous: bool,
+) -> TokenStream {
     let name = Some(&derive_input.ident);
     let inner = match input.map() {
         Map::None => match input {
-            Input::UnitStruct(s) | Input::Struct(s) => generate_struct(input, name, s),
-            Input::Enum(e) => generate_data_enum(input, name, e),
-            Input::UnitOnlyEnum(e) => generate_unit_enum(input, name, e),
+            Input::UnitStruct(s) | Input::Struct(s) => {
+                generate_struct(input, name, s, asynchronous)
+            }
+            Input::Enum(e) => generate_data_enum(input, name, e, asynchronous),
+            Input::UnitOnlyEnum(e) => generate_unit_enum(input, name, e, asynchronous),
+            Input::Union(u) => generate_union(input, name, u, asynchronous),
         },
-        Map::Try(map) | Map::Map(map) => generate_map(input, name, map),
+        Map::Try(map) | Map::Map(map) => generate_map(input, name, map, asynchronous),
         Map::Repr(map) => match input {
-            Input::UnitOnlyEnum(e) => generate_unit_enum(input, name, e),
-            _ => generate_map(input, name, map),
+            Input::UnitOnlyEnum(e) => generate_unit_enum(input, name, e, asynchronous),
+            _ => generate_map(input, name, map, asynchronous),
         },
     };
 
     let writer_var = input.stream_ident_or(WRITER);
+    let stream_position = get_stream_position(&writer_var, asynchronous);
 
     quote! {
         let #writer_var = #WRITER;
-        let #POS = #SEEK_TRAIT::stream_position(#writer_var)?;
+        let #POS = #stream_position?;
         #inner
 
         ::core::result::Result::Ok(())
     }
 }
 
-fn generate_map(input: &Input, name: Option<&Ident>, map: &TokenStream) -> TokenStream {
+fn generate_map(
+    input: &Input,
+    name: Option<&Ident>,
+    map: &TokenStream,
+    asynchronous: bool,
+) -> TokenStream {
     let map_try = input.map().is_try().then(|| {
         let map_err = get_map_err(POS, map.span());
         quote! { #map_err? }
@@ -51,21 +66,39 @@ fn generate_map(input: &Input, name: Option<&Ident>, map: &TokenStream) -> Token
         map.clone()
     };
     let writer_var = input.stream_ident_or(WRITER);
-    let write_data = quote! {
-        #WRITE_METHOD(
-            &((#map)(self) #map_try),
-            #writer_var,
-            #OPT,
-            ()
-        )?;
-    };
+    let write = get_write(
+        &quote! { &((#map)(self) #map_try) },
+        &writer_var,
+        &OPT,
+        &quote! { () },
+        asynchronous,
+    );
+    let write_data = quote! { #write?; };
 
     let magic = input.magic();
     let endian = input.endian();
-    prelude::PreludeGenerator::new(write_data, input, name, &writer_var)
+    prelude::PreludeGenerator::new(write_data, input, name, &writer_var, asynchronous)
         .prefix_magic(magic)
         .prefix_assertions()
         .prefix_endian(endian)
         .prefix_imports()
         .finish()
 }
+
+/// Returns an expression which writes `value`, whose type is inferred from
+/// the value itself.
+fn get_write(
+    value: &TokenStream,
+    writer_var: &impl ToTokens,
+    endian: &impl ToTokens,
+    args: &TokenStream,
+    asynchronous: bool,
+) -> TokenStream {
+    if asynchronous {
+        quote! {
+            #WRITE_ASYNC_METHOD(#value, #writer_var, #endian, #args).await
+        }
+    } else {
+        quote! { #WRITE_METHOD(#value, #writer_var, #endian, #args) }
+    }
+}
@@ -1,6 +1,9 @@
-use super::{prelude::PreludeGenerator, r#struct::StructGenerator};
+use super::{get_write, prelude::PreludeGenerator, r#struct::StructGenerator};
 use crate::binrw::{
-    codegen::sanitization::{OPT, WRITE_METHOD, WRITER},
+    codegen::{
+        FORMAT, get_discriminants, get_stream_position,
+        sanitization::{BIN_ERROR, OPT, SPAN_DEPTH, SPAN_ENTER_VARIANT, SPAN_EXIT, TEMP, WRITER},
+    },
     parser::{Enum, EnumVariant, Input, UnitEnumField, UnitOnlyEnum},
 };
 use proc_macro2::{Ident, TokenStream};
@@ -10,14 +13,15 @@ pub(crate) fn generate_unit_enum(
     input: &Input,
     name: Option<&Ident>,
     en: &UnitOnlyEnum,
+    asynchronous: bool,
 ) -> TokenStream {
     let writer_var = input.stream_ident_or(WRITER);
     let write = match en.map.as_repr() {
-        Some(repr) => generate_unit_enum_repr(&writer_var, repr, &en.fields),
-        None => generate_unit_enum_magic(&writer_var, &en.fields),
+        Some(repr) => generate_unit_enum_repr(&writer_var, repr, &en.fields, asynchronous),
+        None => generate_unit_enum_magic(&writer_var, &en.fields, asynchronous),
     };
 
-    PreludeGenerator::new(write, input, name, &writer_var)
+    PreludeGenerator::new(write, input, name, &writer_var, asynchronous)
         .prefix_map_stream()
         .prefix_magic(&en.magic)
         .prefix_assertions()
@@ -26,8 +30,13 @@ pub(crate) fn generate_unit_enum(
         .finish()
 }
 
-pub(crate) fn generate_data_enum(input: &Input, name: Option<&Ident>, en: &Enum) -> TokenStream {
-    EnumGenerator::new(input, name, en, input.stream_ident_or(WRITER))
+pub(crate) fn generate_data_enum(
+    input: &Input,
+    name: Option<&Ident>,
+    en: &Enum,
+    asynchronous: bool,
+) -> TokenStream {
+    EnumGenerator::new(input, name, en, input.stream_ident_or(WRITER), asynchronous)
         .write_variants()
         .prefix_prelude()
         .finish()
@@ -38,6 +47,7 @@ struct EnumGenerator<'a> {
     input: &'a Input,
     name: Option<&'a Ident>,
     writer_var: TokenStream,
+    asynchronous: bool,
     out: TokenStream,
 }
 
@@ -47,12 +57,14 @@ impl<'a> EnumGenerator<'a> {
         name: Option<&'a Ident>,
         en: &'a Enum,
         writer_var: TokenStream,
+        asynchronous: bool,
     ) -> Self {
         Self {
             input,
             name,
             en,
             writer_var,
+            asynchronous,
             out: TokenStream::new(),
         }
     }
@@ -70,9 +82,10 @@ impl<'a> EnumGenerator<'a> {
                 EnumVariant::Variant { options, .. } => {
                     let input = Input::Struct(variant.clone().into());
 
-                    StructGenerator::new(&input, options, None, &self.writer_var)
+                    StructGenerator::new(&input, options, None, &self.writer_var, self.asynchronous)
                         .write_fields()
                         .prefix_prelude()
+                        .prefix_checksum_recorder()
                         .finish()
                 }
                 EnumVariant::Unit(variant) => variant
@@ -80,18 +93,30 @@ impl<'a> EnumGenerator<'a> {
                     .as_ref()
                     .map(|magic| {
                         let magic = magic.match_value();
-                        quote! {
-                            #WRITE_METHOD (
-                                &#magic,
-                                #writer_var,
-                                #OPT,
-                                ()
-                            )?;
-                        }
+                        let write = get_write(
+                            &quote! { &#magic },
+                            writer_var,
+                            &OPT,
+                            &quote! { () },
+                            self.asynchronous,
+                        );
+                        quote! { #write?; }
                     })
                     .unwrap_or_default(),
             };
 
+            // Spans are recorded using the synchronous stream traits
+            let writing = if self.asynchronous {
+                writing
+            } else {
+                let name = name.to_string();
+                quote! {
+                    let #SPAN_DEPTH = #SPAN_ENTER_VARIANT(#writer_var, #name, ::core::any::type_name::<Self>());
+                    #writing
+                    #SPAN_EXIT(#writer_var, #SPAN_DEPTH);
+                }
+            };
+
             quote! {
                 Self::#name #fields => {
                     #writing
@@ -111,13 +136,19 @@ impl<'a> EnumGenerator<'a> {
     fn prefix_prelude(mut self) -> Self {
         let out = self.out;
 
-        self.out = PreludeGenerator::new(out, self.input, self.name, &self.writer_var)
-            .prefix_map_stream()
-            .prefix_magic(&self.en.magic)
-            .prefix_assertions()
-            .prefix_endian(&self.en.endian)
-            .prefix_imports()
-            .finish();
+        self.out = PreludeGenerator::new(
+            out,
+            self.input,
+            self.name,
+            &self.writer_var,
+            self.asynchronous,
+        )
+        .prefix_map_stream()
+        .prefix_magic(&self.en.magic)
+        .prefix_assertions()
+        .prefix_endian(&self.en.endian)
+        .prefix_imports()
+        .finish();
 
         self
     }
@@ -131,40 +162,99 @@ fn generate_unit_enum_repr(
     writer_var: &TokenStream,
     repr: &TokenStream,
     variants: &[UnitEnumField],
+    asynchronous: bool,
 ) -> TokenStream {
-    let branches = variants.iter().map(|variant| {
-        let name = &variant.ident;
+    let value = if variants.iter().any(|variant| variant.holds_value) {
+        let discriminants = get_discriminants(variants);
+        let known = variants
+            .iter()
+            .zip(&discriminants)
+            .filter(|(variant, _)| !variant.holds_value)
+            .map(|(_, discriminant)| discriminant)
+            .collect::<Vec<_>>();
+        let branches = variants
+            .iter()
+            .zip(&discriminants)
+            .map(|(variant, discriminant)| {
+                let name = &variant.ident;
+                if variant.holds_value {
+                    // A value which belongs to another variant would be read
+                    // back as that variant
+                    let check = (!known.is_empty()).then(|| {
+                        let stream_position = get_stream_position(writer_var, asynchronous);
+                        let message =
+                            format!("{name}({{:?}}) cannot be written because its value belongs to another variant");
+                        let matches_known = known.iter().map(|known| quote! { *#TEMP == #known });
+                        quote! {
+                            if #(#matches_known)||* {
+                                return ::core::result::Result::Err(#BIN_ERROR::AssertFail {
+                                    pos: #stream_position?,
+                                    message: #FORMAT!(#message, #TEMP),
+                                });
+                            }
+                        }
+                    });
+                    quote! {
+                        Self::#name(#TEMP) => {
+                            #check
+                            *#TEMP
+                        }
+                    }
+                } else {
+                    quote! { Self::#name => #discriminant }
+                }
+            });
+
         quote! {
-            Self::#name => Self::#name
+            {
+                let #TEMP: #repr = match self {
+                    #(#branches),*
+                };
+                #TEMP
+            }
         }
-    });
+    } else {
+        let branches = variants.iter().map(|variant| {
+            let name = &variant.ident;
+            quote! {
+                Self::#name => Self::#name
+            }
+        });
 
-    quote! {
-        #WRITE_METHOD (
-            &(match self {
+        quote! {
+            (match self {
                 #(#branches),*
-            } as #repr),
-            #writer_var,
-            #OPT,
-            (),
-        )?;
-    }
+            } as #repr)
+        }
+    };
+
+    let write = get_write(
+        &quote! { &#value },
+        writer_var,
+        &OPT,
+        &quote! { () },
+        asynchronous,
+    );
+    quote! { #write?; }
 }
 
-fn generate_unit_enum_magic(writer_var: &TokenStream, variants: &[UnitEnumField]) -> TokenStream {
+fn generate_unit_enum_magic(
+    writer_var: &TokenStream,
+    variants: &[UnitEnumField],
+    asynchronous: bool,
+) -> TokenStream {
     let branches = variants.iter().map(|variant| {
         let name = &variant.ident;
         let magic = variant.magic.as_ref().map(|magic| {
             let magic = magic.match_value();
-
-            quote! {
-                #WRITE_METHOD (
-                    &#magic,
-                    #writer_var,
-                    #OPT,
-                    (),
-                )?;
-            }
+            let write = get_write(
+                &quote! { &#magic },
+                writer_var,
+                &OPT,
+                &quote! { () },
+                asynchronous,
+            );
+            quote! { #write?; }
         });
 
         quote! {
@@ -1,8 +1,9 @@
+use super::get_write;
 use crate::{
     binrw::{
         codegen::{
             get_assertions, get_destructured_imports, get_endian,
-            sanitization::{ARGS, MAP_WRITER_TYPE_HINT, OPT, WRITE_METHOD, WRITER},
+            sanitization::{ARGS, MAP_WRITER_TYPE_HINT, OPT, WRITER},
         },
         parser::{CondEndian, Input, Magic},
     },
@@ -17,6 +18,7 @@ pub(crate) struct PreludeGenerator<'a> {
     input: &'a Input,
     name: Option<&'a Ident>,
     writer_var: &'a TokenStream,
+    asynchronous: bool,
 }
 
 impl<'a> PreludeGenerator<'a> {
@@ -25,12 +27,14 @@ impl<'a> PreludeGenerator<'a> {
         input: &'a Input,
         name: Option<&'a Ident>,
         writer_var: &'a TokenStream,
+        asynchronous: bool,
     ) -> Self {
         Self {
             out,
             input,
             name,
             writer_var,
+            asynchronous,
         }
     }
 
@@ -59,16 +63,17 @@ impl<'a> PreludeGenerator<'a> {
 
     pub(crate) fn prefix_magic(mut self, magic: &Magic) -> Self {
         if let Some(magic) = magic {
-            let writer_var = &self.writer_var;
             let magic = magic.match_value();
+            let write = get_write(
+                &quote! { &#magic },
+                self.writer_var,
+                &OPT,
+                &quote! { () },
+                self.asynchronous,
+            );
             let out = self.out;
             self.out = quote! {
-                #WRITE_METHOD (
-                    &#magic,
-                    #writer_var,
-                    #OPT,
-                    ()
-                )?;
+                #write?;
 
                 #out
             };
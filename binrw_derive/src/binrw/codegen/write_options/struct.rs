@@ -1,19 +1,37 @@
 use super::{prelude::PreludeGenerator, struct_field::write_field};
 use crate::binrw::{
-    codegen::sanitization::{THIS, WRITER},
-    parser::{Input, Struct},
+    codegen::{
+        get_checksum_bytes, get_checksum_setup, get_checksum_stream, get_endian,
+        sanitization::{
+            CHECKSUM_RECORDER, CHECKSUM_RECORDER_VAR, CHECKSUM_START, CHECKSUM_TRAIT, SEEK_TRAIT,
+            THIS, WRITE_CHECKSUM, WRITE_CHECKSUM_BYTES, WRITER, make_ident,
+        },
+    },
+    parser::{BitSlot, Checksum, ChecksumRange, Input, Struct, StructField},
 };
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::Ident;
-
-pub(super) fn generate_struct(input: &Input, name: Option<&Ident>, st: &Struct) -> TokenStream {
-    StructGenerator::new(input, st, name, &input.stream_ident_or(WRITER))
-        .write_fields()
-        .prefix_prelude()
-        .prefix_borrow_fields()
-        .prefix_imports()
-        .finish()
+use quote::{quote, quote_spanned};
+use syn::{Ident, spanned::Spanned};
+
+pub(super) fn generate_struct(
+    input: &Input,
+    name: Option<&Ident>,
+    st: &Struct,
+    asynchronous: bool,
+) -> TokenStream {
+    StructGenerator::new(
+        input,
+        st,
+        name,
+        &input.stream_ident_or(WRITER),
+        asynchronous,
+    )
+    .write_fields()
+    .prefix_prelude()
+    .prefix_checksum_recorder()
+    .prefix_borrow_fields()
+    .prefix_imports()
+    .finish()
 }
 
 pub(super) struct StructGenerator<'input> {
@@ -21,6 +39,7 @@ pub(super) struct StructGenerator<'input> {
     st: &'input Struct,
     name: Option<&'input Ident>,
     writer_var: &'input TokenStream,
+    asynchronous: bool,
     out: TokenStream,
 }
 
@@ -30,49 +49,169 @@ impl<'input> StructGenerator<'input> {
         st: &'input Struct,
         name: Option<&'input Ident>,
         writer_var: &'input TokenStream,
+        asynchronous: bool,
     ) -> Self {
         Self {
             input,
             st,
             name,
             writer_var,
+            asynchronous,
             out: TokenStream::new(),
         }
     }
 
     pub(super) fn prefix_imports(mut self) -> Self {
-        self.out = PreludeGenerator::new(self.out, self.input, self.name, self.writer_var)
-            .prefix_imports()
-            .finish();
+        self.out = PreludeGenerator::new(
+            self.out,
+            self.input,
+            self.name,
+            self.writer_var,
+            self.asynchronous,
+        )
+        .prefix_imports()
+        .finish();
 
         self
     }
 
     pub(super) fn prefix_prelude(mut self) -> Self {
-        self.out = PreludeGenerator::new(self.out, self.input, self.name, self.writer_var)
-            .prefix_map_stream()
-            .prefix_magic(&self.st.magic)
-            .prefix_endian(&self.st.endian)
-            .prefix_assertions()
-            .finish();
+        self.out = PreludeGenerator::new(
+            self.out,
+            self.input,
+            self.name,
+            self.writer_var,
+            self.asynchronous,
+        )
+        .prefix_map_stream()
+        .prefix_magic(&self.st.magic)
+        .prefix_endian(&self.st.endian)
+        .prefix_assertions()
+        .finish();
 
         self
     }
 
     pub(super) fn write_fields(mut self) -> Self {
-        let write_fields = self
-            .st
-            .fields
+        let bit_order = self.st.bit_order.unwrap_or_default();
+        let writer_var = self.writer_var;
+        let asynchronous = self.asynchronous;
+        let fields = &self.st.fields;
+        // Invalid checksums were already rejected by the parser
+        let checksums = ChecksumRange::resolve(fields).unwrap_or_default();
+        let write_fields =
+            fields
+                .iter()
+                .zip(BitSlot::layout(fields))
+                .enumerate()
+                .map(|(index, (field, slot))| {
+                    let setup = get_checksum_setup(writer_var, fields, &checksums, index);
+                    let field_writer_var =
+                        get_checksum_stream(writer_var, fields, &checksums, index);
+                    let value = field.checksum.as_ref().map(|checksum| {
+                        let complete = !checksum.is_bytes()
+                            && checksums.iter().any(|checksum| {
+                                checksum.field == index && checksum.field > checksum.end
+                            });
+                        checksum_value(writer_var, field, complete)
+                    });
+                    let field_code = write_field(
+                        &field_writer_var,
+                        field,
+                        slot.map(|slot| (slot, bit_order)),
+                        asynchronous,
+                    );
+                    let backpatches = checksums
+                        .iter()
+                        .filter(|checksum| {
+                            checksum.last() == index && checksum.field < checksum.start
+                        })
+                        .map(|checksum| {
+                            let field = &fields[checksum.field];
+                            let state = make_ident(&field.ident, "checksum");
+                            let pos_var = make_ident(&field.ident, "checksum_pos");
+                            let endian = get_endian(&field.endian);
+                            quote! {
+                                #WRITE_CHECKSUM(#writer_var, #endian, &#state, #pos_var)?;
+                            }
+                        });
+
+                    let field_end = field
+                        .checksum
+                        .as_ref()
+                        .filter(|checksum| checksum.is_bytes())
+                        .map(|_| {
+                            let end_var = make_ident(&field.ident, "checksum_end");
+                            quote! {
+                                let #end_var = #SEEK_TRAIT::stream_position(#writer_var)?;
+                            }
+                        });
+
+                    quote! {
+                        #setup
+                        #value
+                        #field_code
+                        #field_end
+                        #(#backpatches)*
+                    }
+                });
+
+        // Checksums over byte positions are computed once every field has been
+        // written, in field order, so a range must not include the placeholder
+        // of a later checksum over byte positions
+        let byte_checksums = self.byte_checksums().collect::<Vec<_>>();
+        let byte_backpatches = byte_checksums
             .iter()
-            .map(|field| write_field(self.writer_var, field));
+            .enumerate()
+            .map(|(index, (field, checksum))| {
+                let algorithm = &checksum.algorithm;
+                let endian = get_endian(&field.endian);
+                let range = get_checksum_bytes(checksum, writer_var);
+                let pending = byte_checksums[index..].iter().map(|(field, _)| {
+                    let pos_var = make_ident(&field.ident, "checksum_pos");
+                    let end_var = make_ident(&field.ident, "checksum_end");
+                    quote! { #pos_var..#end_var }
+                });
+                quote_spanned! {algorithm.span()=>
+                    #WRITE_CHECKSUM_BYTES::<#algorithm, _>(#writer_var, #endian, #range, &[#(#pending),*])?;
+                }
+            });
 
         self.out = quote! {
             #(#write_fields)*
+            #(#byte_backpatches)*
         };
 
         self
     }
 
+    /// Records everything written by the struct, including its magic, when
+    /// it has a checksum over byte positions.
+    pub(super) fn prefix_checksum_recorder(mut self) -> Self {
+        if self.byte_checksums().next().is_some() {
+            let writer_var = self.writer_var;
+            let out = self.out;
+            self.out = quote! {
+                let mut #CHECKSUM_RECORDER_VAR = #CHECKSUM_RECORDER::new(#writer_var)?;
+                let #writer_var = &mut #CHECKSUM_RECORDER_VAR;
+                let #CHECKSUM_START = #SEEK_TRAIT::stream_position(#writer_var)?;
+                #out
+            };
+        }
+
+        self
+    }
+
+    fn byte_checksums(&self) -> impl Iterator<Item = (&'input StructField, &'input Checksum)> {
+        self.st.fields.iter().filter_map(|field| {
+            field
+                .checksum
+                .as_ref()
+                .filter(|checksum| checksum.is_bytes())
+                .map(|checksum| (field, checksum))
+        })
+    }
+
     pub(super) fn prefix_borrow_fields(mut self) -> Self {
         let borrow_fields = self.name.map(|name| {
             let pattern = self.st.fields_pattern();
@@ -96,3 +235,25 @@ impl<'input> StructGenerator<'input> {
         self.out
     }
 }
+
+/// Generates the value written for a checksum field. A checksum which comes
+/// after the fields it covers is already complete, but any other checksum gets
+/// a placeholder which is overwritten once the checksum is done.
+fn checksum_value(writer_var: &TokenStream, field: &StructField, complete: bool) -> TokenStream {
+    let ident = &field.ident;
+    let ty = &field.ty;
+    let state = make_ident(ident, "checksum");
+    let algorithm = &field.checksum.as_ref().unwrap().algorithm;
+
+    if complete {
+        quote_spanned! {algorithm.span()=>
+            let #ident: #ty = <#algorithm as #CHECKSUM_TRAIT>::finish(&#state);
+        }
+    } else {
+        let pos_var = make_ident(ident, "checksum_pos");
+        quote_spanned! {algorithm.span()=>
+            let #pos_var = #SEEK_TRAIT::stream_position(#writer_var)?;
+            let #ident: #ty = <<#algorithm as #CHECKSUM_TRAIT>::Output as ::core::default::Default>::default();
+        }
+    }
+}
@@ -1,17 +1,20 @@
+use super::get_write;
 use crate::{
     binrw::{
         codegen::{
-            get_assertions, get_endian, get_map_err, get_passed_args, get_try_calc,
+            get_assertions, get_endian, get_map_err, get_passed_args, get_seek,
+            get_stream_position, get_try_calc, get_window_size,
             sanitization::{
-                BEFORE_POS, BINWRITE_TRAIT, MAP_WRITER_TYPE_HINT, POS, REQUIRED_ARG_TRAIT,
-                SAVED_POSITION, SEEK_FROM, SEEK_TRAIT, WRITE_ARGS_TYPE_HINT,
-                WRITE_FN_MAP_OUTPUT_TYPE_HINT, WRITE_FN_TRY_MAP_OUTPUT_TYPE_HINT,
-                WRITE_FN_TYPE_HINT, WRITE_FUNCTION, WRITE_MAP_ARGS_TYPE_HINT,
-                WRITE_MAP_INPUT_TYPE_HINT, WRITE_METHOD, WRITE_TRY_MAP_ARGS_TYPE_HINT,
-                WRITE_ZEROES, make_ident,
+                BEFORE_POS, BINWRITE_ASYNC_TRAIT, BINWRITE_TRAIT, CHECK_VERSION,
+                CHECK_VERSION_ASYNC, INSERT_BITS, MAP_WRITER_TYPE_HINT, OPT, POS,
+                REQUIRED_ARG_TRAIT, SAVED_POSITION, SEEK_FROM, SEEK_TRAIT, SPAN_ENTER_FIELD,
+                SPAN_EXIT, WINDOW, WRITE_ARGS_TYPE_HINT, WRITE_BITS, WRITE_FN_MAP_OUTPUT_TYPE_HINT,
+                WRITE_FN_TRY_MAP_OUTPUT_TYPE_HINT, WRITE_FN_TYPE_HINT, WRITE_FUNCTION,
+                WRITE_MAP_ARGS_TYPE_HINT, WRITE_MAP_INPUT_TYPE_HINT, WRITE_METHOD,
+                WRITE_TRY_MAP_ARGS_TYPE_HINT, WRITE_ZEROES, WRITE_ZEROES_ASYNC, make_ident,
             },
         },
-        parser::{FieldMode, Map, StructField},
+        parser::{BitOrder, BitSlot, FieldMode, Map, StructField},
     },
     util::quote_spanned_any,
 };
@@ -21,10 +24,17 @@ use proc_macro2::TokenStream;
 use quote::{ToTokens, quote, quote_spanned};
 use syn::{Ident, spanned::Spanned};
 
-pub(crate) fn write_field(writer_var: &TokenStream, field: &StructField) -> TokenStream {
-    StructFieldGenerator::new(field, writer_var)
+pub(crate) fn write_field(
+    writer_var: &TokenStream,
+    field: &StructField,
+    bits: Option<(BitSlot, BitOrder)>,
+    asynchronous: bool,
+) -> TokenStream {
+    StructFieldGenerator::new(field, writer_var, bits, asynchronous)
         .write_field()
         .wrap_map_stream()
+        .wrap_size()
+        .wrap_span()
         .prefix_map_value()
         .prefix_calc_value()
         .wrap_padding()
@@ -34,26 +44,37 @@ pub(crate) fn write_field(writer_var: &TokenStream, field: &StructField) -> Toke
         .prefix_args()
         .prefix_write_function()
         .prefix_map_function()
+        .prefix_version_check()
+        .wrap_bit_group()
         .finish()
 }
 
 struct StructFieldGenerator<'input> {
     field: &'input StructField,
+    bits: Option<(BitSlot, BitOrder)>,
     outer_writer_var: &'input TokenStream,
     writer_var: Cow<'input, TokenStream>,
+    asynchronous: bool,
     out: TokenStream,
 }
 
 impl<'a> StructFieldGenerator<'a> {
-    fn new(field: &'a StructField, outer_writer_var: &'a TokenStream) -> Self {
+    fn new(
+        field: &'a StructField,
+        outer_writer_var: &'a TokenStream,
+        bits: Option<(BitSlot, BitOrder)>,
+        asynchronous: bool,
+    ) -> Self {
         Self {
             field,
+            bits,
             outer_writer_var,
-            writer_var: if field.map_stream.is_some() {
+            writer_var: if field.map_stream.is_some() || field.size.is_some() {
                 Cow::Owned(make_ident(&field.ident, "reader").into_token_stream())
             } else {
                 Cow::Borrowed(outer_writer_var)
             },
+            asynchronous,
             out: TokenStream::new(),
         }
     }
@@ -74,9 +95,14 @@ impl<'a> StructFieldGenerator<'a> {
         if let Some(map_stream) = &self.field.map_stream {
             let rest = self.out;
             let writer_var = &self.writer_var;
-            let outer_writer_var = self.outer_writer_var;
+            let (outer_writer_ty, outer_writer_var) = if self.field.size.is_some() {
+                let window_var = make_ident(&self.field.ident, "window");
+                (quote! { #WINDOW<&mut W> }, quote! { &mut #window_var })
+            } else {
+                (quote! { W }, self.outer_writer_var.clone())
+            };
             self.out = quote_spanned_any! { map_stream.span()=> {
-                let #writer_var = &mut #MAP_WRITER_TYPE_HINT::<W, _, _>(#map_stream)(#outer_writer_var);
+                let #writer_var = &mut #MAP_WRITER_TYPE_HINT::<#outer_writer_ty, _, _>(#map_stream)(#outer_writer_var);
                 #rest
             }};
         }
@@ -84,8 +110,55 @@ impl<'a> StructFieldGenerator<'a> {
         self
     }
 
+    fn wrap_size(mut self) -> Self {
+        if let Some(size) = &self.field.size
+            && self.field.is_written()
+        {
+            let window_var = make_ident(&self.field.ident, "window");
+            let outer_writer_var = self.outer_writer_var;
+            // If there is a `map_stream`, it already maps the window into the
+            // writer for the field
+            let writer_var = self.field.map_stream.is_none().then(|| {
+                let writer_var = &self.writer_var;
+                quote! { let #writer_var = &mut #window_var; }
+            });
+            let size = get_window_size(size.as_ref(), outer_writer_var);
+            let rest = self.out;
+            self.out = quote! {{
+                let mut #window_var = #WINDOW::new(&mut *#outer_writer_var, #size)?;
+                {
+                    #writer_var
+                    #rest
+                }
+                #WINDOW::pad_remaining(#window_var)?;
+            }};
+        }
+
+        self
+    }
+
+    fn wrap_span(mut self) -> Self {
+        // Spans are recorded using the synchronous stream traits
+        if self.field.is_written() && self.bits.is_none() && !self.asynchronous {
+            let depth_var = make_ident(&self.field.ident, "span");
+            let outer_writer_var = self.outer_writer_var;
+            let name = self.field.name();
+            let ty = &self.field.ty;
+            let rest = self.out;
+            self.out = quote! {{
+                let #depth_var = #SPAN_ENTER_FIELD(#outer_writer_var, #name, ::core::any::type_name::<#ty>());
+                #rest
+                #SPAN_EXIT(#outer_writer_var, #depth_var);
+            }};
+        }
+
+        self
+    }
+
     fn prefix_write_function(mut self) -> Self {
-        if !self.field.is_written() {
+        // Async fields are written by calling the trait directly, since a
+        // write function cannot be generic over the future it returns
+        if !self.field.is_written() || self.bits.is_some() || self.asynchronous {
             return self;
         }
 
@@ -172,10 +245,10 @@ impl<'a> StructFieldGenerator<'a> {
             Map::Try(t) | Map::Repr(t) => {
                 let rest = self.out;
                 let map_err = get_map_err(SAVED_POSITION, t.span());
-                let outer_writer_var = self.outer_writer_var;
+                let stream_position = get_stream_position(self.outer_writer_var, self.asynchronous);
                 quote! {
                     let #name = {
-                        let #SAVED_POSITION = #SEEK_TRAIT::stream_position(#outer_writer_var)?;
+                        let #SAVED_POSITION = #stream_position?;
                         #map_func(#name)#map_err?
                     };
                     #rest
@@ -214,13 +287,45 @@ impl<'a> StructFieldGenerator<'a> {
             })
             .unwrap_or_else(|| quote_spanned! { name.span()=> &#name });
 
-        self.out = quote! {
-            #WRITE_FUNCTION(
-                #name,
-                #writer_var,
-                #endian,
-                #args
-            )?;
+        if let Some((slot, bit_order)) = &self.bits {
+            let ty = &self.field.ty;
+            let bits_var = make_ident(&slot.group, "bits");
+            let pos_var = make_ident(&slot.group, "bits_pos");
+            let shift = slot.shift(*bit_order);
+            let width = slot.width;
+            let offset = slot.offset;
+            self.out = quote! {
+                #bits_var |= #INSERT_BITS::<#ty>(#name, #pos_var, #shift, #width, #offset)?;
+            };
+
+            return self;
+        }
+
+        self.out = if self.asynchronous {
+            // The value type is only known ahead of time when there is no map
+            let ty = &self.field.ty;
+            let trait_path = if self.field.map.is_none() {
+                quote! { <#ty as #BINWRITE_ASYNC_TRAIT> }
+            } else {
+                quote! { #BINWRITE_ASYNC_TRAIT }
+            };
+            quote! {
+                #trait_path::write_options_async(
+                    #name,
+                    #writer_var,
+                    #endian,
+                    #args
+                ).await?;
+            }
+        } else {
+            quote! {
+                #WRITE_FUNCTION(
+                    #name,
+                    #writer_var,
+                    #endian,
+                    #args
+                )?;
+            }
         };
 
         self
@@ -245,8 +350,8 @@ impl<'a> StructFieldGenerator<'a> {
     fn wrap_padding(mut self) -> Self {
         let out = self.out;
 
-        let pad_before = pad_before(self.outer_writer_var, self.field);
-        let pad_after = pad_after(self.outer_writer_var, self.field);
+        let pad_before = pad_before(self.outer_writer_var, self.field, self.asynchronous);
+        let pad_after = pad_after(self.outer_writer_var, self.field, self.asynchronous);
         self.out = quote! {
             #pad_before
             #out
@@ -257,13 +362,15 @@ impl<'a> StructFieldGenerator<'a> {
     }
 
     fn prefix_args(mut self) -> Self {
-        if !self.field.is_written() {
+        if !self.field.is_written() || self.bits.is_some() {
             return self;
         }
 
         let args = args_ident(&self.field.ident);
 
-        let args_val = if let Some(args) = get_passed_args(self.field, self.outer_writer_var) {
+        let args_val = if let Some(args) =
+            get_passed_args(self.field, self.outer_writer_var, self.asynchronous)
+        {
             args
         } else {
             quote_spanned! { self.field.ty.span() => <_ as #REQUIRED_ARG_TRAIT>::args() }
@@ -304,17 +411,80 @@ impl<'a> StructFieldGenerator<'a> {
         if let Some(magic) = &self.field.magic {
             let magic = magic.match_value();
             let endian = get_endian(&self.field.endian);
+            let write = get_write(
+                &quote! { &#magic },
+                self.outer_writer_var,
+                &endian,
+                &quote! { () },
+                self.asynchronous,
+            );
+            let out = self.out;
+            self.out = quote! {
+                #write?;
+
+                #out
+            };
+        }
+
+        self
+    }
+
+    fn prefix_version_check(mut self) -> Self {
+        // Only optional fields can be checked, since there is no other way to
+        // tell whether a field is meant to be written
+        if let Some(version_cond) = &self.field.version_cond
+            && self.field.is_option()
+            && !self.field.generated_value()
+            && self.field.map.is_none()
+        {
+            let name = &self.field.ident;
             let writer_var = self.outer_writer_var;
+            let check = if self.asynchronous {
+                quote! { #CHECK_VERSION_ASYNC }
+            } else {
+                quote! { #CHECK_VERSION }
+            };
+            let wait = self.asynchronous.then(|| quote! { .await });
             let out = self.out;
             self.out = quote! {
-                #WRITE_METHOD (
-                    &#magic,
+                #check(
                     #writer_var,
-                    #endian,
-                    ()
-                )?;
+                    ::core::option::Option::is_some(#name),
+                    #version_cond,
+                    ::core::stringify!(#name),
+                )#wait?;
+                #out
+            };
+        }
+
+        self
+    }
+
+    fn wrap_bit_group(mut self) -> Self {
+        if let Some((slot, _)) = &self.bits {
+            let bits_var = make_ident(&slot.group, "bits");
+            let pos_var = make_ident(&slot.group, "bits_pos");
+            let writer_var = self.outer_writer_var;
+
+            let init = slot.first.then(|| {
+                quote! {
+                    let mut #bits_var = 0u128;
+                    let #pos_var = #SEEK_TRAIT::stream_position(#writer_var)?;
+                }
+            });
+
+            let write = slot.last.then(|| {
+                let bytes = slot.group_bytes() as usize;
+                quote! {
+                    #WRITE_BITS(#writer_var, #OPT, #bits_var, #bytes)?;
+                }
+            });
 
+            let out = self.out;
+            self.out = quote! {
+                #init
                 #out
+                #write
             };
         }
 
@@ -342,36 +512,43 @@ fn map_func_ident(ident: &Ident) -> Ident {
     make_ident(ident, "map_func")
 }
 
-fn pad_after(writer_var: &TokenStream, field: &StructField) -> TokenStream {
+fn pad_after(writer_var: &TokenStream, field: &StructField, asynchronous: bool) -> TokenStream {
+    let stream_position = get_stream_position(writer_var, asynchronous);
+    let (write_zeroes, wait) = get_write_zeroes(asynchronous);
     let pad_size_to = field.pad_size_to.as_ref().map(|size| {
         quote! {{
             let pad_to_size = (#size) as ::core::primitive::u64;
-            let after_pos = #SEEK_TRAIT::stream_position(#writer_var)?;
+            let after_pos = #stream_position?;
             if let ::core::option::Option::Some(size) = after_pos.checked_sub(#BEFORE_POS) {
                 if let ::core::option::Option::Some(padding) = pad_to_size.checked_sub(size) {
-                    #WRITE_ZEROES(#writer_var, padding)?;
+                    #write_zeroes(#writer_var, padding)#wait?;
                 }
             }
         }}
     });
     let pad_after = field.pad_after.as_ref().map(|padding| {
         quote! {
-            #WRITE_ZEROES(#writer_var, (#padding) as ::core::primitive::u64)?;
+            #write_zeroes(#writer_var, (#padding) as ::core::primitive::u64)#wait?;
         }
     });
     let align_after = field.align_after.as_ref().map(|alignment| {
         quote! {{
-            let pos = #SEEK_TRAIT::stream_position(#writer_var)?;
+            let pos = #stream_position?;
             let align = ((#alignment) as ::core::primitive::u64);
             let rem = pos % align;
             if rem != 0 {
-                #WRITE_ZEROES(#writer_var, align - rem)?;
+                #write_zeroes(#writer_var, align - rem)#wait?;
             }
         }}
     });
     let restore_position = field.restore_position.map(|()| {
+        let seek = get_seek(
+            writer_var,
+            &quote! { #SEEK_FROM::Start(#SAVED_POSITION) },
+            asynchronous,
+        );
         quote! {
-            #SEEK_TRAIT::seek(#writer_var, #SEEK_FROM::Start(#SAVED_POSITION))?;
+            #seek?;
         }
     });
 
@@ -383,38 +560,38 @@ fn pad_after(writer_var: &TokenStream, field: &StructField) -> TokenStream {
     }
 }
 
-fn pad_before(writer_var: &TokenStream, field: &StructField) -> TokenStream {
+fn pad_before(writer_var: &TokenStream, field: &StructField, asynchronous: bool) -> TokenStream {
+    let stream_position = get_stream_position(writer_var, asynchronous);
+    let (write_zeroes, wait) = get_write_zeroes(asynchronous);
     let seek_before = field.seek_before.as_ref().map(|seek| {
+        let seek = get_seek(writer_var, seek, asynchronous);
         quote! {
-            #SEEK_TRAIT::seek(
-                #writer_var,
-                #seek,
-            )?;
+            #seek?;
         }
     });
     let pad_before = field.pad_before.as_ref().map(|padding| {
         quote! {
-            #WRITE_ZEROES(#writer_var, (#padding) as ::core::primitive::u64)?;
+            #write_zeroes(#writer_var, (#padding) as ::core::primitive::u64)#wait?;
         }
     });
     let align_before = field.align_before.as_ref().map(|alignment| {
         quote! {{
-            let pos = #SEEK_TRAIT::stream_position(#writer_var)?;
+            let pos = #stream_position?;
             let align = ((#alignment) as ::core::primitive::u64);
             let rem = pos % align;
             if rem != 0 {
-                #WRITE_ZEROES(#writer_var, align - rem)?;
+                #write_zeroes(#writer_var, align - rem)#wait?;
             }
         }}
     });
     let pad_size_to_before = field.pad_size_to.as_ref().map(|_| {
         quote! {
-            let #BEFORE_POS = #SEEK_TRAIT::stream_position(#writer_var)?;
+            let #BEFORE_POS = #stream_position?;
         }
     });
     let store_position = field.restore_position.map(|()| {
         quote! {
-            let #SAVED_POSITION = #SEEK_TRAIT::stream_position(#writer_var)?;
+            let #SAVED_POSITION = #stream_position?;
         }
     });
 
@@ -426,3 +603,11 @@ fn pad_before(writer_var: &TokenStream, field: &StructField) -> TokenStream {
         #pad_size_to_before
     }
 }
+
+fn get_write_zeroes(asynchronous: bool) -> (TokenStream, Option<TokenStream>) {
+    if asynchronous {
+        (quote! { #WRITE_ZEROES_ASYNC }, Some(quote! { .await }))
+    } else {
+        (quote! { #WRITE_ZEROES }, None)
+    }
+}
@@ -0,0 +1,61 @@
+use super::{prelude::PreludeGenerator, struct_field::write_field};
+use crate::binrw::{
+    codegen::sanitization::{BIN_ERROR, POS, THIS, WRITER},
+    parser::{Input, Struct},
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+pub(super) fn generate_union(
+    input: &Input,
+    name: Option<&Ident>,
+    un: &Struct,
+    asynchronous: bool,
+) -> TokenStream {
+    let writer_var = input.stream_ident_or(WRITER);
+
+    let write_fields = un.fields.iter().map(|field| {
+        let ident = &field.ident;
+        let select = field
+            .select
+            .as_ref()
+            .map_or_else(|| quote! { true }, |select| select.condition.clone());
+        let write_field = write_field(&writer_var, field, None, asynchronous);
+
+        // The parser requires every `select` to be an `unsafe` block when
+        // writing, which asserts that it only chooses the active field
+        quote! {
+            if #select {
+                let #ident = unsafe { &#THIS.#ident };
+                #write_field
+            } else
+        }
+    });
+
+    let out = quote! {
+        #(#write_fields)* {
+            return ::core::result::Result::Err(#BIN_ERROR::NoVariantMatch { pos: #POS });
+        }
+    };
+
+    let out = PreludeGenerator::new(out, input, name, &writer_var, asynchronous)
+        .prefix_map_stream()
+        .prefix_magic(&un.magic)
+        .prefix_endian(&un.endian)
+        .prefix_assertions()
+        .finish();
+
+    PreludeGenerator::new(
+        quote! {
+            let #THIS = self;
+            #out
+        },
+        input,
+        name,
+        &writer_var,
+        asynchronous,
+    )
+    .prefix_imports()
+    .finish()
+}
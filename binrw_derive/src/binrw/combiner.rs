@@ -10,19 +10,59 @@ use quote::quote;
 use std::collections::HashSet;
 use syn::{DeriveInput, spanned::Spanned};
 
-pub(crate) fn derive(mut derive_input: DeriveInput) -> proc_macro2::TokenStream {
+pub(crate) fn derive(
+    mut derive_input: DeriveInput,
+    asynchronous: bool,
+) -> proc_macro2::TokenStream {
+    let (binread_input, binwrite_input) = parse(&derive_input, false);
+
+    let generated_read_impl = generate_impl::<false, false>(&derive_input, &binread_input);
+    let generated_write_impl = generate_impl::<true, false>(&derive_input, &binwrite_input);
+
+    // Errors are only reported once, by the synchronous impls
+    let generated_async_impls = (asynchronous
+        && matches!(
+            (&binread_input, &binwrite_input),
+            (ParseResult::Ok(_), ParseResult::Ok(_))
+        ))
+    .then(|| {
+        let (binread_input, binwrite_input) = parse(&derive_input, true);
+        let read_impl = generate_impl::<false, true>(&derive_input, &binread_input);
+        let write_impl = generate_impl::<true, true>(&derive_input, &binwrite_input);
+        quote!(#read_impl #write_impl)
+    });
+
+    // Since temporary fields must be synchronised between binread and binwrite,
+    // the same cleaning mechanism can be used as-if there was only one input
+    super::clean_attr(&mut derive_input, binread_input.ok().as_ref());
+
+    quote!(
+        #derive_input
+        #generated_read_impl
+        #generated_write_impl
+        #generated_async_impls
+    )
+}
+
+/// Parses the read and write inputs and synchronises their temporary fields.
+fn parse(
+    derive_input: &DeriveInput,
+    asynchronous: bool,
+) -> (ParseResult<Input>, ParseResult<Input>) {
     let mut binread_input = Input::from_input(
-        &derive_input,
+        derive_input,
         Options {
             derive: false,
             write: false,
+            asynchronous,
         },
     );
     let mut binwrite_input = Input::from_input(
-        &derive_input,
+        derive_input,
         Options {
             derive: false,
             write: true,
+            asynchronous,
         },
     );
 
@@ -31,18 +71,7 @@ pub(crate) fn derive(mut derive_input: DeriveInput) -> proc_macro2::TokenStream
         binwrite_input = ParseResult::Partial(binwrite_input.unwrap_tuple().0, error);
     }
 
-    let generated_read_impl = generate_impl::<false>(&derive_input, &binread_input);
-    let generated_write_impl = generate_impl::<true>(&derive_input, &binwrite_input);
-
-    // Since temporary fields must be synchronised between binread and binwrite,
-    // the same cleaning mechanism can be used as-if there was only one input
-    super::clean_attr(&mut derive_input, binread_input.ok().as_ref());
-
-    quote!(
-        #derive_input
-        #generated_read_impl
-        #generated_write_impl
-    )
+    (binread_input, binwrite_input)
 }
 
 /// Check the fields of each input and copy temp state to the other input.
@@ -66,7 +95,8 @@ fn apply_temp_crossover(
         }
         // These don't have temp fields.
         (Input::UnitStruct(_), Input::UnitStruct(_))
-        | (Input::UnitOnlyEnum(_), Input::UnitOnlyEnum(_)) => None,
+        | (Input::UnitOnlyEnum(_), Input::UnitOnlyEnum(_))
+        | (Input::Union(_), Input::Union(_)) => None,
         _ => unreachable!("read and write input should always be the same kind"),
     }
 }
@@ -127,7 +157,10 @@ fn validate_fields_temporary(
 ) -> Option<syn::Error> {
     let mut all_errors = None::<syn::Error>;
     for field in fields {
-        if read_temporary.contains(&field.ident) && !field.generated_value() {
+        if read_temporary.contains(&field.ident)
+            && !field.generated_value()
+            && field.checksum.is_none()
+        {
             combine_error(
                 &mut all_errors,
                 syn::Error::new(
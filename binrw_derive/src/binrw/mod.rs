@@ -18,6 +18,8 @@ pub(super) struct Options {
     pub(super) derive: bool,
     /// If `true`, the input is for `BinWrite`.
     pub(super) write: bool,
+    /// If `true`, the input is for `BinReadAsync` or `BinWriteAsync`.
+    pub(super) asynchronous: bool,
 }
 
 #[cfg_attr(coverage_nightly, coverage(off))]
@@ -78,13 +80,15 @@ pub(super) fn derive_from_attribute(
     input: TokenStream,
     write: bool,
 ) -> TokenStream {
-    if attr.to_string() == "ignore" {
+    let attr = attr.to_string();
+    if attr == "ignore" {
         return input;
     }
 
     let mut derive_input = parse_macro_input!(input as DeriveInput);
 
     let mut mixed_rw = false;
+    let mut asynchronous = attr == "async";
     let opposite_attr = if write { "binread" } else { "binwrite" };
     for attr in &mut derive_input.attrs {
         if let Some(seg) = attr.path().segments.last() {
@@ -94,6 +98,12 @@ pub(super) fn derive_from_attribute(
                     mixed_rw = true;
                 }
 
+                if let syn::Meta::List(list) = &attr.meta
+                    && list.tokens.to_string() == "async"
+                {
+                    asynchronous = true;
+                }
+
                 attr.meta = syn::MetaList {
                     path: attr.path().clone(),
                     delimiter: syn::MacroDelimiter::Paren(syn::token::Paren::default()),
@@ -105,15 +115,37 @@ pub(super) fn derive_from_attribute(
     }
 
     if mixed_rw {
-        combiner::derive(derive_input)
+        combiner::derive(derive_input, asynchronous)
     } else {
-        derive_from_input(
-            derive_input,
-            Options {
-                derive: false,
-                write,
-            },
-        )
+        let options = Options {
+            derive: false,
+            write,
+            asynchronous: false,
+        };
+
+        // The async impl is generated here because only the attribute can
+        // remove temporary fields. Errors are only reported once, by the
+        // synchronous impl.
+        let async_impl = (asynchronous
+            && matches!(
+                Input::from_input(&derive_input, options),
+                ParseResult::Ok(_)
+            ))
+        .then(|| {
+            parse(
+                &derive_input,
+                Options {
+                    asynchronous: true,
+                    ..options
+                },
+            )
+            .1
+        });
+        let generated = derive_from_input(derive_input, options);
+        quote! {
+            #generated
+            #async_impl
+        }
     }
     .into()
 }
@@ -168,10 +200,11 @@ fn parse(
     options: Options,
 ) -> (ParseResult<Input>, proc_macro2::TokenStream) {
     let binrw_input = Input::from_input(derive_input, options);
-    let generated_impl = if options.write {
-        generate_impl::<true>(derive_input, &binrw_input)
-    } else {
-        generate_impl::<false>(derive_input, &binrw_input)
+    let generated_impl = match (options.write, options.asynchronous) {
+        (false, false) => generate_impl::<false, false>(derive_input, &binrw_input),
+        (true, false) => generate_impl::<true, false>(derive_input, &binrw_input),
+        (false, true) => generate_impl::<false, true>(derive_input, &binrw_input),
+        (true, true) => generate_impl::<true, true>(derive_input, &binrw_input),
     };
     (binrw_input, generated_impl)
 }
@@ -203,6 +236,7 @@ fn derive_binread_code_coverage_for_tool() {
                         Options {
                             derive: true,
                             write: false,
+                            asynchronous: false,
                         },
                     )
                     .1
@@ -246,6 +280,7 @@ fn derive_binwrite_code_coverage_for_tool() {
                         Options {
                             derive: true,
                             write: true,
+                            asynchronous: false,
                         },
                     )
                     .1
@@ -1,10 +1,20 @@
 use super::{
     FromAttrs, FromField, FromInput, ParseResult, SpannedValue, Struct, TrySet, attr_struct,
     top_level_attrs::StructAttr,
-    types::{Assert, CondEndian, Condition, ErrContext, FieldMode, Magic, Map, PassedArgs},
+    types::{
+        Assert, Bits, Checksum, CondEndian, Condition, ErrContext, FieldMode, Magic, Map,
+        PassedArgs, Select,
+    },
+};
+use crate::{
+    binrw::{
+        Options,
+        codegen::sanitization::{COUNT_OF, PARSE_PREFIXED, WRITE_PREFIXED},
+    },
+    combine_error,
 };
-use crate::{binrw::Options, combine_error};
 use proc_macro2::TokenStream;
+use quote::{ToTokens, quote, quote_spanned};
 use syn::spanned::Spanned;
 
 attr_struct! {
@@ -27,12 +37,28 @@ attr_struct! {
         pub(crate) args: PassedArgs,
         #[from(RW:Calc, RW:TryCalc, RO:Default, RW:Ignore, RO:ParseWith, WO:WriteWith)]
         pub(crate) field_mode: FieldMode,
+        #[from(RW:LenPrefix)]
+        pub(crate) len_prefix: Option<SpannedValue<TokenStream>>,
+        #[from(RW:Bits)]
+        pub(crate) bits: Bits,
+        #[from(RW:Checksum)]
+        pub(crate) checksum: Option<Checksum>,
         #[from(RO:Count)]
         pub(crate) count: Option<TokenStream>,
+        #[from(RW:CountOf)]
+        pub(crate) count_of: Option<syn::Ident>,
         #[from(RO:Offset)]
         pub(crate) offset: Option<TokenStream>,
         #[from(RW:If)]
         pub(crate) if_cond: Option<Condition>,
+        #[from(RW:Select)]
+        pub(crate) select: Option<Select>,
+        #[from(RW:Since)]
+        pub(crate) since: Option<SpannedValue<TokenStream>>,
+        #[from(RW:Until)]
+        pub(crate) until: Option<SpannedValue<TokenStream>>,
+        pub(crate) version_cond: Option<TokenStream>,
+        pub(crate) if_source: Option<TokenStream>,
         #[from(RW:RestorePosition)]
         pub(crate) restore_position: Option<()>,
         #[from(RO:Try)]
@@ -55,12 +81,25 @@ attr_struct! {
         pub(crate) seek_before: Option<TokenStream>,
         #[from(RW:PadSizeTo)]
         pub(crate) pad_size_to: Option<TokenStream>,
+        #[from(RW:Size)]
+        pub(crate) size: Option<SpannedValue<TokenStream>>,
         #[from(RO:Debug)] // TODO is this really RO?
         pub(crate) debug: Option<()>,
     }
 }
 
 impl StructField {
+    /// Returns the name of the field as written in the source, which is its
+    /// index for tuple fields.
+    pub(crate) fn name(&self) -> String {
+        let name = self.ident.to_string();
+        if self.generated_ident {
+            name.trim_start_matches("self_").to_owned()
+        } else {
+            name
+        }
+    }
+
     /// Returns true if this field is generated using a calculated value instead
     /// of a parser.
     pub(crate) fn generated_value(&self) -> bool {
@@ -115,9 +154,15 @@ impl StructField {
             && matches!(self.args, PassedArgs::None)
             && matches!(self.field_mode, FieldMode::Normal)
             && all_fields_none!(
+                bits,
+                checksum,
                 count,
+                count_of,
                 offset,
                 if_cond,
+                select,
+                since,
+                until,
                 restore_position,
                 do_try,
                 temp,
@@ -127,10 +172,74 @@ impl StructField {
                 align_after,
                 seek_before,
                 pad_size_to,
+                size,
                 magic
             )
     }
 
+    /// Returns true if the type of the field is written as an `Option`.
+    pub(crate) fn is_option(&self) -> bool {
+        matches!(&self.ty, syn::Type::Path(ty) if ty.qself.is_none()
+            && ty.path.segments.last().is_some_and(|segment| segment.ident == "Option"))
+    }
+
+    /// Converts `since` and `until` directives into a condition which checks
+    /// the version of the parent object.
+    pub(crate) fn apply_version(&mut self, version: Option<&TokenStream>) -> syn::Result<()> {
+        let bounds = [
+            self.since.as_ref().map(|since| (since, quote! { ge })),
+            self.until.as_ref().map(|until| (until, quote! { lt })),
+        ];
+
+        let Some(span) = bounds
+            .iter()
+            .flatten()
+            .map(|(bound, _)| bound.span())
+            .next()
+        else {
+            return Ok(());
+        };
+
+        let Some(version) = version else {
+            return Err(syn::Error::new(
+                span,
+                "`since` and `until` require a `version` directive on the parent",
+            ));
+        };
+
+        // Using method call syntax auto-references the version, so the same
+        // expression works with owned values when reading and borrowed values
+        // when writing
+        let checks = bounds.into_iter().flatten().map(|(bound, op)| {
+            let bound = bound.as_ref();
+            quote! { (#version).#op(&(#bound)) }
+        });
+        let version_cond = quote! {{
+            use ::core::cmp::PartialOrd as _;
+            #(#checks)&&*
+        }};
+
+        // The original condition is kept so the schema can describe the
+        // directives as they were written
+        self.if_source = self.if_cond.as_ref().map(|cond| cond.condition.clone());
+        self.if_cond = Some(match self.if_cond.take() {
+            Some(Condition {
+                condition,
+                alternate,
+            }) => Condition {
+                condition: quote! { #version_cond && (#condition) },
+                alternate,
+            },
+            None => Condition {
+                condition: version_cond.clone(),
+                alternate: None,
+            },
+        });
+        self.version_cond = Some(version_cond);
+
+        Ok(())
+    }
+
     /// Forces the field to be treated as a temporary variable even if it was
     /// not explicitly specified by a directive.
     ///
@@ -141,6 +250,249 @@ impl StructField {
         self.temp = Some(());
     }
 
+    fn validate_bits(&self, options: Options, all_errors: &mut Option<syn::Error>) {
+        if let Some(bits) = &self.bits {
+            for (used, name) in [
+                (self.needs_endian(), "endianness"),
+                (self.map.is_some(), "map"),
+                (self.map_stream.is_some(), "map_stream"),
+                (self.magic.is_some(), "magic"),
+                (self.args.is_some(), "args"),
+                (
+                    matches!(self.field_mode, FieldMode::Function(_)),
+                    if options.write {
+                        "write_with"
+                    } else {
+                        "parse_with"
+                    },
+                ),
+                (self.len_prefix.is_some(), "len_prefix"),
+                (self.count.is_some(), "count"),
+                (self.offset.is_some(), "offset"),
+                (self.restore_position.is_some(), "restore_position"),
+                (self.pad_before.is_some(), "pad_before"),
+                (self.pad_after.is_some(), "pad_after"),
+                (self.align_before.is_some(), "align_before"),
+                (self.align_after.is_some(), "align_after"),
+                (self.seek_before.is_some(), "seek_before"),
+                (self.pad_size_to.is_some(), "pad_size_to"),
+                (self.size.is_some(), "size"),
+            ] {
+                if used {
+                    combine_error(
+                        all_errors,
+                        syn::Error::new(
+                            bits.span(),
+                            format!("`bits` is incompatible with `{name}`"),
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_checksum(&self, options: Options, all_errors: &mut Option<syn::Error>) {
+        if let Some(checksum) = &self.checksum {
+            for (used, name) in [
+                (self.map.is_some(), "map"),
+                (self.map_stream.is_some(), "map_stream"),
+                (self.magic.is_some(), "magic"),
+                (self.args.is_some(), "args"),
+                (
+                    matches!(self.field_mode, FieldMode::Calc(_) | FieldMode::TryCalc(_)),
+                    "calc",
+                ),
+                (
+                    matches!(self.field_mode, FieldMode::Default),
+                    if options.write { "ignore" } else { "default" },
+                ),
+                (
+                    matches!(self.field_mode, FieldMode::Function(_)),
+                    if options.write {
+                        "write_with"
+                    } else {
+                        "parse_with"
+                    },
+                ),
+                (self.bits.is_some(), "bits"),
+                (self.len_prefix.is_some(), "len_prefix"),
+                (self.count.is_some(), "count"),
+                (self.offset.is_some(), "offset"),
+                (self.if_cond.is_some(), "if"),
+                (self.do_try.is_some(), "try"),
+                (self.pad_before.is_some(), "pad_before"),
+                (self.align_before.is_some(), "align_before"),
+                (self.seek_before.is_some(), "seek_before"),
+                (self.size.is_some(), "size"),
+            ] {
+                if used {
+                    combine_error(
+                        all_errors,
+                        syn::Error::new(
+                            checksum.span,
+                            format!("`checksum` is incompatible with `{name}`"),
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_len_prefix(&self, options: Options, all_errors: &mut Option<syn::Error>) {
+        if let Some(len_prefix) = &self.len_prefix {
+            let mode = match self.field_mode {
+                FieldMode::Normal => None,
+                FieldMode::Default => Some(if options.write { "ignore" } else { "default" }),
+                FieldMode::Calc(_) => Some("calc"),
+                FieldMode::TryCalc(_) => Some("try_calc"),
+                FieldMode::Function(_) => Some(if options.write {
+                    "write_with"
+                } else {
+                    "parse_with"
+                }),
+            };
+
+            for name in mode
+                .into_iter()
+                .chain(self.count.is_some().then_some("count"))
+            {
+                combine_error(
+                    all_errors,
+                    syn::Error::new(
+                        len_prefix.span(),
+                        format!("`len_prefix` is incompatible with `{name}`"),
+                    ),
+                );
+            }
+        }
+    }
+
+    fn validate_count_of(&self, options: Options, all_errors: &mut Option<syn::Error>) {
+        if let Some(count_of) = &self.count_of {
+            let mode = match self.field_mode {
+                FieldMode::Normal => None,
+                FieldMode::Default => Some(if options.write { "ignore" } else { "default" }),
+                FieldMode::Calc(_) => Some("calc"),
+                FieldMode::TryCalc(_) => Some("try_calc"),
+                FieldMode::Function(_) => Some(if options.write {
+                    "write_with"
+                } else {
+                    "parse_with"
+                }),
+            };
+
+            for name in mode.into_iter().chain(
+                [
+                    (self.map.is_some(), "map"),
+                    (self.if_cond.is_some(), "if"),
+                    (self.do_try.is_some(), "try"),
+                ]
+                .into_iter()
+                .filter_map(|(used, name)| used.then_some(name)),
+            ) {
+                combine_error(
+                    all_errors,
+                    syn::Error::new(
+                        count_of.span(),
+                        format!("`count_of` is incompatible with `{name}`"),
+                    ),
+                );
+            }
+        }
+    }
+
+    fn validate_size(&self, options: Options, all_errors: &mut Option<syn::Error>) {
+        if let Some(size) = &self.size {
+            let mode = match self.field_mode {
+                FieldMode::Normal | FieldMode::Function(_) => return,
+                FieldMode::Default => {
+                    if options.write {
+                        "ignore"
+                    } else {
+                        "default"
+                    }
+                }
+                FieldMode::Calc(_) => "calc",
+                FieldMode::TryCalc(_) => "try_calc",
+            };
+
+            combine_error(
+                all_errors,
+                syn::Error::new(size.span(), format!("`size` is incompatible with `{mode}`")),
+            );
+        }
+    }
+
+    fn validate_async(&self, options: Options, all_errors: &mut Option<syn::Error>) {
+        if !options.asynchronous {
+            return;
+        }
+
+        let function = match &self.field_mode {
+            FieldMode::Function(function) if self.len_prefix.is_none() => Some(function.span()),
+            _ => None,
+        };
+
+        for (span, name) in [
+            (
+                function,
+                if options.write {
+                    "write_with"
+                } else {
+                    "parse_with"
+                },
+            ),
+            (
+                self.len_prefix.as_ref().map(SpannedValue::span),
+                "len_prefix",
+            ),
+            (self.map_stream.as_ref().map(Spanned::span), "map_stream"),
+            (self.size.as_ref().map(SpannedValue::span), "size"),
+            (
+                self.checksum.as_ref().map(|checksum| checksum.span),
+                "checksum",
+            ),
+            (self.bits.as_ref().map(SpannedValue::span), "bits"),
+        ] {
+            if let Some(span) = span {
+                combine_error(
+                    all_errors,
+                    syn::Error::new(span, async_unsupported_message(name, options)),
+                );
+            }
+        }
+    }
+
+    /// Replaces a `len_prefix` directive with the equivalent `parse_with` or
+    /// `write_with` function.
+    fn apply_len_prefix(mut self, options: Options) -> Self {
+        if let Some(len_prefix) = &self.len_prefix
+            && matches!(self.field_mode, FieldMode::Normal)
+        {
+            let ty = len_prefix.as_ref();
+            self.field_mode = FieldMode::Function(if options.write {
+                quote_spanned! {len_prefix.span()=> #WRITE_PREFIXED::<#ty, _, _> }
+            } else {
+                quote_spanned! {len_prefix.span()=> #PARSE_PREFIXED::<#ty, _, _> }
+            });
+        }
+        self
+    }
+
+    /// Replaces a `count_of` directive with a `try_calc` which computes the
+    /// length of the target field when writing.
+    fn apply_count_of(mut self, options: Options) -> Self {
+        if let Some(target) = &self.count_of
+            && options.write
+            && matches!(self.field_mode, FieldMode::Normal)
+        {
+            self.field_mode = FieldMode::TryCalc(quote_spanned! {target.span()=>
+                #COUNT_OF(#target.len(), ::core::stringify!(#target))
+            });
+        }
+        self
+    }
+
     fn validate(&self, options: Options) -> syn::Result<()> {
         let mut all_errors = None::<syn::Error>;
 
@@ -172,6 +524,13 @@ impl StructField {
             );
         }
 
+        self.validate_bits(options, &mut all_errors);
+        self.validate_checksum(options, &mut all_errors);
+        self.validate_len_prefix(options, &mut all_errors);
+        self.validate_count_of(options, &mut all_errors);
+        self.validate_size(options, &mut all_errors);
+        self.validate_async(options, &mut all_errors);
+
         if self.has_named_arg_directives()
             && !matches!(self.args, PassedArgs::None | PassedArgs::Named(..))
         {
@@ -232,9 +591,18 @@ impl FromField for StructField {
             magic: <_>::default(),
             args: <_>::default(),
             field_mode: <_>::default(),
+            len_prefix: <_>::default(),
+            bits: <_>::default(),
+            checksum: <_>::default(),
             count: <_>::default(),
+            count_of: <_>::default(),
             offset: <_>::default(),
             if_cond: <_>::default(),
+            select: <_>::default(),
+            since: <_>::default(),
+            until: <_>::default(),
+            version_cond: <_>::default(),
+            if_source: <_>::default(),
             restore_position: <_>::default(),
             do_try: <_>::default(),
             temp: <_>::default(),
@@ -245,6 +613,7 @@ impl FromField for StructField {
             align_after: <_>::default(),
             seek_before: <_>::default(),
             pad_size_to: <_>::default(),
+            size: <_>::default(),
             err_context: <_>::default(),
             debug: <_>::default(),
         };
@@ -271,6 +640,7 @@ impl FromField for StructField {
             }
             ParseResult::Err(error) => ParseResult::Err(error),
         }
+        .map(|this| this.apply_len_prefix(options).apply_count_of(options))
     }
 }
 
@@ -279,10 +649,17 @@ attr_struct! {
     #[derive(Clone, Debug)]
     pub(crate) struct UnitEnumField {
         pub(crate) ident: syn::Ident,
+        /// The explicit discriminant of the variant, if any.
+        pub(crate) discriminant: Option<TokenStream>,
+        /// Whether the variant has a field to hold an unknown `repr` value.
+        /// Only the `other` variant of a unit-like enum may have a field.
+        pub(crate) holds_value: bool,
         #[from(RW:Magic)]
         pub(crate) magic: Magic,
         #[from(RO:PreAssert)]
         pub(crate) pre_assertions: Vec<Assert>,
+        #[from(RW:Other)]
+        pub(crate) other: Option<SpannedValue<()>>,
     }
 }
 
@@ -302,8 +679,14 @@ impl FromField for UnitEnumField {
     fn from_field(field: &Self::In, _: usize, options: Options) -> ParseResult<Self> {
         let this = Self {
             ident: field.ident.clone(),
+            discriminant: field
+                .discriminant
+                .as_ref()
+                .map(|(_, expr)| expr.to_token_stream()),
+            holds_value: !matches!(field.fields, syn::Fields::Unit),
             magic: <_>::default(),
             pre_assertions: <_>::default(),
+            other: <_>::default(),
         };
 
         if options.write {
@@ -345,6 +728,15 @@ impl EnumVariant {
             Self::Unit(_) => true,
         }
     }
+
+    /// Returns the span of the `other` directive if this is the fallback
+    /// variant.
+    pub(crate) fn other(&self) -> Option<&SpannedValue<()>> {
+        match self {
+            Self::Variant { options, .. } => options.other.as_ref(),
+            Self::Unit(field) => field.other.as_ref(),
+        }
+    }
 }
 
 impl From<EnumVariant> for Struct {
@@ -382,3 +774,18 @@ impl FromField for EnumVariant {
         }
     }
 }
+
+/// Returns the error message for a directive which cannot be used by the async
+/// traits.
+pub(super) fn async_unsupported_message(name: &str, options: Options) -> String {
+    let trait_name = if options.write {
+        "BinWriteAsync"
+    } else {
+        "BinReadAsync"
+    };
+    if options.derive {
+        format!("`{name}` is not supported by `#[derive({trait_name})]`")
+    } else {
+        format!("`{name}` is not supported by `{trait_name}`")
+    }
+}
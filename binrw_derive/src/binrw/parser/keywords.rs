@@ -9,17 +9,22 @@ macro_rules! define_keywords {
 define_keywords! {
     align_after,
     align_before,
+    arbitrary,
     args,
     args_raw,
     assert,
     big,
+    bit_order,
+    bits,
     binread,
     br,
     brw,
     binwrite,
     bw,
     calc,
+    checksum,
     count,
+    count_of,
     dbg,
     default,
     err_context,
@@ -28,24 +33,32 @@ define_keywords! {
     import_raw,
     is_big,
     is_little,
+    len_prefix,
     little,
     magic,
     map,
     map_stream,
     offset,
+    other,
     pad_after,
     pad_before,
     pad_size_to,
     parse_with,
     pre_assert,
+    recover,
     repr,
     restore_position,
     return_all_errors,
     return_unexpected_error,
     seek_before,
+    select,
+    since,
+    size,
     stream,
     temp,
     try_calc,
     try_map,
+    until,
+    version,
     write_with,
 }
@@ -11,6 +11,7 @@ use crate::{
     binrw::{Options, is_binread_attr, is_binwrite_attr},
     combine_error,
 };
+use field_level_attrs::async_unsupported_message;
 pub(crate) use field_level_attrs::{EnumVariant, StructField, UnitEnumField};
 use macros::attr_struct;
 pub(crate) use top_level_attrs::{Enum, Input, Struct, UnitOnlyEnum};
@@ -133,12 +134,25 @@ mod tests {
     use syn::DeriveInput;
 
     #[cfg_attr(coverage_nightly, coverage(off))]
-    fn try_input(input: TokenStream) -> ParseResult<Input> {
+    fn try_input(input: TokenStream, asynchronous: bool) -> ParseResult<Input> {
         Input::from_input(
             &syn::parse2::<DeriveInput>(input).unwrap(),
             Options {
-                derive: false,
+                derive: asynchronous,
                 write: false,
+                asynchronous,
+            },
+        )
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn try_write_input(input: TokenStream) -> ParseResult<Input> {
+        Input::from_input(
+            &syn::parse2::<DeriveInput>(input).unwrap(),
+            Options {
+                derive: false,
+                write: true,
+                asynchronous: false,
             },
         )
     }
@@ -149,7 +163,23 @@ mod tests {
             #[cfg_attr(coverage_nightly, coverage(off))]
             #[should_panic(expected = $message)]
             fn $name() {
-                try_input(quote::quote! $tt).unwrap();
+                try_input(quote::quote! $tt, false).unwrap();
+            }
+        };
+        (async $name:ident: $message:literal $tt:tt) => {
+            #[test]
+            #[cfg_attr(coverage_nightly, coverage(off))]
+            #[should_panic(expected = $message)]
+            fn $name() {
+                try_input(quote::quote! $tt, true).unwrap();
+            }
+        };
+        (write $name:ident: $message:literal $tt:tt) => {
+            #[test]
+            #[cfg_attr(coverage_nightly, coverage(off))]
+            #[should_panic(expected = $message)]
+            fn $name() {
+                try_write_input(quote::quote! $tt).unwrap();
             }
         };
     );
@@ -161,6 +191,207 @@ mod tests {
         }
     });
 
+    try_error!(async async_map_stream: "`map_stream` is not supported by `#[derive(BinReadAsync)]`" {
+        #[br(map_stream = |s| s)]
+        struct Foo {
+            a: u8,
+        }
+    });
+
+    try_error!(async async_parse_with: "`parse_with` is not supported by `#[derive(BinReadAsync)]`" {
+        struct Foo {
+            #[br(parse_with = parse_a)]
+            a: u8,
+        }
+    });
+
+    try_error!(async async_temp: "`#[derive(BinReadAsync)]` cannot create temporary fields; use `#[binrw(async)]` or `#[binread(async)]` instead" {
+        struct Foo {
+            #[br(temp)]
+            a: u8,
+        }
+    });
+
+    try_error!(bits_conflict: "`bits` is incompatible with `map`" {
+        struct Foo {
+            #[br(bits = 8, map = |x: u8| x)]
+            a: u8,
+        }
+    });
+
+    try_error!(bits_invalid_order: "expected `lsb` or `msb`" {
+        #[br(bit_order = middle)]
+        struct Foo {
+            #[br(bits = 8)]
+            a: u8,
+        }
+    });
+
+    try_error!(bits_invalid_width: "bit width must be between 1 and 128" {
+        struct Foo {
+            #[br(bits = 0)]
+            a: u8,
+        }
+    });
+
+    try_error!(bits_partial_byte: "must fill a whole number of bytes, but this group has 7 bits" {
+        struct Foo {
+            #[br(bits = 3)]
+            a: u8,
+            #[br(bits = 4)]
+            b: u8,
+            c: u8,
+        }
+    });
+
+    try_error!(bits_too_wide: "must not exceed 128 bits" {
+        struct Foo {
+            #[br(bits = 128)]
+            a: u128,
+            #[br(bits = 8)]
+            b: u8,
+        }
+    });
+
+    try_error!(checksum_conflict: "`checksum` is incompatible with `calc`" {
+        struct Foo {
+            a: u8,
+            #[br(checksum(Crc32, a), calc(0))]
+            b: u32,
+        }
+    });
+
+    try_error!(checksum_invalid_range: "expected an inclusive range of fields" {
+        struct Foo {
+            a: u8,
+            b: u8,
+            #[br(checksum(Crc32, a..b))]
+            c: u32,
+        }
+    });
+
+    try_error!(checksum_missing_field: "cannot find field `d` in this struct" {
+        struct Foo {
+            a: u8,
+            #[br(checksum(Crc32, a..=d))]
+            b: u32,
+        }
+    });
+
+    try_error!(checksum_missing_range: "requires an algorithm and a range" {
+        struct Foo {
+            a: u8,
+            #[br(checksum(Crc32))]
+            b: u32,
+        }
+    });
+
+    try_error!(checksum_bytes_map_stream: "cannot be used in a struct with `map_stream`" {
+        #[br(map_stream = core::convert::identity)]
+        struct Foo {
+            a: u8,
+            #[br(checksum(Crc32, pos(0..1)))]
+            b: u32,
+        }
+    });
+
+    try_error!(checksum_bytes_open_range: "expected a range with a start and an end" {
+        struct Foo {
+            a: u8,
+            #[br(checksum(Crc32, offset(..1)))]
+            b: u32,
+        }
+    });
+
+    try_error!(checksum_bytes_covered: "checksum field cannot be covered by the checksum `c`" {
+        struct Foo {
+            #[br(checksum(Crc32, pos(0..4)))]
+            a: u32,
+            b: u8,
+            #[br(checksum(Sum8, a..=b))]
+            c: u8,
+        }
+    });
+
+    try_error!(checksum_overlap: "checksum range overlaps the range of `c`" {
+        struct Foo {
+            a: u8,
+            b: u8,
+            #[br(checksum(Sum8, a..=b))]
+            c: u8,
+            #[br(checksum(Sum8, b))]
+            d: u8,
+        }
+    });
+
+    try_error!(checksum_self: "checksum field cannot be part of its own range" {
+        struct Foo {
+            a: u8,
+            #[br(checksum(Crc32, a..=b))]
+            b: u32,
+        }
+    });
+
+    try_error!(checksum_split_bits: "must not split a group of bit fields" {
+        struct Foo {
+            #[br(bits = 4)]
+            a: u8,
+            #[br(bits = 4)]
+            b: u8,
+            #[br(checksum(Sum8, b))]
+            c: u8,
+        }
+    });
+
+    try_error!(count_of_calc: "`count_of` is incompatible with `calc`" {
+        struct Foo {
+            #[br(count_of = b, calc = 1)]
+            a: u8,
+            b: Vec<u8>,
+        }
+    });
+
+    try_error!(count_of_count: "cannot use `count` because its count is read from `a`" {
+        struct Foo {
+            #[br(count_of = b)]
+            a: u8,
+            #[br(count = 1)]
+            b: Vec<u8>,
+        }
+    });
+
+    try_error!(count_of_duplicate: "`b` already has its count in `a`" {
+        struct Foo {
+            #[br(count_of = b)]
+            a: u8,
+            #[br(count_of = b)]
+            c: u8,
+            b: Vec<u8>,
+        }
+    });
+
+    try_error!(count_of_earlier: "no field `a` after `b`" {
+        struct Foo {
+            a: Vec<u8>,
+            #[br(count_of = a)]
+            b: u8,
+        }
+    });
+
+    try_error!(len_prefix_count: "`len_prefix` is incompatible with `count`" {
+        struct Foo {
+            #[br(len_prefix = u8, count = 1)]
+            a: Vec<u8>,
+        }
+    });
+
+    try_error!(len_prefix_parse_with: "`len_prefix` is incompatible with `parse_with`" {
+        struct Foo {
+            #[br(len_prefix = u8, parse_with = foo)]
+            a: Vec<u8>,
+        }
+    });
+
     try_error!(conflicting_keyword_bool: "conflicting `restore_position` keyword" {
         struct Foo {
             #[br(restore_position, restore_position)]
@@ -363,20 +594,46 @@ mod tests {
     #[test]
     #[cfg_attr(coverage_nightly, coverage(off))]
     fn non_blocking_errors() {
-        let error = try_input(quote::quote! {
-            #[br(invalid_keyword_struct)]
-            struct Foo {
-                #[br(invalid_keyword_struct_field_a)]
-                a: i32,
-                #[br(invalid_keyword_struct_field_b)]
-                b: i32,
-            }
-        })
+        let error = try_input(
+            quote::quote! {
+                #[br(invalid_keyword_struct)]
+                struct Foo {
+                    #[br(invalid_keyword_struct_field_a)]
+                    a: i32,
+                    #[br(invalid_keyword_struct_field_b)]
+                    b: i32,
+                }
+            },
+            false,
+        )
         .err()
         .unwrap();
         assert_eq!(error.into_iter().count(), 3);
     }
 
+    try_error!(other_multiple: "`other` can only be used on one variant" {
+        enum Foo {
+            #[br(other)]
+            A(u8),
+            #[br(other)]
+            B(u16),
+        }
+    });
+
+    try_error!(other_struct: "`other` can only be used on enum variants" {
+        #[br(other)]
+        struct Foo(u8);
+    });
+
+    try_error!(other_unit_enum_without_field: "requires `repr` and a variant with a single field" {
+        #[br(repr = u8)]
+        enum Foo {
+            A,
+            #[br(other)]
+            B,
+        }
+    });
+
     try_error!(repr_magic_conflict: "mutually exclusive" {
         #[br(repr = u8)]
         enum Foo {
@@ -384,13 +641,70 @@ mod tests {
         }
     });
 
-    try_error!(unsupported_type_enum: "null enums are not supported" {
-        enum Foo {}
+    try_error!(select_enum_variant: "`select` can only be used on union fields" {
+        enum Foo {
+            A(#[br(select = true)] u8),
+        }
+    });
+
+    try_error!(select_struct: "`select` can only be used on union fields" {
+        struct Foo {
+            #[br(select = true)]
+            a: u8,
+        }
     });
 
-    try_error!(unsupported_type_union: "unions are not supported" {
+    try_error!(since_without_version: "require a `version` directive" {
+        struct Foo {
+            #[br(since = 2)]
+            a: u8,
+        }
+    });
+
+    try_error!(size_bits: "`bits` is incompatible with `size`" {
+        struct Foo {
+            #[br(bits = 4, size = 1)]
+            a: u8,
+        }
+    });
+
+    try_error!(size_calc: "`size` is incompatible with `calc`" {
+        struct Foo {
+            #[br(calc = 1, size = 1)]
+            a: u8,
+        }
+    });
+
+    try_error!(union_bits: "`bits` cannot be used on union fields" {
         union Bar {
-            a: i32,
+            #[br(bits = 4)]
+            a: u8,
         }
     });
+
+    try_error!(write union_select_missing: "every field of a union must have `select = unsafe { condition }`" {
+        union Bar {
+            #[bw(select = unsafe { true })]
+            a: u8,
+            b: u8,
+        }
+    });
+
+    try_error!(write union_select_safe: "`select` must be an `unsafe` block when writing a union" {
+        union Bar {
+            #[bw(select = true)]
+            a: u8,
+        }
+    });
+
+    try_error!(union_other: "`other` can only be used on enum variants" {
+        #[br(other)]
+        union Bar {
+            a: u8,
+        }
+    });
+
+    try_error!(unsupported_type_enum: "null enums are not supported" {
+        enum Foo {}
+    });
 }
@@ -1,10 +1,14 @@
 use super::{
-    EnumVariant, FromInput, ParseResult, StructField, TrySet, UnitEnumField, attr_struct,
-    types::{Assert, CondEndian, EnumErrorMode, Imports, Magic, Map},
+    EnumVariant, FromInput, ParseResult, SpannedValue, StructField, TrySet, UnitEnumField,
+    async_unsupported_message, attr_struct,
+    types::{
+        Assert, BitOrder, BitSlot, ChecksumRange, CondEndian, EnumErrorMode, Imports,
+        MAX_GROUP_BITS, Magic, Map,
+    },
 };
-use crate::binrw::Options;
-use proc_macro2::TokenStream;
-use quote::ToTokens;
+use crate::binrw::{Options, is_binread_attr, is_binwrite_attr};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{ToTokens, quote};
 use syn::{Ident, spanned::Spanned};
 
 /// The parsed representation of binrw attributes on a data structure.
@@ -17,6 +21,8 @@ pub(crate) enum Input {
     Enum(Enum),
     /// An enum containing only unit variants.
     UnitOnlyEnum(UnitOnlyEnum),
+    /// A union.
+    Union(Struct),
 }
 
 impl Input {
@@ -40,6 +46,14 @@ impl Input {
                     )
                 };
 
+                let read_struct = check_parsed(read_struct, |st| {
+                    if let Some(other) = &st.other {
+                        Err(syn::Error::new(other.span(), OTHER_ON_STRUCT))
+                    } else {
+                        st.validate_no_select()
+                    }
+                });
+
                 if matches!(st.fields, syn::Fields::Unit) {
                     read_struct.map(Self::UnitStruct)
                 } else {
@@ -56,6 +70,7 @@ impl Input {
                 } else if variants
                     .iter()
                     .all(|v| matches!(v.fields, syn::Fields::Unit))
+                    || is_unit_enum_with_other(attrs, variants)
                 {
                     if options.write {
                         <UnitOnlyEnum as FromInput<UnitEnumAttr<true>>>::from_input(
@@ -91,23 +106,38 @@ impl Input {
                     })
                 }
             }
-            syn::Data::Union(_) => {
-                ParseResult::Err(syn::Error::new(input.span(), "unions are not supported"))
+            syn::Data::Union(union) => {
+                let fields = union.fields.named.iter();
+                let union = if options.write {
+                    <Struct as FromInput<StructAttr<true>>>::from_input(attrs, fields, options)
+                } else {
+                    <Struct as FromInput<StructAttr<false>>>::from_input(attrs, fields, options)
+                };
+
+                check_parsed(union, Struct::validate_union).map(Self::Union)
             }
         }
     }
 
     pub(crate) fn endian(&self) -> &CondEndian {
         match self {
-            Input::Struct(s) | Input::UnitStruct(s) => &s.endian,
+            Input::Struct(s) | Input::UnitStruct(s) | Input::Union(s) => &s.endian,
             Input::Enum(e) => &e.endian,
             Input::UnitOnlyEnum(e) => &e.endian,
         }
     }
 
+    pub(crate) fn arbitrary(&self) -> Option<&SpannedValue<()>> {
+        match self {
+            Input::Struct(s) | Input::UnitStruct(s) | Input::Union(s) => s.arbitrary.as_ref(),
+            Input::Enum(e) => e.arbitrary.as_ref(),
+            Input::UnitOnlyEnum(e) => e.arbitrary.as_ref(),
+        }
+    }
+
     pub(crate) fn imports(&self) -> &Imports {
         match self {
-            Input::Struct(s) | Input::UnitStruct(s) => &s.imports,
+            Input::Struct(s) | Input::UnitStruct(s) | Input::Union(s) => &s.imports,
             Input::Enum(e) => &e.imports,
             Input::UnitOnlyEnum(e) => &e.imports,
         }
@@ -115,7 +145,7 @@ impl Input {
 
     pub(crate) fn is_empty(&self) -> bool {
         match self {
-            Input::Struct(s) => s.fields.is_empty() && s.magic.is_none(),
+            Input::Struct(s) | Input::Union(s) => s.fields.is_empty() && s.magic.is_none(),
             Input::UnitStruct(_) => true,
             Input::Enum(e) => e.variants.is_empty() && e.magic.is_none(),
             Input::UnitOnlyEnum(_) => false,
@@ -138,13 +168,13 @@ impl Input {
                     false
                 }
             }),
-            Input::UnitStruct(_) | Input::UnitOnlyEnum(_) => false,
+            Input::UnitStruct(_) | Input::UnitOnlyEnum(_) | Input::Union(_) => false,
         }
     }
 
     pub(crate) fn map(&self) -> &Map {
         match self {
-            Input::Struct(s) | Input::UnitStruct(s) => &s.map,
+            Input::Struct(s) | Input::UnitStruct(s) | Input::Union(s) => &s.map,
             Input::Enum(e) => &e.map,
             Input::UnitOnlyEnum(e) => &e.map,
         }
@@ -152,7 +182,7 @@ impl Input {
 
     pub(crate) fn magic(&self) -> &Magic {
         match self {
-            Input::Struct(s) | Input::UnitStruct(s) => &s.magic,
+            Input::Struct(s) | Input::UnitStruct(s) | Input::Union(s) => &s.magic,
             Input::Enum(e) => &e.magic,
             Input::UnitOnlyEnum(e) => &e.magic,
         }
@@ -160,7 +190,7 @@ impl Input {
 
     pub(crate) fn map_stream(&self) -> Option<&TokenStream> {
         match self {
-            Input::Struct(s) | Input::UnitStruct(s) => s.map_stream.as_ref(),
+            Input::Struct(s) | Input::UnitStruct(s) | Input::Union(s) => s.map_stream.as_ref(),
             Input::Enum(en) => en.map_stream.as_ref(),
             Input::UnitOnlyEnum(en) => en.map_stream.as_ref(),
         }
@@ -168,7 +198,7 @@ impl Input {
 
     pub(crate) fn pre_assertions(&self) -> &[Assert] {
         match self {
-            Input::Struct(s) | Input::UnitStruct(s) => &s.pre_assertions,
+            Input::Struct(s) | Input::UnitStruct(s) | Input::Union(s) => &s.pre_assertions,
             Input::Enum(e) => &e.pre_assertions,
             Input::UnitOnlyEnum(_) => &[],
         }
@@ -176,7 +206,7 @@ impl Input {
 
     pub(crate) fn stream_ident(&self) -> Option<&Ident> {
         match self {
-            Input::Struct(s) | Input::UnitStruct(s) => s.stream_ident.as_ref(),
+            Input::Struct(s) | Input::UnitStruct(s) | Input::Union(s) => s.stream_ident.as_ref(),
             Input::Enum(en) => en.stream_ident.as_ref(),
             Input::UnitOnlyEnum(en) => en.stream_ident.as_ref(),
         }
@@ -189,13 +219,71 @@ impl Input {
 
     pub(crate) fn assertions(&self) -> &[Assert] {
         match self {
-            Input::Struct(s) | Input::UnitStruct(s) => &s.assertions,
+            Input::Struct(s) | Input::UnitStruct(s) | Input::Union(s) => &s.assertions,
             Input::Enum(e) => &e.assertions,
             Input::UnitOnlyEnum(_) => &[],
         }
     }
 }
 
+/// Adds any error from a check which depends on where a parsed value is used
+/// to its parse result.
+fn check_parsed<T>(
+    result: ParseResult<T>,
+    check: impl FnOnce(&T) -> syn::Result<()>,
+) -> ParseResult<T> {
+    match result {
+        ParseResult::Ok(value) => match check(&value) {
+            Ok(()) => ParseResult::Ok(value),
+            Err(error) => ParseResult::Partial(value, error),
+        },
+        ParseResult::Partial(value, mut error) => {
+            if let Err(check_error) = check(&value) {
+                error.combine(check_error);
+            }
+            ParseResult::Partial(value, error)
+        }
+        ParseResult::Err(error) => ParseResult::Err(error),
+    }
+}
+
+const OTHER_ON_STRUCT: &str = "`other` can only be used on enum variants";
+
+/// Returns true if any binrw attribute contains the given directive.
+///
+/// This is used to decide how an enum should be parsed before its attributes
+/// have been parsed, so it only looks at the first token of each directive.
+fn has_directive(attrs: &[syn::Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| is_binread_attr(attr) || is_binwrite_attr(attr))
+        .filter_map(|attr| attr.meta.require_list().ok())
+        .any(|list| {
+            let mut at_start = true;
+            list.tokens.clone().into_iter().any(|token| {
+                let found = at_start && matches!(&token, TokenTree::Ident(ident) if ident == name);
+                at_start = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == ',');
+                found
+            })
+        })
+}
+
+/// Returns true if an enum is a `repr` enum whose only non-unit variant is an
+/// `other` variant holding a single unknown value.
+fn is_unit_enum_with_other<'a>(
+    attrs: &[syn::Attribute],
+    variants: impl IntoIterator<Item = &'a syn::Variant>,
+) -> bool {
+    has_directive(attrs, "repr")
+        && variants.into_iter().all(|variant| match &variant.fields {
+            syn::Fields::Unit => true,
+            syn::Fields::Unnamed(fields) => {
+                fields.unnamed.len() == 1 && has_directive(&variant.attrs, "other")
+            }
+            syn::Fields::Named(_) => false,
+        })
+}
+
 attr_struct! {
     #[from(StructAttr)]
     #[derive(Clone, Debug, Default)]
@@ -216,6 +304,16 @@ attr_struct! {
         pub(crate) assertions: Vec<Assert>,
         #[from(RO:PreAssert)]
         pub(crate) pre_assertions: Vec<Assert>,
+        #[from(RW:BitOrder)]
+        pub(crate) bit_order: Option<BitOrder>,
+        #[from(RW:Other)]
+        pub(crate) other: Option<SpannedValue<()>>,
+        #[from(RW:Version)]
+        pub(crate) version: Option<TokenStream>,
+        #[from(RO:Recover)]
+        pub(crate) recover: Option<()>,
+        #[from(RO:Arbitrary)]
+        pub(crate) arbitrary: Option<SpannedValue<()>>,
         pub(crate) fields: Vec<StructField>,
         pub(crate) for_write: bool,
     }
@@ -243,9 +341,70 @@ impl Struct {
             && matches!(self.map, Map::None)
             && self.magic.is_none()
             && matches!(self.imports, Imports::None)
+            && self.other.is_none()
+            && self.version.is_none()
+            && self.recover.is_none()
             && self.fields.iter().all(StructField::has_no_attrs)
     }
 
+    /// Rejects `select`, which is only meaningful on union fields.
+    pub(crate) fn validate_no_select(&self) -> syn::Result<()> {
+        match self.fields.iter().find_map(|field| field.select.as_ref()) {
+            Some(select) => Err(syn::Error::new(
+                select.span,
+                "`select` can only be used on union fields",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Rejects directives which cannot be used on a union.
+    pub(crate) fn validate_union(&self) -> syn::Result<()> {
+        if let Some(other) = &self.other {
+            return Err(syn::Error::new(other.span(), OTHER_ON_STRUCT));
+        }
+
+        for field in &self.fields {
+            for (span, name) in [
+                (field.bits.as_ref().map(SpannedValue::span), "bits"),
+                (
+                    field.checksum.as_ref().map(|checksum| checksum.span),
+                    "checksum",
+                ),
+                (field.temp.map(|()| field.field.span()), "temp"),
+            ] {
+                if let Some(span) = span {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`{name}` cannot be used on union fields"),
+                    ));
+                }
+            }
+
+            // Writing a field reads it from the union, which is only sound if
+            // the field holds the value of the union
+            if self.for_write {
+                match &field.select {
+                    Some(select) if select.is_unsafe => {}
+                    Some(select) => {
+                        return Err(syn::Error::new(
+                            select.span,
+                            "`select` must be an `unsafe` block when writing a union, like `select = unsafe { condition }`, to assert that it only chooses the field which holds the value of the union",
+                        ));
+                    }
+                    None => {
+                        return Err(syn::Error::new(
+                            field.field.span(),
+                            "every field of a union must have `select = unsafe { condition }` when writing",
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     // TODO: There should not be codegen in the parser
     pub(crate) fn fields_pattern(&self) -> TokenStream {
         let fields = self.iter_permanent_idents();
@@ -265,7 +424,28 @@ impl Struct {
 impl<const WRITE: bool> FromInput<StructAttr<WRITE>> for Struct {
     type Field = StructField;
 
-    fn push_field(&mut self, field: Self::Field) -> syn::Result<()> {
+    fn push_field(&mut self, mut field: Self::Field) -> syn::Result<()> {
+        field.apply_version(self.version.as_ref())?;
+
+        if !self.for_write
+            && let Some(count_field) = self
+                .fields
+                .iter()
+                .find(|other| other.count_of.as_ref() == Some(&field.ident))
+        {
+            if field.count.is_some() {
+                return Err(syn::Error::new(
+                    field.field.span(),
+                    format!(
+                        "`{}` cannot use `count` because its count is read from `{}`",
+                        field.ident, count_field.ident
+                    ),
+                ));
+            }
+            let count = &count_field.ident;
+            field.count = Some(quote! { #count });
+        }
+
         self.fields.push(field);
         Ok(())
     }
@@ -275,6 +455,77 @@ impl<const WRITE: bool> FromInput<StructAttr<WRITE>> for Struct {
     }
 
     fn validate(&self, options: Options) -> syn::Result<()> {
+        validate_async_map_stream(self.map_stream.as_ref(), options)?;
+
+        for (field, slot) in self.fields.iter().zip(BitSlot::layout(&self.fields)) {
+            let Some(slot) = slot.filter(|slot| slot.first) else {
+                continue;
+            };
+
+            let span = field.bits.as_ref().unwrap().span();
+            if slot.total > MAX_GROUP_BITS {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "consecutive bit fields must not exceed {MAX_GROUP_BITS} bits, but this group has {} bits",
+                        slot.total
+                    ),
+                ));
+            } else if slot.total % 8 != 0 {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "consecutive bit fields must fill a whole number of bytes, but this group has {} bits",
+                        slot.total
+                    ),
+                ));
+            }
+        }
+
+        ChecksumRange::resolve(&self.fields)?;
+
+        if self.map_stream.is_some()
+            && let Some(checksum) = self
+                .fields
+                .iter()
+                .filter_map(|field| field.checksum.as_ref())
+                .find(|checksum| checksum.is_bytes())
+        {
+            return Err(syn::Error::new(
+                checksum.span,
+                "a checksum over byte positions cannot be used in a struct with `map_stream`",
+            ));
+        }
+
+        for (index, field) in self.fields.iter().enumerate() {
+            let Some(target) = &field.count_of else {
+                continue;
+            };
+
+            if !self.fields[index + 1..]
+                .iter()
+                .any(|other| other.ident == *target)
+            {
+                return Err(syn::Error::new(
+                    target.span(),
+                    format!(
+                        "`count_of` must refer to a later field, but there is no field `{target}` after `{}`",
+                        field.ident
+                    ),
+                ));
+            }
+
+            if let Some(other) = self.fields[..index]
+                .iter()
+                .find(|other| other.count_of.as_ref() == Some(target))
+            {
+                return Err(syn::Error::new(
+                    target.span(),
+                    format!("`{target}` already has its count in `{}`", other.ident),
+                ));
+            }
+        }
+
         if self.map.is_none() && !options.derive {
             return Ok(());
         }
@@ -290,10 +541,19 @@ impl<const WRITE: bool> FromInput<StructAttr<WRITE>> for Struct {
             if options.derive && field.is_temp(options.write) {
                 return Err(syn::Error::new(
                     field.field.span(),
-                    if options.write {
-                        "`#[derive(BinWrite)]` cannot create temporary fields; use `#[binrw]` or `#[binwrite]` instead"
-                    } else {
-                        "`#[derive(BinRead)]` cannot create temporary fields; use `#[binrw]` or `#[binread]` instead"
+                    match (options.write, options.asynchronous) {
+                        (false, false) => {
+                            "`#[derive(BinRead)]` cannot create temporary fields; use `#[binrw]` or `#[binread]` instead"
+                        }
+                        (true, false) => {
+                            "`#[derive(BinWrite)]` cannot create temporary fields; use `#[binrw]` or `#[binwrite]` instead"
+                        }
+                        (false, true) => {
+                            "`#[derive(BinReadAsync)]` cannot create temporary fields; use `#[binrw(async)]` or `#[binread(async)]` instead"
+                        }
+                        (true, true) => {
+                            "`#[derive(BinWriteAsync)]` cannot create temporary fields; use `#[binrw(async)]` or `#[binwrite(async)]` instead"
+                        }
                     },
                 ));
             }
@@ -326,6 +586,8 @@ attr_struct! {
         pub(crate) pre_assertions: Vec<Assert>,
         #[from(RO:ReturnAllErrors, RO:ReturnUnexpectedError)]
         pub(crate) error_mode: EnumErrorMode,
+        #[from(RO:Arbitrary)]
+        pub(crate) arbitrary: Option<SpannedValue<()>>,
         pub(crate) variants: Vec<EnumVariant>,
     }
 }
@@ -338,7 +600,16 @@ impl<const WRITE: bool> FromInput<EnumAttr<WRITE>> for Enum {
         Ok(())
     }
 
-    fn validate(&self, _: Options) -> syn::Result<()> {
+    fn validate(&self, options: Options) -> syn::Result<()> {
+        validate_async_map_stream(self.map_stream.as_ref(), options)?;
+        validate_single_other(self.variants.iter().filter_map(EnumVariant::other))?;
+
+        for variant in &self.variants {
+            if let EnumVariant::Variant { options, .. } = variant {
+                options.validate_no_select()?;
+            }
+        }
+
         if self.map.is_some()
             && let Some(variant) = self.variants.iter().find(|variant| !variant.has_no_attrs())
         {
@@ -367,6 +638,8 @@ attr_struct! {
         pub(crate) magic: Magic,
         #[from(RW:Import, RW:ImportRaw)]
         pub(crate) imports: Imports,
+        #[from(RO:Arbitrary)]
+        pub(crate) arbitrary: Option<SpannedValue<()>>,
         pub(crate) fields: Vec<UnitEnumField>,
         pub(crate) is_magic_enum: bool,
     }
@@ -397,6 +670,20 @@ impl<const WRITE: bool> FromInput<UnitEnumAttr<WRITE>> for UnitOnlyEnum {
     }
 
     fn validate(&self, options: Options) -> syn::Result<()> {
+        validate_async_map_stream(self.map_stream.as_ref(), options)?;
+        validate_single_other(self.fields.iter().filter_map(|field| field.other.as_ref()))?;
+
+        for field in &self.fields {
+            if let Some(other) = &field.other
+                && (!field.holds_value || self.map.as_repr().is_none())
+            {
+                return Err(syn::Error::new(
+                    other.span(),
+                    "`other` on a unit-like enum requires `repr` and a variant with a single field to hold the unknown value",
+                ));
+            }
+        }
+
         if self.map.as_repr().is_some() || self.is_magic_enum() {
             Ok(())
         } else if options.write {
@@ -412,3 +699,28 @@ impl<const WRITE: bool> FromInput<UnitEnumAttr<WRITE>> for UnitOnlyEnum {
         }
     }
 }
+
+fn validate_async_map_stream(
+    map_stream: Option<&TokenStream>,
+    options: Options,
+) -> syn::Result<()> {
+    match map_stream {
+        Some(map_stream) if options.asynchronous => Err(syn::Error::new(
+            map_stream.span(),
+            async_unsupported_message("map_stream", options),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn validate_single_other<'a>(
+    mut others: impl Iterator<Item = &'a SpannedValue<()>>,
+) -> syn::Result<()> {
+    match (others.next(), others.next()) {
+        (Some(_), Some(other)) => Err(syn::Error::new(
+            other.span(),
+            "`other` can only be used on one variant",
+        )),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,135 @@
+use super::SpannedValue;
+use crate::{
+    binrw::parser::{StructField, attrs},
+    meta_types::KeywordToken,
+};
+use syn::{Ident, Lit};
+
+/// The order in which consecutive bit fields are assigned to the bits of their
+/// backing integer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum BitOrder {
+    /// The first field occupies the least significant bits.
+    #[default]
+    Lsb,
+    /// The first field occupies the most significant bits.
+    Msb,
+}
+
+impl TryFrom<attrs::BitOrder> for BitOrder {
+    type Error = syn::Error;
+
+    fn try_from(value: attrs::BitOrder) -> Result<Self, Self::Error> {
+        match value.value.to_string().as_str() {
+            "lsb" => Ok(Self::Lsb),
+            "msb" => Ok(Self::Msb),
+            _ => Err(syn::Error::new(
+                value.value.span(),
+                "expected `lsb` or `msb`",
+            )),
+        }
+    }
+}
+
+/// The width of a bit field.
+pub(crate) type Bits = Option<SpannedValue<u32>>;
+
+/// The largest number of bits which can be packed into one backing integer.
+pub(crate) const MAX_GROUP_BITS: u32 = 128;
+
+impl TryFrom<attrs::Bits> for SpannedValue<u32> {
+    type Error = syn::Error;
+
+    fn try_from(bits: attrs::Bits) -> Result<Self, Self::Error> {
+        let width = match &bits.value {
+            Lit::Int(int) => int.base10_parse::<u32>()?,
+            lit => {
+                return Err(syn::Error::new(lit.span(), "expected an integer literal"));
+            }
+        };
+
+        if width == 0 || width > MAX_GROUP_BITS {
+            return Err(syn::Error::new(
+                bits.value.span(),
+                format!("bit width must be between 1 and {MAX_GROUP_BITS}"),
+            ));
+        }
+
+        Ok(Self::new(width, bits.keyword_span()))
+    }
+}
+
+/// The location of a bit field within its backing integer.
+#[derive(Clone, Debug)]
+pub(crate) struct BitSlot {
+    /// The name of the first field in the group, used to name the variables
+    /// holding the backing integer.
+    pub(crate) group: Ident,
+    /// The offset of the field from the start of the group, in declaration
+    /// order.
+    pub(crate) offset: u32,
+    /// The number of bits in the field.
+    pub(crate) width: u32,
+    /// The total number of bits in the group.
+    pub(crate) total: u32,
+    /// Whether this is the first field in the group.
+    pub(crate) first: bool,
+    /// Whether this is the last field in the group.
+    pub(crate) last: bool,
+}
+
+impl BitSlot {
+    /// The number of bytes in the backing integer of the group.
+    pub(crate) fn group_bytes(&self) -> u32 {
+        self.total.div_ceil(8)
+    }
+
+    /// The number of bits the field is shifted left within the backing
+    /// integer.
+    pub(crate) fn shift(&self, order: BitOrder) -> u32 {
+        match order {
+            BitOrder::Lsb => self.offset,
+            BitOrder::Msb => self.total - self.offset - self.width,
+        }
+    }
+
+    /// Groups each run of consecutive bit fields and returns the slot of each
+    /// field, or `None` for fields which are not bit fields.
+    pub(crate) fn layout(fields: &[StructField]) -> Vec<Option<BitSlot>> {
+        let mut slots = Vec::with_capacity(fields.len());
+        let mut start = 0;
+        while start < fields.len() {
+            if fields[start].bits.is_none() {
+                slots.push(None);
+                start += 1;
+                continue;
+            }
+
+            let end = fields[start..]
+                .iter()
+                .position(|field| field.bits.is_none())
+                .map_or(fields.len(), |len| start + len);
+            let widths = fields[start..end]
+                .iter()
+                .map(|field| **field.bits.as_ref().unwrap());
+            let total = widths.clone().sum::<u32>();
+
+            let mut offset = 0;
+            for (index, width) in widths.enumerate() {
+                slots.push(Some(BitSlot {
+                    group: fields[start].ident.clone(),
+                    offset,
+                    width,
+                    total,
+                    first: index == 0,
+                    last: start + index + 1 == end,
+                }));
+                offset += width;
+            }
+
+            start = end;
+        }
+
+        slots
+    }
+}
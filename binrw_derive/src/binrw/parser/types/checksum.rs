@@ -0,0 +1,287 @@
+use super::BitSlot;
+use crate::{
+    binrw::parser::{StructField, attrs},
+    meta_types::KeywordToken,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, format_ident};
+use syn::{Expr, Ident, Lit, RangeLimits, spanned::Spanned};
+
+/// A checksum directive on a field.
+#[derive(Clone, Debug)]
+pub(crate) struct Checksum {
+    /// The type of the checksum algorithm.
+    pub(crate) algorithm: TokenStream,
+    /// The bytes covered by the checksum.
+    pub(crate) cover: ChecksumCover,
+    /// The range argument as it was written.
+    pub(crate) range: TokenStream,
+    /// The span of the directive.
+    pub(crate) span: Span,
+}
+
+/// The bytes covered by a checksum directive.
+#[derive(Clone, Debug)]
+pub(crate) enum ChecksumCover {
+    /// The bytes of a range of fields.
+    Fields {
+        /// The name of the first field covered by the checksum.
+        start: Ident,
+        /// The name of the last field covered by the checksum.
+        end: Ident,
+    },
+    /// A range of byte positions in the stream.
+    Bytes {
+        /// The first position covered by the checksum.
+        start: Box<Expr>,
+        /// The end of the range of positions covered by the checksum.
+        end: Box<Expr>,
+        /// Whether `end` is included in the range.
+        inclusive: bool,
+        /// Whether the positions are relative to the start of the struct
+        /// instead of the start of the stream.
+        relative: bool,
+    },
+}
+
+impl Checksum {
+    /// Returns true if the checksum covers a range of byte positions instead
+    /// of a range of fields.
+    pub(crate) fn is_bytes(&self) -> bool {
+        matches!(self.cover, ChecksumCover::Bytes { .. })
+    }
+}
+
+impl TryFrom<attrs::Checksum> for Checksum {
+    type Error = syn::Error;
+
+    fn try_from(value: attrs::Checksum) -> Result<Self, Self::Error> {
+        let span = value.keyword_span();
+        let mut args = value.fields.iter();
+
+        let (Some(algorithm), Some(range)) = (args.next(), args.next()) else {
+            return Err(syn::Error::new(
+                span,
+                "`checksum` requires an algorithm and a range as arguments",
+            ));
+        };
+
+        super::assert_all_args_consumed(args, span)?;
+
+        let algorithm = match algorithm {
+            Expr::Path(path) => path.into_token_stream(),
+            expr => {
+                return Err(syn::Error::new(
+                    expr.span(),
+                    "expected a checksum algorithm type",
+                ));
+            }
+        };
+
+        let cover = match range {
+            Expr::Call(call) if byte_range_kind(&call.func).is_some() => {
+                let relative = byte_range_kind(&call.func) == Some("offset");
+                let mut args = call.args.iter();
+                match (args.next(), args.next()) {
+                    (Some(Expr::Range(range)), None) => match range {
+                        syn::ExprRange {
+                            start: Some(start),
+                            end: Some(end),
+                            limits,
+                            ..
+                        } => ChecksumCover::Bytes {
+                            start: start.clone(),
+                            end: end.clone(),
+                            inclusive: matches!(limits, RangeLimits::Closed(_)),
+                            relative,
+                        },
+                        _ => {
+                            return Err(syn::Error::new(
+                                range.span(),
+                                "expected a range with a start and an end, like `0..8`",
+                            ));
+                        }
+                    },
+                    _ => {
+                        return Err(syn::Error::new(
+                            call.span(),
+                            "expected a single range of byte positions, like `offset(0..8)`",
+                        ));
+                    }
+                }
+            }
+            Expr::Range(range) => match (&range.start, &range.limits, &range.end) {
+                (Some(start), RangeLimits::Closed(_), Some(end)) => ChecksumCover::Fields {
+                    start: field_name(start)?,
+                    end: field_name(end)?,
+                },
+                _ => {
+                    return Err(syn::Error::new(
+                        range.span(),
+                        "expected an inclusive range of fields, like `a..=b`",
+                    ));
+                }
+            },
+            expr => {
+                let name = field_name(expr)?;
+                ChecksumCover::Fields {
+                    start: name.clone(),
+                    end: name,
+                }
+            }
+        };
+
+        Ok(Self {
+            algorithm,
+            cover,
+            range: range.into_token_stream(),
+            span,
+        })
+    }
+}
+
+/// Returns the name of the byte range form used by the given function path,
+/// if it is one of `offset` or `pos`.
+fn byte_range_kind(func: &Expr) -> Option<&'static str> {
+    match func {
+        Expr::Path(path) if path.path.is_ident("offset") => Some("offset"),
+        Expr::Path(path) if path.path.is_ident("pos") => Some("pos"),
+        _ => None,
+    }
+}
+
+/// Converts a field name or tuple field index into the identifier used for
+/// the field in generated code.
+fn field_name(expr: &Expr) -> syn::Result<Ident> {
+    match expr {
+        Expr::Path(path) if path.qself.is_none() => {
+            if let Some(ident) = path.path.get_ident() {
+                return Ok(ident.clone());
+            }
+        }
+        Expr::Lit(lit) => {
+            if let Lit::Int(index) = &lit.lit {
+                let index = index.base10_parse::<usize>()?;
+                return Ok(format_ident!("self_{}", index, span = lit.span()));
+            }
+        }
+        _ => {}
+    }
+
+    Err(syn::Error::new(expr.span(), "expected a field name"))
+}
+
+/// The location of a checksum field and the fields it covers within a
+/// struct. Checksums over byte positions have no range of fields.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ChecksumRange {
+    /// The index of the checksum field.
+    pub(crate) field: usize,
+    /// The index of the first covered field.
+    pub(crate) start: usize,
+    /// The index of the last covered field.
+    pub(crate) end: usize,
+}
+
+impl ChecksumRange {
+    /// The index of the first field involved in the checksum, which is where
+    /// the checksum state is created.
+    pub(crate) fn first(&self) -> usize {
+        self.field.min(self.start)
+    }
+
+    /// The index of the last field involved in the checksum, after which the
+    /// checksum is complete.
+    pub(crate) fn last(&self) -> usize {
+        self.field.max(self.end)
+    }
+
+    /// Returns true if the field at the given index is covered by the
+    /// checksum.
+    pub(crate) fn covers(&self, index: usize) -> bool {
+        (self.start..=self.end).contains(&index)
+    }
+
+    /// Finds the fields covered by each checksum field, in field order.
+    pub(crate) fn resolve(fields: &[StructField]) -> syn::Result<Vec<ChecksumRange>> {
+        let find = |name: &Ident| {
+            fields
+                .iter()
+                .position(|field| field.ident == *name)
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        name.span(),
+                        format!("cannot find field `{name}` in this struct"),
+                    )
+                })
+        };
+
+        let mut ranges = Vec::<ChecksumRange>::new();
+        for (index, field) in fields.iter().enumerate() {
+            let Some(Checksum {
+                cover: ChecksumCover::Fields { start, end },
+                span,
+                ..
+            }) = &field.checksum
+            else {
+                continue;
+            };
+
+            let range = ChecksumRange {
+                field: index,
+                start: find(start)?,
+                end: find(end)?,
+            };
+
+            if range.start > range.end {
+                return Err(syn::Error::new(
+                    *span,
+                    "checksum range must not end before it starts",
+                ));
+            } else if range.covers(index) {
+                return Err(syn::Error::new(
+                    *span,
+                    "checksum field cannot be part of its own range",
+                ));
+            } else if let Some(other) = ranges.iter().find(|other| {
+                other.covers(range.start) || other.covers(range.end) || range.covers(other.start)
+            }) {
+                let other = &fields[other.field].ident;
+                return Err(syn::Error::new(
+                    *span,
+                    format!("checksum range overlaps the range of `{other}`"),
+                ));
+            }
+
+            ranges.push(range);
+        }
+
+        for (index, field) in fields.iter().enumerate() {
+            let Some(checksum) = &field.checksum else {
+                continue;
+            };
+
+            if let Some(other) = ranges.iter().find(|other| other.covers(index)) {
+                let other = &fields[other.field].ident;
+                return Err(syn::Error::new(
+                    checksum.span,
+                    format!("checksum field cannot be covered by the checksum `{other}`"),
+                ));
+            }
+        }
+
+        let slots = BitSlot::layout(fields);
+        for range in &ranges {
+            let splits_start = slots[range.start].as_ref().is_some_and(|slot| !slot.first);
+            let splits_end = slots[range.end].as_ref().is_some_and(|slot| !slot.last);
+            if splits_start || splits_end {
+                return Err(syn::Error::new(
+                    fields[range.field].checksum.as_ref().unwrap().span,
+                    "checksum range must not split a group of bit fields",
+                ));
+            }
+        }
+
+        Ok(ranges)
+    }
+}
@@ -1,4 +1,6 @@
 mod assert;
+mod bits;
+mod checksum;
 mod cond_endian;
 mod condition;
 mod enum_error_mode;
@@ -8,9 +10,12 @@ mod imports;
 mod magic;
 mod map;
 mod passed_args;
+mod select;
 mod spanned_value;
 
 pub(crate) use assert::{Assert, Error as AssertionError};
+pub(crate) use bits::{BitOrder, BitSlot, Bits, MAX_GROUP_BITS};
+pub(crate) use checksum::{Checksum, ChecksumCover, ChecksumRange};
 pub(crate) use cond_endian::CondEndian;
 pub(crate) use condition::Condition;
 pub(crate) use enum_error_mode::EnumErrorMode;
@@ -20,6 +25,7 @@ pub(crate) use imports::Imports;
 pub(crate) use magic::Magic;
 pub(crate) use map::Map;
 pub(crate) use passed_args::PassedArgs;
+pub(crate) use select::Select;
 pub(crate) use spanned_value::SpannedValue;
 
 fn assert_all_args_consumed<Iter, IterItem>(
@@ -0,0 +1,40 @@
+use crate::{binrw::parser::attrs, meta_types::KeywordToken};
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
+use syn::{Expr, Stmt};
+
+/// A `select` directive on a union field.
+#[derive(Clone, Debug)]
+pub(crate) struct Select {
+    /// The condition which chooses the field.
+    pub(crate) condition: TokenStream,
+    /// Whether the condition is wrapped in an `unsafe` block, which asserts
+    /// that it only chooses the field when the field holds the value of the
+    /// union.
+    pub(crate) is_unsafe: bool,
+    /// The span of the directive.
+    pub(crate) span: Span,
+}
+
+impl From<attrs::Select> for Select {
+    fn from(value: attrs::Select) -> Self {
+        let span = value.keyword_span();
+        match value.value {
+            Expr::Unsafe(expr) => Self {
+                // A block with only an expression is unwrapped so that it does
+                // not trigger lints about blocks in conditions
+                condition: match expr.block.stmts.as_slice() {
+                    [Stmt::Expr(expr, None)] => expr.into_token_stream(),
+                    _ => expr.block.into_token_stream(),
+                },
+                is_unsafe: true,
+                span,
+            },
+            expr => Self {
+                condition: expr.into_token_stream(),
+                is_unsafe: false,
+                span,
+            },
+        }
+    }
+}
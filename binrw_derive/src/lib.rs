@@ -28,6 +28,7 @@ pub fn binread_derive(input: TokenStream) -> TokenStream {
         binrw::Options {
             derive: true,
             write: false,
+            asynchronous: false,
         },
     )
     .into()
@@ -36,10 +37,11 @@ pub fn binread_derive(input: TokenStream) -> TokenStream {
 #[proc_macro_attribute]
 #[cfg_attr(coverage_nightly, coverage(off))]
 pub fn binrw(attr: TokenStream, input: TokenStream) -> TokenStream {
-    if attr.to_string() == "ignore" {
-        input
-    } else {
-        binrw::binrw_derive(parse_macro_input!(input as DeriveInput)).into()
+    match attr.to_string().as_str() {
+        "ignore" => input,
+        attr => {
+            binrw::binrw_derive(parse_macro_input!(input as DeriveInput), attr == "async").into()
+        }
     }
 }
 
@@ -51,6 +53,35 @@ pub fn binwrite_derive(input: TokenStream) -> TokenStream {
         binrw::Options {
             derive: true,
             write: true,
+            asynchronous: false,
+        },
+    )
+    .into()
+}
+
+#[proc_macro_derive(BinReadAsync, attributes(br, brw))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn binread_async_derive(input: TokenStream) -> TokenStream {
+    binrw::derive_from_input(
+        parse_macro_input!(input as DeriveInput),
+        binrw::Options {
+            derive: true,
+            write: false,
+            asynchronous: true,
+        },
+    )
+    .into()
+}
+
+#[proc_macro_derive(BinWriteAsync, attributes(bw, brw))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn binwrite_async_derive(input: TokenStream) -> TokenStream {
+    binrw::derive_from_input(
+        parse_macro_input!(input as DeriveInput),
+        binrw::Options {
+            derive: true,
+            write: true,
+            asynchronous: true,
         },
     )
     .into()
@@ -1,4 +1,4 @@
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Delimiter, Ident, Spacing, Span, TokenStream, TokenTree};
 use quote::{ToTokens, TokenStreamExt, quote};
 
 macro_rules! ident_str {
@@ -133,3 +133,127 @@ macro_rules! quote_spanned_any {
     } }
 }
 pub(crate) use quote_spanned_any;
+
+/// Converts tokens into a string of their source code.
+///
+/// `stringify!` puts spaces around every punctuation character of tokens
+/// which did not come directly from the source file, so the tokens are
+/// formatted here instead. Angle brackets are formatted as generics in types
+/// and after `::`, and as comparison operators everywhere else.
+pub(crate) fn tokens_to_string(tokens: TokenStream, is_type: bool) -> String {
+    let mut formatter = SourceFormatter {
+        out: String::new(),
+        is_type,
+        generics: 0,
+        closure: false,
+    };
+    formatter.write(tokens);
+    formatter.out.trim_end().to_owned()
+}
+
+/// The kind of the previously formatted token.
+#[derive(Clone, Copy, PartialEq)]
+enum Prev {
+    Start,
+    Word,
+    Op,
+    Close,
+}
+
+/// Formatter state for [`tokens_to_string`].
+struct SourceFormatter {
+    out: String,
+    is_type: bool,
+    generics: usize,
+    closure: bool,
+}
+
+impl SourceFormatter {
+    fn write(&mut self, tokens: TokenStream) {
+        let mut prev = Prev::Start;
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Ident(_) | TokenTree::Literal(_) => {
+                    if matches!(prev, Prev::Word | Prev::Close) {
+                        self.out.push(' ');
+                    }
+                    self.out.push_str(&token.to_string());
+                    prev = Prev::Word;
+                }
+                TokenTree::Punct(punct) => {
+                    let mut op = String::from(punct.as_char());
+                    let mut spacing = punct.spacing();
+                    while spacing == Spacing::Joint
+                        && let Some(TokenTree::Punct(next)) = tokens.peek()
+                        && next.as_char() != '\''
+                    {
+                        op.push(next.as_char());
+                        spacing = next.spacing();
+                        tokens.next();
+                    }
+                    prev = self.op(&op, prev);
+                }
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace => ("{ ", " }"),
+                        Delimiter::None => ("", ""),
+                    };
+                    if group.delimiter() == Delimiter::Brace && prev != Prev::Start {
+                        self.out.push(' ');
+                    }
+                    self.out.push_str(open);
+                    let generics = core::mem::take(&mut self.generics);
+                    self.write(group.stream());
+                    self.generics = generics;
+                    self.out.push_str(close);
+                    prev = Prev::Close;
+                }
+            }
+        }
+    }
+
+    fn op(&mut self, op: &str, prev: Prev) -> Prev {
+        let after_value = matches!(prev, Prev::Word | Prev::Close);
+        match op {
+            // A `!` which is not a prefix operator is a macro invocation
+            "::" | "." | ".." | "..=" | "'" | "#" | "!" => self.out.push_str(op),
+            "?" => self.out.push('?'),
+            "," | ";" | ":" => {
+                self.out.push_str(op);
+                self.out.push(' ');
+            }
+            "<" if self.is_type || self.out.ends_with("::") => {
+                self.generics += 1;
+                self.out.push('<');
+            }
+            ">" if self.generics > 0 => {
+                self.generics -= 1;
+                self.out.push('>');
+            }
+            ">>" if self.generics > 1 => {
+                self.generics -= 2;
+                self.out.push_str(">>");
+            }
+            "|" if !after_value || self.closure => {
+                if self.closure {
+                    self.out.push_str("| ");
+                } else {
+                    self.out.push('|');
+                }
+                self.closure = !self.closure;
+            }
+            "&" | "*" | "-" if !after_value => self.out.push_str(op),
+            _ => {
+                if prev != Prev::Start {
+                    self.out.push(' ');
+                }
+                self.out.push_str(op);
+                self.out.push(' ');
+            }
+        }
+        Prev::Op
+    }
+}